@@ -0,0 +1,100 @@
+//! Short thread aliases (`t12`)
+//!
+//! Thread IDs are long opaque numbers. Every conversation shown in the
+//! inbox or a thread view gets a short alias that, unlike inbox positions,
+//! never changes, so `ig reply t12 -m hi` keeps working tomorrow. Anything
+//! that takes a thread ID accepts an alias too.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::store;
+
+/// Data file holding the alias map
+const ALIASES_FILE: &str = "aliases";
+
+/// Alias numbers handed out so far, by thread ID
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Aliases {
+    /// Last number handed out
+    last: u32,
+    threads: HashMap<String, u32>,
+}
+
+impl Aliases {
+    fn load() -> Result<Self> {
+        store::load(ALIASES_FILE)
+    }
+
+    fn save(&self) -> Result<()> {
+        store::save(ALIASES_FILE, self)
+    }
+}
+
+/// Aliases for the given threads, handing out new ones as needed. Best
+/// effort: threads get no alias if the map can't be read or written.
+pub fn assign<'a>(thread_ids: impl IntoIterator<Item = &'a str>) -> HashMap<String, String> {
+    let Ok(mut aliases) = Aliases::load() else {
+        return HashMap::new();
+    };
+
+    let mut changed = false;
+    let mut found = HashMap::new();
+    for id in thread_ids {
+        if id.is_empty() {
+            continue;
+        }
+        let number = match aliases.threads.get(id) {
+            Some(number) => *number,
+            None => {
+                aliases.last += 1;
+                aliases.threads.insert(id.to_string(), aliases.last);
+                changed = true;
+                aliases.last
+            }
+        };
+        found.insert(id.to_string(), format!("t{}", number));
+    }
+
+    if changed && aliases.save().is_err() {
+        return HashMap::new();
+    }
+    found
+}
+
+/// Alias for one thread (see [`assign`])
+pub fn alias_for(thread_id: &str) -> Option<String> {
+    assign([thread_id]).remove(thread_id)
+}
+
+/// The thread ID behind `input` if it is an alias, else `input` unchanged
+pub fn resolve(input: &str) -> Result<String> {
+    let Some(number) = input
+        .strip_prefix('t')
+        .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|n| n.parse::<u32>().ok())
+    else {
+        return Ok(input.to_string());
+    };
+
+    Aliases::load()?
+        .threads
+        .into_iter()
+        .find(|(_, n)| *n == number)
+        .map(|(id, _)| id)
+        .ok_or_else(|| anyhow::anyhow!("unknown thread alias '{}' (aliases are shown in `ig inbox`)", input))
+}
+
+/// clap parser for thread ID arguments: accepts an alias or an ID
+pub fn thread_arg(value: &str) -> Result<String, String> {
+    resolve(value).map_err(|e| e.to_string())
+}
+
+/// clap parser for "thread ID or @username" arguments
+pub fn target_arg(value: &str) -> Result<String, String> {
+    if value.starts_with('@') {
+        return Ok(value.to_string());
+    }
+    thread_arg(value)
+}
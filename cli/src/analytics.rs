@@ -0,0 +1,131 @@
+//! Local command usage (`ig usage`)
+//!
+//! Each run adds one to a tally for its command line's shape: the
+//! subcommands and the flags given, never their values or any other
+//! argument (`ig send @alice "hi" -y` is counted as `send -y`). The tallies
+//! stay in `command_usage.json` in the data directory and are never sent
+//! anywhere; `[usage] track = false` in config.toml stops the counting.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::config::Config;
+use crate::store;
+
+/// Data file holding the tallies
+const USAGE_FILE: &str = "command_usage";
+
+/// Runs of a command line before an alias is suggested for it
+pub const SUGGEST_AFTER: usize = 5;
+
+/// How often one command line shape was run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tally {
+    pub runs: usize,
+    pub last: DateTime<Local>,
+}
+
+/// Tallies by command line shape (e.g. "inbox -i")
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CommandUsage {
+    pub commands: BTreeMap<String, Tally>,
+}
+
+impl CommandUsage {
+    pub fn load() -> Result<Self> {
+        store::load(USAGE_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        store::save(USAGE_FILE, self)
+    }
+
+    /// Count a run of `shape`
+    pub fn count(&mut self, shape: &str, at: DateTime<Local>) {
+        let tally = self
+            .commands
+            .entry(shape.to_string())
+            .or_insert(Tally { runs: 0, last: at });
+        tally.runs += 1;
+        tally.last = at;
+    }
+
+    /// Shapes with their tallies, most run first
+    pub fn ranked(&self) -> Vec<(&str, &Tally)> {
+        let mut ranked: Vec<(&str, &Tally)> = self.commands.iter().map(|(s, t)| (s.as_str(), t)).collect();
+        ranked.sort_by(|a, b| b.1.runs.cmp(&a.1.runs).then_with(|| a.0.cmp(b.0)));
+        ranked
+    }
+
+    /// Often-run command lines worth an alias: run at least
+    /// [`SUGGEST_AFTER`] times, more than one word long and not already
+    /// behind one of `aliases`. Most run first, with a suggested name.
+    pub fn suggestions(&self, aliases: &BTreeMap<String, String>) -> Vec<(String, &str)> {
+        let aliased: Vec<String> = aliases.values().map(|a| normalize(a)).collect();
+        self.ranked()
+            .into_iter()
+            .filter(|(shape, tally)| {
+                tally.runs >= SUGGEST_AFTER && shape.contains(' ') && !aliased.contains(&normalize(shape))
+            })
+            .map(|(shape, _)| (alias_name(shape), shape))
+            .collect()
+    }
+}
+
+/// Words of a command line, single-spaced
+fn normalize(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A short alias name for a command line: the first letter of each
+/// subcommand and flag ("inbox -i" → "ii", "remind list" → "rl")
+pub fn alias_name(shape: &str) -> String {
+    shape
+        .split_whitespace()
+        .filter_map(|word| word.trim_start_matches('-').chars().next())
+        .collect()
+}
+
+/// The shape of a command line: the subcommands and the flags given on it
+/// (short form where there is one), e.g. "inbox -i --tag". Values,
+/// positional arguments and global options such as `--server` are left out.
+pub fn shape(cli: &Command, matches: &ArgMatches) -> Option<String> {
+    let mut words = Vec::new();
+    let (mut command, mut matches) = (cli, matches);
+    while let Some((name, sub_matches)) = matches.subcommand() {
+        let Some(sub) = command.find_subcommand(name) else {
+            break;
+        };
+        words.push(name.to_string());
+        for arg in sub.get_arguments().filter(|a| !a.is_positional()) {
+            if sub_matches.value_source(arg.get_id().as_str()) != Some(ValueSource::CommandLine) {
+                continue;
+            }
+            match (arg.get_short(), arg.get_long()) {
+                (Some(short), _) => words.push(format!("-{}", short)),
+                (None, Some(long)) => words.push(format!("--{}", long)),
+                (None, None) => {}
+            }
+        }
+        command = sub;
+        matches = sub_matches;
+    }
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Count this run's command line, unless tracking is turned off. Best
+/// effort: a data directory that can't be written never fails the command.
+pub fn record(shape: &str) {
+    if !Config::load().is_ok_and(|c| c.usage.track) {
+        return;
+    }
+    let Ok(mut usage) = CommandUsage::load() else {
+        return;
+    };
+    usage.count(shape, Local::now());
+    let _ = usage.save();
+}
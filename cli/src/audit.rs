@@ -0,0 +1,69 @@
+//! Audit trail
+//!
+//! `audit.log` in the data directory is append-only, one JSON object per
+//! line with the time and kind of event, for reviewing later what the CLI
+//! noticed or did (e.g. keyword alerts). Old entries only go when
+//! `keep_messages` retention is configured.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use crate::store;
+
+/// File name of the trail in the data directory
+const AUDIT_FILE: &str = "audit.log";
+
+/// Append an event to the trail. `details` (a JSON object) is merged into
+/// the entry next to `at` and `event`.
+pub fn record(event: &str, details: Value) -> Result<()> {
+    let mut entry = Map::new();
+    entry.insert("at".to_string(), Value::String(Local::now().to_rfc3339()));
+    entry.insert("event".to_string(), Value::String(event.to_string()));
+    if let Value::Object(details) = details {
+        entry.extend(details);
+    }
+
+    let path = store::data_dir()?.join(AUDIT_FILE);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", Value::Object(entry)).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Remove entries recorded before `cutoff`; returns how many went. Lines
+/// that can't be read are kept.
+pub fn prune(cutoff: DateTime<Local>) -> Result<usize> {
+    let path = store::data_dir()?.join(AUDIT_FILE);
+    if !path.exists() {
+        return Ok(0);
+    }
+    let data = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let mut kept = String::new();
+    let mut removed = 0;
+    for line in data.lines().filter(|l| !l.trim().is_empty()) {
+        let at = serde_json::from_str::<Value>(line)
+            .ok()
+            .and_then(|entry| entry["at"].as_str().and_then(|at| DateTime::parse_from_rfc3339(at).ok()));
+        if at.is_some_and(|at| at < cutoff) {
+            removed += 1;
+        } else {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    if removed == 0 {
+        return Ok(0);
+    }
+
+    // Write to a temp file first so a crash never loses the whole trail
+    let tmp = path.with_extension("log.tmp");
+    fs::write(&tmp, kept).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(removed)
+}
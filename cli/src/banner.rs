@@ -0,0 +1,208 @@
+//! Banner rendering
+//!
+//! `ig banner` prints the classic framed banner; `ig banner "hello"` renders
+//! custom text in a block font with the Instagram gradient running across
+//! the characters. Banners are centered and fall back to a smaller font when
+//! the terminal is too narrow.
+
+use clap::ValueEnum;
+use colored::Colorize;
+use crossterm::terminal;
+use std::io::{self, IsTerminal};
+
+use crate::colors::{gradient_text, palette};
+
+/// Width assumed when the terminal size is unknown (e.g. piped output)
+const DEFAULT_WIDTH: usize = 80;
+
+/// Columns between glyphs
+const LETTER_SPACING: usize = 1;
+
+/// Banner fonts, largest first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BannerFont {
+    /// Full blocks, twice the size of `block`
+    Big,
+    /// Three rows of half blocks (same style as the classic banner)
+    #[default]
+    Block,
+    /// Spaced-out capitals on one line
+    Plain,
+}
+
+impl BannerFont {
+    /// The next smaller font, if any
+    fn smaller(self) -> Option<Self> {
+        match self {
+            BannerFont::Big => Some(BannerFont::Block),
+            BannerFont::Block => Some(BannerFont::Plain),
+            BannerFont::Plain => None,
+        }
+    }
+}
+
+/// Print the classic framed banner, centered
+pub fn print_gradient_banner() {
+    // Each line gets a different color from the gradient
+    let p = palette();
+    let lines = [
+        ("╔══════════════════════════════════════════╗", p.purple),
+        ("║                                          ║", p.purple),
+        ("║   ▀█▀ █▀▀   █▀▄ █▀█▀█   █▀▀ █   ▀█▀      ║", p.pink),
+        ("║    █  █ █   █ █ █ ▀ █   █   █    █       ║", p.pink),
+        ("║   ▀▀▀ ▀▀▀   ▀▀  ▀   ▀   ▀▀▀ ▀▀▀ ▀▀▀      ║", p.orange),
+        ("║                                          ║", p.orange),
+        ("║       Instagram Direct Messages          ║", p.yellow),
+        ("║            from your terminal            ║", p.yellow),
+        ("║                                          ║", p.orange),
+        ("╚══════════════════════════════════════════╝", p.purple),
+    ];
+
+    let width = terminal_width();
+    println!();
+    for (line, (r, g, b)) in lines {
+        println!("{}{}", padding(line, width), line.truecolor(r, g, b));
+    }
+    println!();
+}
+
+/// Print custom text as a gradient banner
+pub fn print_text_banner(text: &str, font: BannerFont) {
+    let width = terminal_width();
+
+    // Step down to a smaller font until the banner fits
+    let mut font = font;
+    let mut lines = render(text, font);
+    while lines.iter().any(|l| l.chars().count() > width) {
+        match font.smaller() {
+            Some(smaller) => {
+                font = smaller;
+                lines = render(text, font);
+            }
+            None => break,
+        }
+    }
+
+    println!();
+    for line in &lines {
+        println!("{}{}", padding(line, width), gradient_text(line));
+    }
+    println!();
+}
+
+/// Render text in a font, one string per row (all rows the same width)
+pub fn render(text: &str, font: BannerFont) -> Vec<String> {
+    let text = text.to_uppercase();
+    match font {
+        BannerFont::Plain => {
+            let spaced: Vec<String> = text.chars().map(|c| c.to_string()).collect();
+            vec![spaced.join(" ")]
+        }
+        BannerFont::Block => render_block(&text),
+        BannerFont::Big => upscale(&render_block(&text)),
+    }
+}
+
+/// Lay out block glyphs side by side
+fn render_block(text: &str) -> Vec<String> {
+    let mut rows = vec![String::new(); 3];
+    for (i, ch) in text.chars().enumerate() {
+        let glyph = block_glyph(ch);
+        for (row, part) in rows.iter_mut().zip(glyph) {
+            if i > 0 {
+                row.push_str(&" ".repeat(LETTER_SPACING));
+            }
+            row.push_str(part);
+        }
+    }
+    rows
+}
+
+/// Turn half-block rows into twice as many rows of double-width full blocks
+fn upscale(rows: &[String]) -> Vec<String> {
+    let mut big = Vec::with_capacity(rows.len() * 2);
+    for row in rows {
+        let mut top = String::new();
+        let mut bottom = String::new();
+        for ch in row.chars() {
+            let (upper, lower) = match ch {
+                '█' => (true, true),
+                '▀' => (true, false),
+                '▄' => (false, true),
+                _ => (false, false),
+            };
+            top.push_str(if upper { "██" } else { "  " });
+            bottom.push_str(if lower { "██" } else { "  " });
+        }
+        big.push(top);
+        big.push(bottom);
+    }
+    // Glyphs sit on the top half of the last row, so drop the empty bottom
+    while big.len() > 1 && big.last().is_some_and(|row| row.trim().is_empty()) {
+        big.pop();
+    }
+    big
+}
+
+/// Half-block glyph for a character (unknown characters render as `?`)
+fn block_glyph(ch: char) -> [&'static str; 3] {
+    match ch {
+        'A' => ["█▀█", "█▀█", "▀ ▀"],
+        'B' => ["█▀▄", "█▀▄", "▀▀ "],
+        'C' => ["█▀▀", "█  ", "▀▀▀"],
+        'D' => ["█▀▄", "█ █", "▀▀ "],
+        'E' => ["█▀▀", "█▀▀", "▀▀▀"],
+        'F' => ["█▀▀", "█▀ ", "▀  "],
+        'G' => ["█▀▀", "█ █", "▀▀▀"],
+        'H' => ["█ █", "█▀█", "▀ ▀"],
+        'I' => ["▀█▀", " █ ", "▀▀▀"],
+        'J' => ["  █", "▄ █", "▀▀▀"],
+        'K' => ["█ █", "█▀▄", "▀ ▀"],
+        'L' => ["█  ", "█  ", "▀▀▀"],
+        'M' => ["█▀█▀█", "█ ▀ █", "▀   ▀"],
+        'N' => ["█▀▀█", "█  █", "▀  ▀"],
+        'O' | '0' => ["█▀█", "█ █", "▀▀▀"],
+        'P' => ["█▀█", "█▀▀", "▀  "],
+        'Q' => ["█▀█", "█ █", "▀▀█"],
+        'R' => ["█▀█", "█▀▄", "▀ ▀"],
+        'S' => ["█▀▀", "▀▀█", "▀▀▀"],
+        'T' => ["▀█▀", " █ ", " ▀ "],
+        'U' => ["█ █", "█ █", "▀▀▀"],
+        'V' => ["█ █", "█ █", " ▀ "],
+        'W' => ["█ █ █", "█ █ █", " ▀ ▀ "],
+        'X' => ["█ █", "▄▀▄", "▀ ▀"],
+        'Y' => ["█ █", "▀█▀", " ▀ "],
+        'Z' => ["▀▀█", "▄▀ ", "▀▀▀"],
+        '1' => ["▄█ ", " █ ", "▀▀▀"],
+        '2' => ["▀▀█", "█▀▀", "▀▀▀"],
+        '3' => ["▀▀█", " ▀█", "▀▀▀"],
+        '4' => ["█ █", "▀▀█", "  ▀"],
+        '5' => ["█▀▀", "▀▀█", "▀▀▀"],
+        '6' => ["█▀▀", "█▀█", "▀▀▀"],
+        '7' => ["▀▀█", "  █", "  ▀"],
+        '8' => ["█▀█", "█▀█", "▀▀▀"],
+        '9' => ["█▀█", "▀▀█", "▀▀▀"],
+        ' ' => ["  ", "  ", "  "],
+        '!' => ["█", "▀", "▀"],
+        '.' => [" ", " ", "▀"],
+        '-' => ["  ", "▀▀", "  "],
+        '@' => ["█▀▀█", "█ ██", "▀▀▀▀"],
+        _ => ["▀▀█", " █▀", " ▀ "],
+    }
+}
+
+/// Terminal width in columns (or a default when not a terminal)
+fn terminal_width() -> usize {
+    if !io::stdout().is_terminal() {
+        return DEFAULT_WIDTH;
+    }
+    terminal::size()
+        .map(|(cols, _)| cols as usize)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Leading spaces that center a line
+fn padding(line: &str, width: usize) -> String {
+    let len = line.chars().count();
+    " ".repeat(width.saturating_sub(len) / 2)
+}
@@ -0,0 +1,68 @@
+//! Quick-reply catalog for selling over DMs
+//!
+//! `ig catalog add SKU123 "Price is $40, ships in 2 days"` saves a reply
+//! under a shortcode; typing `#SKU123` in `ig chat` sends that text in its
+//! place. Codes are matched case-insensitively, and a `#word` that isn't in
+//! the catalog is sent as written, so ordinary hashtags still work. The
+//! catalog is kept in the data directory.
+
+use anyhow::Result;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use crate::store;
+
+/// Data file holding the catalog
+const CATALOG_FILE: &str = "catalog";
+
+/// Replies per shortcode (uppercase, without the `#`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Catalog {
+    pub by_code: BTreeMap<String, String>,
+}
+
+impl Catalog {
+    /// Load the catalog from disk
+    pub fn load() -> Result<Self> {
+        store::load(CATALOG_FILE)
+    }
+
+    /// Save the catalog to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(CATALOG_FILE, self)
+    }
+
+    /// The reply saved under `code` (with or without the `#`)
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.by_code.get(&normalize(code)).map(String::as_str)
+    }
+
+    /// `text` with every `#CODE` in the catalog replaced by its reply
+    pub fn expand(&self, text: &str) -> String {
+        static SHORTCODE: OnceLock<Regex> = OnceLock::new();
+        if self.by_code.is_empty() {
+            return text.to_string();
+        }
+        let shortcode = SHORTCODE.get_or_init(|| Regex::new(r"(^|[^\w#])#([\w-]+)").unwrap());
+        shortcode
+            .replace_all(text, |caps: &Captures| match self.get(&caps[2]) {
+                Some(reply) => format!("{}{}", &caps[1], reply),
+                None => caps[0].to_string(),
+            })
+            .into_owned()
+    }
+}
+
+/// How a shortcode is stored: without the `#`, uppercase
+pub fn normalize(code: &str) -> String {
+    code.trim().trim_start_matches('#').to_uppercase()
+}
+
+/// Whether `code` can be typed as `#code` (letters, digits, `_` and `-`)
+pub fn valid_code(code: &str) -> bool {
+    let code = code.trim().trim_start_matches('#');
+    !code.is_empty() && code.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
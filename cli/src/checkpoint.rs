@@ -0,0 +1,53 @@
+//! Checkpoints for long-running commands
+//!
+//! A command that works through a lot of pages (e.g. `ig export-all` on a
+//! years-long conversation) saves where it got to after each step. If the
+//! network drops or the command is interrupted, running it again loads the
+//! checkpoint and carries on from there; it's removed once the work is done.
+//! The state is whatever the command needs, as long as it serializes.
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Progress of one operation, kept in a JSON file
+pub struct Checkpoint {
+    path: PathBuf,
+}
+
+impl Checkpoint {
+    /// A checkpoint kept at `path`
+    pub fn at(path: impl AsRef<Path>) -> Self {
+        Self { path: path.as_ref().to_path_buf() }
+    }
+
+    /// The saved state, if there is one. A checkpoint that can't be read
+    /// (e.g. from an older version) is ignored, so the work starts over.
+    pub fn load<T: DeserializeOwned>(&self) -> Option<T> {
+        let data = fs::read_to_string(&self.path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    /// Save the state after a step
+    pub fn save<T: Serialize>(&self, state: &T) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        // Write to a temp file first so a crash never leaves a half-written checkpoint
+        let tmp = self.path.with_extension("tmp");
+        let data = serde_json::to_string(state)?;
+        fs::write(&tmp, data).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path).with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Remove the checkpoint once the operation is done
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
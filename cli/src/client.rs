@@ -1,35 +1,190 @@
 //! HTTP client for communicating with the Instagram DM server
 
 use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::multipart::{Form, Part};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
 
+use crate::config::Permission;
 use crate::crypto::encrypt_password;
+use crate::exclusions;
+use crate::http::{CountedRequest, Http, HttpStats};
 use crate::models::*;
+use crate::profile;
+use crate::reconcile::{reconcile_inbox, reconcile_thread};
+use crate::responses::CachedResponse;
 
 /// Default server URL
 const DEFAULT_SERVER_URL: &str = "http://localhost:8000";
 
+/// API version this CLI was built against
+pub const API_VERSION: u32 = 1;
+
+/// Header used to tell the server which API version the CLI speaks
+const API_VERSION_HEADER: &str = "X-Api-Version";
+
 /// Instagram DM API client
+///
+/// Cloning is cheap: clones share one connection pool, response cache and
+/// set of request counters.
+#[derive(Clone)]
 pub struct ApiClient {
-    client: Client,
+    http: Arc<Http>,
     base_url: String,
+    /// Whether to send conditional requests at all
+    conditional: bool,
+    /// What the server said it supports, asked once per run (None when the
+    /// server predates `/capabilities`)
+    capabilities: Arc<OnceCell<Option<Capabilities>>>,
 }
 
 impl ApiClient {
     /// Create a new API client
-    pub fn new(base_url: Option<&str>) -> Self {
-        Self {
-            client: Client::new(),
+    ///
+    /// Responses are requested gzip compressed (all the server offers) and
+    /// decompressed transparently.
+    pub fn new(base_url: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            http: Arc::new(Http::new(true)?),
             base_url: base_url.unwrap_or(DEFAULT_SERVER_URL).to_string(),
+            conditional: true,
+            capabilities: Arc::default(),
+        })
+    }
+
+    /// The server this client talks to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Request and connection counters (shared by clones)
+    pub fn stats(&self) -> &HttpStats {
+        &self.http.stats
+    }
+
+    /// Start a GET on the shared client, counted when sent
+    fn get(&self, url: &str) -> CountedRequest {
+        CountedRequest::new(self.http.client.get(url), Arc::clone(&self.http.stats))
+    }
+
+    /// Start a POST on the shared client, counted when sent
+    fn post(&self, url: &str) -> CountedRequest {
+        CountedRequest::new(self.http.client.post(url), Arc::clone(&self.http.stats))
+    }
+
+    /// Start a DELETE on the shared client, counted when sent
+    fn delete(&self, url: &str) -> CountedRequest {
+        CountedRequest::new(self.http.client.delete(url), Arc::clone(&self.http.stats))
+    }
+
+    /// The same client but always downloading full responses (so benchmarks
+    /// measure real payloads rather than 304s)
+    pub fn without_conditional_requests(mut self) -> Self {
+        self.conditional = false;
+        self
+    }
+
+    /// A client for the same server that never asks for compression
+    /// (to measure what compression saves)
+    pub fn without_compression(&self) -> Result<Self> {
+        Ok(Self {
+            http: Arc::new(Http::new(false)?),
+            base_url: self.base_url.clone(),
+            conditional: self.conditional,
+            capabilities: Arc::clone(&self.capabilities),
+        })
+    }
+
+    /// Size in bytes of a response as sent over the wire, and its
+    /// Content-Encoding, when asking for `accept_encoding`
+    ///
+    /// Only meaningful on a client from [`ApiClient::without_compression`],
+    /// which leaves the body undecoded.
+    pub async fn wire_size(&self, path: &str, accept_encoding: &str) -> Result<(usize, Option<String>)> {
+        let url = format!("{}{}", self.base_url, path);
+        let resp = self
+            .get(&url)
+            .header(reqwest::header::ACCEPT_ENCODING, accept_encoding)
+            .send()
+            .await
+            .context("Failed to connect to server")?;
+        let encoding = resp
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = resp.bytes().await.context("Failed to read response")?;
+        Ok((body.len(), encoding))
+    }
+
+    /// GET with conditional-request support
+    ///
+    /// Sends back the ETag / Last-Modified of the URL's previous response,
+    /// from this run or an earlier one (see [`crate::responses`]); when the
+    /// server answers 304 Not Modified the cached body is returned as a 200,
+    /// so fetching an unchanged endpoint downloads nothing. Responses that
+    /// aren't `cacheable` (excluded threads) are never kept.
+    async fn get_conditional(&self, url: &str, cacheable: bool) -> reqwest::Result<(StatusCode, Vec<u8>)> {
+        let mut request = self.get(url);
+        if !cacheable {
+            if let Ok(mut cache) = self.http.cache.lock() {
+                cache.remove(url);
+            }
+        }
+        if !self.conditional || !cacheable {
+            let resp = request.send().await?;
+            let status = resp.status();
+            return Ok((status, resp.bytes().await?));
+        }
+        if let Some(cached) = self.http.cache.lock().ok().as_mut().and_then(|c| c.get(url)) {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let resp = request.send().await?;
+        let status = resp.status();
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = self.http.cache.lock().ok().as_mut().and_then(|c| c.get(url)) {
+                self.http.stats.record_cache_hit();
+                return Ok((StatusCode::OK, cached.body.clone()));
+            }
+        }
+
+        let header = |name| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = resp.bytes().await?;
+
+        if let Ok(mut cache) = self.http.cache.lock() {
+            if status.is_success() && (etag.is_some() || last_modified.is_some()) {
+                cache.insert(url, CachedResponse { etag, last_modified, body: body.to_vec() });
+            } else {
+                cache.remove(url);
+            }
         }
+        Ok((status, body))
     }
 
     /// Check server health and authentication status
+    ///
+    /// Also acts as the version handshake: the CLI sends its API version and
+    /// the server replies with its own (see [`ApiCompatibility`]).
     pub async fn health(&self) -> Result<HealthResponse> {
         let url = format!("{}/health", self.base_url);
         let resp = self
-            .client
             .get(&url)
+            .header(API_VERSION_HEADER, API_VERSION)
             .send()
             .await
             .context("Failed to connect to server")?;
@@ -39,11 +194,45 @@ impl ApiClient {
             .context("Failed to parse health response")
     }
 
+    /// Optional features the server supports, or None if it predates
+    /// `/capabilities`. Fetched once and shared by clones.
+    pub async fn capabilities(&self) -> Result<Option<Capabilities>> {
+        let capabilities = self
+            .capabilities
+            .get_or_try_init(|| async {
+                let url = format!("{}/capabilities", self.base_url);
+                let resp = self.get(&url).send().await.context("Failed to connect to server")?;
+                if resp.status().as_u16() == 404 {
+                    return Ok(None);
+                }
+                resp.json().await.map(Some).context("Failed to parse capabilities")
+            })
+            .await?;
+        Ok(capabilities.clone())
+    }
+
+    /// Whether the server supports `feature`. Servers that predate
+    /// `/capabilities`, or that can't be asked, are given the benefit of the
+    /// doubt: the request itself then reports what went wrong.
+    pub async fn supports(&self, feature: Feature) -> bool {
+        match self.capabilities().await {
+            Ok(Some(capabilities)) => feature.supported_by(&capabilities),
+            Ok(None) | Err(_) => true,
+        }
+    }
+
+    /// Fail with "not supported by your server" unless it supports `feature`
+    async fn require(&self, feature: Feature) -> Result<()> {
+        if !self.supports(feature).await {
+            anyhow::bail!(feature.unsupported());
+        }
+        Ok(())
+    }
+
     /// Get the server's public key for password encryption
     pub async fn get_public_key(&self) -> Result<String> {
         let url = format!("{}/auth/public-key", self.base_url);
         let resp = self
-            .client
             .get(&url)
             .send()
             .await
@@ -57,8 +246,14 @@ impl ApiClient {
         Ok(key_resp.public_key)
     }
 
-    /// Login with encrypted password
-    pub async fn login(&self, username: &str, password: &str) -> Result<LoginResponse> {
+    /// Login with encrypted password, identifying as the given device
+    pub async fn login(
+        &self,
+        username: &str,
+        password: &str,
+        device: Option<DeviceInfo>,
+    ) -> Result<LoginResponse> {
+        profile::require(Permission::Manage)?;
         // First, get the server's public key
         let public_key = self.get_public_key().await?;
 
@@ -71,10 +266,10 @@ impl ApiClient {
             username: username.to_string(),
             password: None,
             encrypted_password: Some(encrypted_password),
+            device,
         };
 
         let resp = self
-            .client
             .post(&url)
             .json(&req)
             .send()
@@ -96,29 +291,181 @@ impl ApiClient {
         }
     }
 
-    /// Logout from Instagram
+    /// Start a QR code login, identifying as the given device
+    ///
+    /// Returns `None` if the server can't hand logins off to the app.
+    pub async fn start_qr_login(&self, device: Option<DeviceInfo>) -> Result<Option<QrLoginResponse>> {
+        profile::require(Permission::Manage)?;
+        if !self.supports(Feature::QrLogin).await {
+            return Ok(None);
+        }
+        let url = format!("{}/auth/qr", self.base_url);
+        let resp = self
+            .post(&url)
+            .json(&QrLoginRequest { device })
+            .send()
+            .await
+            .context("Failed to start QR login")?;
+
+        if matches!(resp.status().as_u16(), 404 | 501) {
+            return Ok(None);
+        }
+        resp.json()
+            .await
+            .map(Some)
+            .context("Failed to parse QR login response")
+    }
+
+    /// Check whether a QR code login has been approved
+    pub async fn qr_login_status(&self, token: &str) -> Result<QrLoginResponse> {
+        let url = format!("{}/auth/qr/{}", self.base_url, token);
+        let resp = self
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to check QR login")?;
+        resp.json()
+            .await
+            .context("Failed to parse QR login response")
+    }
+
+    /// Logout from Instagram, dropping the cached responses of this server
     pub async fn logout(&self) -> Result<()> {
+        profile::require(Permission::Manage)?;
         let url = format!("{}/auth/logout", self.base_url);
-        self.client
-            .post(&url)
+        self.post(&url)
             .send()
             .await
             .context("Failed to logout")?;
+        if let Ok(mut cache) = self.http.cache.lock() {
+            cache.retain(|url| !url.starts_with(&self.base_url));
+        }
         Ok(())
     }
 
+    /// List devices that have logged in through the server
+    ///
+    /// Returns `None` if the server has no device management.
+    pub async fn list_devices(&self) -> Result<Option<DevicesResponse>> {
+        let url = format!("{}/auth/devices", self.base_url);
+        let resp = self
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch devices")?;
+
+        if resp.status().as_u16() == 404 {
+            return Ok(None);
+        }
+        resp.json()
+            .await
+            .map(Some)
+            .context("Failed to parse devices response")
+    }
+
+    /// Forget a device (the server drops its saved session if it is current)
+    ///
+    /// Returns the remaining devices, or `None` if the server has no device
+    /// management.
+    pub async fn forget_device(&self, device_id: &str) -> Result<Option<DevicesResponse>> {
+        profile::require(Permission::Manage)?;
+        let url = format!("{}/auth/devices/{}", self.base_url, device_id);
+        let resp = self
+            .delete(&url)
+            .send()
+            .await
+            .context("Failed to forget device")?;
+
+        if resp.status().as_u16() == 404 {
+            return Ok(None);
+        }
+        resp.json()
+            .await
+            .map(Some)
+            .context("Failed to parse devices response")
+    }
+
     /// Get inbox (list of conversation threads)
     pub async fn get_inbox(&self, limit: u32) -> Result<InboxResponse> {
         let url = format!("{}/inbox?limit={}", self.base_url, limit);
+        let (status, body) = self
+            .get_conditional(&url, true)
+            .await
+            .context("Failed to fetch inbox")?;
+
+        if status.is_success() {
+            let mut response = serde_json::from_slice(&body).context("Failed to parse inbox response")?;
+            reconcile_inbox(self, &mut response).await;
+            Ok(response)
+        } else if status.as_u16() == 401 {
+            anyhow::bail!("Not authenticated. Please login first.")
+        } else {
+            anyhow::bail!("Failed to fetch inbox: {}", status)
+        }
+    }
+
+    /// Get a specific thread with messages
+    pub async fn get_thread(&self, thread_id: &str, limit: u32) -> Result<ThreadResponse> {
+        let url = format!("{}/thread/{}?limit={}", self.base_url, thread_id, limit);
+        let (status, body) = self
+            .get_conditional(&url, !exclusions::is_excluded(thread_id))
+            .await
+            .context("Failed to fetch thread")?;
+
+        if status.is_success() {
+            let mut response = serde_json::from_slice(&body).context("Failed to parse thread response")?;
+            reconcile_thread(self, &mut response).await;
+            Ok(response)
+        } else if status.as_u16() == 401 {
+            anyhow::bail!("Not authenticated. Please login first.")
+        } else {
+            anyhow::bail!("Failed to fetch thread: {}", status)
+        }
+    }
+
+    /// Get only the messages of a thread newer than `last_message_id`
+    ///
+    /// Returns `None` if the server predates this endpoint; callers fall
+    /// back to [`ApiClient::get_thread`].
+    pub async fn get_thread_since(
+        &self,
+        thread_id: &str,
+        last_message_id: &str,
+    ) -> Result<Option<ThreadResponse>> {
+        let url = format!("{}/thread/{}/since/{}", self.base_url, thread_id, last_message_id);
+        let (status, body) = self
+            .get_conditional(&url, !exclusions::is_excluded(thread_id))
+            .await
+            .context("Failed to fetch thread")?;
+
+        if status.is_success() {
+            let mut response = serde_json::from_slice(&body).context("Failed to parse thread response")?;
+            reconcile_thread(self, &mut response).await;
+            Ok(Some(response))
+        } else if status.as_u16() == 404 {
+            Ok(None)
+        } else if status.as_u16() == 401 {
+            anyhow::bail!("Not authenticated. Please login first.")
+        } else {
+            anyhow::bail!("Failed to fetch thread: {}", status)
+        }
+    }
+
+    /// Get one page of the inbox (pass `""` for the first page, then each
+    /// response's `next_cursor`)
+    pub async fn get_inbox_page(&self, cursor: &str) -> Result<InboxResponse> {
+        let url = format!("{}/inbox", self.base_url);
         let resp = self
-            .client
             .get(&url)
+            .query(&[("cursor", cursor)])
             .send()
             .await
             .context("Failed to fetch inbox")?;
 
         if resp.status().is_success() {
-            resp.json().await.context("Failed to parse inbox response")
+            let mut response = resp.json().await.context("Failed to parse inbox response")?;
+            reconcile_inbox(self, &mut response).await;
+            Ok(response)
         } else if resp.status().as_u16() == 401 {
             anyhow::bail!("Not authenticated. Please login first.")
         } else {
@@ -126,11 +473,37 @@ impl ApiClient {
         }
     }
 
-    /// Get a specific thread with messages
-    pub async fn get_thread(&self, thread_id: &str, limit: u32) -> Result<ThreadResponse> {
+    /// Get one page of a thread's messages, newest first (pass `""` for the
+    /// newest page, then each response's `next_cursor`)
+    pub async fn get_thread_page(
+        &self,
+        thread_id: &str,
+        limit: u32,
+        cursor: &str,
+    ) -> Result<ThreadResponse> {
+        let url = format!("{}/thread/{}", self.base_url, thread_id);
+        let resp = self
+            .get(&url)
+            .query(&[("limit", limit.to_string().as_str()), ("cursor", cursor)])
+            .send()
+            .await
+            .context("Failed to fetch thread")?;
+
+        if resp.status().is_success() {
+            let mut response = resp.json().await.context("Failed to parse thread response")?;
+            reconcile_thread(self, &mut response).await;
+            Ok(response)
+        } else if resp.status().as_u16() == 401 {
+            anyhow::bail!("Not authenticated. Please login first.")
+        } else {
+            anyhow::bail!("Failed to fetch thread: {}", resp.status())
+        }
+    }
+
+    /// Get a thread as untyped JSON (for inspecting fields the models don't cover)
+    pub async fn get_thread_raw(&self, thread_id: &str, limit: u32) -> Result<serde_json::Value> {
         let url = format!("{}/thread/{}?limit={}", self.base_url, thread_id, limit);
         let resp = self
-            .client
             .get(&url)
             .send()
             .await
@@ -147,13 +520,13 @@ impl ApiClient {
 
     /// Send a message to an existing thread
     pub async fn send_to_thread(&self, thread_id: &str, text: &str) -> Result<SendMessageResponse> {
+        profile::require(Permission::Send)?;
         let url = format!("{}/thread/{}/send", self.base_url, thread_id);
         let req = SendMessageRequest {
             text: text.to_string(),
         };
 
         let resp = self
-            .client
             .post(&url)
             .json(&req)
             .send()
@@ -171,15 +544,120 @@ impl ApiClient {
         }
     }
 
+    /// Mark a thread as read (the other side sees it as seen)
+    pub async fn mark_thread_seen(&self, thread_id: &str) -> Result<ThreadActionResponse> {
+        self.thread_action(thread_id, "seen", "mark thread as read").await
+    }
+
+    /// Mute notifications for a thread
+    pub async fn mute_thread(&self, thread_id: &str) -> Result<ThreadActionResponse> {
+        self.thread_action(thread_id, "mute", "mute thread").await
+    }
+
+    /// Hide a thread from the inbox until a new message arrives in it
+    pub async fn archive_thread(&self, thread_id: &str) -> Result<ThreadActionResponse> {
+        self.thread_action(thread_id, "archive", "archive thread").await
+    }
+
+    /// Set a group thread's title. Cached responses for the thread and the
+    /// inbox are dropped, so the next fetch shows the new title.
+    pub async fn rename_thread(&self, thread_id: &str, title: &str) -> Result<ThreadActionResponse> {
+        profile::require(Permission::Send)?;
+        let url = format!("{}/thread/{}/title", self.base_url, thread_id);
+        let req = RenameThreadRequest {
+            title: title.to_string(),
+        };
+        let resp = self
+            .post(&url)
+            .json(&req)
+            .send()
+            .await
+            .context("Failed to rename thread")?;
+
+        if resp.status().is_success() {
+            let response: ThreadActionResponse = resp.json().await.context("Failed to parse title response")?;
+            if response.success {
+                self.forget_cached(thread_id);
+            }
+            Ok(response)
+        } else if resp.status().as_u16() == 401 {
+            anyhow::bail!("Not authenticated. Please login first.")
+        } else if resp.status().as_u16() == 404 {
+            anyhow::bail!("Renaming conversations is not supported by your server (updating the server may add it)")
+        } else {
+            anyhow::bail!("Failed to rename thread: {}", resp.status())
+        }
+    }
+
+    /// Drop cached responses that may show a thread as it was
+    fn forget_cached(&self, thread_id: &str) {
+        let inbox = format!("{}/inbox", self.base_url);
+        let thread = format!("{}/thread/{}", self.base_url, thread_id);
+        if let Ok(mut cache) = self.http.cache.lock() {
+            let shows = |url: &str, prefix: &str| {
+                url.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with(['?', '/']))
+            };
+            cache.retain(|url| !shows(url, &inbox) && !shows(url, &thread));
+        }
+    }
+
+    /// React to a message with an emoji
+    pub async fn react(&self, thread_id: &str, message_id: &str, emoji: &str) -> Result<ThreadActionResponse> {
+        profile::require(Permission::Send)?;
+        self.require(Feature::Reactions).await?;
+        let url = format!("{}/thread/{}/react", self.base_url, thread_id);
+        let req = ReactRequest {
+            message_id: message_id.to_string(),
+            emoji: emoji.to_string(),
+        };
+        let resp = self
+            .post(&url)
+            .json(&req)
+            .send()
+            .await
+            .context("Failed to send reaction")?;
+
+        if resp.status().is_success() {
+            resp.json().await.context("Failed to parse react response")
+        } else if resp.status().as_u16() == 401 {
+            anyhow::bail!("Not authenticated. Please login first.")
+        } else if resp.status().as_u16() == 404 {
+            anyhow::bail!(Feature::Reactions.unsupported())
+        } else {
+            anyhow::bail!("Failed to send reaction: {}", resp.status())
+        }
+    }
+
+    /// POST `/thread/{id}/{action}`; `what` names it in errors
+    async fn thread_action(&self, thread_id: &str, action: &str, what: &str) -> Result<ThreadActionResponse> {
+        profile::require(Permission::Send)?;
+        let url = format!("{}/thread/{}/{}", self.base_url, thread_id, action);
+        let resp = self
+            .post(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to {}", what))?;
+
+        if resp.status().is_success() {
+            resp.json()
+                .await
+                .with_context(|| format!("Failed to parse {} response", action))
+        } else if resp.status().as_u16() == 401 {
+            anyhow::bail!("Not authenticated. Please login first.")
+        } else {
+            anyhow::bail!("Failed to {}: {}", what, resp.status())
+        }
+    }
+
     /// Send a message to a user by username
     pub async fn send_to_user(&self, username: &str, text: &str) -> Result<SendMessageResponse> {
+        profile::require(Permission::Send)?;
         let url = format!("{}/send/{}", self.base_url, username);
         let req = SendMessageRequest {
             text: text.to_string(),
         };
 
         let resp = self
-            .client
             .post(&url)
             .json(&req)
             .send()
@@ -197,11 +675,129 @@ impl ApiClient {
         }
     }
 
+    /// Send a photo, video or voice message to an existing thread, uploaded
+    /// as multipart form data (the server picks the kind by extension)
+    pub async fn upload_photo_to_thread(
+        &self,
+        thread_id: &str,
+        filename: &str,
+        data: Vec<u8>,
+    ) -> Result<SendMessageResponse> {
+        let url = format!("{}/thread/{}/photo", self.base_url, thread_id);
+        self.upload_media(&url, filename, data).await
+    }
+
+    /// Send a photo, video or voice message to a user by username, uploaded
+    /// as multipart form data (the file as is, rather than base64 in JSON)
+    pub async fn upload_photo_to_user(
+        &self,
+        username: &str,
+        filename: &str,
+        data: Vec<u8>,
+    ) -> Result<SendMessageResponse> {
+        let url = format!("{}/send/{}/photo", self.base_url, username);
+        self.upload_media(&url, filename, data).await
+    }
+
+    async fn upload_media(&self, url: &str, filename: &str, data: Vec<u8>) -> Result<SendMessageResponse> {
+        profile::require(Permission::Send)?;
+        self.require(Feature::Media).await?;
+        let form = Form::new().part("file", Part::bytes(data).file_name(filename.to_string()));
+
+        let resp = self
+            .post(url)
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to send media")?;
+
+        match resp.status().as_u16() {
+            200..=299 => resp.json().await.context("Failed to parse send response"),
+            401 => anyhow::bail!("Not authenticated. Please login first."),
+            404 => anyhow::bail!(Feature::Media.unsupported()),
+            _ => anyhow::bail!("Failed to send media: {}", resp.status()),
+        }
+    }
+
+    /// Which team member is answering which thread
+    pub async fn get_assignments(&self) -> Result<AssignmentsResponse> {
+        self.require(Feature::Assignments).await?;
+        let url = format!("{}/assignments", self.base_url);
+        let resp = self
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch assignments")?;
+
+        match resp.status().as_u16() {
+            200..=299 => resp.json().await.context("Failed to parse assignments response"),
+            401 => anyhow::bail!("Not authenticated. Please login first."),
+            404 => anyhow::bail!(Feature::Assignments.unsupported()),
+            _ => anyhow::bail!("Failed to fetch assignments: {}", resp.status()),
+        }
+    }
+
+    /// Assign a thread to a team member with an optional handoff note, or
+    /// unassign it when `assignee` is None; returns the updated list
+    pub async fn assign_thread(
+        &self,
+        thread_id: &str,
+        assignee: Option<&str>,
+        note: Option<&str>,
+        assigned_by: Option<&str>,
+    ) -> Result<AssignmentsResponse> {
+        profile::require(Permission::Send)?;
+        self.require(Feature::Assignments).await?;
+        let url = format!("{}/thread/{}/assign", self.base_url, thread_id);
+        let req = AssignRequest {
+            assignee: assignee.map(str::to_string),
+            note: note.map(str::to_string),
+            assigned_by: assigned_by.map(str::to_string),
+        };
+
+        let resp = self
+            .post(&url)
+            .json(&req)
+            .send()
+            .await
+            .context("Failed to assign thread")?;
+
+        match resp.status().as_u16() {
+            200..=299 => resp.json().await.context("Failed to parse assignments response"),
+            401 => anyhow::bail!("Not authenticated. Please login first."),
+            404 => anyhow::bail!(Feature::Assignments.unsupported()),
+            _ => anyhow::bail!("Failed to assign thread: {}", resp.status()),
+        }
+    }
+
+    /// Download the photo, video or voice clip at a message's `media_url`
+    ///
+    /// The body is read in chunks, calling `progress` with the bytes read so
+    /// far and the total size (when the CDN sends one) after each.
+    pub async fn download_media(&self, url: &str, mut progress: impl FnMut(u64, Option<u64>)) -> Result<Vec<u8>> {
+        let mut resp = self
+            .get(url)
+            .send()
+            .await
+            .context("Failed to download the media")?;
+        if !resp.status().is_success() {
+            anyhow::bail!("Media download returned {}", resp.status());
+        }
+
+        let total = resp.content_length();
+        let mut data = Vec::with_capacity(total.unwrap_or_default() as usize);
+        progress(0, total);
+        while let Some(chunk) = resp.chunk().await.context("Failed to download the media")? {
+            data.extend_from_slice(&chunk);
+            progress(data.len() as u64, total);
+        }
+        Ok(data)
+    }
+
     /// Search for a user by username
     pub async fn search_user(&self, username: &str) -> Result<SearchUserResponse> {
         let url = format!("{}/user/{}", self.base_url, username);
         let resp = self
-            .client
             .get(&url)
             .send()
             .await
@@ -219,4 +815,123 @@ impl ApiClient {
             anyhow::bail!("Failed to search user: {}", resp.status())
         }
     }
+
+    /// Look up a user by ID
+    ///
+    /// Returns `None` if the user doesn't exist or the server predates this
+    /// endpoint.
+    pub async fn get_user_by_pk(&self, user_pk: &str) -> Result<Option<UserDetails>> {
+        let url = format!("{}/user/id/{}", self.base_url, user_pk);
+        let resp = self
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to look up user")?;
+
+        if resp.status().is_success() {
+            let response: SearchUserResponse = resp
+                .json()
+                .await
+                .context("Failed to parse user response")?;
+            Ok(response.user)
+        } else if resp.status().as_u16() == 404 {
+            Ok(None)
+        } else if resp.status().as_u16() == 401 {
+            anyhow::bail!("Not authenticated. Please login first.")
+        } else {
+            anyhow::bail!("Failed to look up user: {}", resp.status())
+        }
+    }
+}
+
+/// Optional server features, checked against [`Capabilities`] before use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Reactions,
+    Media,
+    Presence,
+    Websocket,
+    Assignments,
+    QrLogin,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 6] = [
+        Feature::Reactions,
+        Feature::Media,
+        Feature::Presence,
+        Feature::Websocket,
+        Feature::Assignments,
+        Feature::QrLogin,
+    ];
+
+    /// Short name, as in the `/capabilities` response
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Reactions => "reactions",
+            Feature::Media => "media",
+            Feature::Presence => "presence",
+            Feature::Websocket => "websocket",
+            Feature::Assignments => "assignments",
+            Feature::QrLogin => "qr_login",
+        }
+    }
+
+    pub fn supported_by(self, capabilities: &Capabilities) -> bool {
+        match self {
+            Feature::Reactions => capabilities.reactions,
+            Feature::Media => capabilities.media,
+            Feature::Presence => capabilities.presence,
+            Feature::Websocket => capabilities.websocket,
+            Feature::Assignments => capabilities.assignments,
+            Feature::QrLogin => capabilities.qr_login,
+        }
+    }
+
+    /// Error message for a server without this feature
+    pub fn unsupported(self) -> String {
+        let what = match self {
+            Feature::Reactions => "Reacting to messages",
+            Feature::Media => "Sending photos, videos and voice messages",
+            Feature::Presence => "Showing who's online",
+            Feature::Websocket => "Live updates over a websocket",
+            Feature::Assignments => "Assigning threads to team members",
+            Feature::QrLogin => "Logging in with a QR code",
+        };
+        format!("{} is not supported by your server (updating the server may add it)", what)
+    }
+}
+
+/// How the server's API version relates to the CLI's
+#[derive(Debug, PartialEq, Eq)]
+pub enum ApiCompatibility {
+    /// Same API version
+    Compatible,
+    /// Server speaks a newer API, but still supports this CLI
+    ServerNewer(u32),
+    /// Server speaks an older API
+    ServerOlder(u32),
+    /// Server no longer supports this CLI's API version
+    CliTooOld(u32),
+    /// Server did not report a version (predates versioning)
+    Unknown,
+}
+
+impl ApiCompatibility {
+    /// Compare the versions reported in a health response against [`API_VERSION`]
+    pub fn check(health: &HealthResponse) -> Self {
+        let Some(server) = health.api_version else {
+            return Self::Unknown;
+        };
+
+        if health.min_client_api_version.is_some_and(|min| API_VERSION < min) {
+            Self::CliTooOld(server)
+        } else if server > API_VERSION {
+            Self::ServerNewer(server)
+        } else if server < API_VERSION {
+            Self::ServerOlder(server)
+        } else {
+            Self::Compatible
+        }
+    }
 }
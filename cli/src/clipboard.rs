@@ -0,0 +1,36 @@
+//! System clipboard access for pasting into chat
+
+use anyhow::{Context, Result};
+use arboard::Clipboard;
+use image::{ImageFormat, RgbaImage};
+use std::io::Cursor;
+
+/// What the clipboard holds
+pub enum Paste {
+    /// An image, encoded as PNG
+    Image(Vec<u8>),
+    Text(String),
+}
+
+/// Read the clipboard, preferring an image over text. `None` if it's empty
+/// or holds something else.
+pub fn paste() -> Result<Option<Paste>> {
+    let mut clipboard = Clipboard::new().context("Clipboard is not available")?;
+
+    if let Ok(image) = clipboard.get_image() {
+        let width = u32::try_from(image.width).context("Clipboard image is too large")?;
+        let height = u32::try_from(image.height).context("Clipboard image is too large")?;
+        let rgba = RgbaImage::from_raw(width, height, image.bytes.into_owned())
+            .context("Clipboard image data is malformed")?;
+        let mut png = Cursor::new(Vec::new());
+        rgba.write_to(&mut png, ImageFormat::Png)
+            .context("Failed to encode the clipboard image")?;
+        return Ok(Some(Paste::Image(png.into_inner())));
+    }
+
+    Ok(clipboard
+        .get_text()
+        .ok()
+        .filter(|t| !t.is_empty())
+        .map(Paste::Text))
+}
@@ -2,9 +2,13 @@
 //!
 //! Instagram's gradient: Purple → Pink → Orange → Yellow
 //! This module provides color constants and helper functions for consistent styling.
+//! Alternative palettes (high-contrast, colorblind-safe) can be selected with `--theme`.
 
 #![allow(dead_code)]
 
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
 use colored::{ColoredString, Colorize};
 
 /// Instagram Brand Colors (RGB values)
@@ -26,49 +30,189 @@ pub mod instagram {
     pub const DARK_GRAY: (u8, u8, u8) = (38, 38, 38);
 }
 
+/// Selectable color themes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ThemeName {
+    /// Instagram brand colors
+    #[default]
+    Default,
+    /// Bright, saturated colors for low-vision users and dim terminals
+    HighContrast,
+    /// Okabe-Ito palette, distinguishable with common color vision deficiencies
+    Colorblind,
+}
+
+/// The color slots every theme fills in
+///
+/// Slot names follow the Instagram palette; other themes map their own colors
+/// onto the same roles (e.g. `pink` is always used for usernames).
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub purple: (u8, u8, u8),
+    pub pink: (u8, u8, u8),
+    pub orange: (u8, u8, u8),
+    pub yellow: (u8, u8, u8),
+    pub blue: (u8, u8, u8),
+    pub red: (u8, u8, u8),
+    pub green: (u8, u8, u8),
+    pub gray: (u8, u8, u8),
+    /// Add a text label to unread items, on top of the colored dot
+    pub label_unread: bool,
+}
+
+impl Palette {
+    const DEFAULT: Palette = Palette {
+        purple: instagram::PURPLE,
+        pink: instagram::PINK,
+        orange: instagram::ORANGE,
+        yellow: instagram::YELLOW,
+        blue: instagram::BLUE,
+        red: instagram::RED,
+        green: (46, 204, 113),
+        gray: instagram::LIGHT_GRAY,
+        label_unread: false,
+    };
+
+    const HIGH_CONTRAST: Palette = Palette {
+        purple: (255, 255, 255),
+        pink: (255, 255, 0),
+        orange: (0, 255, 255),
+        yellow: (255, 215, 0),
+        blue: (0, 191, 255),
+        red: (255, 85, 85),
+        green: (0, 255, 0),
+        gray: (200, 200, 200),
+        label_unread: true,
+    };
+
+    const COLORBLIND: Palette = Palette {
+        purple: (204, 121, 167), // Reddish purple
+        pink: (230, 159, 0),     // Orange
+        orange: (86, 180, 233),  // Sky blue
+        yellow: (240, 228, 66),  // Yellow
+        blue: (0, 114, 178),     // Blue
+        red: (213, 94, 0),       // Vermillion
+        green: (0, 158, 115),    // Bluish green
+        gray: (160, 160, 160),
+        label_unread: true,
+    };
+
+    /// Colors used for gradients (banner, spinner)
+    pub fn gradient(&self) -> [(u8, u8, u8); 4] {
+        [self.purple, self.pink, self.orange, self.yellow]
+    }
+}
+
+impl From<ThemeName> for Palette {
+    fn from(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Palette::DEFAULT,
+            ThemeName::HighContrast => Palette::HIGH_CONTRAST,
+            ThemeName::Colorblind => Palette::COLORBLIND,
+        }
+    }
+}
+
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+/// Select the theme for this process (call once at startup)
+pub fn set_theme(name: ThemeName) {
+    let _ = PALETTE.set(name.into());
+}
+
+/// The active palette
+pub fn palette() -> &'static Palette {
+    PALETTE.get_or_init(|| Palette::DEFAULT)
+}
+
+/// A palette color picked by hashing `key`, so the same key always gets
+/// the same color
+pub fn hashed_color(key: &str) -> (u8, u8, u8) {
+    let p = palette();
+    let colors = [p.purple, p.pink, p.orange, p.yellow, p.blue, p.green];
+    let hash = key
+        .bytes()
+        .fold(0usize, |acc, b| acc.wrapping_mul(31).wrapping_add(b as usize));
+    colors[hash % colors.len()]
+}
+
+/// Disable all color output for this process
+pub fn disable_colors() {
+    colored::control::set_override(false);
+}
+
+/// Whether color output is enabled (respects `--no-color`, NO_COLOR and non-tty output)
+pub fn colors_enabled() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+/// Raw ANSI foreground escape for a color, or nothing when colors are disabled
+pub fn fg_escape((r, g, b): (u8, u8, u8)) -> String {
+    if colors_enabled() {
+        format!("\x1b[38;2;{};{};{}m", r, g, b)
+    } else {
+        String::new()
+    }
+}
+
+/// Raw ANSI reset escape, or nothing when colors are disabled
+pub fn reset_escape() -> &'static str {
+    if colors_enabled() {
+        "\x1b[0m"
+    } else {
+        ""
+    }
+}
+
 /// Color theme for CLI elements
 pub struct Theme;
 
 impl Theme {
     /// Apply Instagram purple to text
     pub fn purple(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::PURPLE;
+        let (r, g, b) = palette().purple;
         text.truecolor(r, g, b)
     }
 
     /// Apply Instagram pink to text
     pub fn pink(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::PINK;
+        let (r, g, b) = palette().pink;
         text.truecolor(r, g, b)
     }
 
     /// Apply Instagram orange to text
     pub fn orange(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::ORANGE;
+        let (r, g, b) = palette().orange;
         text.truecolor(r, g, b)
     }
 
     /// Apply Instagram yellow to text
     pub fn yellow(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::YELLOW;
+        let (r, g, b) = palette().yellow;
         text.truecolor(r, g, b)
     }
 
     /// Apply Instagram blue to text
     pub fn blue(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::BLUE;
+        let (r, g, b) = palette().blue;
         text.truecolor(r, g, b)
     }
 
     /// Apply Instagram red (for errors/alerts)
     pub fn red(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::RED;
+        let (r, g, b) = palette().red;
+        text.truecolor(r, g, b)
+    }
+
+    /// Apply green to text
+    pub fn green(text: &str) -> ColoredString {
+        let (r, g, b) = palette().green;
         text.truecolor(r, g, b)
     }
 
     /// Dimmed/muted text
     pub fn muted(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::LIGHT_GRAY;
+        let (r, g, b) = palette().gray;
         text.truecolor(r, g, b)
     }
 
@@ -76,141 +220,126 @@ impl Theme {
 
     /// Success messages
     pub fn success(text: &str) -> ColoredString {
-        text.truecolor(46, 204, 113).bold() // Green
+        let (r, g, b) = palette().green;
+        text.truecolor(r, g, b).bold()
     }
 
     /// Error messages
     pub fn error(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::RED;
+        let (r, g, b) = palette().red;
         text.truecolor(r, g, b).bold()
     }
 
     /// Warning messages
     pub fn warning(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::YELLOW;
+        let (r, g, b) = palette().yellow;
         text.truecolor(r, g, b)
     }
 
     /// Usernames (@mentions)
     pub fn username(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::PINK;
+        let (r, g, b) = palette().pink;
         text.truecolor(r, g, b).bold()
     }
 
     /// Headers and titles
     pub fn header(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::PURPLE;
+        let (r, g, b) = palette().purple;
         text.truecolor(r, g, b).bold()
     }
 
     /// Accent/highlight color
     pub fn accent(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::ORANGE;
+        let (r, g, b) = palette().orange;
         text.truecolor(r, g, b)
     }
 
     /// Unread indicator
     pub fn unread(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::BLUE;
+        let (r, g, b) = palette().blue;
         text.truecolor(r, g, b).bold()
     }
 
     /// Timestamps (default gray)
     pub fn timestamp(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::LIGHT_GRAY;
+        let (r, g, b) = palette().gray;
         text.truecolor(r, g, b)
     }
 
     /// Timestamp - just now (green)
     pub fn timestamp_now(text: &str) -> ColoredString {
-        text.truecolor(46, 204, 113) // Green
+        let (r, g, b) = palette().green;
+        text.truecolor(r, g, b)
     }
 
     /// Timestamp - minutes ago (blue)
     pub fn timestamp_minutes(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::BLUE;
+        let (r, g, b) = palette().blue;
         text.truecolor(r, g, b)
     }
 
     /// Timestamp - hours ago (orange)
     pub fn timestamp_hours(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::ORANGE;
+        let (r, g, b) = palette().orange;
         text.truecolor(r, g, b)
     }
 
     /// Timestamp - days ago (gray/muted)
     pub fn timestamp_days(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::LIGHT_GRAY;
+        let (r, g, b) = palette().gray;
         text.truecolor(r, g, b)
     }
 
     /// Separator lines
     pub fn separator(width: usize) -> ColoredString {
-        let (r, g, b) = instagram::LIGHT_GRAY;
+        let (r, g, b) = palette().gray;
         "━".repeat(width).truecolor(r, g, b)
     }
 
     /// Check mark (success indicator)
     pub fn check() -> ColoredString {
-        "✓".truecolor(46, 204, 113).bold()
+        let (r, g, b) = palette().green;
+        "✓".truecolor(r, g, b).bold()
     }
 
     /// X mark (error indicator)
     pub fn cross() -> ColoredString {
-        let (r, g, b) = instagram::RED;
+        let (r, g, b) = palette().red;
         "✗".truecolor(r, g, b).bold()
     }
 
     /// Warning indicator
     pub fn warn_icon() -> ColoredString {
-        let (r, g, b) = instagram::YELLOW;
+        let (r, g, b) = palette().yellow;
         "⚠".truecolor(r, g, b).bold()
     }
 
     /// Unread dot indicator
     pub fn unread_dot() -> ColoredString {
-        let (r, g, b) = instagram::BLUE;
+        let (r, g, b) = palette().blue;
         "●".truecolor(r, g, b)
     }
 
+    /// Unread marker: the dot, plus a `[NEW]` label in accessibility themes
+    /// so unread state never depends on color alone
+    pub fn unread_marker() -> String {
+        if palette().label_unread || !colors_enabled() {
+            format!("{} {}", Self::unread_dot(), Self::unread("[NEW]"))
+        } else {
+            Self::unread_dot().to_string()
+        }
+    }
+
     /// Input prompt
     pub fn prompt(text: &str) -> ColoredString {
-        let (r, g, b) = instagram::PINK;
+        let (r, g, b) = palette().pink;
         text.truecolor(r, g, b).bold()
     }
 }
 
-/// Print the Instagram-gradient banner
-pub fn print_gradient_banner() {
-    // Each line gets a different color from the gradient
-    let lines = [
-        ("    ╔══════════════════════════════════════════╗", instagram::PURPLE),
-        ("    ║                                          ║", instagram::PURPLE),
-        ("    ║   ▀█▀ █▀▀   █▀▄ █▀█▀█   █▀▀ █   ▀█▀      ║", instagram::PINK),
-        ("    ║    █  █ █   █ █ █ ▀ █   █   █    █       ║", instagram::PINK),
-        ("    ║   ▀▀▀ ▀▀▀   ▀▀  ▀   ▀   ▀▀▀ ▀▀▀ ▀▀▀      ║", instagram::ORANGE),
-        ("    ║                                          ║", instagram::ORANGE),
-        ("    ║       Instagram Direct Messages          ║", instagram::YELLOW),
-        ("    ║            from your terminal            ║", instagram::YELLOW),
-        ("    ║                                          ║", instagram::ORANGE),
-        ("    ╚══════════════════════════════════════════╝", instagram::PURPLE),
-    ];
-
-    println!();
-    for (line, (r, g, b)) in lines {
-        println!("{}", line.truecolor(r, g, b));
-    }
-    println!();
-}
-
 /// Print a gradient text effect (horizontal)
 pub fn gradient_text(text: &str) -> String {
-    let colors = [
-        instagram::PURPLE,
-        instagram::PINK,
-        instagram::ORANGE,
-        instagram::YELLOW,
-    ];
+    let colors = palette().gradient();
 
     let chars: Vec<char> = text.chars().collect();
     let len = chars.len();
@@ -219,6 +348,10 @@ pub fn gradient_text(text: &str) -> String {
         return String::new();
     }
 
+    if !colors_enabled() {
+        return text.to_string();
+    }
+
     let mut result = String::new();
     for (i, ch) in chars.iter().enumerate() {
         // Calculate which color to use based on position
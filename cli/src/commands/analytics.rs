@@ -0,0 +1,103 @@
+//! Local command usage (`ig usage`)
+//!
+//! Lists the command lines run most and suggests an `[alias]` for the ones
+//! typed often. The tallies are kept by [`crate::analytics`].
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::analytics::CommandUsage;
+use crate::colors::Theme;
+use crate::config::Config;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::util::format_time_ago;
+
+/// Command lines listed
+const TOP_COMMANDS: usize = 15;
+
+/// Aliases suggested at most
+const TOP_SUGGESTIONS: usize = 3;
+
+#[derive(Serialize)]
+struct UsageRow<'a> {
+    command: &'a str,
+    runs: usize,
+    last: DateTime<Local>,
+}
+
+#[derive(Serialize)]
+struct AliasSuggestion<'a> {
+    alias: String,
+    command: &'a str,
+}
+
+#[derive(Serialize)]
+struct UsageReport<'a> {
+    tracking: bool,
+    commands: Vec<UsageRow<'a>>,
+    suggestions: Vec<AliasSuggestion<'a>>,
+}
+
+/// Show the commands run most, with alias suggestions (`ig usage`)
+pub fn show_command_usage(output: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    let usage = CommandUsage::load()?;
+    let report = UsageReport {
+        tracking: config.usage.track,
+        commands: usage
+            .ranked()
+            .into_iter()
+            .map(|(command, tally)| UsageRow { command, runs: tally.runs, last: tally.last })
+            .collect(),
+        suggestions: usage
+            .suggestions(&config.alias)
+            .into_iter()
+            .take(TOP_SUGGESTIONS)
+            .map(|(alias, command)| AliasSuggestion { alias, command })
+            .collect(),
+    };
+
+    if output == OutputFormat::Json {
+        return print_json(&report);
+    }
+
+    if !report.tracking {
+        println!("{}", Theme::muted("Command tracking is off ([usage] track = false in config.toml)."));
+    }
+    if report.commands.is_empty() {
+        println!("{}", Theme::muted("No commands recorded yet."));
+        return Ok(());
+    }
+
+    let total: usize = report.commands.iter().map(|c| c.runs).sum();
+    println!();
+    println!("{}", Theme::header("Command usage"));
+    println!("{}", Theme::separator(50));
+    let mut table = Table::new(&["Command", "Runs", "Last run"]);
+    for row in report.commands.iter().take(TOP_COMMANDS) {
+        table.row([
+            format!("ig {}", row.command),
+            row.runs.to_string(),
+            format_time_ago(&row.last.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        ]);
+    }
+    table.print();
+    println!(
+        "{}",
+        Theme::muted(&format!("{} runs of {} different command lines", total, report.commands.len()))
+    );
+
+    if !report.suggestions.is_empty() {
+        println!();
+        println!("{}", Theme::muted("Suggestions:"));
+        for suggestion in &report.suggestions {
+            println!(
+                "  You often run {}; alias it under [alias] in config.toml: {}",
+                Theme::accent(&format!("ig {}", suggestion.command)),
+                Theme::accent(&format!("{} = \"{}\"", suggestion.alias, suggestion.command))
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,472 @@
+//! Full account backup (`ig export-all`)
+//!
+//! Writes every conversation, its whole history and the photos and videos
+//! in it to a directory:
+//!
+//! ```text
+//! archive/
+//! ├── manifest.json            # what was exported, with SHA-256 hashes
+//! ├── threads/<thread id>.json # (.jsonl with --format jsonl)
+//! └── media/<thread id>/<message id>.jpg
+//! ```
+//!
+//! The manifest is saved after every conversation, so an interrupted run
+//! picks up where it stopped: conversations whose file is intact and that
+//! have had no new messages since are skipped, and so are media files that
+//! still match their hash. Within a conversation, each page of history is
+//! appended to a `.partial` file next to a [`Checkpoint`] of the paging
+//! cursor, so a long history resumes at the page it stopped on.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use rsa::sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::checkpoint::Checkpoint;
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::ocr::download;
+use crate::commands::pages::{fetch_inbox, PAGE_SIZE};
+use crate::exclusions;
+use crate::models::{Message, Thread, User};
+use crate::spinner::create_progress;
+use crate::util::format_count;
+
+/// Name of the manifest in the archive directory
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Paging checkpoint of the conversation being fetched
+const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+/// How conversations are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    /// One JSON document per conversation
+    Json,
+    /// One message per line
+    Jsonl,
+}
+
+impl ArchiveFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::Json => "json",
+            ArchiveFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// `manifest.json`: everything in the archive
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    created_at: Option<DateTime<Local>>,
+    updated_at: Option<DateTime<Local>>,
+    format: Option<ArchiveFormat>,
+    /// Exported conversations by thread ID
+    threads: BTreeMap<String, ThreadEntry>,
+}
+
+/// An exported conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ThreadEntry {
+    title: String,
+    participants: Vec<String>,
+    /// Latest message when exported, to tell whether there's anything new
+    last_timestamp: Option<String>,
+    messages: usize,
+    /// Path of the conversation's file, relative to the archive
+    file: String,
+    sha256: String,
+    media: Vec<MediaEntry>,
+    /// False while any media is missing; the conversation is then exported
+    /// again on the next run
+    complete: bool,
+}
+
+/// A photo or video from a conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MediaEntry {
+    message_id: String,
+    url: String,
+    /// Path relative to the archive; None when the download failed
+    file: Option<String>,
+    sha256: Option<String>,
+    bytes: Option<u64>,
+    error: Option<String>,
+}
+
+/// How far the history of a conversation has been fetched
+#[derive(Debug, Serialize, Deserialize)]
+struct Paging {
+    thread_id: String,
+    /// Cursor of the next page
+    cursor: String,
+    /// Messages saved to the `.partial` file so far
+    fetched: usize,
+}
+
+/// A conversation's file with `--format json`
+#[derive(Serialize)]
+struct ThreadDocument<'a> {
+    thread_id: &'a str,
+    title: &'a str,
+    participants: &'a [User],
+    exported_at: DateTime<Local>,
+    /// Oldest first
+    messages: &'a [Message],
+}
+
+impl Manifest {
+    fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn save(&self, dir: &Path) -> Result<()> {
+        write_file(&dir.join(MANIFEST_FILE), serde_json::to_string_pretty(self)?.as_bytes())
+    }
+
+    /// Whether `thread` is already in the archive, intact and up to date
+    fn has_current(&self, dir: &Path, thread: &Thread) -> bool {
+        self.threads.get(&thread.id).is_some_and(|entry| {
+            entry.complete
+                && entry.last_timestamp == thread.last_message_timestamp
+                && hash_file(&dir.join(&entry.file)).as_ref() == Some(&entry.sha256)
+        })
+    }
+}
+
+/// Export every conversation to `out` (`ig export-all`)
+pub async fn export_all(client: &ApiClient, out: &Path, format: ArchiveFormat) -> Result<()> {
+    fs::create_dir_all(out).with_context(|| format!("Failed to create {}", out.display()))?;
+    let mut manifest = Manifest::load(out)?;
+    match manifest.format {
+        Some(existing) if existing != format => anyhow::bail!(
+            "{} holds a {} archive; pass --format {} to resume it, or pick another directory",
+            out.display(),
+            existing.extension(),
+            existing.extension()
+        ),
+        _ => manifest.format = Some(format),
+    }
+    manifest.created_at.get_or_insert_with(Local::now);
+
+    let response = fetch_inbox(client, None).await?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to fetch inbox".to_string()));
+    }
+    let excluded = exclusions::excluded_ids();
+    let (threads, skipped): (Vec<Thread>, Vec<Thread>) = response
+        .threads
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|t| !excluded.contains(&t.id));
+
+    println!(
+        "{}",
+        Theme::header(&format!("Exporting {} conversations to {}", threads.len(), out.display()))
+    );
+    let (mut exported, mut current, mut incomplete, mut failed) = (0, 0, 0, 0);
+    for (i, thread) in threads.iter().enumerate() {
+        let position = Theme::muted(&format!("[{}/{}]", i + 1, threads.len()));
+        let title = title(thread);
+        if manifest.has_current(out, thread) {
+            println!("{} {} {}", position, title, Theme::muted("up to date"));
+            current += 1;
+            continue;
+        }
+
+        let previous = manifest.threads.get(&thread.id).cloned();
+        match export_thread(client, out, format, thread, previous.as_ref()).await {
+            Ok(entry) => {
+                let missing = entry.media.iter().filter(|m| m.file.is_none()).count();
+                let mut summary = format!(
+                    "{} messages, {} media",
+                    format_count(entry.messages as u64),
+                    entry.media.len() - missing
+                );
+                if missing > 0 {
+                    summary.push_str(&format!(", {} failed", missing));
+                    incomplete += 1;
+                }
+                println!("{} {} {}", position, title, Theme::muted(&summary));
+                manifest.threads.insert(thread.id.clone(), entry);
+                exported += 1;
+            }
+            Err(e) => {
+                println!("{} {} {}", position, title, Theme::error(&format!("{:#}", e)));
+                failed += 1;
+            }
+        }
+        manifest.updated_at = Some(Local::now());
+        manifest.save(out)?;
+    }
+    manifest.updated_at = Some(Local::now());
+    manifest.save(out)?;
+
+    println!();
+    let summary = format!("Exported {}, {} already up to date", exported, current);
+    if incomplete + failed > 0 {
+        let summary = format!("{}; {} incomplete, {} failed (run again to retry)", summary, incomplete, failed);
+        println!("{} {}", Theme::warn_icon(), Theme::warning(&summary));
+    } else {
+        println!("{} {}", Theme::check(), Theme::success(&summary));
+    }
+    if !skipped.is_empty() {
+        println!(
+            "{}",
+            Theme::muted(&format!(
+                "{} excluded conversation(s) left out; `ig sync include` adds them back.",
+                skipped.len()
+            ))
+        );
+    }
+    Ok(())
+}
+
+/// Write one conversation and its media, reusing media files from the
+/// previous export that still match their hash
+async fn export_thread(
+    client: &ApiClient,
+    out: &Path,
+    format: ArchiveFormat,
+    thread: &Thread,
+    previous: Option<&ThreadEntry>,
+) -> Result<ThreadEntry> {
+    let mut messages = fetch_history(client, out, &thread.id).await?;
+    messages.reverse();
+
+    let title = title(thread);
+    let contents = match format {
+        ArchiveFormat::Json => serde_json::to_vec_pretty(&ThreadDocument {
+            thread_id: &thread.id,
+            title: &title,
+            participants: &thread.users,
+            exported_at: Local::now(),
+            messages: &messages,
+        })?,
+        ArchiveFormat::Jsonl => jsonl(&messages)?,
+    };
+    let file = format!("threads/{}.{}", file_safe(&thread.id), format.extension());
+    write_file(&out.join(&file), &contents)?;
+
+    let mut media = Vec::new();
+    for message in &messages {
+        let Some(url) = message.media_url.as_deref().filter(|u| !u.is_empty()) else {
+            continue;
+        };
+        let kept = previous.and_then(|p| p.media.iter().find(|m| m.message_id == message.id));
+        media.push(save_media(out, &thread.id, message, url, kept).await);
+    }
+
+    Checkpoint::at(out.join(CHECKPOINT_FILE)).clear()?;
+    let _ = fs::remove_file(partial_path(out, &thread.id));
+
+    Ok(ThreadEntry {
+        title,
+        participants: thread.users.iter().map(|u| u.username.clone()).collect(),
+        last_timestamp: thread.last_message_timestamp.clone(),
+        messages: messages.len(),
+        file,
+        sha256: sha256(&contents),
+        complete: media.iter().all(|m| m.file.is_some()),
+        media,
+    })
+}
+
+/// A conversation's whole history, newest first, continuing from the
+/// checkpoint when the last run stopped partway through it
+async fn fetch_history(client: &ApiClient, out: &Path, thread_id: &str) -> Result<Vec<Message>> {
+    let checkpoint = Checkpoint::at(out.join(CHECKPOINT_FILE));
+    let partial = partial_path(out, thread_id);
+    let (mut messages, mut cursor) = match checkpoint.load::<Paging>() {
+        Some(paging) if paging.thread_id == thread_id => match read_partial(&partial, paging.fetched) {
+            Some(messages) => (messages, paging.cursor),
+            None => (Vec::new(), String::new()),
+        },
+        _ => (Vec::new(), String::new()),
+    };
+    // Start the file over when not resuming, or rewrite it without lines
+    // saved after the checkpoint
+    write_partial(&partial, &messages)?;
+
+    let mut progress = create_progress("Fetching messages", "messages", None);
+    progress.page_done(messages.len() as u64);
+    loop {
+        let response = match client.get_thread_page(thread_id, PAGE_SIZE, &cursor).await {
+            Ok(response) if response.success => response,
+            Ok(response) => {
+                progress.finish_and_clear();
+                anyhow::bail!(response.error.unwrap_or("Failed to fetch conversation".to_string()));
+            }
+            Err(e) => {
+                progress.finish_and_clear();
+                return Err(e);
+            }
+        };
+        let Some(page) = response.thread else {
+            break;
+        };
+        let page = page.messages.unwrap_or_default();
+        let fetched = page.len();
+        progress.page_done(fetched as u64);
+        append_partial(&partial, &page)?;
+        messages.extend(page);
+
+        match response.next_cursor {
+            Some(next) if fetched > 0 => {
+                cursor = next;
+                let fetched = messages.len();
+                checkpoint.save(&Paging { thread_id: thread_id.to_string(), cursor: cursor.clone(), fetched })?;
+            }
+            _ => break,
+        }
+    }
+    progress.finish_and_clear();
+    Ok(messages)
+}
+
+/// Where the pages of a conversation being fetched are kept
+fn partial_path(out: &Path, thread_id: &str) -> PathBuf {
+    out.join(format!("threads/{}.partial", file_safe(thread_id)))
+}
+
+/// The first `count` messages saved to a `.partial` file; None if it has
+/// fewer (or can't be read), in which case the conversation starts over
+fn read_partial(path: &Path, count: usize) -> Option<Vec<Message>> {
+    let data = fs::read_to_string(path).ok()?;
+    let messages: Vec<Message> = data
+        .lines()
+        .take(count)
+        .map(serde_json::from_str)
+        .collect::<std::result::Result<_, _>>()
+        .ok()?;
+    (messages.len() == count).then_some(messages)
+}
+
+fn write_partial(path: &Path, messages: &[Message]) -> Result<()> {
+    write_file(path, &jsonl(messages)?)
+}
+
+fn append_partial(path: &Path, messages: &[Message]) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(&jsonl(messages)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Messages as JSON lines
+fn jsonl(messages: &[Message]) -> Result<Vec<u8>> {
+    let mut lines = Vec::new();
+    for message in messages {
+        serde_json::to_writer(&mut lines, message)?;
+        lines.push(b'\n');
+    }
+    Ok(lines)
+}
+
+/// Download a message's photo or video, unless the copy from last time is
+/// still intact. Failures are recorded rather than returned.
+async fn save_media(
+    out: &Path,
+    thread_id: &str,
+    message: &Message,
+    url: &str,
+    kept: Option<&MediaEntry>,
+) -> MediaEntry {
+    let mut entry = MediaEntry {
+        message_id: message.id.clone(),
+        url: url.to_string(),
+        file: None,
+        sha256: None,
+        bytes: None,
+        error: None,
+    };
+    let intact = |kept: &&MediaEntry| match (&kept.file, &kept.sha256) {
+        (Some(file), Some(hash)) => hash_file(&out.join(file)).as_ref() == Some(hash),
+        _ => false,
+    };
+    if let Some(kept) = kept.filter(intact) {
+        entry.file = kept.file.clone();
+        entry.sha256 = kept.sha256.clone();
+        entry.bytes = kept.bytes;
+        return entry;
+    }
+
+    let extension = media_extension(message, url);
+    let file = format!("media/{}/{}.{}", file_safe(thread_id), file_safe(&message.id), extension);
+    let saved = match download(url).await {
+        Ok(bytes) => write_file(&out.join(&file), &bytes).map(|_| bytes),
+        Err(e) => Err(e),
+    };
+    match saved {
+        Ok(bytes) => {
+            entry.sha256 = Some(sha256(&bytes));
+            entry.bytes = Some(bytes.len() as u64);
+            entry.file = Some(file);
+        }
+        Err(e) => entry.error = Some(format!("{:#}", e)),
+    }
+    entry
+}
+
+/// Name shown for a conversation
+fn title(thread: &Thread) -> String {
+    thread.thread_title.clone().unwrap_or_else(|| {
+        let names: Vec<String> = thread.users.iter().map(|u| format!("@{}", u.username)).collect();
+        names.join(", ")
+    })
+}
+
+/// File extension for a message's media: the URL's when it has a plausible
+/// one, else by media type
+pub(crate) fn media_extension<'a>(message: &Message, url: &'a str) -> &'a str {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let name = path.rsplit('/').next().unwrap_or_default();
+    match name.rsplit_once('.') {
+        Some((_, ext)) if (2..=4).contains(&ext.len()) && ext.chars().all(|c| c.is_ascii_alphanumeric()) => ext,
+        _ => match message.media_type.as_deref() {
+            Some("video") => "mp4",
+            Some("audio" | "voice") => "m4a",
+            _ => "jpg",
+        },
+    }
+}
+
+/// An ID as a file name
+pub(crate) fn file_safe(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn hash_file(path: &Path) -> Option<String> {
+    fs::read(path).ok().map(|bytes| sha256(&bytes))
+}
+
+/// Write a file through a temp file, so an interrupted run never leaves a
+/// half-written one behind
+pub(crate) fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,180 @@
+//! `ig assign`: who on the team is answering a thread
+//!
+//! For several people sharing one account. Assignments and their handoff
+//! notes are kept by the server, so everyone using it sees the same list
+//! and two people don't answer the same person at once. With
+//! `[team] operator` set, `ig inbox` shows who each thread is assigned to.
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::client::{ApiClient, Feature};
+use crate::colors::Theme;
+use crate::config::Config;
+use crate::models::{Assignment, Thread};
+use crate::output::{print_json, OutputFormat};
+use crate::util::format_time_ago;
+
+/// Thread assignments by thread ID, as the inbox shows them
+#[derive(Debug, Default)]
+pub struct Assignments {
+    by_thread: HashMap<String, Assignment>,
+    /// `[team] operator`, shown as "you"
+    operator: Option<String>,
+    /// Whether team mode is on (`[team] operator` is set)
+    pub enabled: bool,
+}
+
+impl Assignments {
+    /// Assignments for the inbox: only asked for when `[team] operator` is
+    /// set, and empty when the server has none to give
+    pub async fn for_inbox(client: &ApiClient) -> Self {
+        let operator = Config::load().ok().and_then(|c| c.team.operator).filter(|o| !o.trim().is_empty());
+        let Some(operator) = operator else {
+            return Self::default();
+        };
+        let assignments = match client.supports(Feature::Assignments).await {
+            true => client.get_assignments().await.map(|r| r.assignments).unwrap_or_default(),
+            false => Vec::new(),
+        };
+        Self {
+            by_thread: assignments.into_iter().map(|a| (a.thread_id.clone(), a)).collect(),
+            operator: Some(operator),
+            enabled: true,
+        }
+    }
+
+    /// A thread's assignment, if it has one
+    pub fn get(&self, thread: &Thread) -> Option<&Assignment> {
+        self.by_thread.get(&thread.id)
+    }
+
+    /// Who a thread is assigned to ("you" for `[team] operator`)
+    pub fn assignee(&self, thread: &Thread) -> Option<String> {
+        let assignment = self.get(thread)?;
+        if self.operator.as_deref().is_some_and(|o| same_person(o, &assignment.assignee)) {
+            Some("you".to_string())
+        } else {
+            Some(assignment.assignee.clone())
+        }
+    }
+
+    /// "→ alice" after a thread in the inbox (empty when unassigned)
+    pub fn chip(&self, thread: &Thread) -> String {
+        match self.assignee(thread) {
+            Some(name) if name == "you" => format!("{} ", Theme::success(&format!("→ {}", name))),
+            Some(name) => format!("{} ", Theme::warning(&format!("→ {}", name))),
+            None => String::new(),
+        }
+    }
+}
+
+/// Team member names compare without case or a leading @
+fn same_person(a: &str, b: &str) -> bool {
+    a.trim().trim_start_matches('@').eq_ignore_ascii_case(b.trim().trim_start_matches('@'))
+}
+
+/// `ig assign`: assign a thread to `assignee` ("me" for `[team] operator`)
+/// with an optional handoff `note`, update just the note, unassign it with
+/// `clear`, or with none of those show who has it
+pub async fn assign(
+    client: &ApiClient,
+    thread_id: &str,
+    assignee: Option<&str>,
+    note: Option<&str>,
+    clear: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let operator = Config::load()?.team.operator.filter(|o| !o.trim().is_empty());
+    let assignee = assignee.map(|a| a.trim().trim_start_matches('@'));
+    if assignee == Some("me") && operator.is_none() {
+        anyhow::bail!("Set operator under [team] in config.toml to assign threads to yourself");
+    }
+    let current = client
+        .get_assignments()
+        .await?
+        .assignments
+        .into_iter()
+        .find(|a| a.thread_id == thread_id);
+
+    if assignee.is_none() && note.is_none() && !clear {
+        if output == OutputFormat::Json {
+            return print_json(&current);
+        }
+        match &current {
+            Some(assignment) => print_assignment(assignment),
+            None => println!("{}", Theme::muted("Not assigned to anyone.")),
+        }
+        return Ok(());
+    }
+
+    let assignee = match assignee {
+        _ if clear => None,
+        Some("me") => operator.as_deref().map(|o| o.trim().to_string()),
+        Some("") => anyhow::bail!("Give someone to assign the thread to"),
+        Some(assignee) => Some(assignee.to_string()),
+        // A new note for whoever has it now
+        None => match &current {
+            Some(current) => Some(current.assignee.clone()),
+            None => anyhow::bail!("The thread isn't assigned; give someone to assign it to"),
+        },
+    };
+    // The handoff note stays with the thread until replaced; `--note ""`
+    // removes it
+    let note = match note {
+        Some(note) => Some(note.trim()).filter(|n| !n.is_empty()),
+        None if assignee.is_some() => current.as_ref().and_then(|c| c.note.as_deref()),
+        None => None,
+    };
+
+    let response = client
+        .assign_thread(thread_id, assignee.as_deref(), note, operator.as_deref())
+        .await?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to assign thread".to_string()));
+    }
+    let updated = response.assignments.into_iter().find(|a| a.thread_id == thread_id);
+
+    if output == OutputFormat::Json {
+        return print_json(&updated);
+    }
+    let previous = current.map(|c| c.assignee);
+    match &assignee {
+        None if previous.is_none() => println!("{}", Theme::muted("Not assigned to anyone.")),
+        None => println!(
+            "{} {}",
+            Theme::check(),
+            Theme::success(&format!("Unassigned (was {})", previous.unwrap_or_default()))
+        ),
+        Some(assignee) => {
+            let was = previous
+                .filter(|p| !same_person(p, assignee))
+                .map(|p| Theme::muted(&format!(" (was {})", p)).to_string())
+                .unwrap_or_default();
+            println!("{} {}{}", Theme::check(), Theme::success(&format!("Assigned to {}", assignee)), was);
+            if let Some(note) = updated.as_ref().and_then(|a| a.note.as_deref()) {
+                println!("  {} {}", Theme::muted("Note:"), note);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Who has a thread, since when and the handoff note
+fn print_assignment(assignment: &Assignment) {
+    let mut details = Vec::new();
+    if let Some(by) = &assignment.assigned_by {
+        details.push(format!("by {}", by));
+    }
+    if let Some(ago) = assignment.assigned_at.as_deref().map(format_time_ago).filter(|a| !a.is_empty()) {
+        details.push(format!("{} ago", ago));
+    }
+    let details = match details.is_empty() {
+        true => String::new(),
+        false => format!(" {}", Theme::muted(&format!("({})", details.join(", ")))),
+    };
+    println!("Assigned to {}{}", Theme::username(&assignment.assignee), details);
+    if let Some(note) = &assignment.note {
+        println!("  {} {}", Theme::muted("Note:"), note);
+    }
+}
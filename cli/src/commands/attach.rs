@@ -0,0 +1,360 @@
+//! Attachment staging (`ig attach`)
+//!
+//! Chat has no file picker, so photos and videos are staged beforehand
+//! with `ig attach add` and go out with the next message sent by `ig send`,
+//! `ig reply` or chat. Files are read when they're sent, not when staged.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+use crate::store;
+
+/// Data file holding staged attachments
+const ATTACHMENTS_FILE: &str = "attachments";
+
+/// File types Instagram DMs accept
+const PHOTO_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "m4v"];
+
+/// A file waiting to be sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub path: PathBuf,
+    pub added: DateTime<Local>,
+}
+
+impl Attachment {
+    /// File name shown in listings and sent to the server
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// All staged attachments, in the order they'll be sent
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Attachments {
+    pub items: Vec<Attachment>,
+}
+
+impl Attachments {
+    /// Load staged attachments from disk
+    pub fn load() -> Result<Self> {
+        store::load(ATTACHMENTS_FILE)
+    }
+
+    /// Save staged attachments to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(ATTACHMENTS_FILE, self)
+    }
+}
+
+/// Where staged attachments go
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Recipient<'a> {
+    Thread(&'a str),
+    User(&'a str),
+}
+
+/// Stage files for the next message (`ig attach add`)
+pub fn add_attachments(paths: &[PathBuf]) -> Result<()> {
+    let mut attachments = Attachments::load()?;
+
+    for path in paths {
+        let path = fs::canonicalize(path).with_context(|| format!("Cannot find {}", path.display()))?;
+        if !path.is_file() {
+            anyhow::bail!("{} is not a file", path.display());
+        }
+        if !is_supported(&path) {
+            anyhow::bail!(
+                "{} isn't a photo or video Instagram accepts ({})",
+                path.display(),
+                [PHOTO_EXTENSIONS, VIDEO_EXTENSIONS].concat().join(", ")
+            );
+        }
+        if attachments.items.iter().any(|a| a.path == path) {
+            println!("{}", Theme::muted(&format!("{} is already staged.", path.display())));
+            continue;
+        }
+
+        let attachment = Attachment {
+            path,
+            added: Local::now(),
+        };
+        println!(
+            "{} {} {}",
+            Theme::check(),
+            Theme::success("Staged"),
+            Theme::accent(&attachment.name())
+        );
+        attachments.items.push(attachment);
+    }
+
+    attachments.save()?;
+    println!(
+        "{}",
+        Theme::muted(&format!(
+            "{} attachment(s) will go with your next message.",
+            attachments.items.len()
+        ))
+    );
+    Ok(())
+}
+
+/// Show staged attachments (`ig attach list`)
+pub fn list_attachments(output: OutputFormat) -> Result<()> {
+    let attachments = Attachments::load()?;
+
+    if output == OutputFormat::Json {
+        return print_json(&attachments.items);
+    }
+
+    if attachments.items.is_empty() {
+        println!("{}", Theme::muted("No staged attachments."));
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["#", "File", "Size", "Added"]);
+        for (i, attachment) in attachments.items.iter().enumerate() {
+            table.row([
+                (i + 1).to_string(),
+                attachment.path.display().to_string(),
+                file_size(&attachment.path),
+                attachment.added.format("%Y-%m-%d %H:%M").to_string(),
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Staged attachments"));
+    println!("{}", Theme::separator(60));
+    for (i, attachment) in attachments.items.iter().enumerate() {
+        println!(
+            "  {} {} {}",
+            Theme::muted(&format!("{}.", i + 1)),
+            Theme::accent(&attachment.name()),
+            Theme::muted(&format!("({})", file_size(&attachment.path)))
+        );
+        println!("     {} {}", Theme::muted("└"), Theme::muted(&attachment.path.display().to_string()));
+    }
+    println!("{}", Theme::separator(60));
+    println!("{}", Theme::muted("They go with your next message; `ig attach clear` unstages them."));
+    Ok(())
+}
+
+/// Unstage everything (`ig attach clear`)
+pub fn clear_attachments() -> Result<()> {
+    let mut attachments = Attachments::load()?;
+    let count = attachments.items.len();
+    attachments.items.clear();
+    attachments.save()?;
+    println!(
+        "{} {}",
+        Theme::check(),
+        Theme::success(&format!("Cleared {} staged attachment(s)", count))
+    );
+    Ok(())
+}
+
+/// How many attachments are waiting
+pub(crate) fn staged_count() -> usize {
+    Attachments::load().map(|a| a.items.len()).unwrap_or(0)
+}
+
+/// Send every staged attachment to `recipient`, unstaging the ones that
+/// went out. Failures are printed and stay staged for the next message.
+pub(crate) async fn send_staged(client: &ApiClient, recipient: Recipient<'_>) -> Result<()> {
+    let mut attachments = Attachments::load()?;
+    if attachments.items.is_empty() {
+        return Ok(());
+    }
+
+    let mut failed = Vec::new();
+    for attachment in attachments.items.drain(..) {
+        if !send_path(client, recipient, &attachment.path).await {
+            failed.push(attachment);
+        }
+    }
+
+    let kept = failed.len();
+    attachments.items = failed;
+    attachments.save()?;
+    if kept > 0 {
+        println!(
+            "{}",
+            Theme::muted(&format!("{} attachment(s) still staged for the next message.", kept))
+        );
+    }
+    Ok(())
+}
+
+/// Read and send a file, printing how it went; true if it was sent
+pub(crate) async fn send_path(client: &ApiClient, recipient: Recipient<'_>, path: &Path) -> bool {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            println!(
+                "{} {}",
+                Theme::cross(),
+                Theme::error(&format!("Cannot read {}: {}", path.display(), e))
+            );
+            return false;
+        }
+    };
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    send_file(client, recipient, &name, &data).await
+}
+
+/// Send one photo or video, printing how it went; true if it was sent
+pub(crate) async fn send_file(client: &ApiClient, recipient: Recipient<'_>, name: &str, data: &[u8]) -> bool {
+    let spinner = create_spinner(&format!("Sending {}...", name));
+    let result = match recipient {
+        Recipient::Thread(thread_id) => client.upload_photo_to_thread(thread_id, name, data.to_vec()).await,
+        Recipient::User(username) => client.upload_photo_to_user(username, name, data.to_vec()).await,
+    };
+    spinner.finish_and_clear();
+
+    let error = match result {
+        Ok(response) if response.success => None,
+        Ok(response) => Some(response.error.unwrap_or("Failed to send".to_string())),
+        Err(e) => Some(e.to_string()),
+    };
+    match error {
+        None => {
+            println!("{} {}", Theme::check(), Theme::muted(&format!("Sent {}", name)));
+            true
+        }
+        Some(error) => {
+            println!("{} {}", Theme::cross(), Theme::error(&format!("{}: {}", name, error)));
+            false
+        }
+    }
+}
+
+/// The files in `text` if it is nothing but paths to photos or videos, as
+/// terminals insert them when files are dragged in: quoted
+/// (`'/tmp/My Pic.png'`), backslash-escaped (`/tmp/My\ Pic.png`) or as
+/// `file://` URLs, several separated by spaces
+pub(crate) fn dropped_files(text: &str) -> Option<Vec<PathBuf>> {
+    let words = split_words(text.trim())?;
+    if words.is_empty() {
+        return None;
+    }
+
+    words
+        .into_iter()
+        .map(|word| {
+            let path = match word.strip_prefix("file://") {
+                Some(url) => PathBuf::from(percent_decode(url)?),
+                None => PathBuf::from(word),
+            };
+            (path.is_absolute() && path.is_file() && is_supported(&path)).then_some(path)
+        })
+        .collect()
+}
+
+/// Split like a shell would: whitespace separates words, except inside
+/// quotes or after a backslash. `None` if a quote is left open.
+fn split_words(text: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') if !cfg!(windows) => {
+                word.push(chars.next()?);
+                in_word = true;
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return None;
+    }
+    if in_word {
+        words.push(word);
+    }
+    Some(words)
+}
+
+/// Decode `%20`-style escapes in a file URL path
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Whether the file is a photo Instagram takes
+pub(crate) fn is_photo(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| PHOTO_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+}
+
+/// Whether Instagram takes this file type
+fn is_supported(path: &Path) -> bool {
+    let Some(ext) = path.extension().map(|e| e.to_string_lossy().to_lowercase()) else {
+        return false;
+    };
+    PHOTO_EXTENSIONS.contains(&ext.as_str()) || VIDEO_EXTENSIONS.contains(&ext.as_str())
+}
+
+/// Size of a file for listings, or "missing"
+fn file_size(path: &Path) -> String {
+    match fs::metadata(path) {
+        Ok(meta) => format_size(meta.len()),
+        Err(_) => "missing".to_string(),
+    }
+}
+
+/// "1.2 MB" / "340 KB"
+pub(crate) fn format_size(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1024.0 * 1024.0 {
+        format!("{:.1} MB", bytes / (1024.0 * 1024.0))
+    } else {
+        format!("{:.0} KB", (bytes / 1024.0).ceil())
+    }
+}
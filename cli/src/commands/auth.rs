@@ -1,11 +1,19 @@
 //! Authentication commands with interactive prompts
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dialoguer::{Input, Password};
+use std::time::{Duration, Instant};
 
-use crate::client::ApiClient;
+use crate::client::{ApiClient, ApiCompatibility, Feature, API_VERSION};
 use crate::colors::Theme;
+use crate::commands::cooldown::{clear_login_cooldown, note_login_failure};
+use crate::commands::qrcode::render_qr;
+use crate::completer::prompt_line;
+use crate::config::device_info;
+use crate::header;
+use crate::models::HealthResponse;
 use crate::spinner::create_spinner;
+use crate::util::format_count;
 
 /// Interactive login with encrypted password
 pub async fn login_interactive(client: &ApiClient) -> Result<()> {
@@ -31,14 +39,16 @@ pub async fn login_interactive(client: &ApiClient) -> Result<()> {
     let spinner = create_spinner("Authenticating...");
 
     // Attempt login with encrypted password
-    let result = client.login(&username, &password).await;
+    let result = client.login(&username, &password, Some(device_info()?)).await;
     spinner.finish_and_clear();
 
     match result {
         Ok(response) => {
             if response.success {
+                clear_login_cooldown()?;
                 println!("{} {}", Theme::check(), Theme::success("Login successful!"));
                 if let Some(user) = response.user {
+                    header::remember_user(Some(&user.username));
                     println!(
                         "  {} {} ({})",
                         Theme::muted("Logged in as:"),
@@ -47,16 +57,15 @@ pub async fn login_interactive(client: &ApiClient) -> Result<()> {
                     );
                 }
             } else {
-                println!(
-                    "{} {}",
-                    Theme::cross(),
-                    Theme::error(&response.message.unwrap_or("Login failed".to_string()))
-                );
+                let message = response.message.unwrap_or("Login failed".to_string());
+                println!("{} {}", Theme::cross(), Theme::error(&message));
+                note_login_failure(&message).await?;
             }
             Ok(())
         }
         Err(e) => {
             println!("{} {}", Theme::cross(), Theme::error(&format!("{}", e)));
+            note_login_failure(&e.to_string()).await?;
             Err(e)
         }
     }
@@ -70,14 +79,16 @@ pub async fn login_with_credentials(
 ) -> Result<()> {
     let spinner = create_spinner("Authenticating...");
 
-    let result = client.login(username, password).await;
+    let result = client.login(username, password, Some(device_info()?)).await;
     spinner.finish_and_clear();
 
     match result {
         Ok(response) => {
             if response.success {
+                clear_login_cooldown()?;
                 println!("{} {}", Theme::check(), Theme::success("Login successful!"));
                 if let Some(user) = response.user {
+                    header::remember_user(Some(&user.username));
                     println!(
                         "  {} {} ({})",
                         Theme::muted("Logged in as:"),
@@ -86,10 +97,85 @@ pub async fn login_with_credentials(
                     );
                 }
             } else {
+                let message = response.message.unwrap_or("Login failed".to_string());
+                println!("{} {}", Theme::cross(), Theme::error(&message));
+                note_login_failure(&message).await?;
+            }
+            Ok(())
+        }
+        Err(e) => {
+            println!("{} {}", Theme::cross(), Theme::error(&format!("{}", e)));
+            note_login_failure(&e.to_string()).await?;
+            Err(e)
+        }
+    }
+}
+
+/// How often to ask the server whether the QR login was approved
+const QR_POLL_SECS: u64 = 2;
+
+/// How long to wait when the server doesn't say when the token expires
+const QR_DEFAULT_EXPIRY_SECS: u64 = 120;
+
+/// Login by approving a QR code in the Instagram app (no password typed)
+pub async fn login_qr(client: &ApiClient) -> Result<()> {
+    let spinner = create_spinner("Starting QR login...");
+    let started = client.start_qr_login(Some(device_info()?)).await;
+    spinner.finish_and_clear();
+
+    let Some(started) = started? else {
+        anyhow::bail!("This server doesn't support QR login; use `ig login` with your password");
+    };
+    if !started.success {
+        anyhow::bail!(started.error.unwrap_or("Failed to start QR login".to_string()));
+    }
+    let token = started.token.context("Server returned no QR login token")?;
+    let url = started.url.context("Server returned no QR login URL")?;
+
+    let rendered = render_qr(&url)?;
+
+    println!("{}", Theme::header("Instagram QR Login"));
+    println!("{}", Theme::separator(40));
+    println!("{}", rendered);
+    println!(
+        "{}",
+        Theme::muted("Scan with your phone and approve the login in the Instagram app.")
+    );
+    println!("{} {}", Theme::muted("Or open:"), url);
+    println!();
+
+    let expiry = started.expires_in.unwrap_or(QR_DEFAULT_EXPIRY_SECS);
+    let deadline = Instant::now() + Duration::from_secs(expiry);
+    let spinner = create_spinner("Waiting for approval (Ctrl-C to cancel)...");
+    let result = loop {
+        tokio::time::sleep(Duration::from_secs(QR_POLL_SECS)).await;
+        let state = match client.qr_login_status(&token).await {
+            Ok(state) => state,
+            Err(e) => break Err(e),
+        };
+        match state.status.as_str() {
+            "approved" => break Ok(state),
+            "pending" if Instant::now() < deadline => continue,
+            "pending" | "expired" => break Err(anyhow::anyhow!("QR code expired; run `ig login --qr` again")),
+            other => {
+                let error = state.error.unwrap_or(format!("QR login {}", other));
+                break Err(anyhow::anyhow!(error));
+            }
+        }
+    };
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(state) => {
+            clear_login_cooldown()?;
+            println!("{} {}", Theme::check(), Theme::success("Login successful!"));
+            if let Some(user) = state.user {
+                header::remember_user(Some(&user.username));
                 println!(
-                    "{} {}",
-                    Theme::cross(),
-                    Theme::error(&response.message.unwrap_or("Login failed".to_string()))
+                    "  {} {} ({})",
+                    Theme::muted("Logged in as:"),
+                    Theme::username(&user.username),
+                    user.full_name.unwrap_or_default()
                 );
             }
             Ok(())
@@ -107,6 +193,7 @@ pub async fn logout(client: &ApiClient) -> Result<()> {
 
     client.logout().await?;
     spinner.finish_and_clear();
+    header::remember_user(None);
 
     println!("{} {}", Theme::check(), Theme::success("Logged out successfully"));
     Ok(())
@@ -121,6 +208,7 @@ pub async fn status(client: &ApiClient) -> Result<()> {
 
     match result {
         Ok(health) => {
+            header::remember_user(health.username.as_deref().filter(|_| health.authenticated));
             println!("{}", Theme::header("Server Status"));
             println!("{}", Theme::separator(40));
             println!(
@@ -128,6 +216,8 @@ pub async fn status(client: &ApiClient) -> Result<()> {
                 Theme::muted("Server:"),
                 Theme::success(&health.status)
             );
+            print_api_compatibility(&health);
+            print_capabilities(client).await;
             if health.authenticated {
                 println!(
                     "  {} {} ({})",
@@ -156,6 +246,80 @@ pub async fn status(client: &ApiClient) -> Result<()> {
     }
 }
 
+/// Print the server/CLI API version comparison for `status`
+/// Which optional features the server supports
+async fn print_capabilities(client: &ApiClient) {
+    let line = match client.capabilities().await {
+        Ok(Some(capabilities)) => Feature::ALL
+            .iter()
+            .map(|f| {
+                if f.supported_by(&capabilities) {
+                    Theme::success(f.name()).to_string()
+                } else {
+                    Theme::muted(&format!("no {}", f.name())).to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        Ok(None) => Theme::warning("Server does not list its features - it may be older than the CLI").to_string(),
+        Err(e) => Theme::warning(&format!("Could not ask the server: {:#}", e)).to_string(),
+    };
+    println!("  {} {}", Theme::muted("Features:"), line);
+}
+
+fn print_api_compatibility(health: &HealthResponse) {
+    match ApiCompatibility::check(health) {
+        ApiCompatibility::Compatible => {
+            println!(
+                "  {} {}",
+                Theme::muted("API:"),
+                Theme::success(&format!("v{}", API_VERSION))
+            );
+        }
+        ApiCompatibility::ServerNewer(server) => {
+            println!(
+                "  {} {} {}",
+                Theme::muted("API:"),
+                Theme::warn_icon(),
+                Theme::warning(&format!(
+                    "Server is newer than CLI (server v{}, CLI v{}) - consider updating the CLI",
+                    server, API_VERSION
+                ))
+            );
+        }
+        ApiCompatibility::ServerOlder(server) => {
+            println!(
+                "  {} {} {}",
+                Theme::muted("API:"),
+                Theme::warn_icon(),
+                Theme::warning(&format!(
+                    "Server is older than CLI (server v{}, CLI v{}) - some features may be unavailable",
+                    server, API_VERSION
+                ))
+            );
+        }
+        ApiCompatibility::CliTooOld(server) => {
+            println!(
+                "  {} {} {}",
+                Theme::muted("API:"),
+                Theme::cross(),
+                Theme::error(&format!(
+                    "CLI is too old for this server (server v{}, CLI v{}) - please update the CLI",
+                    server, API_VERSION
+                ))
+            );
+        }
+        ApiCompatibility::Unknown => {
+            println!(
+                "  {} {} {}",
+                Theme::muted("API:"),
+                Theme::warn_icon(),
+                Theme::warning("Server did not report an API version - it may be older than the CLI")
+            );
+        }
+    }
+}
+
 /// Show current logged-in user info
 pub async fn show_me(client: &ApiClient) -> Result<()> {
     let spinner = create_spinner("Loading profile...");
@@ -197,9 +361,16 @@ pub async fn show_me(client: &ApiClient) -> Result<()> {
 }
 
 /// Search for a user by username
-pub async fn search_user(client: &ApiClient, query: &str) -> Result<()> {
+pub async fn search_user(client: &ApiClient, query: Option<&str>) -> Result<()> {
+    let query = match query {
+        Some(q) => q.to_string(),
+        None => prompt_line("Username: @")?,
+    };
     // Remove @ prefix if present
-    let username = query.trim_start_matches('@');
+    let username = query.trim().trim_start_matches('@');
+    if username.is_empty() {
+        return Ok(());
+    }
 
     let spinner = create_spinner(&format!("Searching for @{}...", username));
 
@@ -260,14 +431,3 @@ pub async fn search_user(client: &ApiClient, query: &str) -> Result<()> {
         }
     }
 }
-
-/// Format large numbers (1000 -> 1K, 1000000 -> 1M)
-fn format_count(n: u64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.1}K", n as f64 / 1_000.0)
-    } else {
-        n.to_string()
-    }
-}
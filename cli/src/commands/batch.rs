@@ -0,0 +1,183 @@
+//! Batch actions in the interactive inbox
+//!
+//! Space marks conversations in `ig inbox -i`; `r`, `m`, `a` and `T` then
+//! mark them read, mute, archive or tag them all at once. Each conversation
+//! is tried on its own so one failure doesn't stop the rest, and every
+//! outcome is listed at the end.
+
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{self, ClearType},
+};
+use std::io::{self, Write};
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::tags::{tag_chip, Tags};
+use crate::models::{Thread, ThreadActionResponse};
+
+/// What to do with the marked conversations
+#[derive(Debug, Clone)]
+pub enum BatchAction {
+    MarkRead,
+    Mute,
+    Archive,
+    /// Tag everyone the conversation is with (local only)
+    Tag(String),
+}
+
+impl BatchAction {
+    /// Heading while the action runs
+    fn heading(&self) -> String {
+        match self {
+            BatchAction::MarkRead => "Marking as read".to_string(),
+            BatchAction::Mute => "Muting".to_string(),
+            BatchAction::Archive => "Archiving".to_string(),
+            BatchAction::Tag(tag) => format!("Tagging {}", tag_chip(tag)),
+        }
+    }
+
+    /// Apply the action to one conversation
+    async fn apply(&self, client: &ApiClient, thread: &Thread) -> Result<()> {
+        let response = match self {
+            BatchAction::MarkRead => client.mark_thread_seen(&thread.id).await?,
+            BatchAction::Mute => client.mute_thread(&thread.id).await?,
+            BatchAction::Archive => client.archive_thread(&thread.id).await?,
+            BatchAction::Tag(tag) => return tag_thread(thread, tag),
+        };
+        check(response)
+    }
+}
+
+/// Turn an unsuccessful server response into an error
+fn check(response: ThreadActionResponse) -> Result<()> {
+    if response.success {
+        Ok(())
+    } else {
+        anyhow::bail!(response.error.unwrap_or_else(|| "Server refused".to_string()))
+    }
+}
+
+/// Tag the participants of a thread
+fn tag_thread(thread: &Thread, tag: &str) -> Result<()> {
+    if thread.users.is_empty() {
+        anyhow::bail!("No participants to tag");
+    }
+    let mut tags = Tags::load()?;
+    for user in &thread.users {
+        tags.by_user
+            .entry(user.username.to_lowercase())
+            .or_default()
+            .insert(tag.to_string());
+    }
+    tags.save()
+}
+
+/// Name of a thread for the progress list
+fn thread_label(thread: &Thread) -> String {
+    let username = thread.users.first().map(|u| u.username.as_str()).unwrap_or("unknown");
+    thread
+        .thread_title
+        .clone()
+        .unwrap_or_else(|| format!("@{}", username))
+}
+
+/// Run `action` on each of `threads` (raw mode), listing every outcome and
+/// waiting for a key at the end. Returns the IDs of the threads it worked on.
+pub async fn run_batch(
+    stdout: &mut io::Stdout,
+    client: &ApiClient,
+    action: &BatchAction,
+    threads: &[&Thread],
+) -> Result<Vec<String>> {
+    execute!(stdout, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+    writeln!(
+        stdout,
+        "\r\n{} {}",
+        Theme::header(&action.heading()),
+        Theme::muted(&format!("{} conversations", threads.len()))
+    )?;
+    writeln!(stdout, "\r{}", Theme::separator(60))?;
+
+    let mut done = Vec::new();
+    let mut failed = 0;
+    for (i, thread) in threads.iter().enumerate() {
+        let label = thread_label(thread);
+        write!(
+            stdout,
+            "\r{} {}",
+            Theme::muted(&format!("[{}/{}]", i + 1, threads.len())),
+            label
+        )?;
+        stdout.flush()?;
+
+        let result = action.apply(client, thread).await;
+        execute!(stdout, terminal::Clear(ClearType::CurrentLine))?;
+        match result {
+            Ok(()) => {
+                writeln!(stdout, "\r{} {}", Theme::check(), label)?;
+                done.push(thread.id.clone());
+            }
+            Err(e) => {
+                writeln!(stdout, "\r{} {} {}", Theme::cross(), label, Theme::error(&e.to_string()))?;
+                failed += 1;
+            }
+        }
+    }
+
+    writeln!(stdout, "\r{}", Theme::separator(60))?;
+    let summary = format!("{} done", done.len());
+    if failed == 0 {
+        writeln!(stdout, "\r{}", Theme::success(&summary))?;
+    } else {
+        writeln!(
+            stdout,
+            "\r{}, {} {}",
+            Theme::success(&summary),
+            Theme::error(&format!("{} failed", failed)),
+            Theme::muted("(still selected, so you can retry)")
+        )?;
+    }
+    writeln!(stdout, "\r{}", Theme::muted("Press any key to return"))?;
+    stdout.flush()?;
+
+    loop {
+        if let Event::Key(key_event) = event::read()? {
+            if key_event.kind == KeyEventKind::Press {
+                return Ok(done);
+            }
+        }
+    }
+}
+
+/// Ask for a tag name under the list (raw mode); `None` if cancelled with
+/// Esc or left empty
+pub fn prompt_tag(stdout: &mut io::Stdout) -> Result<Option<String>> {
+    let mut input = String::new();
+    execute!(stdout, cursor::Show)?;
+    let tag = loop {
+        execute!(stdout, terminal::Clear(ClearType::CurrentLine))?;
+        write!(stdout, "\r{} {}", Theme::prompt("Tag:"), input)?;
+        stdout.flush()?;
+
+        if let Event::Key(key_event) = event::read()? {
+            if key_event.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key_event.code {
+                KeyCode::Enter => break Some(input.trim().to_lowercase()),
+                KeyCode::Esc => break None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+        }
+    };
+    execute!(stdout, cursor::Hide)?;
+    Ok(tag.filter(|t| !t.is_empty()))
+}
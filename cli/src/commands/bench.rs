@@ -0,0 +1,315 @@
+//! Backend benchmark: hammer `/health` and `/inbox` and report latency
+//!
+//! `/inbox` is measured with and without response compression, and the
+//! payload size both ways is reported.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+
+/// Threads requested per `/inbox` call (same as `ig inbox`)
+const INBOX_LIMIT: u32 = 20;
+
+/// Endpoints exercised by the benchmark
+#[derive(Debug, Clone, Copy)]
+enum Endpoint {
+    Health,
+    Inbox,
+}
+
+impl Endpoint {
+    fn path(self) -> &'static str {
+        match self {
+            Endpoint::Health => "/health",
+            Endpoint::Inbox => "/inbox",
+        }
+    }
+}
+
+/// Latency summary for one endpoint
+#[derive(Debug, Serialize)]
+struct EndpointStats {
+    endpoint: &'static str,
+    compressed: bool,
+    requests: usize,
+    errors: usize,
+    min_ms: f64,
+    mean_ms: f64,
+    p50_ms: f64,
+    p90_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+    requests_per_sec: f64,
+    /// First error seen, to hint at what went wrong
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_error: Option<String>,
+}
+
+/// Response size of an endpoint with and without compression
+#[derive(Debug, Serialize)]
+struct PayloadSize {
+    endpoint: &'static str,
+    uncompressed_bytes: Option<usize>,
+    /// Size when compression was requested
+    compressed_bytes: Option<usize>,
+    /// Content-Encoding the server chose (None if it didn't compress)
+    encoding: Option<String>,
+}
+
+/// Everything `ig bench --output json` prints
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    endpoints: Vec<EndpointStats>,
+    payload: PayloadSize,
+}
+
+/// Run the benchmark (`ig bench --requests 50 --concurrency 5`)
+pub async fn run_bench(
+    client: &ApiClient,
+    requests: usize,
+    concurrency: usize,
+    output: OutputFormat,
+) -> Result<()> {
+    let requests = requests.max(1);
+    let concurrency = concurrency.clamp(1, requests);
+    let client = client.clone().without_conditional_requests();
+    let uncompressed = Arc::new(client.without_compression()?);
+    let client = Arc::new(client);
+
+    let runs = [
+        (&client, Endpoint::Health, true),
+        (&client, Endpoint::Inbox, true),
+        (&uncompressed, Endpoint::Inbox, false),
+    ];
+    let mut stats = Vec::new();
+    for (client, endpoint, compressed) in runs {
+        let spinner = create_spinner(&format!(
+            "Benchmarking {}{} ({} requests, concurrency {})...",
+            endpoint.path(),
+            if compressed { "" } else { " without compression" },
+            requests,
+            concurrency
+        ));
+        let mut result = bench_endpoint(client, endpoint, requests, concurrency).await;
+        result.compressed = compressed;
+        spinner.finish_and_clear();
+        stats.push(result);
+    }
+
+    let inbox_path = format!("{}?limit={}", Endpoint::Inbox.path(), INBOX_LIMIT);
+    let compressed = uncompressed.wire_size(&inbox_path, "gzip").await.ok();
+    let payload = PayloadSize {
+        endpoint: Endpoint::Inbox.path(),
+        uncompressed_bytes: uncompressed.wire_size(&inbox_path, "identity").await.ok().map(|(n, _)| n),
+        compressed_bytes: compressed.as_ref().map(|(n, _)| *n),
+        encoding: compressed.and_then(|(_, encoding)| encoding),
+    };
+
+    if output == OutputFormat::Json {
+        return print_json(&BenchReport { endpoints: stats, payload });
+    }
+
+    println!();
+    println!(
+        "{}",
+        Theme::header(&format!(
+            "Benchmark ({} requests per endpoint, concurrency {})",
+            requests, concurrency
+        ))
+    );
+
+    let mut table = Table::new(&[
+        "Endpoint", "Compressed", "OK", "Errors", "Min", "Mean", "p50", "p90", "p99", "Max", "Req/s",
+    ]);
+    for s in &stats {
+        table.row([
+            s.endpoint.to_string(),
+            if s.compressed { "yes" } else { "no" }.to_string(),
+            (s.requests - s.errors).to_string(),
+            s.errors.to_string(),
+            format_ms(s.min_ms),
+            format_ms(s.mean_ms),
+            format_ms(s.p50_ms),
+            format_ms(s.p90_ms),
+            format_ms(s.p99_ms),
+            format_ms(s.max_ms),
+            format!("{:.1}", s.requests_per_sec),
+        ]);
+    }
+    table.print();
+    print_compression_summary(&stats, &payload);
+
+    for s in &stats {
+        if let Some(error) = &s.first_error {
+            println!(
+                "{} {} {}",
+                Theme::warn_icon(),
+                Theme::warning(&format!("{} errors on {}:", s.errors, s.endpoint)),
+                Theme::muted(error)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare compressed and uncompressed `/inbox` latency and size
+fn print_compression_summary(stats: &[EndpointStats], payload: &PayloadSize) {
+    let inbox = |compressed| {
+        stats
+            .iter()
+            .find(|s| s.endpoint == payload.endpoint && s.compressed == compressed && s.p50_ms > 0.0)
+    };
+    if let (Some(on), Some(off)) = (inbox(true), inbox(false)) {
+        let saved = off.p50_ms - on.p50_ms;
+        println!(
+            "{} {}",
+            Theme::accent("Compression:"),
+            Theme::muted(&format!(
+                "{} p50 {} with vs {} without ({}{:.1}ms)",
+                payload.endpoint,
+                format_ms(on.p50_ms),
+                format_ms(off.p50_ms),
+                if saved >= 0.0 { "-" } else { "+" },
+                saved.abs()
+            ))
+        );
+    }
+
+    match (payload.uncompressed_bytes, payload.compressed_bytes, &payload.encoding) {
+        (Some(raw), Some(size), Some(encoding)) => {
+            let smaller = 100.0 - (size as f64 / raw.max(1) as f64) * 100.0;
+            println!(
+                "{} {}",
+                Theme::accent("Payload:"),
+                Theme::muted(&format!(
+                    "{} is {} uncompressed, {} with {} ({:.0}% smaller)",
+                    payload.endpoint,
+                    format_bytes(raw),
+                    format_bytes(size),
+                    encoding,
+                    smaller
+                ))
+            );
+        }
+        (Some(raw), _, _) => println!(
+            "{} {}",
+            Theme::accent("Payload:"),
+            Theme::muted(&format!(
+                "{} is {} and the server did not compress it (update the server, or it is under 1 KB)",
+                payload.endpoint,
+                format_bytes(raw)
+            ))
+        ),
+        _ => {}
+    }
+}
+
+/// Human-readable byte count
+fn format_bytes(bytes: usize) -> String {
+    if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Send `requests` calls to one endpoint, at most `concurrency` in flight
+async fn bench_endpoint(
+    client: &Arc<ApiClient>,
+    endpoint: Endpoint,
+    requests: usize,
+    concurrency: usize,
+) -> EndpointStats {
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = JoinSet::new();
+    let started = Instant::now();
+
+    for _ in 0..requests {
+        let client = Arc::clone(client);
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let start = Instant::now();
+            let result = match endpoint {
+                Endpoint::Health => client.health().await.map(|_| ()),
+                Endpoint::Inbox => client.get_inbox(INBOX_LIMIT).await.and_then(|resp| {
+                    if resp.success {
+                        Ok(())
+                    } else {
+                        Err(anyhow::anyhow!(resp.error.unwrap_or_else(|| "request failed".to_string())))
+                    }
+                }),
+            };
+            (start.elapsed(), result)
+        });
+    }
+
+    let mut latencies = Vec::with_capacity(requests);
+    let mut errors = 0;
+    let mut first_error = None;
+    while let Some(joined) = tasks.join_next().await {
+        let (elapsed, result) = match joined {
+            Ok(outcome) => outcome,
+            Err(e) => (Duration::ZERO, Err(e.into())),
+        };
+        match result {
+            Ok(()) => latencies.push(elapsed),
+            Err(e) => {
+                errors += 1;
+                first_error.get_or_insert_with(|| format!("{:#}", e));
+            }
+        }
+    }
+    let wall = started.elapsed();
+
+    latencies.sort();
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let mean = if latencies.is_empty() {
+        0.0
+    } else {
+        latencies.iter().map(|d| ms(*d)).sum::<f64>() / latencies.len() as f64
+    };
+
+    EndpointStats {
+        endpoint: endpoint.path(),
+        compressed: true,
+        requests,
+        errors,
+        min_ms: latencies.first().map(|d| ms(*d)).unwrap_or_default(),
+        mean_ms: mean,
+        p50_ms: percentile(&latencies, 50.0),
+        p90_ms: percentile(&latencies, 90.0),
+        p99_ms: percentile(&latencies, 99.0),
+        max_ms: latencies.last().map(|d| ms(*d)).unwrap_or_default(),
+        requests_per_sec: requests as f64 / wall.as_secs_f64().max(f64::EPSILON),
+        first_error,
+    }
+}
+
+/// Nearest-rank percentile of sorted latencies, in milliseconds
+fn percentile(sorted: &[Duration], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    let index = rank.clamp(1, sorted.len()) - 1;
+    sorted[index].as_secs_f64() * 1000.0
+}
+
+/// Format a latency for the table ("-" when nothing succeeded)
+fn format_ms(value: f64) -> String {
+    if value == 0.0 {
+        "-".to_string()
+    } else {
+        format!("{:.1}ms", value)
+    }
+}
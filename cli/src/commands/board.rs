@@ -0,0 +1,374 @@
+//! `ig board`: conversations as cards on a kanban-style triage board
+//!
+//! Four columns — Inbox, Waiting on them, Waiting on me and Done — turn
+//! the DM inbox into a lightweight support queue. A thread's column is kept
+//! as a tag (`waiting-on-them`, `waiting-on-me`, `done`), so it also shows
+//! as a chip in `ig inbox` and works with `ig inbox --tag done`; threads
+//! without one are in Inbox. A 1:1 thread's tag is on the other person, a
+//! group chat's on the thread itself, so moving a group leaves its members'
+//! own conversations where they are.
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Modifier;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{self, IsTerminal};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::client::ApiClient;
+use crate::colors::{palette, Theme};
+use crate::commands::fetch_inbox;
+use crate::commands::tags::Tags;
+use crate::commands::tui::{fg, highlight, pane, thread_title};
+use crate::models::Thread;
+use crate::sync::{SyncEvent, SyncHandle, SyncOptions};
+use crate::tasks::Supervisor;
+use crate::util::{ellipsize, format_time_ago};
+
+/// Seconds between sync polls
+const BOARD_POLL_INTERVAL: u64 = 5;
+
+/// How long to wait for a key before looking for sync events
+const TICK: Duration = Duration::from_millis(100);
+
+/// Key help shown in the status line when there's nothing to report
+const HELP: &str = "←/→: Column  ↑/↓: Select  H/L: Move card  1-4: Move to column  r: Refresh  q: Quit";
+
+/// A column of the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Inbox,
+    WaitingOnThem,
+    WaitingOnMe,
+    Done,
+}
+
+impl Column {
+    /// Left to right
+    pub const ALL: [Column; 4] = [Column::Inbox, Column::WaitingOnThem, Column::WaitingOnMe, Column::Done];
+
+    /// Heading shown on the board
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Inbox => "Inbox",
+            Column::WaitingOnThem => "Waiting on them",
+            Column::WaitingOnMe => "Waiting on me",
+            Column::Done => "Done",
+        }
+    }
+
+    /// Tag that puts a thread in this column (None for Inbox)
+    pub fn tag(self) -> Option<&'static str> {
+        match self {
+            Column::Inbox => None,
+            Column::WaitingOnThem => Some("waiting-on-them"),
+            Column::WaitingOnMe => Some("waiting-on-me"),
+            Column::Done => Some("done"),
+        }
+    }
+
+    /// The column a thread is in, from its board tag (the rightmost wins
+    /// if it somehow has more than one)
+    pub fn of(tags: &Tags, thread: &Thread) -> Column {
+        let board_tags = match thread.users.as_slice() {
+            [user] => tags.for_user(&user.username),
+            _ => tags.by_thread.get(&thread.id),
+        };
+        Column::ALL
+            .into_iter()
+            .rev()
+            .find(|column| column.tag().is_some_and(|tag| board_tags.is_some_and(|t| t.contains(tag))))
+            .unwrap_or(Column::Inbox)
+    }
+
+    fn index(self) -> usize {
+        Column::ALL.iter().position(|&c| c == self).unwrap_or_default()
+    }
+}
+
+/// Put a thread in `column`, replacing its board tag: the other person's
+/// for a 1:1 thread, the thread's own for a group (other tags are kept)
+pub fn move_thread(tags: &mut Tags, thread: &Thread, column: Column) {
+    let set = match thread.users.as_slice() {
+        [user] => tags.by_user.entry(user.username.to_lowercase()).or_default(),
+        _ => tags.by_thread.entry(thread.id.clone()).or_default(),
+    };
+    for other in Column::ALL.iter().filter_map(|c| c.tag()) {
+        set.remove(other);
+    }
+    if let Some(tag) = column.tag() {
+        set.insert(tag.to_string());
+    }
+    tags.by_user.retain(|_, set| !set.is_empty());
+    tags.by_thread.retain(|_, set| !set.is_empty());
+}
+
+/// Everything on screen
+struct Board {
+    /// Most recent first, as in the inbox
+    threads: Vec<Arc<Thread>>,
+    tags: Tags,
+    /// Threads in each column, in inbox order
+    columns: [Vec<Arc<Thread>>; 4],
+    lists: [ListState; 4],
+    focus: usize,
+    status: Option<(String, bool)>,
+}
+
+/// Open the board on the `limit` most recent conversations
+pub async fn run_board(client: &ApiClient, limit: u32) -> Result<()> {
+    if !io::stdout().is_terminal() {
+        anyhow::bail!("ig board needs a terminal");
+    }
+
+    let response = fetch_inbox(client, Some(limit)).await?;
+    if !response.success {
+        println!(
+            "{} {}",
+            Theme::cross(),
+            Theme::error(&response.error.unwrap_or("Failed to fetch inbox".to_string()))
+        );
+        return Ok(());
+    }
+
+    let mut tasks = Supervisor::new();
+    let sync = SyncHandle::spawn(
+        &mut tasks,
+        client,
+        SyncOptions {
+            interval: Duration::from_secs(BOARD_POLL_INTERVAL),
+            inbox_limit: limit,
+            inbox_messages: false,
+            delta: false,
+            threads: Vec::new(),
+        },
+    );
+    let mut events = sync.subscribe();
+
+    let mut board = Board::new(response.threads.unwrap_or_default(), Tags::load()?);
+    let mut terminal = ratatui::try_init()?;
+    let result = board.run(&mut terminal, client, &mut events, limit).await;
+    ratatui::restore();
+    tasks.shutdown().await;
+    result
+}
+
+impl Board {
+    fn new(threads: Vec<Thread>, tags: Tags) -> Self {
+        let mut board = Self {
+            threads: threads.into_iter().map(Arc::new).collect(),
+            tags,
+            columns: Default::default(),
+            lists: Default::default(),
+            focus: 0,
+            status: None,
+        };
+        board.regroup();
+        board
+    }
+
+    async fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        client: &ApiClient,
+        events: &mut broadcast::Receiver<SyncEvent>,
+        limit: u32,
+    ) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !self.handle_key(key, client, limit).await {
+                        return Ok(());
+                    }
+                }
+            }
+
+            loop {
+                match events.try_recv() {
+                    Ok(event) => self.apply(event),
+                    Err(TryRecvError::Lagged(_)) => continue,
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+
+    /// Sort the threads into columns again, keeping each column's selection
+    /// on the same thread where it's still there
+    fn regroup(&mut self) {
+        let selected: Vec<Option<String>> = (0..Column::ALL.len()).map(|i| self.selected_in(i)).collect();
+        self.columns = Default::default();
+        for thread in &self.threads {
+            self.columns[Column::of(&self.tags, thread).index()].push(Arc::clone(thread));
+        }
+        for (i, id) in selected.into_iter().enumerate() {
+            let cards = &self.columns[i];
+            let index = id
+                .and_then(|id| cards.iter().position(|t| t.id == id))
+                .or(self.lists[i].selected().map(|s| s.min(cards.len().saturating_sub(1))))
+                .or(Some(0))
+                .filter(|_| !cards.is_empty());
+            self.lists[i].select(index);
+        }
+    }
+
+    fn selected_in(&self, column: usize) -> Option<String> {
+        let index = self.lists[column].selected()?;
+        self.columns[column].get(index).map(|t| t.id.clone())
+    }
+
+    fn selected(&self) -> Option<&Arc<Thread>> {
+        self.columns[self.focus].get(self.lists[self.focus].selected()?)
+    }
+
+    /// Handle a key press; false to quit
+    async fn handle_key(&mut self, key: KeyEvent, client: &ApiClient, limit: u32) -> bool {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return false;
+        }
+        match key.code {
+            KeyCode::Left if shift => self.move_selected(self.focus.checked_sub(1)),
+            KeyCode::Right if shift => self.move_selected(Some(self.focus + 1)),
+            KeyCode::Char('H') => self.move_selected(self.focus.checked_sub(1)),
+            KeyCode::Char('L') => self.move_selected(Some(self.focus + 1)),
+            KeyCode::Char(c @ '1'..='4') => self.move_selected(c.to_digit(10).map(|d| d as usize - 1)),
+            KeyCode::Left | KeyCode::Char('h') => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.focus = (self.focus + 1).min(Column::ALL.len() - 1),
+            KeyCode::Up | KeyCode::Char('k') => self.select_offset(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select_offset(1),
+            KeyCode::Char('r') => self.refresh(client, limit).await,
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            _ => {}
+        }
+        true
+    }
+
+    fn select_offset(&mut self, offset: isize) {
+        let list = &mut self.lists[self.focus];
+        let Some(current) = list.selected() else {
+            return;
+        };
+        let last = self.columns[self.focus].len().saturating_sub(1);
+        list.select(Some(current.saturating_add_signed(offset).min(last)));
+        self.status = None;
+    }
+
+    /// Move the selected card to another column and save it; the focus
+    /// stays put, on the next card down
+    fn move_selected(&mut self, to: Option<usize>) {
+        let (Some(thread), Some(&column)) = (self.selected().cloned(), to.and_then(|i| Column::ALL.get(i))) else {
+            return;
+        };
+        if column.index() == self.focus {
+            return;
+        }
+        move_thread(&mut self.tags, &thread, column);
+        self.status = Some(match self.tags.save() {
+            Ok(()) => (format!("Moved {} to {}", thread_title(&thread), column.title()), false),
+            Err(e) => (format!("Couldn't save the board: {:#}", e), true),
+        });
+        self.regroup();
+    }
+
+    /// Fetch the inbox again, and reread the tags in case `ig tag` changed
+    /// them meanwhile (keeping the ones we have if they can't be read, so
+    /// the next move doesn't save over them)
+    async fn refresh(&mut self, client: &ApiClient, limit: u32) {
+        match client.get_inbox(limit).await {
+            Ok(response) if response.success => {
+                self.threads = response.threads.unwrap_or_default().into_iter().map(Arc::new).collect();
+                self.status = match Tags::load() {
+                    Ok(tags) => {
+                        self.tags = tags;
+                        None
+                    }
+                    Err(e) => Some((format!("Couldn't reload tags: {:#}", e), true)),
+                };
+                self.regroup();
+            }
+            Ok(response) => {
+                self.status = Some((response.error.unwrap_or("Failed to fetch inbox".to_string()), true));
+            }
+            Err(e) => self.status = Some((format!("{:#}", e), true)),
+        }
+    }
+
+    fn apply(&mut self, event: SyncEvent) {
+        match event {
+            SyncEvent::ThreadUpdated { thread, new_activity } => {
+                let position = self.threads.iter().position(|t| t.id == thread.id);
+                if let Some(position) = position {
+                    self.threads.remove(position);
+                }
+                let position = position.filter(|_| !new_activity).unwrap_or(0);
+                self.threads.insert(position.min(self.threads.len()), thread);
+                self.regroup();
+            }
+            SyncEvent::PollFailed(error) => self.status = Some((error, true)),
+            event => {
+                if let Some(notice) = event.server_notice() {
+                    self.status = Some((notice.to_string(), true));
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let areas = Layout::horizontal([Constraint::Ratio(1, 4); 4]).split(main);
+        for (i, area) in areas.iter().enumerate() {
+            self.draw_column(frame, i, *area);
+        }
+
+        let line = match &self.status {
+            Some((text, false)) => Line::styled(text.as_str(), fg(palette().green)),
+            Some((text, true)) => Line::styled(format!("⚠ {}", text), fg(palette().yellow)),
+            None => Line::styled(HELP, fg(palette().gray)),
+        };
+        frame.render_widget(Paragraph::new(line), status);
+    }
+
+    fn draw_column(&mut self, frame: &mut Frame, index: usize, area: Rect) {
+        let width = area.width.saturating_sub(6) as usize;
+        let items: Vec<ListItem> = self.columns[index]
+            .iter()
+            .map(|thread| {
+                let unread = thread.has_unread.unwrap_or(false);
+                let mut title_style = fg(palette().orange);
+                if unread {
+                    title_style = title_style.add_modifier(Modifier::BOLD);
+                }
+                let time = thread.last_message_timestamp.as_deref().map(format_time_ago).unwrap_or_default();
+                let preview = thread.last_message_text.as_deref().unwrap_or("[media]");
+                ListItem::new(vec![
+                    Line::from(vec![
+                        Span::styled(if unread { "● " } else { "  " }, fg(palette().blue)),
+                        Span::styled(ellipsize(&thread_title(thread), width), title_style),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  ", fg(palette().gray)),
+                        Span::raw(ellipsize(&preview.replace('\n', " "), width)),
+                    ]),
+                    Line::styled(format!("  {}", time), fg(palette().gray)),
+                ])
+            })
+            .collect();
+
+        let column = Column::ALL[index];
+        let title = format!(" {} ({}) ", column.title(), self.columns[index].len());
+        let focused = index == self.focus;
+        let mut list = List::new(items).block(pane(&title, focused));
+        if focused {
+            list = list.highlight_style(highlight()).highlight_symbol("►");
+        }
+        frame.render_stateful_widget(list, area, &mut self.lists[index]);
+    }
+}
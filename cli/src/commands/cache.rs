@@ -0,0 +1,89 @@
+//! Local data retention (`ig cache vacuum`)
+//!
+//! `keep_messages` under `[cache]` in the config limits how long incidental
+//! copies of message text stay in the data directory (the audit trail,
+//! which records the text of keyword alerts, and the cached responses kept
+//! for conditional requests), and `keep_media` how long the files
+//! `ig download` saved are kept. `ig notify` applies both periodically;
+//! `ig cache vacuum` applies them on demand.
+//!
+//! Transcripts are left out on purpose: they're records the user chose to
+//! keep, so they follow their own `keep` under `[transcript]` (applied
+//! whenever a chat or `ig tail` opens them) and a shorter cache retention
+//! never deletes them.
+
+use anyhow::Result;
+use chrono::Local;
+
+use crate::audit;
+use crate::colors::Theme;
+use crate::commands::download::prune_downloads;
+use crate::config::Config;
+use crate::duration::parse_duration;
+use crate::responses;
+
+/// What a vacuum removed, for the kinds of data with a retention set
+#[derive(Debug, Default)]
+pub(crate) struct Vacuumed {
+    /// Audit entries and cached responses past `keep_messages`
+    pub messages: Option<usize>,
+    /// Files `ig download` saved, past `keep_media`
+    pub media: Option<usize>,
+}
+
+impl Vacuumed {
+    pub fn is_empty(&self) -> bool {
+        self.messages.unwrap_or(0) == 0 && self.media.unwrap_or(0) == 0
+    }
+}
+
+impl std::fmt::Display for Vacuumed {
+    /// "3 stored message(s) and 2 downloaded file(s)"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = [
+            self.messages.map(|n| format!("{} stored message(s)", n)),
+            self.media.map(|n| format!("{} downloaded file(s)", n)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(f, "{}", parts.join(" and "))
+    }
+}
+
+/// Apply the retention settings; `None` if nothing is configured, else what
+/// was removed
+pub(crate) fn vacuum() -> Result<Option<Vacuumed>> {
+    let cache = Config::load()?.cache;
+    if cache.keep_messages.is_none() && cache.keep_media.is_none() {
+        return Ok(None);
+    }
+    let mut vacuumed = Vacuumed::default();
+    if let Some(keep) = &cache.keep_messages {
+        let cutoff = Local::now() - parse_duration(keep)?;
+        vacuumed.messages = Some(audit::prune(cutoff)? + responses::prune(cutoff));
+    }
+    if let Some(keep) = &cache.keep_media {
+        vacuumed.media = Some(prune_downloads(Local::now() - parse_duration(keep)?)?);
+    }
+    Ok(Some(vacuumed))
+}
+
+/// Remove local data older than the retention settings allow
+pub fn vacuum_cache() -> Result<()> {
+    let Some(removed) = vacuum()? else {
+        println!(
+            "{}",
+            Theme::muted(
+                "No retention configured; set keep_messages or keep_media under [cache] in the config (e.g. \"180d\")."
+            )
+        );
+        return Ok(());
+    };
+    println!(
+        "{} {}",
+        Theme::check(),
+        Theme::success(&format!("Removed {} past the retention period", removed))
+    );
+    Ok(())
+}
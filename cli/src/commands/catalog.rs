@@ -0,0 +1,76 @@
+//! `ig catalog`: saved replies sent by shortcode in `ig chat`
+
+use anyhow::Result;
+
+use crate::catalog::{normalize, valid_code, Catalog};
+use crate::colors::Theme;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::util::truncate;
+
+/// Save `text` under `code`, replacing what was there
+/// (`ig catalog add SKU123 "Price is $40, ships in 2 days"`)
+pub fn add_catalog_item(code: &str, text: &str) -> Result<()> {
+    if !valid_code(code) {
+        anyhow::bail!("Shortcodes can only use letters, digits, '_' and '-' (got '{}')", code);
+    }
+    let text = text.trim();
+    if text.is_empty() {
+        anyhow::bail!("Give the reply to save (ig catalog add {} \"text\")", normalize(code));
+    }
+
+    let code = normalize(code);
+    let mut catalog = Catalog::load()?;
+    let replaced = catalog.by_code.insert(code.clone(), text.to_string()).is_some();
+    catalog.save()?;
+    let verb = if replaced { "Updated" } else { "Saved" };
+    println!(
+        "{} {}",
+        Theme::check(),
+        Theme::success(&format!("{} #{} — type it in ig chat to send the reply", verb, code))
+    );
+    Ok(())
+}
+
+/// Remove `code` from the catalog
+pub fn remove_catalog_item(code: &str) -> Result<()> {
+    let code = normalize(code);
+    let mut catalog = Catalog::load()?;
+    if catalog.by_code.remove(&code).is_none() {
+        anyhow::bail!("No catalog entry #{} (see ig catalog list)", code);
+    }
+    catalog.save()?;
+    println!("{} {}", Theme::check(), Theme::success(&format!("Removed #{}", code)));
+    Ok(())
+}
+
+/// List the catalog
+pub fn list_catalog(output: OutputFormat) -> Result<()> {
+    let catalog = Catalog::load()?;
+
+    if output == OutputFormat::Json {
+        return print_json(&catalog.by_code);
+    }
+
+    if catalog.by_code.is_empty() {
+        println!("{}", Theme::muted("The catalog is empty. Add a reply with: ig catalog add SKU123 \"text\""));
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Code", "Reply"]);
+        for (code, text) in &catalog.by_code {
+            table.row([format!("#{}", code), text.clone()]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Catalog"));
+    println!("{}", Theme::separator(60));
+    for (code, text) in &catalog.by_code {
+        println!("  {} {}", Theme::username(&format!("#{}", code)), truncate(text, 60));
+    }
+    println!("{}", Theme::separator(60));
+    Ok(())
+}
@@ -0,0 +1,362 @@
+//! Searching a chat's history (`/` in `ig chat`)
+//!
+//! Pressing `/` on an empty chat line opens a search over the conversation's
+//! loaded messages. Every match is highlighted; `n` steps to the next older
+//! match and `N` back to newer ones. Past the oldest loaded match, `n`
+//! fetches the page before it, so a search reaches as far back as the
+//! conversation goes without loading all of it up front.
+
+use anyhow::Result;
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute, terminal,
+};
+use std::io::{self, Write};
+use std::ops::Range;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::pages::PAGE_SIZE;
+use crate::models::{Message, User};
+use crate::render::Canvas;
+use crate::spinner::create_spinner;
+use crate::users;
+use crate::util::{ellipsize, format_time_ago};
+
+/// Messages shown above and below a match
+pub const SEARCH_CONTEXT: usize = 2;
+
+const REGION_HEADER: usize = 0;
+const REGION_MESSAGES: usize = 1;
+const REGION_FOOTER: usize = 2;
+
+/// Byte ranges of `text` that match `query`, ignoring case
+pub fn match_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    let needle: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        // Lowercase the text one character at a time so the ranges stay
+        // on the original's character boundaries
+        let mut matched = 0;
+        let mut j = i;
+        while matched < needle.len() && j < chars.len() {
+            let lower: Vec<char> = chars[j].1.to_lowercase().collect();
+            if !needle[matched..].starts_with(&lower) {
+                break;
+            }
+            matched += lower.len();
+            j += 1;
+        }
+
+        if matched == needle.len() {
+            let end = chars.get(j).map(|&(at, _)| at).unwrap_or(text.len());
+            ranges.push(chars[i].0..end);
+            i = j;
+        } else {
+            i += 1;
+        }
+    }
+    ranges
+}
+
+/// Positions of the messages whose text matches `query`, newest first
+/// (`messages` are newest first, as the server sends them)
+pub fn find_matches(messages: &[Message], query: &str) -> Vec<usize> {
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.text.as_deref().is_some_and(|t| !match_ranges(t, query).is_empty()))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// `text` with every match of `query` highlighted
+pub fn highlight_matches(text: &str, query: &str) -> String {
+    let mut out = String::new();
+    let mut copied = 0;
+    for range in match_ranges(text, query) {
+        out.push_str(&text[copied..range.start]);
+        out.push_str(&Theme::accent(&text[range.clone()]).bold().reversed().to_string());
+        copied = range.end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// One line of at most `width` characters from `text`, starting a little
+/// before the first match when it would otherwise be cut off
+pub fn excerpt(text: &str, query: &str, width: usize) -> String {
+    let flat = text.replace(['\r', '\n'], " ");
+    let Some(first) = match_ranges(&flat, query).into_iter().next() else {
+        return ellipsize(&flat, width);
+    };
+
+    let start = flat[..first.start].chars().count();
+    let len = flat[first].chars().count();
+    if start + len <= width {
+        return ellipsize(&flat, width);
+    }
+    let from = start.saturating_sub(width / 3);
+    let rest: String = flat.chars().skip(from).collect();
+    format!("...{}", ellipsize(&rest, width.saturating_sub(3)))
+}
+
+/// A search over a thread's messages, loaded a page at a time
+struct Search {
+    thread_id: String,
+    users: Vec<User>,
+    /// Loaded messages, newest first
+    messages: Vec<Message>,
+    /// Cursor of the next older page; None once the oldest is loaded
+    older: Option<String>,
+    query: String,
+    matches: Vec<usize>,
+    /// Position in `matches` being shown
+    current: usize,
+    /// One-line note for the footer (e.g. "No newer matches")
+    status: Option<String>,
+}
+
+impl Search {
+    fn set_query(&mut self, query: String) {
+        self.matches = find_matches(&self.messages, &query);
+        self.query = query;
+        self.current = 0;
+        self.status = None;
+    }
+
+    /// Load the page before the oldest loaded message, if there is one
+    async fn load_older(&mut self, client: &ApiClient) -> Result<()> {
+        let Some(cursor) = self.older.take() else {
+            return Ok(());
+        };
+        let response = client.get_thread_page(&self.thread_id, PAGE_SIZE, &cursor).await?;
+        if !response.success {
+            self.older = Some(cursor);
+            anyhow::bail!(response.error.unwrap_or_else(|| "Failed to load older messages".to_string()));
+        }
+        self.older = response.next_cursor;
+
+        let from = self.messages.len();
+        let page = response.thread.and_then(|t| t.messages).unwrap_or_default();
+        self.messages.extend(page);
+        self.matches.extend(find_matches(&self.messages[from..], &self.query).into_iter().map(|i| i + from));
+        Ok(())
+    }
+
+    /// Step to the next older match, loading older pages until one turns
+    /// up or the start of the conversation is reached
+    async fn older_match(&mut self, client: &ApiClient, canvas: &mut Canvas, stdout: &mut io::Stdout) -> Result<()> {
+        self.status = None;
+        let next = if self.matches.is_empty() { 0 } else { self.current + 1 };
+        while next >= self.matches.len() {
+            if self.older.is_none() {
+                self.status = Some(if self.matches.is_empty() {
+                    "No matches in the whole conversation".to_string()
+                } else {
+                    "No older matches".to_string()
+                });
+                return Ok(());
+            }
+            canvas.set(REGION_FOOTER, vec![Theme::muted("Searching older messages...").to_string()]);
+            canvas.draw(stdout)?;
+            if let Err(e) = self.load_older(client).await {
+                self.status = Some(format!("{:#}", e));
+                return Ok(());
+            }
+        }
+        self.current = next;
+        Ok(())
+    }
+
+    fn newer_match(&mut self) {
+        self.status = None;
+        match self.current.checked_sub(1) {
+            Some(previous) if !self.matches.is_empty() => self.current = previous,
+            _ => self.status = Some("No newer matches".to_string()),
+        }
+    }
+
+    fn header(&self) -> Vec<String> {
+        let counts = if self.matches.is_empty() {
+            Theme::muted(&format!("no matches in {} messages", self.messages.len()))
+        } else {
+            Theme::muted(&format!(
+                "match {} of {} in {} messages",
+                self.current + 1,
+                self.matches.len(),
+                self.messages.len()
+            ))
+        };
+        vec![
+            String::new(),
+            format!("{} {} {}", Theme::header("Search"), Theme::accent(&format!("\"{}\"", self.query)), counts),
+            Theme::separator(60).to_string(),
+        ]
+    }
+
+    /// The current match with [`SEARCH_CONTEXT`] messages either side, oldest first
+    fn message_lines(&self, width: usize) -> Vec<String> {
+        let Some(&at) = self.matches.get(self.current) else {
+            let hint = if self.older.is_some() {
+                "Press n to search older messages."
+            } else {
+                "Press / to search for something else."
+            };
+            return vec![Theme::muted(hint).to_string()];
+        };
+
+        let newest = at.saturating_sub(SEARCH_CONTEXT);
+        let oldest = (at + SEARCH_CONTEXT).min(self.messages.len() - 1);
+        (newest..=oldest)
+            .rev()
+            .map(|i| {
+                let msg = &self.messages[i];
+                let sender = msg
+                    .user_id
+                    .as_ref()
+                    .and_then(|uid| users::username(&self.users, uid))
+                    .unwrap_or("You".to_string());
+                let time = msg.timestamp.as_deref().map(format_time_ago).unwrap_or_default();
+                let prefix = format!("{} {} ", sender, time);
+                let room = width.saturating_sub(prefix.chars().count() + 2);
+                let text = excerpt(msg.text.as_deref().unwrap_or("[media]"), &self.query, room);
+                let marker = if i == at { Theme::accent("►") } else { " ".into() };
+                format!(
+                    "{} {} {} {}",
+                    marker,
+                    Theme::pink(&sender),
+                    Theme::timestamp(&time),
+                    highlight_matches(&text, &self.query)
+                )
+            })
+            .collect()
+    }
+
+    fn footer(&self) -> Vec<String> {
+        let mut lines = vec![Theme::separator(60).to_string()];
+        if let Some(status) = &self.status {
+            lines.push(Theme::warning(status).to_string());
+        }
+        lines.push(Theme::muted("n: Older match  N: Newer match  /: New search  q: Back to chat").to_string());
+        lines
+    }
+}
+
+/// Search a thread's history from the chat (see the module docs). Returns
+/// once the user goes back to the chat.
+pub async fn search_chat(client: &ApiClient, thread_id: &str) -> Result<()> {
+    let spinner = create_spinner("Loading messages...");
+    let response = client.get_thread_page(thread_id, PAGE_SIZE, "").await;
+    spinner.finish_and_clear();
+    let response = response?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or_else(|| "Failed to load messages".to_string()));
+    }
+    let thread = response.thread.unwrap_or_default();
+
+    let mut search = Search {
+        thread_id: thread_id.to_string(),
+        users: thread.users,
+        messages: thread.messages.unwrap_or_default(),
+        older: response.next_cursor,
+        query: String::new(),
+        matches: Vec::new(),
+        current: 0,
+        status: None,
+    };
+
+    let mut stdout = io::stdout();
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+    terminal::enable_raw_mode()?;
+    let result = run(client, &mut search, &mut stdout).await;
+    terminal::disable_raw_mode()?;
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    result
+}
+
+async fn run(client: &ApiClient, search: &mut Search, stdout: &mut io::Stdout) -> Result<()> {
+    let mut canvas = Canvas::new(3);
+    let mut width = terminal::size()
+        .ok()
+        .filter(|&(cols, _)| cols > 0)
+        .map(|(cols, _)| cols as usize)
+        .unwrap_or(80);
+
+    let Some(query) = prompt_query(&mut canvas, stdout)? else {
+        return Ok(());
+    };
+    search.set_query(query);
+
+    loop {
+        canvas.set(REGION_HEADER, search.header());
+        canvas.set(REGION_MESSAGES, search.message_lines(width));
+        canvas.set(REGION_FOOTER, search.footer());
+        canvas.draw(stdout)?;
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            Event::Resize(cols, rows) => {
+                width = cols as usize;
+                canvas.resize(rows);
+                continue;
+            }
+            _ => continue,
+        };
+        match key.code {
+            KeyCode::Char('n') => search.older_match(client, &mut canvas, stdout).await?,
+            KeyCode::Char('N') => search.newer_match(),
+            KeyCode::Char('/') => {
+                if let Some(query) = prompt_query(&mut canvas, stdout)? {
+                    search.set_query(query);
+                }
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+/// Ask for a search term in the footer; `None` if cancelled with Esc or
+/// left empty
+fn prompt_query(canvas: &mut Canvas, stdout: &mut io::Stdout) -> Result<Option<String>> {
+    let mut input = String::new();
+    execute!(stdout, cursor::Show)?;
+    let query = loop {
+        canvas.set(
+            REGION_FOOTER,
+            vec![
+                Theme::separator(60).to_string(),
+                format!("{} {}", Theme::prompt("/"), input),
+            ],
+        );
+        canvas.draw(stdout)?;
+        stdout.flush()?;
+
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Some(input.trim().to_string()),
+                KeyCode::Esc => break None,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Event::Resize(_, rows) => canvas.resize(rows),
+            _ => {}
+        }
+    };
+    execute!(stdout, cursor::Hide)?;
+    Ok(query.filter(|q| !q.is_empty()))
+}
@@ -0,0 +1,98 @@
+//! A message in context (`ig context`)
+//!
+//! Shows the messages just before and after one message, e.g. one found
+//! with `ig search-run` or linked with `ig link`. The history is paged back
+//! only until the message and the context before it are loaded, so it
+//! works for old messages without fetching the whole conversation.
+
+use anyhow::Result;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::pages::fetch_thread_until;
+use crate::nicknames;
+use crate::users;
+use crate::util::format_time_ago;
+
+/// Show `context` messages either side of `message_id`, with it marked
+pub async fn show_context(client: &ApiClient, thread_id: &str, message_id: &str, context: usize) -> Result<()> {
+    // Newest first, so the context before the message is further along
+    let response = fetch_thread_until(client, thread_id, "Looking for the message", |messages| {
+        messages
+            .iter()
+            .position(|m| m.id == message_id)
+            .is_some_and(|i| messages.len() > i + context)
+    })
+    .await?;
+
+    if !response.success {
+        println!(
+            "{} {}",
+            Theme::cross(),
+            Theme::error(&response.error.unwrap_or("Failed to fetch thread".to_string()))
+        );
+        return Ok(());
+    }
+    let Some(thread) = response.thread else {
+        println!("{}", Theme::muted("Thread not found."));
+        return Ok(());
+    };
+    users::remember(&thread.users);
+
+    // Oldest first
+    let mut messages = thread.messages.unwrap_or_default();
+    messages.reverse();
+    let Some(index) = messages.iter().position(|m| m.id == message_id) else {
+        println!(
+            "{} {}",
+            Theme::warn_icon(),
+            Theme::warning(&format!("Message {} not found in this thread", message_id))
+        );
+        return Ok(());
+    };
+
+    println!();
+    let participants: Vec<String> = thread.users.iter().map(|u| nicknames::name(&u.username)).collect();
+    println!(
+        "{} {}",
+        Theme::header("Conversation with:"),
+        Theme::username(&participants.join(", "))
+    );
+    println!("{}", Theme::separator(60));
+
+    let start = index.saturating_sub(context);
+    let end = (index + context + 1).min(messages.len());
+    if start > 0 {
+        println!("{}", Theme::muted("  … earlier messages"));
+        println!();
+    }
+    for (i, msg) in messages.iter().enumerate().take(end).skip(start) {
+        let sender = msg
+            .user_id
+            .as_ref()
+            .and_then(|uid| users::username(&thread.users, uid))
+            .map(|username| nicknames::name(&username))
+            .unwrap_or("You".to_string());
+        let text = msg.text.as_deref().unwrap_or("[media]");
+        let time = msg.timestamp.as_ref().map(|t| format_time_ago(t)).unwrap_or_default();
+
+        if i == index {
+            println!("{} {} {}", Theme::accent("▶"), Theme::pink(&sender), Theme::timestamp(&time));
+            println!("  {}", Theme::accent(text));
+        } else {
+            println!("{} {}", Theme::pink(&sender), Theme::timestamp(&time));
+            println!("  {}", text);
+        }
+        println!();
+    }
+    if end < messages.len() {
+        println!("{}", Theme::muted(&format!("  … {} newer", messages.len() - end)));
+    }
+
+    println!("{}", Theme::separator(60));
+    println!(
+        "{}",
+        Theme::muted(&format!("Thread ID: {} · `ig thread {}` shows the rest", thread_id, thread_id))
+    );
+    Ok(())
+}
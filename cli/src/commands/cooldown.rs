@@ -0,0 +1,158 @@
+//! Login lockout protection
+//!
+//! When Instagram answers a login with "please wait a few minutes", retrying
+//! right away only makes it worse and can get the account flagged. The CLI
+//! records a cooldown (doubling on every consecutive rate limit) and refuses
+//! to log in again until it has passed, unless `--force` is given.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use crossterm::{cursor, execute, terminal};
+use serde::{Deserialize, Serialize};
+use std::io::{self, IsTerminal, Write};
+
+use crate::colors::Theme;
+use crate::store;
+
+/// Data file holding the login cooldown
+const COOLDOWN_FILE: &str = "login_cooldown";
+
+/// Cooldown after the first rate limit
+const BASE_COOLDOWN_MINUTES: i64 = 5;
+
+/// Longest cooldown, however many rate limits in a row
+const MAX_COOLDOWN_MINUTES: i64 = 60;
+
+/// Phrases in login errors that mean the account is being rate limited
+const RATE_LIMIT_PHRASES: [&str; 3] = ["wait a few minutes", "rate limit", "too many"];
+
+/// Login cooldown state
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LoginCooldown {
+    /// No login attempts before this time
+    pub until: Option<DateTime<Local>>,
+    /// Rate limits in a row (reset by a successful login)
+    pub strikes: u32,
+}
+
+impl LoginCooldown {
+    /// Load the cooldown from disk
+    pub fn load() -> Result<Self> {
+        store::load(COOLDOWN_FILE)
+    }
+
+    /// Save the cooldown to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(COOLDOWN_FILE, self)
+    }
+
+    /// Time left before the next login attempt, if still cooling down
+    pub fn remaining(&self) -> Option<Duration> {
+        let left = self.until? - Local::now();
+        (left > Duration::zero()).then_some(left)
+    }
+}
+
+/// Refuse to log in while a cooldown is active (unless forced)
+pub fn check_login_cooldown(force: bool) -> Result<()> {
+    let cooldown = LoginCooldown::load().unwrap_or_default();
+    let Some(left) = cooldown.remaining() else {
+        return Ok(());
+    };
+
+    if force {
+        println!(
+            "{} {}",
+            Theme::warn_icon(),
+            Theme::warning(&format!(
+                "Ignoring login cooldown ({} left). Repeated attempts may get the account flagged.",
+                format_remaining(left)
+            ))
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} {}",
+        Theme::cross(),
+        Theme::error(&format!(
+            "Instagram asked us to slow down. Try again in {}.",
+            format_remaining(left)
+        ))
+    );
+    println!("{}", Theme::muted("Use --force to try anyway."));
+    anyhow::bail!("Login cooldown active")
+}
+
+/// Record the outcome of a failed login; starts a cooldown and shows the
+/// timer if Instagram rate limited us
+pub async fn note_login_failure(error: &str) -> Result<()> {
+    let lower = error.to_lowercase();
+    if !RATE_LIMIT_PHRASES.iter().any(|p| lower.contains(p)) {
+        return Ok(());
+    }
+
+    let mut cooldown = LoginCooldown::load().unwrap_or_default();
+    let minutes = (BASE_COOLDOWN_MINUTES << cooldown.strikes.min(4)).min(MAX_COOLDOWN_MINUTES);
+    cooldown.strikes += 1;
+    cooldown.until = Some(Local::now() + Duration::minutes(minutes));
+    cooldown.save()?;
+
+    println!(
+        "{} {}",
+        Theme::warn_icon(),
+        Theme::warning(&format!(
+            "Rate limited by Instagram. Login is locked for {} minutes.",
+            minutes
+        ))
+    );
+    show_countdown(&cooldown).await
+}
+
+/// Clear the cooldown after a successful login
+pub fn clear_login_cooldown() -> Result<()> {
+    let cooldown = LoginCooldown::load().unwrap_or_default();
+    if cooldown.until.is_none() && cooldown.strikes == 0 {
+        return Ok(());
+    }
+    LoginCooldown::default().save()
+}
+
+/// Count down the cooldown in place until it ends or Ctrl+C
+async fn show_countdown(cooldown: &LoginCooldown) -> Result<()> {
+    if !io::stdout().is_terminal() {
+        return Ok(());
+    }
+
+    println!("{}", Theme::muted("Press Ctrl+C to stop waiting (the lock stays in place)."));
+    let mut stdout = io::stdout();
+    execute!(stdout, cursor::Hide)?;
+
+    while let Some(left) = cooldown.remaining() {
+        execute!(stdout, cursor::MoveToColumn(0), terminal::Clear(terminal::ClearType::CurrentLine))?;
+        write!(
+            stdout,
+            "{} {}",
+            Theme::accent("⏳"),
+            Theme::muted(&format!("Cooldown: {} left", format_remaining(left)))
+        )?;
+        stdout.flush()?;
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(1)) => {}
+        }
+    }
+
+    execute!(stdout, cursor::MoveToColumn(0), terminal::Clear(terminal::ClearType::CurrentLine), cursor::Show)?;
+    if cooldown.remaining().is_none() {
+        println!("{} {}", Theme::check(), Theme::success("Cooldown over. You can log in again."));
+    }
+    Ok(())
+}
+
+/// Format a duration as "m:ss"
+fn format_remaining(left: Duration) -> String {
+    let secs = left.num_seconds().max(0);
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
@@ -0,0 +1,253 @@
+//! Debugging aids (`ig debug`, hidden from `--help`)
+//!
+//! `ig debug thread-diff` fetches a thread twice and lists the messages
+//! that were added, removed or changed in between, plus duplicate IDs and
+//! reordering within a response. With `--cached`, the second fetch goes
+//! through the conditional-request cache the pollers use and is compared
+//! against a fresh download, which shows whether a 304 handed back a stale
+//! body. Both are what to reach for when live chat or `ig tail` shows a
+//! message twice, misses one or reports reactions that didn't change.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::models::{Message, Thread};
+use crate::spinner::create_spinner;
+use crate::users;
+use crate::util::ellipsize;
+
+/// Width of message text in the diff
+const TEXT_WIDTH: usize = 50;
+
+/// A field that differs between two versions of a message
+#[derive(Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+/// What changed between two fetches of a thread
+#[derive(Debug, Default)]
+pub struct ThreadDiff<'a> {
+    /// Messages only in the second fetch
+    pub added: Vec<&'a Message>,
+    /// Messages only in the first fetch
+    pub removed: Vec<&'a Message>,
+    /// Messages in both whose fields differ, in the second fetch's order
+    pub changed: Vec<(&'a Message, Vec<FieldChange>)>,
+    pub unchanged: usize,
+    /// Whether the messages in both come back in a different order
+    pub reordered: bool,
+    /// IDs that appear more than once in the first or second fetch
+    pub duplicates: (Vec<String>, Vec<String>),
+}
+
+impl ThreadDiff<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && !self.reordered
+            && self.duplicates.0.is_empty()
+            && self.duplicates.1.is_empty()
+    }
+}
+
+/// The fields of a message worth comparing, as display strings
+fn fields(message: &Message) -> [(&'static str, String); 7] {
+    let show = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+    [
+        ("text", show(&message.text)),
+        ("timestamp", show(&message.timestamp)),
+        ("user_id", show(&message.user_id)),
+        ("item_type", show(&message.item_type)),
+        ("media_url", show(&message.media_url)),
+        ("link_url", show(&message.link_url)),
+        (
+            "reactions",
+            serde_json::to_string(message.reactions.as_deref().unwrap_or_default()).unwrap_or_default(),
+        ),
+    ]
+}
+
+/// IDs that appear more than once, in order of first repeat
+fn duplicate_ids(messages: &[Message]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    for message in messages {
+        if !seen.insert(message.id.as_str()) && !duplicates.contains(&message.id) {
+            duplicates.push(message.id.clone());
+        }
+    }
+    duplicates
+}
+
+/// Compare two fetches of a thread's messages by message ID
+pub fn diff_messages<'a>(before: &'a [Message], after: &'a [Message]) -> ThreadDiff<'a> {
+    let old: HashMap<&str, &Message> = before.iter().map(|m| (m.id.as_str(), m)).collect();
+    let new: HashMap<&str, &Message> = after.iter().map(|m| (m.id.as_str(), m)).collect();
+
+    let mut diff = ThreadDiff {
+        duplicates: (duplicate_ids(before), duplicate_ids(after)),
+        ..Default::default()
+    };
+    let mut listed = HashSet::new();
+    for message in after {
+        if !listed.insert(message.id.as_str()) {
+            continue;
+        }
+        let Some(previous) = old.get(message.id.as_str()) else {
+            diff.added.push(message);
+            continue;
+        };
+        let changes: Vec<FieldChange> = fields(previous)
+            .into_iter()
+            .zip(fields(message))
+            .filter(|((_, was), (_, is))| was != is)
+            .map(|((field, before), (_, after))| FieldChange { field, before, after })
+            .collect();
+        if changes.is_empty() {
+            diff.unchanged += 1;
+        } else {
+            diff.changed.push((message, changes));
+        }
+    }
+    let mut listed = HashSet::new();
+    diff.removed = before
+        .iter()
+        .filter(|m| !new.contains_key(m.id.as_str()) && listed.insert(m.id.as_str()))
+        .collect();
+
+    let order = |messages: &[Message], other: &HashMap<&str, &Message>| -> Vec<String> {
+        let mut seen = HashSet::new();
+        messages
+            .iter()
+            .filter(|m| other.contains_key(m.id.as_str()) && seen.insert(m.id.as_str()))
+            .map(|m| m.id.clone())
+            .collect()
+    };
+    diff.reordered = order(before, &new) != order(after, &old);
+    diff
+}
+
+/// Fetch a thread and check the response
+async fn fetch(client: &ApiClient, thread_id: &str, limit: u32) -> Result<Thread> {
+    let response = client.get_thread(thread_id, limit).await?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to fetch thread".to_string()));
+    }
+    response
+        .thread
+        .ok_or_else(|| anyhow::anyhow!("Thread {} not found", thread_id))
+}
+
+/// Fetch a thread twice, `wait` apart, and print what changed
+/// (`ig debug thread-diff`)
+pub async fn thread_diff(client: &ApiClient, thread_id: &str, limit: u32, wait: Duration, cached: bool) -> Result<()> {
+    let spinner = create_spinner("Fetching thread...");
+    let first = fetch(client, thread_id, limit).await;
+    spinner.finish_and_clear();
+    let first = first?;
+
+    let spinner = create_spinner(&format!("Fetching again in {}s...", wait.as_secs()));
+    tokio::time::sleep(wait).await;
+    spinner.finish_and_clear();
+
+    // With --cached, compare what the pollers would see (possibly a cached
+    // body replayed after a 304) against a fresh download
+    let spinner = create_spinner("Fetching thread...");
+    let (before, after, labels) = if cached {
+        let polled = fetch(client, thread_id, limit).await;
+        let fresh = fetch(&client.clone().without_conditional_requests(), thread_id, limit).await;
+        spinner.finish_and_clear();
+        (polled?, fresh?, ("polled (cache)", "fresh"))
+    } else {
+        let second = fetch(client, thread_id, limit).await;
+        spinner.finish_and_clear();
+        (first, second?, ("first", "second"))
+    };
+    users::remember(&before.users);
+
+    let old = before.messages.unwrap_or_default();
+    let new = after.messages.unwrap_or_default();
+    let diff = diff_messages(&old, &new);
+
+    println!();
+    println!(
+        "{} {}",
+        Theme::header("Thread diff"),
+        Theme::muted(&format!(
+            "{} · {}: {} messages → {}: {} messages",
+            thread_id,
+            labels.0,
+            old.len(),
+            labels.1,
+            new.len()
+        ))
+    );
+    println!("{}", Theme::separator(60));
+
+    let describe = |message: &Message| {
+        let sender = message
+            .user_id
+            .as_ref()
+            .and_then(|uid| users::username(&before.users, uid))
+            .unwrap_or("You".to_string());
+        let text = ellipsize(message.text.as_deref().unwrap_or("[media]"), TEXT_WIDTH);
+        format!("{} {} {}", Theme::muted(&message.id), Theme::pink(&sender), text)
+    };
+    for message in &diff.added {
+        println!("{} {}", Theme::success("+"), describe(message));
+    }
+    for message in &diff.removed {
+        println!("{} {}", Theme::error("-"), describe(message));
+    }
+    for (message, changes) in &diff.changed {
+        println!("{} {}", Theme::warning("~"), describe(message));
+        for change in changes {
+            println!(
+                "    {} {} → {}",
+                Theme::muted(&format!("{}:", change.field)),
+                ellipsize(&change.before, TEXT_WIDTH),
+                ellipsize(&change.after, TEXT_WIDTH)
+            );
+        }
+    }
+    for (label, ids) in [(labels.0, &diff.duplicates.0), (labels.1, &diff.duplicates.1)] {
+        if !ids.is_empty() {
+            println!(
+                "{} {}",
+                Theme::warn_icon(),
+                Theme::warning(&format!("Duplicate IDs in the {} response: {}", label, ids.join(", ")))
+            );
+        }
+    }
+    if diff.reordered {
+        println!(
+            "{} {}",
+            Theme::warn_icon(),
+            Theme::warning("Messages in both responses come back in a different order")
+        );
+    }
+
+    if diff.is_empty() {
+        println!("{}", Theme::muted(&format!("No differences ({} messages).", diff.unchanged)));
+    } else {
+        println!("{}", Theme::separator(60));
+        println!(
+            "{}",
+            Theme::muted(&format!(
+                "{} added, {} removed, {} changed, {} unchanged",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changed.len(),
+                diff.unchanged
+            ))
+        );
+    }
+    Ok(())
+}
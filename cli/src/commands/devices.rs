@@ -0,0 +1,121 @@
+//! Device management (`ig devices list/forget`)
+
+use anyhow::Result;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::config::Config;
+use crate::models::{Device, DevicesResponse};
+use crate::output::{print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+
+/// List devices that have logged in through the server
+pub async fn list_devices(client: &ApiClient, output: OutputFormat) -> Result<()> {
+    let spinner = create_spinner("Fetching devices...");
+    let response = client.list_devices().await;
+    spinner.finish_and_clear();
+
+    let Some(response) = response? else {
+        print_unsupported();
+        return Ok(());
+    };
+    let Some(devices) = devices_or_error(response) else {
+        return Ok(());
+    };
+
+    if output == OutputFormat::Json {
+        return print_json(&devices);
+    }
+
+    if devices.is_empty() {
+        println!("{}", Theme::muted("No devices have logged in yet."));
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["ID", "Name", "Last login", "Current"]);
+        for device in &devices {
+            table.row([
+                device.id.clone(),
+                device.name.clone(),
+                device.last_login.clone().unwrap_or_default(),
+                if device.current { "yes" } else { "" }.to_string(),
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Devices"));
+    println!("{}", Theme::separator(60));
+    for device in &devices {
+        let current = if device.current {
+            Theme::success("(current)").to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "  {} {} {}",
+            Theme::accent(&device.name),
+            Theme::muted(&device.id),
+            current
+        );
+        if let Some(last_login) = &device.last_login {
+            println!("     {} {}", Theme::muted("└ last login"), Theme::timestamp(last_login));
+        }
+    }
+    println!("{}", Theme::separator(60));
+    if let Ok(path) = Config::path() {
+        println!(
+            "{}",
+            Theme::muted(&format!("Device name and seed are set in {}", path.display()))
+        );
+    }
+
+    Ok(())
+}
+
+/// Forget a device; forgetting the current one ends the server's session
+pub async fn forget_device(client: &ApiClient, device_id: &str) -> Result<()> {
+    let spinner = create_spinner("Forgetting device...");
+    let response = client.forget_device(device_id).await;
+    spinner.finish_and_clear();
+
+    let Some(response) = response? else {
+        print_unsupported();
+        return Ok(());
+    };
+    if devices_or_error(response).is_none() {
+        return Ok(());
+    }
+
+    println!(
+        "{} {}",
+        Theme::check(),
+        Theme::success(&format!("Forgot device {}", device_id))
+    );
+    Ok(())
+}
+
+/// The device list, or print the server's error
+fn devices_or_error(response: DevicesResponse) -> Option<Vec<Device>> {
+    if response.success {
+        return Some(response.devices);
+    }
+    println!(
+        "{} {}",
+        Theme::cross(),
+        Theme::error(&response.error.unwrap_or("Request failed".to_string()))
+    );
+    None
+}
+
+/// Explain that the server is too old for device management
+fn print_unsupported() {
+    println!(
+        "{} {}",
+        Theme::warn_icon(),
+        Theme::warning("This server does not support device management. Update the server to use ig devices.")
+    );
+}
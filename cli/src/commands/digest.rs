@@ -0,0 +1,151 @@
+//! Inbox digest: a compact summary of recent activity
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::duration::Span;
+use crate::nicknames;
+use crate::spinner::create_spinner;
+use crate::util::{format_time_ago, parse_timestamp, truncate};
+
+/// Number of inbox threads scanned for the digest
+const INBOX_SCAN_LIMIT: u32 = 50;
+
+/// Messages fetched per active thread to count activity
+const THREAD_SCAN_LIMIT: u32 = 50;
+
+/// Number of threads listed under "Most active"
+const TOP_THREADS: usize = 5;
+
+/// A thread with its activity inside the digest window
+struct ThreadActivity {
+    username: String,
+    last_timestamp: Option<String>,
+    messages: usize,
+    last_line: Option<String>,
+    /// Whether the conversation started inside the window
+    new: bool,
+}
+
+/// Everything the digest reports
+struct Digest {
+    active: Vec<ThreadActivity>,
+    /// Unread messages across the inbox
+    unread: usize,
+}
+
+/// Print a digest of inbox activity since `since` (e.g. "24h", "7d")
+///
+/// Output is plain (no colors, no spinner) when stdout is not a terminal, so
+/// it can be piped into email or shown as a terminal greeting.
+pub async fn show_digest(client: &ApiClient, since: &Span) -> Result<()> {
+    let cutoff = Local::now() - since.duration;
+
+    let spinner = create_spinner("Building digest...");
+    let result = collect_digest(client, cutoff).await;
+    spinner.finish_and_clear();
+    let mut digest = result?;
+
+    digest.active.sort_by_key(|a| std::cmp::Reverse(a.messages));
+    let total_messages: usize = digest.active.iter().map(|a| a.messages).sum();
+
+    println!("{}", Theme::header(&format!("Inbox digest (last {})", since)));
+    println!("{}", Theme::separator(50));
+    let new = digest.active.iter().filter(|a| a.new).count();
+    println!("  {} {}", Theme::accent(&new.to_string()), Theme::muted("new conversations"));
+    println!(
+        "  {} {}",
+        Theme::unread(&digest.unread.to_string()),
+        Theme::muted("unread messages")
+    );
+    println!(
+        "  {} {}",
+        Theme::accent(&total_messages.to_string()),
+        Theme::muted(&format!("messages in {} active conversations", digest.active.len()))
+    );
+
+    if !digest.active.is_empty() {
+        println!();
+        println!("{}", Theme::header("Most active"));
+        for (i, entry) in digest.active.iter().take(TOP_THREADS).enumerate() {
+            let time = entry
+                .last_timestamp
+                .as_deref()
+                .map(format_time_ago)
+                .unwrap_or_default();
+            println!(
+                "  {}. {} {} {}",
+                i + 1,
+                Theme::username(&nicknames::display(&entry.username)),
+                Theme::muted(&format!("({} messages)", entry.messages)),
+                Theme::timestamp(&time)
+            );
+            if let Some(line) = &entry.last_line {
+                println!("     {} {}", Theme::muted("└"), truncate(line, 60));
+            }
+        }
+    }
+
+    println!("{}", Theme::separator(50));
+
+    Ok(())
+}
+
+/// Fetch the inbox and count per-thread activity after the cutoff
+async fn collect_digest(client: &ApiClient, cutoff: DateTime<Local>) -> Result<Digest> {
+    let response = client.get_inbox(INBOX_SCAN_LIMIT).await?;
+    let threads = response.threads.unwrap_or_default();
+    // Older servers don't send unseen counts: an unread thread has at least one
+    let unread = threads
+        .iter()
+        .filter(|t| t.has_unread.unwrap_or(false))
+        .map(|t| t.unseen_count.unwrap_or(1).max(1) as usize)
+        .sum();
+
+    let mut active = Vec::new();
+    for thread in threads
+        .iter()
+        .filter(|t| is_after(t.last_message_timestamp.as_deref(), cutoff))
+    {
+        let (messages, last_line, new) = match client.get_thread(&thread.id, THREAD_SCAN_LIMIT).await {
+            Ok(resp) => {
+                let messages = resp.thread.and_then(|t| t.messages).unwrap_or_default();
+                let in_window: Vec<_> = messages
+                    .iter()
+                    .filter(|m| is_after(m.timestamp.as_deref(), cutoff))
+                    .collect();
+                // Messages come newest first; a short page is the whole
+                // history, so the conversation is new if all of it is recent
+                let last_line = in_window.first().and_then(|m| m.text.clone());
+                let new = !in_window.is_empty()
+                    && in_window.len() == messages.len()
+                    && messages.len() < THREAD_SCAN_LIMIT as usize;
+                (in_window.len(), last_line, new)
+            }
+            Err(_) => (0, None, false),
+        };
+
+        active.push(ThreadActivity {
+            username: thread
+                .users
+                .first()
+                .map(|u| u.username.clone())
+                .unwrap_or_else(|| "unknown".to_string()),
+            last_timestamp: thread.last_message_timestamp.clone(),
+            messages,
+            last_line: last_line.or_else(|| thread.last_message_text.clone()),
+            new,
+        });
+    }
+
+    Ok(Digest { active, unread })
+}
+
+/// Whether a server timestamp falls after the cutoff
+pub(crate) fn is_after(timestamp: Option<&str>, cutoff: DateTime<Local>) -> bool {
+    timestamp
+        .and_then(parse_timestamp)
+        .is_some_and(|t| t >= cutoff)
+}
@@ -0,0 +1,381 @@
+//! `ig doctor`: diagnose the server, session and local setup
+
+use anyhow::Result;
+use chrono::Local;
+use reqwest::Url;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+
+use crate::client::{ApiClient, ApiCompatibility, API_VERSION};
+use crate::colors::{colors_enabled, Theme};
+use crate::commands::cooldown::LoginCooldown;
+use crate::config::Config;
+use crate::http;
+use crate::models::HealthResponse;
+use crate::output::{print_json, OutputFormat};
+use crate::spinner::create_spinner;
+use crate::store;
+use crate::util::{format_time_ago, parse_timestamp};
+
+/// Sessions older than this are worth refreshing
+const STALE_SESSION_DAYS: i64 = 30;
+
+/// Server round trips slower than this are reported
+const SLOW_SERVER_MS: u128 = 2000;
+
+/// How long to wait for the proxy to accept a connection
+const PROXY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One diagnostic result
+#[derive(Debug, Serialize)]
+pub(crate) struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    /// What to do about a warning or failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Pass, detail: detail.into(), hint: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name, status: Status::Warn, detail: detail.into(), hint: Some(hint.into()) }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name, status: Status::Fail, detail: detail.into(), hint: Some(hint.into()) }
+    }
+}
+
+/// Run every check and print a report
+pub async fn run_doctor(client: &ApiClient, output: OutputFormat) -> Result<()> {
+    let spinner = create_spinner("Running checks...");
+    let checks = collect_checks(client).await;
+    spinner.finish_and_clear();
+
+    let failures = checks.iter().filter(|c| c.status == Status::Fail).count();
+
+    if output == OutputFormat::Json {
+        print_json(&checks)?;
+    } else {
+        print_report(&checks);
+    }
+
+    if failures > 0 {
+        anyhow::bail!("{} check{} failed", failures, if failures == 1 { "" } else { "s" });
+    }
+    Ok(())
+}
+
+/// Run every check (also bundled by `ig report`)
+pub(crate) async fn collect_checks(client: &ApiClient) -> Vec<Check> {
+    let started = Instant::now();
+    let health = client.health().await;
+    let elapsed = started.elapsed().as_millis();
+
+    let mut checks = Vec::new();
+    if let Some(url) = http::proxy_url() {
+        checks.push(check_proxy(&url).await);
+    } else if let Some(check) = check_resolution(client.base_url()).await {
+        checks.push(check);
+    }
+    match &health {
+        Ok(health) => {
+            checks.push(if elapsed > SLOW_SERVER_MS {
+                Check::warn(
+                    "Server",
+                    format!("reachable but slow ({}ms)", elapsed),
+                    "Run `ig bench` to see whether the server or Instagram is slow",
+                )
+            } else {
+                Check::pass("Server", format!("reachable ({}ms)", elapsed))
+            });
+            checks.push(check_version(health));
+            checks.push(check_auth(health));
+            checks.push(check_session_age(health));
+        }
+        Err(e) => checks.push(Check::fail(
+            "Server",
+            format!("unreachable: {}", e),
+            "Start the server with `python main.py` in server/, or pass --server",
+        )),
+    }
+    checks.push(check_rate_limit());
+    checks.push(check_local_data());
+    checks.push(check_config());
+    checks.extend(check_terminal());
+    checks
+}
+
+/// The proxy requests go through accepts connections
+async fn check_proxy(url: &str) -> Check {
+    const NAME: &str = "Proxy";
+    let Some((host, port)) = http::proxy_address(url) else {
+        return Check::fail(NAME, format!("invalid URL '{}'", url), "Fix proxy under [http] in config.toml");
+    };
+    match tokio::time::timeout(PROXY_TIMEOUT, TcpStream::connect((host.as_str(), port))).await {
+        Ok(Ok(_)) => Check::pass(NAME, format!("{} (accepting connections)", url)),
+        Ok(Err(e)) => Check::fail(
+            NAME,
+            format!("{} unreachable: {}", url, e),
+            "Start the proxy (for --tor, the Tor service), or change proxy under [http] in config.toml",
+        ),
+        Err(_) => Check::fail(
+            NAME,
+            format!("{} did not answer within {}s", url, PROXY_TIMEOUT.as_secs()),
+            "Check that the proxy is running and reachable from this machine",
+        ),
+    }
+}
+
+/// The server's hostname resolves under the `[http]` name resolution
+/// settings; None when resolution is left to the system or the server is
+/// given by address
+async fn check_resolution(server: &str) -> Option<Check> {
+    const NAME: &str = "DNS";
+    let resolver = match http::resolver() {
+        Ok(resolver) => resolver?,
+        Err(e) => return Some(Check::fail(NAME, e.to_string(), "Fix the name resolution settings under [http]")),
+    };
+    let host = Url::parse(server).ok()?.host_str()?.to_string();
+    if host.parse::<IpAddr>().is_ok() || host.starts_with('[') {
+        return None;
+    }
+    Some(match resolver.lookup(&host).await {
+        Ok(addresses) => {
+            let addresses: Vec<String> = addresses.iter().map(IpAddr::to_string).collect();
+            Check::pass(NAME, format!("{} resolves to {}", host, addresses.join(", ")))
+        }
+        Err(e) => Check::fail(
+            NAME,
+            format!("{:#}", e),
+            "Check dns_servers and ip_version under [http], or pin the server under [http.hosts]",
+        ),
+    })
+}
+
+/// Print checks as pass/warn/fail lines with hints
+fn print_report(checks: &[Check]) {
+    println!();
+    println!("{}", Theme::header("ig doctor"));
+    println!("{}", Theme::separator(60));
+    for check in checks {
+        let icon = match check.status {
+            Status::Pass => Theme::check(),
+            Status::Warn => Theme::warn_icon(),
+            Status::Fail => Theme::cross(),
+        };
+        println!("  {} {} {}", icon, Theme::accent(&format!("{}:", check.name)), check.detail);
+        if let Some(hint) = &check.hint {
+            println!("     {} {}", Theme::muted("→"), Theme::muted(hint));
+        }
+    }
+    println!("{}", Theme::separator(60));
+
+    let warnings = checks.iter().filter(|c| c.status == Status::Warn).count();
+    let failures = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if failures == 0 && warnings == 0 {
+        println!("{}", Theme::success("Everything looks good."));
+    } else {
+        println!(
+            "{}",
+            Theme::muted(&format!("{} failed, {} warnings", failures, warnings))
+        );
+    }
+}
+
+/// Server and CLI speak compatible API versions
+fn check_version(health: &HealthResponse) -> Check {
+    const NAME: &str = "API version";
+    match ApiCompatibility::check(health) {
+        ApiCompatibility::Compatible => Check::pass(NAME, format!("v{}", API_VERSION)),
+        ApiCompatibility::ServerNewer(server) => Check::warn(
+            NAME,
+            format!("server v{} is newer than CLI v{}", server, API_VERSION),
+            "Update the CLI to use the server's newer features",
+        ),
+        ApiCompatibility::ServerOlder(server) => Check::warn(
+            NAME,
+            format!("server v{} is older than CLI v{}", server, API_VERSION),
+            "Update the server; some commands may not work",
+        ),
+        ApiCompatibility::CliTooOld(server) => Check::fail(
+            NAME,
+            format!("server v{} no longer supports CLI v{}", server, API_VERSION),
+            "Update the CLI",
+        ),
+        ApiCompatibility::Unknown => Check::warn(
+            NAME,
+            "server did not report a version",
+            "Update the server",
+        ),
+    }
+}
+
+/// The server has a logged-in Instagram session
+fn check_auth(health: &HealthResponse) -> Check {
+    if health.authenticated {
+        Check::pass(
+            "Authenticated",
+            format!("as @{}", health.username.as_deref().unwrap_or("unknown")),
+        )
+    } else {
+        Check::fail("Authenticated", "not logged in", "Run `ig login`")
+    }
+}
+
+/// The session is not so old that Instagram is likely to expire it
+fn check_session_age(health: &HealthResponse) -> Check {
+    const NAME: &str = "Session age";
+    if !health.authenticated {
+        return Check::warn(NAME, "no session", "Run `ig login`");
+    }
+    let Some(started) = health.session_started_at.as_deref().and_then(parse_timestamp) else {
+        return Check::warn(NAME, "unknown", "Update the server to report session age");
+    };
+
+    let age = format_time_ago(&started.format("%Y-%m-%dT%H:%M:%S").to_string());
+    if Local::now() - started > chrono::Duration::days(STALE_SESSION_DAYS) {
+        Check::warn(
+            NAME,
+            format!("logged in {} ago", age),
+            "Old sessions get challenged more often; `ig logout` then `ig login` to refresh",
+        )
+    } else {
+        Check::pass(NAME, format!("logged in {} ago", age))
+    }
+}
+
+/// No login cooldown is active
+fn check_rate_limit() -> Check {
+    const NAME: &str = "Rate limit";
+    let cooldown = LoginCooldown::load().unwrap_or_default();
+    match cooldown.remaining() {
+        Some(left) => Check::fail(
+            NAME,
+            format!("login locked for {} more minutes", left.num_minutes() + 1),
+            "Wait for the cooldown to end before logging in again",
+        ),
+        None if cooldown.strikes > 0 => Check::warn(
+            NAME,
+            format!("rate limited {} time(s) recently", cooldown.strikes),
+            "Log in sparingly; the next cooldown will be longer",
+        ),
+        None => Check::pass(NAME, "no recent rate limits"),
+    }
+}
+
+/// Every local data file parses and no interrupted writes are left behind
+fn check_local_data() -> Check {
+    const NAME: &str = "Local data";
+    let dir = match store::data_dir() {
+        Ok(dir) => dir,
+        Err(e) => return Check::fail(NAME, e.to_string(), "Check permissions on your home directory"),
+    };
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => return Check::fail(NAME, e.to_string(), format!("Check permissions on {}", dir.display())),
+    };
+
+    let mut files = 0;
+    let mut broken = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if name.ends_with(".tmp") {
+            broken.push(name);
+            continue;
+        }
+        if path.extension().is_some_and(|ext| ext == "json") {
+            files += 1;
+            let valid = fs::read_to_string(&path)
+                .ok()
+                .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+                .is_some();
+            if !valid {
+                broken.push(name);
+            }
+        }
+    }
+
+    if broken.is_empty() {
+        Check::pass(NAME, format!("{} files OK in {}", files, dir.display()))
+    } else {
+        Check::fail(
+            NAME,
+            format!("unreadable: {}", broken.join(", ")),
+            format!("Fix or delete these files in {}", dir.display()),
+        )
+    }
+}
+
+/// The config file (if any) parses
+fn check_config() -> Check {
+    const NAME: &str = "Config";
+    let path = Config::path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    match Config::load() {
+        Ok(_) if std::path::Path::new(&path).exists() => Check::pass(NAME, path),
+        Ok(_) => Check::pass(NAME, "no config file (using defaults)"),
+        Err(e) => Check::fail(NAME, format!("{:#}", e), format!("Fix the syntax in {}", path)),
+    }
+}
+
+/// Color and unicode support of the terminal
+fn check_terminal() -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    if !io::stdout().is_terminal() {
+        checks.push(Check::pass("Terminal", "output is not a terminal (plain output)"));
+        return checks;
+    }
+
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    checks.push(if !colors_enabled() {
+        Check::pass("Colors", "disabled (--no-color or NO_COLOR)")
+    } else if colorterm == "truecolor" || colorterm == "24bit" {
+        Check::pass("Colors", "truecolor")
+    } else {
+        Check::warn(
+            "Colors",
+            "truecolor not advertised (COLORTERM unset)",
+            "Colors may look off; try `--theme high-contrast` or `--no-color`",
+        )
+    });
+
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|var| env::var(var).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_default();
+    let utf8 = locale.to_uppercase().replace('-', "").contains("UTF8");
+    checks.push(if utf8 || cfg!(windows) {
+        Check::pass("Unicode", if locale.is_empty() { "supported".to_string() } else { locale })
+    } else {
+        Check::warn(
+            "Unicode",
+            format!("locale is {}", if locale.is_empty() { "unset" } else { &locale }),
+            "Set LANG to a UTF-8 locale (e.g. en_US.UTF-8) so icons and borders render",
+        )
+    });
+
+    checks
+}
@@ -0,0 +1,247 @@
+//! `ig download`: save the photos, videos and voice clips of a conversation
+//!
+//! Files are named after their message ID (`<message id>.jpg`), so running
+//! it again skips what's already been saved. Files over
+//! [`LARGE_FILE_BYTES`] get a progress bar while they download.
+//!
+//! Each file downloaded is also listed in a data file, so `keep_media`
+//! retention (see [`crate::commands::cache`]) can remove exactly what was
+//! saved here and nothing else in the directories it went to.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::archive::{file_safe, media_extension, write_file};
+use crate::commands::attach::format_size;
+use crate::commands::message::{find_message, message_not_found, SEARCH_LIMIT};
+use crate::models::Message;
+use crate::output::{print_json, OutputFormat};
+use crate::spinner::{create_download_progress, create_spinner, Progress};
+use crate::store;
+
+/// Data file listing the files `ig download` saved
+const DOWNLOADS_FILE: &str = "downloads";
+
+/// Show a progress bar for downloads at least this big (or of unknown size
+/// once they pass it)
+pub const LARGE_FILE_BYTES: u64 = 1024 * 1024;
+
+/// A media file saved (or already there)
+#[derive(Debug, Serialize)]
+struct Downloaded {
+    message_id: String,
+    media_type: String,
+    file: PathBuf,
+    bytes: u64,
+    /// False when the file was already there
+    downloaded: bool,
+}
+
+/// A file `ig download` saved
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedFile {
+    /// Absolute path
+    path: PathBuf,
+    bytes: u64,
+    at: DateTime<Local>,
+}
+
+/// Every file `ig download` saved that is still kept
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DownloadLog {
+    files: Vec<SavedFile>,
+}
+
+impl DownloadLog {
+    fn load() -> Result<Self> {
+        store::load(DOWNLOADS_FILE)
+    }
+
+    fn save(&self) -> Result<()> {
+        store::save(DOWNLOADS_FILE, self)
+    }
+}
+
+/// Add the files just downloaded to the log
+fn record_downloads(saved: &[Downloaded]) -> Result<()> {
+    let mut log = DownloadLog::load()?;
+    let at = Local::now();
+    for entry in saved.iter().filter(|e| e.downloaded) {
+        let path = fs::canonicalize(&entry.file).unwrap_or_else(|_| entry.file.clone());
+        log.files.retain(|f| f.path != path);
+        log.files.push(SavedFile { path, bytes: entry.bytes, at });
+    }
+    log.save()
+}
+
+/// Remove downloaded files saved before `cutoff`; returns how many went.
+/// Files changed since (a different size) are left alone, and entries for
+/// files that are gone are forgotten.
+pub(crate) fn prune_downloads(cutoff: DateTime<Local>) -> Result<usize> {
+    let mut log = DownloadLog::load()?;
+    let before = log.files.len();
+    let mut removed = 0;
+    log.files.retain(|file| {
+        let Ok(meta) = file.path.metadata() else {
+            return false;
+        };
+        if file.at >= cutoff {
+            return true;
+        }
+        if meta.len() != file.bytes {
+            return false;
+        }
+        match fs::remove_file(&file.path) {
+            Ok(()) => {
+                removed += 1;
+                false
+            }
+            // Tried again next time
+            Err(_) => true,
+        }
+    });
+    if log.files.len() != before {
+        log.save()?;
+    }
+    Ok(removed)
+}
+
+/// Save the media of one message (`message_id`), or of every message among
+/// the thread's last [`SEARCH_LIMIT`], to `out` (the current directory by
+/// default)
+pub async fn download_thread_media(
+    client: &ApiClient,
+    thread_id: &str,
+    message_id: Option<&str>,
+    out: Option<&Path>,
+    output: OutputFormat,
+) -> Result<()> {
+    let messages = match message_id {
+        Some(message_id) => {
+            let spinner = create_spinner("Fetching message...");
+            let found = find_message(client, thread_id, message_id).await;
+            spinner.finish_and_clear();
+            let found = found?.ok_or_else(|| message_not_found(message_id))?;
+            if media_url(&found.message).is_none() {
+                anyhow::bail!("Message {} has no photo, video or voice clip", message_id);
+            }
+            vec![found.message]
+        }
+        None => {
+            let spinner = create_spinner("Fetching messages...");
+            let response = client.get_thread(thread_id, SEARCH_LIMIT).await;
+            spinner.finish_and_clear();
+            let response = response?;
+            if !response.success {
+                anyhow::bail!(response.error.unwrap_or("Failed to fetch thread".to_string()));
+            }
+            let messages = response.thread.and_then(|t| t.messages).unwrap_or_default();
+            messages.into_iter().filter(|m| media_url(m).is_some()).collect()
+        }
+    };
+
+    if messages.is_empty() {
+        if output == OutputFormat::Json {
+            return print_json(&Vec::<Downloaded>::new());
+        }
+        println!(
+            "{}",
+            Theme::muted(&format!(
+                "No photos, videos or voice clips in the last {} messages.",
+                SEARCH_LIMIT
+            ))
+        );
+        return Ok(());
+    }
+
+    let out = out.unwrap_or(Path::new("."));
+    let mut saved = Vec::new();
+    let mut failed = 0;
+    for message in &messages {
+        match save(client, message, out).await {
+            Ok(entry) => {
+                if output != OutputFormat::Json {
+                    let note = if entry.downloaded { "" } else { " · already saved" };
+                    println!(
+                        "{} {} {} {}",
+                        Theme::check(),
+                        entry.media_type,
+                        Theme::muted("→"),
+                        Theme::muted(&format!("{} ({}{})", entry.file.display(), format_size(entry.bytes), note))
+                    );
+                }
+                saved.push(entry);
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("{} {} {}", Theme::cross(), message.id, Theme::error(&format!("{:#}", e)));
+            }
+        }
+    }
+
+    if saved.iter().any(|e| e.downloaded) {
+        if let Err(e) = record_downloads(&saved) {
+            eprintln!("{} {}", Theme::warn_icon(), Theme::warning(&format!("{:#}", e)));
+        }
+    }
+    if output == OutputFormat::Json {
+        print_json(&saved)?;
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} downloads failed", failed, messages.len());
+    }
+    Ok(())
+}
+
+/// The message's media URL, if it has one
+fn media_url(message: &Message) -> Option<&str> {
+    message.media_url.as_deref().filter(|u| !u.is_empty())
+}
+
+/// Download one message's media into `out`, unless it's already there
+async fn save(client: &ApiClient, message: &Message, out: &Path) -> Result<Downloaded> {
+    let url = media_url(message).unwrap_or_default();
+    let file = out.join(format!("{}.{}", file_safe(&message.id), media_extension(message, url)));
+    let media_type = message.media_type.clone().unwrap_or_else(|| "media".to_string());
+
+    if let Ok(meta) = file.metadata() {
+        return Ok(Downloaded {
+            message_id: message.id.clone(),
+            media_type,
+            file,
+            bytes: meta.len(),
+            downloaded: false,
+        });
+    }
+
+    let label = format!("Downloading {}", message.media_filename().unwrap_or(&media_type));
+    let mut progress: Option<Progress> = None;
+    let data = client
+        .download_media(url, |done, total| {
+            if progress.is_none() && total.unwrap_or(done) >= LARGE_FILE_BYTES {
+                progress = Some(create_download_progress(&label, total));
+            }
+            if let Some(progress) = &progress {
+                progress.set_position(done);
+            }
+        })
+        .await;
+    if let Some(progress) = progress {
+        progress.finish_and_clear();
+    }
+    let data = data?;
+
+    write_file(&file, &data)?;
+    Ok(Downloaded {
+        message_id: message.id.clone(),
+        media_type,
+        file,
+        bytes: data.len() as u64,
+        downloaded: true,
+    })
+}
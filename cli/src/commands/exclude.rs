@@ -0,0 +1,138 @@
+//! Sync exclusion list (`ig sync exclude`, `ig sync include`, `ig sync list`)
+
+use anyhow::Result;
+use chrono::Local;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::inbox::find_thread_by_username;
+use crate::exclusions::{Excluded, Exclusions};
+use crate::output::{print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+
+/// Stop syncing, caching, searching and exporting a conversation (by
+/// thread ID or @username)
+pub async fn exclude_thread(client: &ApiClient, target: &str) -> Result<()> {
+    let spinner = create_spinner("Finding conversation...");
+    let found = resolve(client, target).await;
+    spinner.finish_and_clear();
+    let Some((thread_id, label)) = found? else {
+        println!(
+            "{} {}",
+            Theme::cross(),
+            Theme::error(&format!("No conversation with {} found", target))
+        );
+        return Ok(());
+    };
+
+    let mut exclusions = Exclusions::load()?;
+    if exclusions.contains(&thread_id) {
+        println!("{}", Theme::muted(&format!("{} is already excluded.", label)));
+        return Ok(());
+    }
+    exclusions.threads.push(Excluded {
+        thread_id,
+        label: label.clone(),
+        added: Local::now(),
+    });
+    exclusions.save()?;
+
+    println!(
+        "{} {} {}",
+        Theme::check(),
+        Theme::success("Excluded from sync:"),
+        Theme::username(&label)
+    );
+    println!(
+        "{}",
+        Theme::muted("Watchers, caching, saved searches and exports now skip it; `ig sync include` undoes this.")
+    );
+    Ok(())
+}
+
+/// Take a conversation off the exclusion list (by thread ID or the
+/// @username it was excluded as)
+pub fn include_thread(target: &str) -> Result<()> {
+    let mut exclusions = Exclusions::load()?;
+    let before = exclusions.threads.len();
+    exclusions
+        .threads
+        .retain(|t| t.thread_id != target && !t.label.eq_ignore_ascii_case(target));
+
+    if exclusions.threads.len() == before {
+        println!("{}", Theme::muted(&format!("{} is not excluded.", target)));
+        return Ok(());
+    }
+    exclusions.save()?;
+    println!(
+        "{} {} {}",
+        Theme::check(),
+        Theme::success("Syncing again:"),
+        Theme::username(target)
+    );
+    Ok(())
+}
+
+/// Show excluded conversations (`ig sync list`)
+pub fn list_exclusions(output: OutputFormat) -> Result<()> {
+    let exclusions = Exclusions::load()?;
+
+    if output == OutputFormat::Json {
+        return print_json(&exclusions.threads);
+    }
+
+    if exclusions.threads.is_empty() {
+        println!("{}", Theme::muted("No conversations are excluded from sync."));
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Conversation", "Thread ID", "Excluded"]);
+        for excluded in &exclusions.threads {
+            table.row([
+                excluded.label.clone(),
+                excluded.thread_id.clone(),
+                excluded.added.format("%Y-%m-%d %H:%M").to_string(),
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Excluded from sync"));
+    println!("{}", Theme::separator(60));
+    for excluded in &exclusions.threads {
+        println!(
+            "  {} {}",
+            Theme::username(&excluded.label),
+            Theme::muted(&format!("({})", excluded.thread_id))
+        );
+    }
+    println!("{}", Theme::separator(60));
+    Ok(())
+}
+
+/// Thread ID and a label for `target`: the conversation with an @username,
+/// or a thread ID labelled with its participants
+async fn resolve(client: &ApiClient, target: &str) -> Result<Option<(String, String)>> {
+    if let Some(username) = target.strip_prefix('@') {
+        let thread = find_thread_by_username(client, username).await?;
+        return Ok(thread.map(|t| (t.id, format!("@{}", username))));
+    }
+
+    let response = client.get_thread(target, 1).await?;
+    let label = response
+        .thread
+        .filter(|_| response.success)
+        .map(|t| {
+            t.users
+                .iter()
+                .map(|u| format!("@{}", u.username))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .filter(|label| !label.is_empty())
+        .unwrap_or_else(|| target.to_string());
+    Ok(Some((target.to_string(), label)))
+}
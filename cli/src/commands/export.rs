@@ -0,0 +1,118 @@
+//! Export inbox metadata as CSV or JSON
+//!
+//! One row per thread, suitable for spreadsheets and CRM imports.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+
+use crate::client::ApiClient;
+use crate::commands::inbox::filter_threads;
+use crate::commands::pages::fetch_inbox;
+use crate::commands::tags::Tags;
+use crate::exclusions;
+use crate::models::Thread;
+use crate::output::csv_field;
+
+/// Export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// One exported inbox row (also the `--output json` shape of the inbox)
+#[derive(Debug, Serialize)]
+pub(crate) struct InboxRow {
+    pub thread_id: String,
+    pub title: String,
+    pub participants: Vec<String>,
+    pub last_message: Option<String>,
+    pub unread: bool,
+    pub last_timestamp: Option<String>,
+    pub tags: Vec<String>,
+    /// Team member the thread is assigned to (`ig inbox` with `[team]` set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<String>,
+}
+
+impl InboxRow {
+    pub fn new(thread: &Thread, tags: &Tags) -> Self {
+        let participants: Vec<String> = thread.users.iter().map(|u| u.username.clone()).collect();
+        Self {
+            thread_id: thread.id.clone(),
+            title: thread
+                .thread_title
+                .clone()
+                .unwrap_or_else(|| participants.join(", ")),
+            participants,
+            last_message: thread.last_message_text.clone(),
+            unread: thread.has_unread.unwrap_or(false),
+            last_timestamp: thread.last_message_timestamp.clone(),
+            tags: tags.for_thread(thread).into_iter().collect(),
+            assigned_to: None,
+        }
+    }
+}
+
+/// CSV column headers, in row order
+const CSV_HEADERS: [&str; 7] = [
+    "thread_id",
+    "title",
+    "participants",
+    "last_message",
+    "unread",
+    "last_timestamp",
+    "tags",
+];
+
+/// Write the inbox to stdout as CSV or JSON
+pub async fn export_inbox(
+    client: &ApiClient,
+    limit: Option<u32>,
+    unread_only: bool,
+    tag: Option<&str>,
+    format: ExportFormat,
+) -> Result<()> {
+    let response = fetch_inbox(client, limit).await?;
+
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to fetch inbox".to_string()));
+    }
+
+    let tags = Tags::load().unwrap_or_default();
+    let threads = filter_threads(response.threads.unwrap_or_default(), unread_only, tag, &tags);
+    let excluded = exclusions::excluded_ids();
+    let rows: Vec<InboxRow> = threads
+        .iter()
+        .filter(|t| !excluded.contains(&t.id))
+        .map(|t| InboxRow::new(t, &tags))
+        .collect();
+
+    let mut stdout = io::stdout().lock();
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut stdout, &rows)?;
+            writeln!(stdout)?;
+        }
+        ExportFormat::Csv => {
+            writeln!(stdout, "{}", CSV_HEADERS.join(","))?;
+            for row in &rows {
+                let fields = [
+                    row.thread_id.clone(),
+                    row.title.clone(),
+                    row.participants.join(";"),
+                    row.last_message.clone().unwrap_or_default(),
+                    row.unread.to_string(),
+                    row.last_timestamp.clone().unwrap_or_default(),
+                    row.tags.join(";"),
+                ];
+                let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                writeln!(stdout, "{}", line.join(","))?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,265 @@
+//! CRM-style follow-up tracking
+//!
+//! A follow-up is a dated note about a conversation partner. `ig followup list`
+//! shows what is overdue, and opening the conversation offers to clear or
+//! reschedule it.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use dialoguer::{Input, Select};
+use serde::{Deserialize, Serialize};
+use std::io::{self, IsTerminal};
+
+use crate::colors::Theme;
+use crate::commands::tags::{tag_chip, Tags};
+use crate::duration::{parse_duration, Span};
+use crate::output::{print_json, OutputFormat, Table};
+use crate::store;
+
+/// Data file holding follow-ups
+const FOLLOWUPS_FILE: &str = "followups";
+
+/// A pending follow-up with a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowUp {
+    pub username: String,
+    pub due: DateTime<Local>,
+    pub note: String,
+    pub created: DateTime<Local>,
+}
+
+/// All follow-ups (at most one per user)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FollowUps {
+    pub items: Vec<FollowUp>,
+}
+
+impl FollowUps {
+    /// Load follow-ups from disk
+    pub fn load() -> Result<Self> {
+        store::load(FOLLOWUPS_FILE)
+    }
+
+    /// Save follow-ups to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(FOLLOWUPS_FILE, self)
+    }
+
+    /// The follow-up for a user, if any
+    pub fn for_user(&self, username: &str) -> Option<&FollowUp> {
+        self.items
+            .iter()
+            .find(|f| f.username.eq_ignore_ascii_case(username))
+    }
+
+    /// Remove a user's follow-up, returning whether one existed
+    pub fn remove(&mut self, username: &str) -> bool {
+        let before = self.items.len();
+        self.items.retain(|f| !f.username.eq_ignore_ascii_case(username));
+        self.items.len() != before
+    }
+}
+
+/// Schedule a follow-up (`ig followup @user --in 2d --note "send quote"`)
+pub fn add_followup(target: &str, due_in: &Span, note: Option<&str>) -> Result<()> {
+    let username = target.trim_start_matches('@').to_lowercase();
+    let due = Local::now() + due_in.duration;
+
+    let mut followups = FollowUps::load()?;
+    let replaced = followups.remove(&username);
+    followups.items.push(FollowUp {
+        username: username.clone(),
+        due,
+        note: note.unwrap_or_default().to_string(),
+        created: Local::now(),
+    });
+    followups.save()?;
+
+    println!(
+        "{} {} {} {}",
+        Theme::check(),
+        Theme::success(if replaced { "Follow-up rescheduled for" } else { "Follow-up set for" }),
+        Theme::username(&format!("@{}", username)),
+        Theme::muted(&format!("due {}", due.format("%Y-%m-%d %H:%M")))
+    );
+    Ok(())
+}
+
+/// Mark a follow-up as done
+pub fn clear_followup(target: &str) -> Result<()> {
+    let username = target.trim_start_matches('@');
+    let mut followups = FollowUps::load()?;
+
+    if !followups.remove(username) {
+        println!(
+            "{}",
+            Theme::muted(&format!("No follow-up pending with @{}", username))
+        );
+        return Ok(());
+    }
+
+    followups.save()?;
+    println!(
+        "{} {} {}",
+        Theme::check(),
+        Theme::success("Follow-up done:"),
+        Theme::username(&format!("@{}", username))
+    );
+    Ok(())
+}
+
+/// List follow-ups, overdue first, then upcoming by due date
+pub fn list_followups(output: OutputFormat) -> Result<()> {
+    let mut followups = FollowUps::load()?;
+    followups.items.sort_by_key(|f| f.due);
+
+    if output == OutputFormat::Json {
+        return print_json(&followups.items);
+    }
+
+    if followups.items.is_empty() {
+        println!("{}", Theme::muted("No follow-ups scheduled."));
+        return Ok(());
+    }
+
+    let now = Local::now();
+    let tags = Tags::load().unwrap_or_default();
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Username", "Due", "Status", "Note", "Tags"]);
+        for followup in &followups.items {
+            let user_tags = tags
+                .for_user(&followup.username)
+                .map(|set| set.iter().cloned().collect::<Vec<_>>().join(", "))
+                .unwrap_or_default();
+            table.row([
+                format!("@{}", followup.username),
+                followup.due.format("%Y-%m-%d %H:%M").to_string(),
+                if followup.due <= now { "overdue" } else { "upcoming" }.to_string(),
+                followup.note.clone(),
+                user_tags,
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+    let (overdue, upcoming): (Vec<_>, Vec<_>) =
+        followups.items.iter().partition(|f| f.due <= now);
+
+    println!();
+    if !overdue.is_empty() {
+        println!("{}", Theme::error("Overdue"));
+        println!("{}", Theme::separator(60));
+        for followup in &overdue {
+            print_followup(followup, &tags, true);
+        }
+        println!();
+    }
+    if !upcoming.is_empty() {
+        println!("{}", Theme::header("Upcoming"));
+        println!("{}", Theme::separator(60));
+        for followup in &upcoming {
+            print_followup(followup, &tags, false);
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Print one follow-up line (with the user's tags)
+fn print_followup(followup: &FollowUp, tags: &Tags, overdue: bool) {
+    let due = followup.due.format("%a %b %d %H:%M").to_string();
+    let due = if overdue {
+        Theme::error(&due)
+    } else {
+        Theme::timestamp(&due)
+    };
+    let chips = tags
+        .for_user(&followup.username)
+        .map(|set| {
+            set.iter()
+                .map(|t| tag_chip(t))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+
+    println!(
+        "  {} {} {}",
+        due,
+        Theme::username(&format!("@{}", followup.username)),
+        chips
+    );
+    if !followup.note.is_empty() {
+        println!("     {} {}", Theme::muted("└"), followup.note);
+    }
+}
+
+/// When opening a conversation, offer to clear or reschedule a pending
+/// follow-up with any of the given users. Does nothing when not interactive.
+pub fn review_followups<'a>(usernames: impl IntoIterator<Item = &'a str>) -> Result<()> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Ok(());
+    }
+
+    let mut followups = FollowUps::load()?;
+    let pending: Vec<FollowUp> = usernames
+        .into_iter()
+        .filter_map(|u| followups.for_user(u).cloned())
+        .collect();
+
+    for followup in pending {
+        let when = if followup.due <= Local::now() {
+            Theme::error("overdue").to_string()
+        } else {
+            Theme::muted(&format!("due {}", followup.due.format("%a %b %d %H:%M"))).to_string()
+        };
+        println!(
+            "{} {} {} {}",
+            Theme::warn_icon(),
+            Theme::warning("Follow-up with"),
+            Theme::username(&format!("@{}", followup.username)),
+            when
+        );
+        if !followup.note.is_empty() {
+            println!("   {} {}", Theme::muted("└"), followup.note);
+        }
+
+        let choice = Select::new()
+            .items(&["Mark done", "Reschedule", "Keep"])
+            .default(2)
+            .interact()?;
+
+        match choice {
+            0 => {
+                followups.remove(&followup.username);
+                followups.save()?;
+                println!("{} {}", Theme::check(), Theme::success("Follow-up done"));
+            }
+            1 => {
+                let due_in: String = Input::new()
+                    .with_prompt("Follow up again in (e.g. 2d)")
+                    .interact_text()?;
+                let due = Local::now() + parse_duration(&due_in)?;
+                if let Some(entry) = followups
+                    .items
+                    .iter_mut()
+                    .find(|f| f.username == followup.username)
+                {
+                    entry.due = due;
+                }
+                followups.save()?;
+                println!(
+                    "{} {}",
+                    Theme::check(),
+                    Theme::success(&format!("Rescheduled for {}", due.format("%Y-%m-%d %H:%M")))
+                );
+            }
+            _ => {}
+        }
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! Jumping to a date in a conversation
+//!
+//! `ig thread @user --around 2024-12-25` and `:goto 2024-12-25` in `ig chat`
+//! page back through the history until they reach that day, then show the
+//! messages either side of where it starts. Only as many pages as needed
+//! are fetched, so a years-long conversation doesn't have to be loaded (or
+//! scrolled through) to find one evening.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::pages::fetch_thread_until;
+use crate::models::{Message, ThreadResponse};
+use crate::util::parse_timestamp;
+
+/// Parse a `YYYY-MM-DD` date
+pub fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("invalid date '{}' (expected YYYY-MM-DD, e.g. 2024-12-25)", value.trim()))
+}
+
+/// clap parser for date arguments
+pub fn date_arg(value: &str) -> Result<NaiveDate, String> {
+    parse_date(value).map_err(|e| e.to_string())
+}
+
+/// Local day a message was sent
+pub fn message_day(message: &Message) -> Option<NaiveDate> {
+    message
+        .timestamp
+        .as_deref()
+        .and_then(parse_timestamp)
+        .map(|t| t.date_naive())
+}
+
+/// Position of the first message sent on or after `date` in `messages`
+/// (newest first). If every message is older, that's the newest one.
+pub fn anchor(messages: &[Message], date: NaiveDate) -> usize {
+    messages
+        .iter()
+        .rposition(|m| message_day(m).is_some_and(|day| day >= date))
+        .unwrap_or(0)
+}
+
+/// The `count` messages around the start of `date` (newest first), with
+/// about half of them from before it
+pub fn window(messages: &[Message], date: NaiveDate, count: usize) -> std::ops::Range<usize> {
+    if messages.is_empty() {
+        return 0..0;
+    }
+    let at = anchor(messages, date);
+    let older = count / 2;
+    let start = (at + 1).saturating_sub(count - older);
+    let end = (at + 1 + older).min(messages.len());
+    // Near either end of the history, fill up from the other side
+    let start = start.min(end.saturating_sub(count));
+    let end = end.max((start + count).min(messages.len()));
+    start..end
+}
+
+/// Fetch `count` messages of a thread around the start of `date`, paging
+/// back only as far as needed
+pub async fn fetch_around(
+    client: &ApiClient,
+    thread_id: &str,
+    date: NaiveDate,
+    count: u32,
+) -> Result<ThreadResponse> {
+    let older_wanted = (count as usize / 2).max(1);
+    // Far enough back once there's context from before the date
+    let mut response = fetch_thread_until(client, thread_id, &format!("Paging back to {}", date), |messages| {
+        messages
+            .iter()
+            .filter(|m| message_day(m).is_some_and(|day| day < date))
+            .count()
+            >= older_wanted
+    })
+    .await?;
+
+    if let Some(thread) = response.thread.as_mut() {
+        let mut messages = thread.messages.take().unwrap_or_default();
+        let range = window(&messages, date, count as usize);
+        thread.messages = Some(messages.drain(range).collect());
+    }
+    Ok(response)
+}
+
+/// Line marking where `date` starts, shown above its first message. Says so
+/// when nothing was sent that day.
+pub fn day_marker(date: NaiveDate, first_after: Option<&Message>) -> String {
+    let label = date.format("%A, %B %-d, %Y").to_string();
+    match first_after.and_then(message_day) {
+        Some(day) if day == date => Theme::accent(&format!("── {} ──", label)).to_string(),
+        Some(day) => format!(
+            "{} {}",
+            Theme::accent(&format!("── {} ──", label)),
+            Theme::muted(&format!("(no messages that day; next on {})", day))
+        ),
+        None => format!(
+            "{} {}",
+            Theme::accent(&format!("── {} ──", label)),
+            Theme::muted("(no messages since)")
+        ),
+    }
+}
@@ -0,0 +1,376 @@
+//! Activity heatmap (`ig heatmap`)
+//!
+//! Counts messages per day over the last few weeks, GitHub-style, and per
+//! weekday and hour of the day, for the whole inbox or one conversation.
+//! Shading shows how busy a cell was; its color shows who did the talking:
+//! pink when it was mostly messages you sent, blue when mostly ones you
+//! received, purple when it was both. Without colors only the shading is left.
+//!
+//! `--output csv` gives one row per day (`kind` "day") followed by one per
+//! weekday and hour (`kind` "hour").
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::client::ApiClient;
+use crate::colors::{palette, Theme};
+use crate::commands::inbox::find_thread_by_username;
+use crate::commands::pages::{fetch_inbox, fetch_thread_until};
+use crate::commands::stats::STATS_SCHEMA;
+use crate::models::Message;
+use crate::nicknames;
+use crate::output::{print_csv, print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+use crate::util::parse_timestamp;
+
+/// Inbox threads looked at for the whole-inbox heatmap
+const INBOX_SCAN_LIMIT: u32 = 50;
+
+/// Messages fetched per thread for the whole-inbox heatmap
+const THREAD_SCAN_LIMIT: u32 = 100;
+
+/// Cell shades from least to most busy
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Messages in one cell, by direction
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+struct Counts {
+    sent: usize,
+    received: usize,
+}
+
+impl Counts {
+    fn total(self) -> usize {
+        self.sent + self.received
+    }
+
+    fn add(&mut self, from_me: bool) {
+        if from_me {
+            self.sent += 1;
+        } else {
+            self.received += 1;
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DayRow {
+    date: NaiveDate,
+    #[serde(flatten)]
+    counts: Counts,
+}
+
+#[derive(Serialize)]
+struct HourRow {
+    weekday: &'static str,
+    hour: u32,
+    #[serde(flatten)]
+    counts: Counts,
+}
+
+#[derive(Serialize)]
+struct HeatmapReport {
+    schema: u32,
+    user: Option<String>,
+    from: NaiveDate,
+    to: NaiveDate,
+    days: Vec<DayRow>,
+    hours: Vec<HourRow>,
+}
+
+/// Message counts over the heatmap's window
+struct Activity {
+    /// First day shown (a Monday)
+    start: NaiveDate,
+    today: NaiveDate,
+    days: BTreeMap<NaiveDate, Counts>,
+    /// By weekday (Monday first), then hour
+    hours: [[Counts; 24]; 7],
+}
+
+impl Activity {
+    fn new(start: NaiveDate, today: NaiveDate) -> Self {
+        Self {
+            start,
+            today,
+            days: BTreeMap::new(),
+            hours: [[Counts::default(); 24]; 7],
+        }
+    }
+
+    fn add(&mut self, message: &Message) {
+        let Some(time) = message.timestamp.as_deref().and_then(parse_timestamp) else {
+            return;
+        };
+        let date = time.date_naive();
+        if date < self.start || date > self.today {
+            return;
+        }
+        let from_me = message.is_sent_by_viewer.unwrap_or(false);
+        self.days.entry(date).or_default().add(from_me);
+        self.hours[time.weekday().num_days_from_monday() as usize][time.hour() as usize].add(from_me);
+    }
+
+    fn day(&self, date: NaiveDate) -> Counts {
+        self.days.get(&date).copied().unwrap_or_default()
+    }
+
+    fn report(&self, user: Option<&str>) -> HeatmapReport {
+        let days = self.start.iter_days().take_while(|d| *d <= self.today);
+        HeatmapReport {
+            schema: STATS_SCHEMA,
+            user: user.map(str::to_string),
+            from: self.start,
+            to: self.today,
+            days: days.map(|date| DayRow { date, counts: self.day(date) }).collect(),
+            hours: (0..7)
+                .flat_map(|day| (0..24).map(move |hour| (day, hour)))
+                .map(|(day, hour)| HourRow {
+                    weekday: WEEKDAYS[day],
+                    hour: hour as u32,
+                    counts: self.hours[day][hour],
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Show message activity over the last `weeks` weeks, for the whole inbox
+/// or, with `target` (@user), one conversation
+pub async fn show_heatmap(
+    client: &ApiClient,
+    target: Option<&str>,
+    weeks: u32,
+    output: OutputFormat,
+) -> Result<()> {
+    let today = Local::now().date_naive();
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let start = monday - Duration::weeks(weeks.saturating_sub(1) as i64);
+    let cutoff = start
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .unwrap_or_else(Local::now);
+
+    let username = target.map(|t| t.trim_start_matches('@'));
+    let mut activity = Activity::new(start, today);
+    for message in collect_messages(client, username, cutoff).await? {
+        activity.add(&message);
+    }
+
+    match output {
+        OutputFormat::Json => return print_json(&activity.report(username)),
+        OutputFormat::Csv => return print_report_csv(&activity.report(username)),
+        OutputFormat::Table => {
+            let mut table = Table::new(&["Date", "Sent", "Received"]);
+            for row in activity.report(username).days.iter().filter(|r| r.counts.total() > 0) {
+                table.row([
+                    row.date.format("%a %Y-%m-%d").to_string(),
+                    row.counts.sent.to_string(),
+                    row.counts.received.to_string(),
+                ]);
+            }
+            table.print();
+            return Ok(());
+        }
+        OutputFormat::Plain => {}
+    }
+
+    let title = match username {
+        Some(u) => format!("Activity with {}", nicknames::display(u)),
+        None => "Inbox activity".to_string(),
+    };
+    println!();
+    println!(
+        "{} {}",
+        Theme::header(&title),
+        Theme::muted(&format!("(last {} week{})", weeks, if weeks == 1 { "" } else { "s" }))
+    );
+    println!("{}", Theme::separator(60));
+    print_calendar(&activity);
+    println!();
+    print_hours(&activity);
+    println!();
+    print_legend();
+    print_summary(&activity);
+    println!("{}", Theme::separator(60));
+    Ok(())
+}
+
+fn print_report_csv(report: &HeatmapReport) -> Result<()> {
+    let days = report.days.iter().map(|row| {
+        vec![
+            "day".to_string(),
+            row.date.to_string(),
+            row.date.format("%a").to_string(),
+            String::new(),
+            row.counts.sent.to_string(),
+            row.counts.received.to_string(),
+        ]
+    });
+    let hours = report.hours.iter().map(|row| {
+        vec![
+            "hour".to_string(),
+            String::new(),
+            row.weekday.to_string(),
+            row.hour.to_string(),
+            row.counts.sent.to_string(),
+            row.counts.received.to_string(),
+        ]
+    });
+    print_csv(&["kind", "date", "weekday", "hour", "sent", "received"], days.chain(hours))
+}
+
+/// Messages from the window, newest first
+async fn collect_messages(
+    client: &ApiClient,
+    username: Option<&str>,
+    cutoff: DateTime<Local>,
+) -> Result<Vec<Message>> {
+    let reaches_cutoff = |messages: &[Message]| {
+        messages
+            .last()
+            .and_then(|m| m.timestamp.as_deref().and_then(parse_timestamp))
+            .is_some_and(|t| t < cutoff)
+    };
+
+    if let Some(username) = username {
+        let spinner = create_spinner(&format!("Finding conversation with @{}...", username));
+        let thread = find_thread_by_username(client, username).await;
+        spinner.finish_and_clear();
+        let Some(thread) = thread? else {
+            anyhow::bail!("No conversation with @{} found", username);
+        };
+        let response = fetch_thread_until(client, &thread.id, "Counting messages", reaches_cutoff).await?;
+        if !response.success {
+            anyhow::bail!(response.error.unwrap_or("Failed to fetch thread".to_string()));
+        }
+        return Ok(response.thread.and_then(|t| t.messages).unwrap_or_default());
+    }
+
+    let inbox = fetch_inbox(client, Some(INBOX_SCAN_LIMIT)).await?;
+    if !inbox.success {
+        anyhow::bail!(inbox.error.unwrap_or("Failed to fetch inbox".to_string()));
+    }
+    let threads = inbox.threads.unwrap_or_default();
+    let spinner = create_spinner("Counting messages...");
+    let mut messages = Vec::new();
+    for thread in threads.iter().filter(|t| {
+        t.last_message_timestamp
+            .as_deref()
+            .and_then(parse_timestamp)
+            .is_some_and(|time| time >= cutoff)
+    }) {
+        if let Ok(response) = client.get_thread(&thread.id, THREAD_SCAN_LIMIT).await {
+            messages.extend(response.thread.and_then(|t| t.messages).unwrap_or_default());
+        }
+    }
+    spinner.finish_and_clear();
+    Ok(messages)
+}
+
+/// A two-column cell shaded against the busiest cell (`max`) and colored
+/// by who sent most of its messages
+fn cell(counts: Counts, max: usize) -> String {
+    let total = counts.total();
+    if total == 0 {
+        return format!("{} ", Theme::muted("·"));
+    }
+    let level = (total * SHADES.len()).div_ceil(max.max(1)).clamp(1, SHADES.len());
+    let shade = SHADES[level - 1].to_string();
+    let p = palette();
+    let (r, g, b) = if counts.sent * 3 >= total * 2 {
+        p.pink
+    } else if counts.received * 3 >= total * 2 {
+        p.blue
+    } else {
+        p.purple
+    };
+    format!("{} ", shade.truecolor(r, g, b))
+}
+
+/// One column per week, one row per weekday, with month names on top
+fn print_calendar(activity: &Activity) {
+    let mondays: Vec<NaiveDate> = activity.start.iter_weeks().take_while(|d| *d <= activity.today).collect();
+    let max = activity.days.values().map(|c| c.total()).max().unwrap_or(0);
+
+    let mut months = String::new();
+    let mut last_month = None;
+    for (i, monday) in mondays.iter().enumerate() {
+        let column = i * 2;
+        if last_month != Some(monday.month()) && months.chars().count() <= column {
+            months.push_str(&" ".repeat(column - months.chars().count()));
+            months.push_str(&monday.format("%b").to_string());
+            last_month = Some(monday.month());
+        }
+    }
+    println!("     {}", Theme::muted(&months));
+
+    for (weekday, label) in WEEKDAYS.iter().enumerate() {
+        let row: String = mondays
+            .iter()
+            .map(|monday| *monday + Duration::days(weekday as i64))
+            .take_while(|date| *date <= activity.today)
+            .map(|date| cell(activity.day(date), max))
+            .collect();
+        println!(" {} {}", Theme::muted(label), row);
+    }
+}
+
+/// One row per weekday, one column per hour of the day
+fn print_hours(activity: &Activity) {
+    let max = activity.hours.iter().flatten().map(|c| c.total()).max().unwrap_or(0);
+    println!("     {}", Theme::muted("0           6           12          18"));
+    for (weekday, label) in WEEKDAYS.iter().enumerate() {
+        let row: String = activity.hours[weekday].iter().map(|c| cell(*c, max)).collect();
+        println!(" {} {}", Theme::muted(label), row);
+    }
+}
+
+fn print_legend() {
+    let p = palette();
+    let shades: String = SHADES.iter().map(|s| format!("{} ", s)).collect();
+    println!(
+        " {} {}{}   {} sent  {} received  {} both",
+        Theme::muted("less"),
+        shades,
+        Theme::muted("more"),
+        "█".truecolor(p.pink.0, p.pink.1, p.pink.2),
+        "█".truecolor(p.blue.0, p.blue.1, p.blue.2),
+        "█".truecolor(p.purple.0, p.purple.1, p.purple.2),
+    );
+}
+
+/// Totals and the busiest weekday and hour
+fn print_summary(activity: &Activity) {
+    let total = activity.days.values().fold(Counts::default(), |acc, c| Counts {
+        sent: acc.sent + c.sent,
+        received: acc.received + c.received,
+    });
+    if total.total() == 0 {
+        println!(" {}", Theme::muted("No messages in this period."));
+        return;
+    }
+
+    let by_weekday: Vec<usize> = activity.hours.iter().map(|h| h.iter().map(|c| c.total()).sum()).collect();
+    let busiest_day = (0..7).max_by_key(|&d| (by_weekday[d], std::cmp::Reverse(d))).unwrap_or(0);
+    let busiest_hour = (0..24)
+        .max_by_key(|&h| {
+            let messages: usize = activity.hours.iter().map(|day| day[h].total()).sum();
+            (messages, std::cmp::Reverse(h))
+        })
+        .unwrap_or(0);
+
+    println!(
+        " {} {}",
+        Theme::accent(&total.total().to_string()),
+        Theme::muted(&format!(
+            "messages · {} sent · {} received · busiest on {} · around {:02}:00",
+            total.sent, total.received, WEEKDAYS[busiest_day], busiest_hour
+        ))
+    );
+}
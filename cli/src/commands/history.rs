@@ -0,0 +1,229 @@
+//! Activity timeline for one contact
+//!
+//! `ig history @user` merges the conversation's messages with everything kept
+//! locally about that person (pinned messages, follow-ups and reminders) into
+//! one chronological view, paged from the most recent activity backwards.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::followup::FollowUps;
+use crate::commands::inbox::find_thread_by_username;
+use crate::commands::pages::fetch_thread;
+use crate::commands::pins::Pins;
+use crate::commands::remind::Reminders;
+use crate::commands::tags::{tag_chip, Tags};
+use crate::nicknames;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+use crate::users;
+use crate::util::{parse_timestamp, truncate};
+
+/// What a timeline entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum EntryKind {
+    Message,
+    Pin,
+    Followup,
+    Reminder,
+}
+
+impl EntryKind {
+    fn label(self) -> &'static str {
+        match self {
+            EntryKind::Message => "message",
+            EntryKind::Pin => "pin",
+            EntryKind::Followup => "follow-up",
+            EntryKind::Reminder => "reminder",
+        }
+    }
+}
+
+/// One event in the timeline
+#[derive(Debug, Serialize)]
+struct Entry {
+    time: DateTime<Local>,
+    kind: EntryKind,
+    /// Who sent the message (empty for local notes)
+    from: String,
+    text: String,
+}
+
+/// Show the timeline for `target` (@user); `page` 1 is the most recent
+pub async fn show_history(
+    client: &ApiClient,
+    target: &str,
+    limit: Option<u32>,
+    page: usize,
+    per_page: usize,
+    output: OutputFormat,
+) -> Result<()> {
+    let username = target.trim_start_matches('@');
+    let mut entries = local_entries(username)?;
+
+    let spinner = create_spinner(&format!("Finding conversation with @{}...", username));
+    let thread = find_thread_by_username(client, username).await;
+    spinner.finish_and_clear();
+
+    if let Some(thread) = thread? {
+        let response = fetch_thread(client, &thread.id, limit).await?;
+        if !response.success {
+            anyhow::bail!(response.error.unwrap_or("Failed to fetch thread".to_string()));
+        }
+        let thread = response.thread.unwrap_or(thread);
+        for msg in thread.messages.iter().flatten() {
+            let Some(time) = msg.timestamp.as_deref().and_then(parse_timestamp) else {
+                continue;
+            };
+            let from = msg
+                .user_id
+                .as_ref()
+                .and_then(|uid| users::username(&thread.users, uid))
+                .unwrap_or("You".to_string());
+            entries.push(Entry {
+                time,
+                kind: EntryKind::Message,
+                from,
+                text: msg.text.clone().unwrap_or("[media]".to_string()),
+            });
+        }
+
+        let pins = Pins::load().unwrap_or_default();
+        for pin in pins.for_thread(&thread.id) {
+            entries.push(Entry {
+                time: pin.pinned_at,
+                kind: EntryKind::Pin,
+                from: String::new(),
+                text: format!("Pinned {}: {}", pin.sender, pin.preview()),
+            });
+        }
+    }
+
+    // Newest first for paging; each page is then shown oldest first
+    entries.sort_by_key(|e| std::cmp::Reverse(e.time));
+    let per_page = per_page.max(1);
+    let pages = entries.len().div_ceil(per_page);
+    let page = page.max(1);
+    let mut shown: Vec<&Entry> = entries.iter().skip((page - 1) * per_page).take(per_page).collect();
+    shown.reverse();
+
+    if output == OutputFormat::Json {
+        return print_json(&shown);
+    }
+
+    if entries.is_empty() {
+        println!("{}", Theme::muted(&format!("No history with @{}.", username)));
+        return Ok(());
+    }
+    if shown.is_empty() {
+        println!(
+            "{}",
+            Theme::muted(&format!("Page {} is past the end ({} pages).", page, pages))
+        );
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Time", "Type", "From", "Text"]);
+        for entry in &shown {
+            table.row([
+                entry.time.format("%Y-%m-%d %H:%M").to_string(),
+                entry.kind.label().to_string(),
+                entry.from.clone(),
+                truncate(&entry.text, 60),
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    let tags = Tags::load().unwrap_or_default();
+    let chips = tags
+        .for_user(username)
+        .map(|set| set.iter().map(|t| tag_chip(t)).collect::<Vec<_>>().join(" "))
+        .unwrap_or_default();
+
+    println!();
+    println!(
+        "{} {} {}",
+        Theme::header("History with"),
+        Theme::username(&nicknames::display(username)),
+        chips
+    );
+    println!("{}", Theme::separator(60));
+
+    let mut day = String::new();
+    for entry in &shown {
+        let entry_day = entry.time.format("%a %b %d %Y").to_string();
+        if entry_day != day {
+            println!("{}", Theme::accent(&entry_day));
+            day = entry_day;
+        }
+        print_entry(entry);
+    }
+
+    println!("{}", Theme::separator(60));
+    let mut footer = format!("Page {} of {}", page, pages);
+    if page < pages {
+        footer.push_str(&format!(" — older: ig history @{} --page {}", username, page + 1));
+    }
+    println!("{}", Theme::muted(&footer));
+
+    Ok(())
+}
+
+/// Follow-ups and reminders about `username`
+fn local_entries(username: &str) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+
+    if let Some(followup) = FollowUps::load()?.for_user(username) {
+        entries.push(Entry {
+            time: followup.created,
+            kind: EntryKind::Followup,
+            from: String::new(),
+            text: note_text("Follow-up due", followup.due, &followup.note),
+        });
+    }
+
+    let reminders = Reminders::load()?;
+    for reminder in reminders
+        .items
+        .iter()
+        .filter(|r| r.username.eq_ignore_ascii_case(username))
+    {
+        entries.push(Entry {
+            time: reminder.created,
+            kind: EntryKind::Reminder,
+            from: String::new(),
+            text: note_text("Reminder for", reminder.due, &reminder.note),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// "Follow-up due Mon Mar 02 09:00: send quote"
+fn note_text(what: &str, due: DateTime<Local>, note: &str) -> String {
+    let mut text = format!("{} {}", what, due.format("%a %b %d %H:%M"));
+    if !note.is_empty() {
+        text.push_str(": ");
+        text.push_str(note);
+    }
+    text
+}
+
+/// Print one timeline line
+fn print_entry(entry: &Entry) {
+    let time = Theme::timestamp(&entry.time.format("%H:%M").to_string());
+    match entry.kind {
+        EntryKind::Message => println!("  {} {} {}", time, Theme::pink(&entry.from), entry.text),
+        EntryKind::Pin => println!("  {} {} {}", time, Theme::accent("📌"), Theme::muted(&entry.text)),
+        EntryKind::Followup | EntryKind::Reminder => {
+            println!("  {} {} {}", time, Theme::warn_icon(), Theme::warning(&entry.text))
+        }
+    }
+}
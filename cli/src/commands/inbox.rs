@@ -1,9 +1,13 @@
 //! Inbox and thread commands
 
 use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use clap::ValueEnum;
 use colored::Colorize;
-use std::io::{self, Write};
-use std::time::Duration;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal, Write};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 use crossterm::{
     cursor,
     event::{self, Event, KeyCode, KeyEventKind},
@@ -11,20 +15,153 @@ use crossterm::{
     terminal::{self, ClearType},
 };
 
+use crate::aliases;
 use crate::client::ApiClient;
-use crate::colors::{Theme, instagram};
-use crate::models::Thread;
+use crate::duration::{format_duration, Interval};
+use crate::emoji;
+use crate::colors::{colors_enabled, hashed_color, reset_escape, Theme};
+use crate::config::Config;
+use crate::models::{Message, Thread};
+use crate::nicknames;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::commands::assign::Assignments;
+use crate::commands::batch::{prompt_tag, run_batch, BatchAction};
 use crate::commands::chat_with_user;
+use crate::commands::followup::review_followups;
+use crate::commands::goto::{anchor, day_marker, fetch_around, message_day};
+use crate::commands::{fetch_inbox, fetch_recent, fetch_thread, Prefetch, PREFETCH_THREADS};
+use crate::commands::pins::{pin_lines, Pins};
+use crate::commands::export::InboxRow;
+use crate::commands::tags::{tag_chip, Tags};
+use crate::language;
+use crate::render::{Canvas, FrameClock};
+use crate::snapshot::{self, InboxSnapshot};
 use crate::spinner::create_spinner;
+use crate::translate::{Provider, Translation, Translator};
+use crate::users;
+use crate::util::{ellipsize, format_time_ago, parse_time_ago, parse_timestamp, truncate, TimeAge};
+
+/// Messages shown in the interactive inbox's preview pane
+const PREVIEW_MESSAGES: usize = 3;
+
+/// Messages shown by `ig thread --around` when `--limit` isn't given
+const AROUND_DEFAULT: u32 = 20;
+
+/// Conversations listed by `ig inbox` when the terminal size is unknown
+/// (output piped, or JSON, tables and exports)
+pub const INBOX_LIMIT: u32 = 20;
+
+/// Lines of the inbox listing besides the conversations (blank line,
+/// header, two separators, footer) plus the shell prompt after it
+const INBOX_CHROME: u16 = 6;
+
+/// Most lines `--group-by recency` adds to the listing: a header for each
+/// of the four sections, with a blank line before all but the first
+const SECTION_LINES: u16 = 7;
+
+/// Never list fewer conversations than this, however small the terminal
+const INBOX_MIN_FIT: u32 = 3;
+
+/// Width of each unseen message shown under an unread conversation
+const UNSEEN_WIDTH: usize = 50;
+
+/// How often the interactive inbox checks whether a preview has arrived
+const PREVIEW_POLL_MS: u64 = 200;
+
+/// Screen regions of the interactive inbox, top to bottom
+const REGION_HEADER: usize = 0;
+const REGION_LIST: usize = 1;
+const REGION_PREVIEW: usize = 2;
+const REGION_FOOTER: usize = 3;
+
+/// How the plain inbox listing is laid out (`--group-by`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    /// One list, newest first
+    #[default]
+    None,
+    /// Unread first, then Today, This week and Older
+    Recency,
+}
 
-/// Display inbox (list of conversations)
-pub async fn show_inbox(client: &ApiClient, limit: u32, unread_only: bool) -> Result<()> {
-    let spinner = create_spinner("Fetching inbox...");
+/// Section of the inbox grouped by recency, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Section {
+    Unread,
+    Today,
+    ThisWeek,
+    Older,
+}
 
-    let response = client.get_inbox(limit).await;
-    spinner.finish_and_clear();
+impl Section {
+    /// Section a thread falls in at `now`; threads without a usable
+    /// timestamp count as older
+    pub fn of(thread: &Thread, now: DateTime<Local>) -> Self {
+        if thread.has_unread.unwrap_or(false) {
+            return Section::Unread;
+        }
+        let Some(time) = thread.last_message_timestamp.as_deref().and_then(parse_timestamp) else {
+            return Section::Older;
+        };
+        if time.date_naive() == now.date_naive() {
+            Section::Today
+        } else if now.signed_duration_since(time) < chrono::Duration::days(7) {
+            Section::ThisWeek
+        } else {
+            Section::Older
+        }
+    }
 
-    let response = response?;
+    pub fn label(self) -> &'static str {
+        match self {
+            Section::Unread => "Unread",
+            Section::Today => "Today",
+            Section::ThisWeek => "This week",
+            Section::Older => "Older",
+        }
+    }
+}
+
+/// Sort threads into their sections, keeping inbox order within each
+/// one; returns each thread's section in the new order
+pub fn group_by_recency(threads: &mut [Thread], now: DateTime<Local>) -> Vec<Section> {
+    threads.sort_by_key(|t| Section::of(t, now));
+    threads.iter().map(|t| Section::of(t, now)).collect()
+}
+
+/// How many conversations fit on a terminal `rows` high, at two lines each
+/// and leaving room for the section headers when grouped
+pub fn fitting_limit(rows: u16, group_by: GroupBy) -> u32 {
+    let chrome = match group_by {
+        GroupBy::Recency => INBOX_CHROME + SECTION_LINES,
+        GroupBy::None => INBOX_CHROME,
+    };
+    (u32::from(rows.saturating_sub(chrome)) / 2).max(INBOX_MIN_FIT)
+}
+
+/// Conversations to list when `--limit` isn't given: as many as fit the
+/// terminal, or None when stdout isn't one or its size is unknown
+pub fn default_inbox_limit(group_by: GroupBy) -> Option<u32> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    match terminal::size() {
+        Ok((_, rows)) if rows > 0 => Some(fitting_limit(rows, group_by)),
+        _ => None,
+    }
+}
+
+/// Display inbox (list of conversations)
+pub async fn show_inbox(
+    client: &ApiClient,
+    limit: Option<u32>,
+    unread_only: bool,
+    tag: Option<&str>,
+    group_by: GroupBy,
+    compact: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let response = fetch_inbox(client, limit).await?;
 
     if !response.success {
         println!(
@@ -35,14 +172,35 @@ pub async fn show_inbox(client: &ApiClient, limit: u32, unread_only: bool) -> Re
         return Ok(());
     }
 
-    let threads = response.threads.unwrap_or_default();
-
-    // Filter to unread only if flag is set
-    let threads: Vec<_> = if unread_only {
-        threads.into_iter().filter(|t| t.has_unread.unwrap_or(false)).collect()
-    } else {
-        threads
+    let tags = Tags::load().unwrap_or_default();
+    let mut threads = filter_threads(response.threads.unwrap_or_default(), unread_only, tag, &tags);
+    let assignments = Assignments::for_inbox(client).await;
+
+    match output {
+        OutputFormat::Json => {
+            let rows: Vec<InboxRow> = threads
+                .iter()
+                .map(|t| InboxRow {
+                    assigned_to: assignments.get(t).map(|a| a.assignee.clone()),
+                    ..InboxRow::new(t, &tags)
+                })
+                .collect();
+            return print_json(&rows);
+        }
+        OutputFormat::Table => {
+            print_inbox_table(&threads, &tags, &assignments);
+            snapshot::record(&threads);
+            return Ok(());
+        }
+        // Refused up front for anything but the statistics commands
+        OutputFormat::Plain | OutputFormat::Csv => {}
+    }
+    // Number the threads in the order they're shown
+    let sections = match group_by {
+        GroupBy::Recency => Some(group_by_recency(&mut threads, Local::now())),
+        GroupBy::None => None,
     };
+    snapshot::record(&threads);
 
     if threads.is_empty() {
         if unread_only {
@@ -53,143 +211,386 @@ pub async fn show_inbox(client: &ApiClient, limit: u32, unread_only: bool) -> Re
         return Ok(());
     }
 
-    println!();
-    if unread_only {
-        println!("{} {}", Theme::header("Inbox"), Theme::blue("(unread)"));
-    } else {
-        println!("{}", Theme::header("Inbox"));
+    let title = inbox_header(unread_only, tag);
+    for line in inbox_lines(&threads, sections.as_deref(), &title, &tags, &assignments, compact) {
+        println!("{}", line);
     }
-    println!("{}", Theme::separator(60));
 
+    Ok(())
+}
+
+/// The plain inbox listing line by line, from the blank line above the
+/// header to the footer
+///
+/// With `compact`, each conversation takes exactly two lines (see
+/// [`unseen_preview`]), so a listing sized by [`fitting_limit`] fits the
+/// terminal.
+pub fn inbox_lines(
+    threads: &[Thread],
+    sections: Option<&[Section]>,
+    title: &str,
+    tags: &Tags,
+    assignments: &Assignments,
+    compact: bool,
+) -> Vec<String> {
+    let mut lines = vec![String::new(), title.to_string(), Theme::separator(60).to_string()];
+
+    let aliases = aliases::assign(threads.iter().map(|t| t.id.as_str()));
     for (i, thread) in threads.iter().enumerate() {
-        print_thread_summary(i + 1, thread);
+        if let Some(header) = section_header(sections, i) {
+            lines.extend(header.split('\n').map(str::to_string));
+        }
+        let alias = aliases.get(&thread.id).map(String::as_str);
+        lines.extend(thread_summary_lines(i + 1, thread, tags, assignments, alias, None, compact));
     }
 
-    println!("{}", Theme::separator(60));
-    println!(
-        "{}",
-        Theme::muted(&format!("Showing {} conversations", threads.len()))
-    );
+    lines.push(Theme::separator(60).to_string());
+    lines.push(Theme::muted(&format!("Showing {} conversations", threads.len())).to_string());
+    lines
+}
 
-    Ok(())
+/// Header to print before the `i`th thread when it starts a new section
+fn section_header(sections: Option<&[Section]>, i: usize) -> Option<String> {
+    let sections = sections?;
+    let section = *sections.get(i)?;
+    if i > 0 && sections[i - 1] == section {
+        return None;
+    }
+    let count = sections.iter().filter(|s| **s == section).count();
+    let blank = if i > 0 { "\n" } else { "" };
+    Some(format!("{}{} {}", blank, Theme::blue(section.label()), Theme::muted(&format!("({})", count))))
+}
+
+/// Print the inbox as a table
+///
+/// With `[team] operator` set there is an Assigned column too.
+fn print_inbox_table(threads: &[Thread], tags: &Tags, assignments: &Assignments) {
+    let aliases = aliases::assign(threads.iter().map(|t| t.id.as_str()));
+    let mut headers = vec!["#", "Alias", "Conversation", "Username", "Last message", "Time", "Unread", "Tags"];
+    if assignments.enabled {
+        headers.push("Assigned");
+    }
+    let mut table = Table::new(&headers);
+    for (i, thread) in threads.iter().enumerate() {
+        let row = InboxRow::new(thread, tags);
+        let username = row.participants.first().cloned().unwrap_or_default();
+        let mut cells = vec![
+            (i + 1).to_string(),
+            aliases.get(&thread.id).cloned().unwrap_or_default(),
+            thread.thread_title.clone().unwrap_or_else(|| nicknames::name(&username)),
+            format!("@{}", username),
+            truncate(row.last_message.as_deref().unwrap_or("[media]"), 35),
+            row.last_timestamp.as_deref().map(format_time_ago).unwrap_or_default(),
+            if row.unread { "● NEW".to_string() } else { String::new() },
+            row.tags.join(", "),
+        ];
+        if assignments.enabled {
+            cells.push(assignments.assignee(thread).unwrap_or_default());
+        }
+        table.row(cells);
+    }
+    table.print();
+}
+
+/// Apply the unread and tag filters to a list of threads
+pub(crate) fn filter_threads(threads: Vec<Thread>, unread_only: bool, tag: Option<&str>, tags: &Tags) -> Vec<Thread> {
+    threads
+        .into_iter()
+        .filter(|t| !unread_only || t.has_unread.unwrap_or(false))
+        .filter(|t| tag.is_none_or(|tag| tags.thread_has(t, tag)))
+        .collect()
 }
 
-/// Watch mode - auto-refresh inbox every N seconds
-pub async fn show_inbox_watch(client: &ApiClient, limit: u32, unread_only: bool, interval: u64) -> Result<()> {
+/// Inbox title with the active filters
+pub(crate) fn inbox_header(unread_only: bool, tag: Option<&str>) -> String {
+    let mut header = Theme::header("Inbox").to_string();
+    if unread_only {
+        header.push_str(&format!(" {}", Theme::blue("(unread)")));
+    }
+    if let Some(tag) = tag {
+        header.push_str(&format!(" {}", tag_chip(tag)));
+    }
+    header
+}
+
+/// Consecutive failed refreshes after which watch mode's footer turns red
+const STALE_AFTER_FAILURES: u32 = 3;
+
+/// How current the list in watch mode is, shown in its footer
+#[derive(Debug, Default)]
+pub struct Freshness {
+    /// When the list was last refreshed
+    updated: Option<Instant>,
+    /// Refreshes that failed since then
+    failures: u32,
+    error: Option<String>,
+}
+
+impl Freshness {
+    /// The list was just refreshed
+    pub fn refreshed(&mut self, at: Instant) {
+        *self = Self { updated: Some(at), failures: 0, error: None };
+    }
+
+    /// A refresh failed; the list on screen is kept
+    pub fn failed(&mut self, error: String) {
+        self.failures += 1;
+        self.error = Some(error);
+    }
+
+    /// Whether there is a list to show
+    pub fn has_data(&self) -> bool {
+        self.updated.is_some()
+    }
+
+    /// Footer line: muted while refreshes succeed, yellow once one fails and
+    /// red after several in a row
+    pub fn footer(&self, now: Instant, interval: &Interval) -> String {
+        let age = self.updated.map(|at| {
+            let secs = now.saturating_duration_since(at).as_secs();
+            match secs {
+                0 => "just now".to_string(),
+                secs => format!("{} ago", format_duration(chrono::Duration::seconds(secs as i64))),
+            }
+        });
+        let error = self.error.as_deref().unwrap_or_default();
+        match (self.failures, age) {
+            (0, age) => format!(
+                "{} {} {}",
+                Theme::muted(&format!("Updated {}", age.unwrap_or("never".to_string()))),
+                Theme::muted("•"),
+                Theme::muted(&format!("Refreshing every {}", interval))
+            ),
+            (failures, age) if failures < STALE_AFTER_FAILURES => format!(
+                "{} {}",
+                Theme::warn_icon(),
+                Theme::warning(&format!(
+                    "Refresh failed ({}) • last updated {} • retrying every {}",
+                    error,
+                    age.unwrap_or("never".to_string()),
+                    interval
+                ))
+            ),
+            (failures, age) => format!(
+                "{} {}",
+                Theme::cross(),
+                Theme::error(&format!(
+                    "{} refreshes failed ({}) • {} • retrying every {}",
+                    failures,
+                    error,
+                    match age {
+                        Some(age) => format!("data is stale, last updated {}", age),
+                        None => "no data yet".to_string(),
+                    },
+                    interval
+                ))
+            ),
+        }
+    }
+}
+
+/// Watch mode - auto-refresh inbox every `interval`
+///
+/// The footer shows how long ago the list was refreshed, ticking every
+/// second. A failed refresh keeps the last list on screen and says so.
+pub async fn show_inbox_watch(
+    client: &ApiClient,
+    limit: u32,
+    unread_only: bool,
+    tag: Option<&str>,
+    group_by: GroupBy,
+    compact: bool,
+    interval: Interval,
+) -> Result<()> {
     // Enable raw mode for keyboard detection
     terminal::enable_raw_mode()?;
     let mut stdout = io::stdout();
 
     // Hide cursor
     execute!(stdout, cursor::Hide)?;
+    // The list, then the footer
+    let mut canvas = Canvas::new(2);
+    let mut freshness = Freshness::default();
 
     loop {
-        // Clear screen
-        execute!(stdout, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        let mut frame = Vec::new();
 
         // Fetch inbox
-        let response = client.get_inbox(limit).await;
+        let response = client
+            .get_inbox(limit)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|response| match response.success {
+                true => Ok(response),
+                false => Err(response.error.unwrap_or("Failed to fetch inbox".to_string())),
+            });
 
         match response {
             Ok(response) => {
-                if !response.success {
-                    writeln!(
-                        stdout,
-                        "\r{} {}",
-                        Theme::cross(),
-                        Theme::error(&response.error.unwrap_or("Failed to fetch inbox".to_string()))
-                    )?;
-                } else {
-                    let threads = response.threads.unwrap_or_default();
-
-                    // Filter to unread only if flag is set
-                    let threads: Vec<_> = if unread_only {
-                        threads.into_iter().filter(|t| t.has_unread.unwrap_or(false)).collect()
-                    } else {
-                        threads
-                    };
-
-                    // Header
-                    writeln!(stdout, "\r")?;
+                let tags = Tags::load().unwrap_or_default();
+                let mut threads = filter_threads(response.threads.unwrap_or_default(), unread_only, tag, &tags);
+                // Fetched each refresh, as teammates pick threads up
+                let assignments = Assignments::for_inbox(client).await;
+                let sections = match group_by {
+                    GroupBy::Recency => Some(group_by_recency(&mut threads, Local::now())),
+                    GroupBy::None => None,
+                };
+                snapshot::record(&threads);
+
+                // Header
+                writeln!(frame, "\r")?;
+                writeln!(frame, "\r{}", inbox_header(unread_only, tag))?;
+                writeln!(frame, "\r{}", Theme::separator(60))?;
+
+                if threads.is_empty() {
                     if unread_only {
-                        writeln!(stdout, "\r{} {}", Theme::header("Inbox"), Theme::blue("(unread)"))?;
+                        writeln!(frame, "\r{}", Theme::muted("No unread conversations."))?;
                     } else {
-                        writeln!(stdout, "\r{}", Theme::header("Inbox"))?;
+                        writeln!(frame, "\r{}", Theme::muted("No conversations found."))?;
                     }
-                    writeln!(stdout, "\r{}", Theme::separator(60))?;
-
-                    if threads.is_empty() {
-                        if unread_only {
-                            writeln!(stdout, "\r{}", Theme::muted("No unread conversations."))?;
-                        } else {
-                            writeln!(stdout, "\r{}", Theme::muted("No conversations found."))?;
-                        }
-                    } else {
-                        for (i, thread) in threads.iter().enumerate() {
-                            print_thread_summary_watch(&mut stdout, i + 1, thread)?;
+                } else {
+                    let aliases = aliases::assign(threads.iter().map(|t| t.id.as_str()));
+                    for (i, thread) in threads.iter().enumerate() {
+                        if let Some(header) = section_header(sections.as_deref(), i) {
+                            writeln!(frame, "\r{}", header.replace('\n', "\r\n"))?;
                         }
+                        let alias = aliases.get(&thread.id).map(String::as_str);
+                        print_thread_summary_watch(&mut frame, i + 1, thread, &tags, &assignments, alias, compact)?;
                     }
-
-                    writeln!(stdout, "\r{}", Theme::separator(60))?;
-                    writeln!(
-                        stdout,
-                        "\r{} {} {}",
-                        Theme::muted(&format!("Showing {} conversations", threads.len())),
-                        Theme::muted("•"),
-                        Theme::muted(&format!("Refreshing every {}s", interval))
-                    )?;
                 }
+
+                writeln!(frame, "\r{}", Theme::separator(60))?;
+                writeln!(frame, "\r{}", Theme::muted(&format!("Showing {} conversations", threads.len())))?;
+                canvas.set_output(0, &frame);
+                freshness.refreshed(Instant::now());
             }
             Err(e) => {
-                writeln!(stdout, "\r{} {}", Theme::cross(), Theme::error(&format!("{}", e)))?;
+                // The last list stays up; the footer says it's out of date
+                if !freshness.has_data() {
+                    writeln!(frame, "\r")?;
+                    writeln!(frame, "\r{}", inbox_header(unread_only, tag))?;
+                    writeln!(frame, "\r{}", Theme::separator(60))?;
+                    canvas.set_output(0, &frame);
+                }
+                freshness.failed(e);
             }
         }
 
-        writeln!(stdout, "\r")?;
-        writeln!(stdout, "\r{}", Theme::muted("Press 'q' to quit"))?;
-        stdout.flush()?;
-
-        // Wait for interval, but check for 'q' key every 100ms
+        // Wait for interval, but check for 'q' key every 100ms and tick the
+        // footer every second
         let check_interval = Duration::from_millis(100);
-        let total_checks = (interval * 1000) / 100;
+        let total_checks = (interval.0.as_millis() / check_interval.as_millis()).max(1);
 
-        for _ in 0..total_checks {
+        for check in 0..total_checks {
+            if check % 10 == 0 {
+                let footer = freshness.footer(Instant::now(), &interval);
+                canvas.set(1, vec![footer, String::new(), Theme::muted("Press 'q' to quit").to_string()]);
+                canvas.draw(&mut stdout)?;
+            }
             if event::poll(check_interval)? {
-                if let Event::Key(key_event) = event::read()? {
-                    if key_event.kind == KeyEventKind::Press {
-                        if matches!(key_event.code, KeyCode::Char('q') | KeyCode::Esc) {
-                            // Restore terminal
-                            execute!(stdout, cursor::Show)?;
-                            terminal::disable_raw_mode()?;
-                            println!("\r");
-                            return Ok(());
-                        }
+                match event::read()? {
+                    Event::Key(key_event)
+                        if key_event.kind == KeyEventKind::Press
+                            && matches!(key_event.code, KeyCode::Char('q') | KeyCode::Esc) =>
+                    {
+                        // Restore terminal
+                        canvas.move_below(&mut stdout)?;
+                        execute!(stdout, cursor::Show)?;
+                        terminal::disable_raw_mode()?;
+                        println!("\r");
+                        return Ok(());
                     }
+                    Event::Resize(_, rows) => {
+                        canvas.resize(rows);
+                        canvas.draw(&mut stdout)?;
+                    }
+                    _ => {}
                 }
             }
         }
     }
 }
 
+/// A thread's title in its `[thread."<name>"]` color from config.toml, or
+/// `default` for threads without one
+fn paint_title(thread: &Thread, title: &str, default: fn(&str) -> String) -> String {
+    match display_config().thread_settings(thread).and_then(|t| t.color) {
+        Some(color) => color.paint(title).to_string(),
+        None => default(title),
+    }
+}
+
+/// config.toml as loaded once for inbox rendering
+fn display_config() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(|| Config::load().unwrap_or_default())
+}
+
+/// A colored badge with the thread's initials followed by a space, when
+/// `[display] avatars` is on. The color comes from the username (the thread
+/// id for groups), so each person keeps theirs as titles and nicknames change.
+fn avatar(thread: &Thread, title: &str) -> String {
+    if !display_config().display.avatars {
+        return String::new();
+    }
+    let initials = initials(title);
+    if !colors_enabled() {
+        return format!("{} ", initials);
+    }
+    let key = match thread.users.as_slice() {
+        [user] => user.username.as_str(),
+        _ => thread.id.as_str(),
+    };
+    let (r, g, b) = hashed_color(key);
+    // Dark text on the light palette colors, white on the rest
+    let luma = (299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000;
+    let text = if luma > 160 { (0, 0, 0) } else { (255, 255, 255) };
+    format!(
+        "{} ",
+        format!(" {} ", initials)
+            .on_truecolor(r, g, b)
+            .truecolor(text.0, text.1, text.2)
+            .bold()
+    )
+}
+
+/// Two uppercase initials: the first letters of the title's first two
+/// words ("Phyu Sin" → "PS", "phyu_sin_htett" → "PS"), or its first two
+/// letters when it's a single word ("alice" → "AL")
+fn initials(title: &str) -> String {
+    let words: Vec<&str> = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let letters: String = match words.as_slice() {
+        [] => "?".to_string(),
+        [word] => word.chars().take(2).collect(),
+        [first, second, ..] => first.chars().take(1).chain(second.chars().take(1)).collect(),
+    };
+    format!("{:<2}", letters.to_uppercase())
+}
+
 /// Print thread summary for watch mode (with \r for raw mode)
-fn print_thread_summary_watch(stdout: &mut io::Stdout, index: usize, thread: &Thread) -> Result<()> {
+fn print_thread_summary_watch(
+    stdout: &mut impl Write,
+    index: usize,
+    thread: &Thread,
+    tags: &Tags,
+    assignments: &Assignments,
+    alias: Option<&str>,
+    compact: bool,
+) -> Result<()> {
     let username = thread.users.first().map(|u| u.username.as_str()).unwrap_or("unknown");
 
     let title = thread
         .thread_title
         .clone()
-        .unwrap_or_else(|| username.to_string());
+        .unwrap_or_else(|| nicknames::name(username));
 
     let last_msg = thread
         .last_message_text
         .as_ref()
-        .map(|s| {
-            if s.len() > 40 {
-                format!("{}...", &s[..37])
-            } else {
-                s.clone()
-            }
-        })
+        .map(|s| ellipsize(s, 37))
         .unwrap_or_else(|| "[no messages]".to_string());
 
     let time_ago = thread
@@ -199,33 +600,92 @@ fn print_thread_summary_watch(stdout: &mut io::Stdout, index: usize, thread: &Th
         .unwrap_or_else(|| Theme::timestamp("").to_string());
 
     let unread_indicator = if thread.has_unread.unwrap_or(false) {
-        format!("{} ", Theme::unread_dot())
+        format!("{} ", Theme::unread_marker())
     } else {
         "  ".to_string()
     };
 
     writeln!(
         stdout,
-        "\r{}{:>2}. {} {} {}",
+        "\r{}{:>2}. {}{} {} {}{}{}",
         unread_indicator,
         index,
-        title,
+        avatar(thread, &title),
+        paint_title(thread, &title, str::to_string),
         Theme::muted(&format!("@{}", username)),
+        chips_suffix(tags, thread),
+        assignments.chip(thread),
         time_ago
     )?;
-    writeln!(stdout, "\r     └ {}", Theme::muted(&last_msg))?;
+    match unseen_preview(thread, compact) {
+        Some(lines) => {
+            for (i, line) in lines.iter().enumerate() {
+                let alias = if i + 1 == lines.len() { alias_suffix(alias) } else { String::new() };
+                writeln!(stdout, "\r     {}{}", line, alias)?;
+            }
+        }
+        None => writeln!(stdout, "\r     └ {}{}", Theme::muted(&last_msg), alias_suffix(alias))?,
+    }
 
     Ok(())
 }
 
-/// Display a specific thread with messages
-pub async fn show_thread(client: &ApiClient, thread_id: &str, limit: u32) -> Result<()> {
-    let spinner = create_spinner("Fetching messages...");
+/// Diff-style lines for an unread conversation's unseen messages, oldest
+/// first, or None when the one-line preview says it all (read, or a server
+/// that doesn't send them)
+///
+/// With `compact`, only the newest message is shown and the rest are
+/// counted as earlier, keeping the conversation to its two lines.
+pub fn unseen_preview(thread: &Thread, compact: bool) -> Option<Vec<String>> {
+    if !thread.has_unread.unwrap_or(false) {
+        return None;
+    }
+    let all = thread.unseen_messages.as_deref().filter(|m| !m.is_empty())?;
+    let messages = if compact { &all[..1] } else { all };
+    let group = thread.users.len() > 1;
 
-    let response = client.get_thread(thread_id, limit).await;
-    spinner.finish_and_clear();
+    let mut lines: Vec<String> = messages
+        .iter()
+        .rev()
+        .map(|message| {
+            let text = message.text.as_deref().unwrap_or("[media]").replace('\n', " ");
+            let sender = message
+                .user_id
+                .as_deref()
+                .filter(|_| group)
+                .and_then(|uid| users::username(&thread.users, uid))
+                .map(|username| nicknames::name(&username));
+            let text = match sender {
+                Some(sender) => format!("{}: {}", sender, text),
+                None => text,
+            };
+            Theme::success(&format!("+ {}", ellipsize(&text, UNSEEN_WIDTH))).to_string()
+        })
+        .collect();
 
-    let response = response?;
+    let unseen = (thread.unseen_count.unwrap_or(0) as usize).max(all.len());
+    let earlier = unseen - messages.len();
+    if earlier > 0 {
+        lines[0].push_str(&format!(" {}", Theme::muted(&format!("(+{} earlier)", earlier))));
+    }
+    Some(lines)
+}
+
+/// Display a specific thread with messages
+///
+/// With `around`, shows `limit` messages around the start of that day
+/// instead of the latest ones (see [`fetch_around`]).
+pub async fn show_thread(
+    client: &ApiClient,
+    thread_id: &str,
+    limit: Option<u32>,
+    translate: Option<&str>,
+    around: Option<NaiveDate>,
+) -> Result<()> {
+    let response = match around {
+        Some(date) => fetch_around(client, thread_id, date, limit.unwrap_or(AROUND_DEFAULT)).await?,
+        None => fetch_thread(client, thread_id, limit).await?,
+    };
 
     if !response.success {
         println!(
@@ -245,7 +705,7 @@ pub async fn show_thread(client: &ApiClient, thread_id: &str, limit: u32) -> Res
     };
 
     println!();
-    let participants: Vec<&str> = thread.users.iter().map(|u| u.username.as_str()).collect();
+    let participants: Vec<String> = thread.users.iter().map(|u| nicknames::name(&u.username)).collect();
     println!(
         "{} {}",
         Theme::header("Conversation with:"),
@@ -253,6 +713,16 @@ pub async fn show_thread(client: &ApiClient, thread_id: &str, limit: u32) -> Res
     );
     println!("{}", Theme::separator(60));
 
+    // Locally pinned messages go in the header for quick reference
+    let pins = Pins::load().unwrap_or_default();
+    let thread_pins = pins.for_thread(thread_id);
+    if !thread_pins.is_empty() {
+        for line in pin_lines(&thread_pins) {
+            println!("{}", line);
+        }
+        println!("{}", Theme::separator(60));
+    }
+
     let messages = thread.messages.unwrap_or_default();
 
     if messages.is_empty() {
@@ -260,37 +730,138 @@ pub async fn show_thread(client: &ApiClient, thread_id: &str, limit: u32) -> Res
         return Ok(());
     }
 
-    for msg in messages.iter().rev() {
-        // Find the sender
-        let sender = msg.user_id.as_ref().and_then(|uid| {
-            thread.users.iter().find(|u| &u.pk == uid)
-        }).map(|u| u.username.as_str()).unwrap_or("You");
+    let translations = match translate {
+        Some(target) => translate_messages(&messages, target).await,
+        None => Vec::new(),
+    };
+    let alias = aliases::alias_for(thread_id);
 
-        let text = msg.text.as_deref().unwrap_or("[media]");
+    // Where the day starts: above its first message, or below them all
+    let day_start = around.map(|date| {
+        let at = anchor(&messages, date);
+        let reached = message_day(&messages[at]).is_some_and(|day| day >= date);
+        (date, reached.then_some(at))
+    });
+    for (i, msg) in messages.iter().enumerate().rev() {
+        if let Some((date, Some(_))) = day_start.filter(|&(_, at)| at == Some(i)) {
+            println!("{}\n", day_marker(date, Some(msg)));
+        }
+
+        // Find the sender
+        let sender = msg
+            .user_id
+            .as_ref()
+            .and_then(|uid| users::username(&thread.users, uid))
+            .map(|username| nicknames::name(&username))
+            .unwrap_or("You".to_string());
+
+        let label = msg.media_label();
+        let text = msg.text.as_deref().or(label.as_deref()).unwrap_or("[media]");
         let time = msg.timestamp.as_ref()
             .map(|t| format_time_ago(t))
             .unwrap_or_default();
 
+        let tag = if msg.is_sent_by_viewer == Some(true) {
+            String::new()
+        } else {
+            language::tag(text)
+        };
+
         println!(
-            "{} {}",
-            Theme::pink(sender),
+            "{} {}{}",
+            Theme::pink(&sender),
+            tag,
             Theme::timestamp(&time)
         );
-        println!("  {}", text);
+        match emoji::big(text) {
+            Some(big) => println!("\n{}\n", big),
+            None => println!("  {}", text),
+        }
+        if let Some(Some(translation)) = translations.get(i) {
+            println!("  {}", Theme::muted(&format!("↳ {}", translation.text)));
+        }
+        if msg.text.is_none() && label.is_some() {
+            let thread = alias.as_deref().unwrap_or(thread_id);
+            println!("  {}", Theme::muted(&format!("↳ ig download {} -m {} to save it", thread, msg.id)));
+        }
         println!();
     }
+    if let Some((date, None)) = day_start {
+        println!("{}\n", day_marker(date, None));
+    }
 
     println!("{}", Theme::separator(60));
     println!(
         "{}",
-        Theme::muted(&format!("Thread ID: {}", thread_id))
+        Theme::muted(&format!("Thread: {}", alias.as_deref().unwrap_or(thread_id)))
     );
 
+    review_followups(thread.users.iter().map(|u| u.username.as_str()))?;
+
     Ok(())
 }
 
+/// Translations of each message's text into `target` (None for media and
+/// messages already in that language). A failure is reported and leaves
+/// the thread untranslated.
+async fn translate_messages(messages: &[Message], target: &str) -> Vec<Option<Translation>> {
+    let texts: Vec<String> = messages.iter().filter_map(|m| m.text.clone()).collect();
+    let spinner = create_spinner(&format!("Translating {} messages...", texts.len()));
+    let result = async { Provider::from_config()?.translate(&texts, target).await }.await;
+    spinner.finish_and_clear();
+
+    let mut translated = match result {
+        Ok(translated) => translated.into_iter(),
+        Err(e) => {
+            println!("{} {}", Theme::warn_icon(), Theme::warning(&format!("Translation failed: {:#}", e)));
+            return Vec::new();
+        }
+    };
+    messages
+        .iter()
+        .map(|m| {
+            m.text.as_ref()?;
+            translated.next().filter(|t| !t.is_same_language(target))
+        })
+        .collect()
+}
+
+/// Tag chips followed by a space, or nothing for untagged threads
+fn chips_suffix(tags: &Tags, thread: &Thread) -> String {
+    let chips = tags.chips(thread);
+    if chips.is_empty() {
+        chips
+    } else {
+        format!("{} ", chips)
+    }
+}
+
 /// Print a thread summary for inbox view
-fn print_thread_summary(index: usize, thread: &Thread) {
+pub(crate) fn print_thread_summary(
+    index: usize,
+    thread: &Thread,
+    tags: &Tags,
+    assignments: &Assignments,
+    alias: Option<&str>,
+    profile: Option<&str>,
+    compact: bool,
+) {
+    for line in thread_summary_lines(index, thread, tags, assignments, alias, profile, compact) {
+        println!("{}", line);
+    }
+}
+
+/// A thread's summary lines for inbox view: its title line, then the
+/// preview or its unseen messages
+fn thread_summary_lines(
+    index: usize,
+    thread: &Thread,
+    tags: &Tags,
+    assignments: &Assignments,
+    alias: Option<&str>,
+    profile: Option<&str>,
+    compact: bool,
+) -> Vec<String> {
     // Get username for sending messages
     let username = thread.users.first().map(|u| u.username.as_str()).unwrap_or("unknown");
 
@@ -298,7 +869,7 @@ fn print_thread_summary(index: usize, thread: &Thread) {
     let title = thread
         .thread_title
         .clone()
-        .unwrap_or_else(|| username.to_string());
+        .unwrap_or_else(|| nicknames::name(username));
 
     let preview = thread
         .last_message_text
@@ -306,15 +877,11 @@ fn print_thread_summary(index: usize, thread: &Thread) {
         .unwrap_or_else(|| "[media]".to_string());
 
     // Truncate preview
-    let preview = if preview.chars().count() > 35 {
-        format!("{}...", preview.chars().take(35).collect::<String>())
-    } else {
-        preview
-    };
+    let preview = ellipsize(&preview, 35);
 
     // Unread indicator
     let unread = if thread.has_unread.unwrap_or(false) {
-        format!("{}", Theme::unread_dot())
+        Theme::unread_marker()
     } else {
         " ".to_string()
     };
@@ -326,99 +893,109 @@ fn print_thread_summary(index: usize, thread: &Thread) {
         .map(|t| format_time_ago_colored(t))
         .unwrap_or_default();
 
-    // Show: "1. Display Name (@username) 13d"
-    println!(
-        "{:>3}. {} {} {} {}",
+    // Which account it's in, when listing several
+    let badge = profile
+        .map(|p| format!("{} ", Theme::blue(&format!("[{}]", p))))
+        .unwrap_or_default();
+
+    // Show: "1. Display Name (@username) [tag] → alice 13d"
+    let mut lines = vec![format!(
+        "{:>3}. {}{}{} {} {}{}{} {}",
         Theme::muted(&index.to_string()),
-        Theme::orange(&title),
+        badge,
+        avatar(thread, &title),
+        paint_title(thread, &title, |t| Theme::orange(t).to_string()),
         Theme::username(&format!("@{}", username)),
+        chips_suffix(tags, thread),
+        assignments.chip(thread),
         time,  // Already colored
         unread
-    );
-    println!("     {} {}", Theme::muted("└"), preview);
+    )];
+    match unseen_preview(thread, compact) {
+        Some(unseen) => {
+            for (i, line) in unseen.iter().enumerate() {
+                let alias = if i + 1 == unseen.len() { alias_suffix(alias) } else { String::new() };
+                lines.push(format!("     {}{}", line, alias));
+            }
+        }
+        None => lines.push(format!("     {} {}{}", Theme::muted("└"), preview, alias_suffix(alias))),
+    }
+    lines
 }
 
-/// Format ISO timestamp to relative time (plain string)
-fn format_time_ago(timestamp: &str) -> String {
-    let (text, _) = parse_time_ago(timestamp);
-    text
+/// A thread's short alias, set off after its preview line
+fn alias_suffix(alias: Option<&str>) -> String {
+    alias
+        .map(|alias| format!(" {}", Theme::muted(&format!("· {}", alias))))
+        .unwrap_or_default()
 }
 
 /// Format ISO timestamp to colored relative time
-fn format_time_ago_colored(timestamp: &str) -> String {
+pub(crate) fn format_time_ago_colored(timestamp: &str) -> String {
     let (text, age_type) = parse_time_ago(timestamp);
 
     match age_type {
-        TimeAge::Now => format!("{}", text.truecolor(46, 204, 113)), // Green
-        TimeAge::Minutes => {
-            let (r, g, b) = instagram::BLUE;
-            format!("{}", text.truecolor(r, g, b))
-        }
-        TimeAge::Hours => {
-            let (r, g, b) = instagram::ORANGE;
-            format!("{}", text.truecolor(r, g, b))
-        }
-        TimeAge::Days => {
-            let (r, g, b) = instagram::LIGHT_GRAY;
-            format!("{}", text.truecolor(r, g, b))
-        }
+        TimeAge::Now => Theme::timestamp_now(&text).to_string(),
+        TimeAge::Minutes => Theme::timestamp_minutes(&text).to_string(),
+        TimeAge::Hours => Theme::timestamp_hours(&text).to_string(),
+        TimeAge::Days => Theme::timestamp_days(&text).to_string(),
         TimeAge::Unknown => text,
     }
 }
 
-/// Time age categories for coloring
-enum TimeAge {
-    Now,
-    Minutes,
-    Hours,
-    Days,
-    Unknown,
-}
+/// Open chat by inbox number (1, 2, 3...), counting in the last listing
+/// shown so the number means what the user saw
+pub async fn open_by_number(client: &ApiClient, number: usize) -> Result<()> {
+    if number == 0 {
+        println!("{} {}", Theme::cross(), Theme::error("Number must be 1 or greater"));
+        return Ok(());
+    }
 
-/// Parse ISO timestamp to relative time with age category
-fn parse_time_ago(timestamp: &str) -> (String, TimeAge) {
-    use chrono::{Local, NaiveDateTime, TimeZone};
+    let snapshot = InboxSnapshot::load().unwrap_or_default();
+    if snapshot.taken_at.is_none() {
+        return open_by_live_number(client, number).await;
+    }
 
-    // Parse "2026-01-24T16:07:11" format (ISO 8601 without timezone)
-    let naive = match NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S") {
-        Ok(dt) => dt,
-        Err(_) => return (String::new(), TimeAge::Unknown),
+    let Some(listed) = snapshot.get(number) else {
+        println!(
+            "{} {}",
+            Theme::cross(),
+            Theme::error(&format!(
+                "No conversation at position {}. The last inbox listing had {} conversations.",
+                number,
+                snapshot.threads.len()
+            ))
+        );
+        return Ok(());
     };
 
-    // Treat the timestamp as local time
-    let msg_time = match Local.from_local_datetime(&naive).single() {
-        Some(dt) => dt,
-        None => return (String::new(), TimeAge::Unknown),
-    };
+    if let Some(age) = snapshot.age().filter(|_| snapshot.is_stale()) {
+        println!(
+            "{} {}",
+            Theme::warn_icon(),
+            Theme::warning(&format!(
+                "The inbox was listed {} ago and may have changed since. Run `ig inbox` to refresh the numbers.",
+                format_duration(chrono::Duration::minutes(age.num_minutes()))
+            ))
+        );
+    }
 
-    let now = Local::now();
-    let duration = now.signed_duration_since(msg_time);
-    let secs = duration.num_seconds();
+    let username = listed.usernames.first().map(String::as_str).unwrap_or("unknown");
+    let usernames: Vec<String> = snapshot
+        .threads
+        .iter()
+        .flat_map(|t| t.usernames.iter().cloned())
+        .collect();
 
-    if secs < 0 {
-        // Future timestamp (shouldn't happen, but handle gracefully)
-        return ("now".to_string(), TimeAge::Now);
-    }
+    let spinner = create_spinner("Opening chat...");
+    let recent = fetch_recent(client, &listed.thread_id).await;
+    spinner.finish_and_clear();
 
-    let secs = secs as u64;
-    if secs < 60 {
-        ("now".to_string(), TimeAge::Now)
-    } else if secs < 3600 {
-        (format!("{}m", secs / 60), TimeAge::Minutes)
-    } else if secs < 86400 {
-        (format!("{}h", secs / 3600), TimeAge::Hours)
-    } else {
-        (format!("{}d", secs / 86400), TimeAge::Days)
-    }
+    chat_with_user(client, username, usernames, recent.as_ref()).await
 }
 
-/// Open chat by inbox number (1, 2, 3...)
-pub async fn open_by_number(client: &ApiClient, number: usize) -> Result<()> {
-    if number == 0 {
-        println!("{} {}", Theme::cross(), Theme::error("Number must be 1 or greater"));
-        return Ok(());
-    }
-
+/// Open chat by position in the inbox as it is now (nothing listed yet)
+async fn open_by_live_number(client: &ApiClient, number: usize) -> Result<()> {
     let spinner = create_spinner("Fetching inbox...");
 
     let response = client.get_inbox(number as u32).await;
@@ -456,51 +1033,49 @@ pub async fn open_by_number(client: &ApiClient, number: usize) -> Result<()> {
         .flat_map(|t| t.users.iter().map(|u| u.username.clone()))
         .collect();
 
+    // Load the conversation from the thread we already have, instead of
+    // letting the chat look it up again in the inbox
+    let spinner = create_spinner("Opening chat...");
+    let recent = fetch_recent(client, &thread.id).await;
+    spinner.finish_and_clear();
+
     // Start chat with this user
-    chat_with_user(client, username, usernames).await
+    chat_with_user(client, username, usernames, recent.as_ref()).await
 }
 
 /// Show thread by ID or @username
-pub async fn show_thread_or_user(client: &ApiClient, target: &str, limit: u32) -> Result<()> {
+pub async fn show_thread_or_user(
+    client: &ApiClient,
+    target: &str,
+    limit: Option<u32>,
+    translate: Option<&str>,
+    around: Option<NaiveDate>,
+) -> Result<()> {
     // Check if target starts with @ (username)
-    if target.starts_with('@') {
-        let username = &target[1..]; // Remove @ prefix
-        show_thread_by_username(client, username, limit).await
+    if let Some(username) = target.strip_prefix('@') {
+        show_thread_by_username(client, username, limit, translate, around).await
     } else {
         // Assume it's a thread ID
-        show_thread(client, target, limit).await
+        show_thread(client, target, limit, translate, around).await
     }
 }
 
 /// Show thread by username (finds the thread first)
-async fn show_thread_by_username(client: &ApiClient, username: &str, limit: u32) -> Result<()> {
+async fn show_thread_by_username(
+    client: &ApiClient,
+    username: &str,
+    limit: Option<u32>,
+    translate: Option<&str>,
+    around: Option<NaiveDate>,
+) -> Result<()> {
     let spinner = create_spinner(&format!("Finding conversation with @{}...", username));
 
-    // Fetch inbox to find the thread
-    let response = client.get_inbox(100).await;
+    let thread = find_thread_by_username(client, username).await;
     spinner.finish_and_clear();
 
-    let response = response?;
-
-    if !response.success {
-        println!(
-            "{} {}",
-            Theme::cross(),
-            Theme::error(&response.error.unwrap_or("Failed to fetch inbox".to_string()))
-        );
-        return Ok(());
-    }
-
-    let threads = response.threads.unwrap_or_default();
-
-    // Find thread with this username
-    let thread = threads.iter().find(|t| {
-        t.users.iter().any(|u| u.username.eq_ignore_ascii_case(username))
-    });
-
-    match thread {
+    match thread? {
         Some(t) => {
-            show_thread(client, &t.id, limit).await
+            show_thread(client, &t.id, limit, translate, around).await
         }
         None => {
             println!(
@@ -513,14 +1088,35 @@ async fn show_thread_by_username(client: &ApiClient, username: &str, limit: u32)
     }
 }
 
-/// Interactive inbox with arrow key navigation
-pub async fn show_inbox_interactive(client: &ApiClient, limit: u32) -> Result<()> {
-    let spinner = create_spinner("Fetching inbox");
+/// Find the conversation with a user in the (first 100 threads of the) inbox
+pub(crate) async fn find_thread_by_username(client: &ApiClient, username: &str) -> Result<Option<Thread>> {
+    let username = username.trim_start_matches('@');
+    let response = client.get_inbox(100).await?;
 
-    let response = client.get_inbox(limit).await;
-    spinner.finish_and_clear();
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to fetch inbox".to_string()));
+    }
 
-    let response = response?;
+    Ok(response
+        .threads
+        .unwrap_or_default()
+        .into_iter()
+        .find(|t| t.users.iter().any(|u| u.username.eq_ignore_ascii_case(username))))
+}
+
+/// Indices of the threads that pass the tag filter
+fn visible_threads(threads: &[Thread], tags: &Tags, tag_filter: Option<&str>) -> Vec<usize> {
+    threads
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| tag_filter.is_none_or(|tag| tags.thread_has(t, tag)))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Interactive inbox with arrow key navigation
+pub async fn show_inbox_interactive(client: &ApiClient, limit: Option<u32>, tag: Option<&str>) -> Result<()> {
+    let response = fetch_inbox(client, limit).await?;
 
     if !response.success {
         println!(
@@ -531,13 +1127,19 @@ pub async fn show_inbox_interactive(client: &ApiClient, limit: u32) -> Result<()
         return Ok(());
     }
 
-    let threads = response.threads.unwrap_or_default();
+    let mut threads = response.threads.unwrap_or_default();
 
     if threads.is_empty() {
         println!("{}", Theme::muted("No conversations found."));
         return Ok(());
     }
 
+    // Fetch the top threads' messages while the user looks at the list
+    let prefetch = Prefetch::spawn(
+        client,
+        threads.iter().take(PREFETCH_THREADS).map(|t| t.id.clone()),
+    );
+
     // Enter raw mode for keyboard input
     terminal::enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -545,58 +1147,191 @@ pub async fn show_inbox_interactive(client: &ApiClient, limit: u32) -> Result<()
     // Hide cursor
     execute!(stdout, cursor::Hide)?;
 
+    let mut tags = Tags::load().unwrap_or_default();
+    let mut all_tags: Vec<String> = tags.all().into_iter().collect();
+    let mut tag_filter: Option<String> = tag.map(str::to_string);
+
     let mut selected: usize = 0;
     let mut should_open: Option<usize> = None;
+    // Threads marked with Space for a batch action
+    let mut marked: HashSet<String> = HashSet::new();
 
-    loop {
-        // Clear screen and draw
-        execute!(stdout, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+    let mut canvas = Canvas::new(4);
+    let mut clock = FrameClock::default();
 
-        // Header
-        let header = format!("{}", Theme::header("Inbox"));
-        writeln!(stdout, "\r\n{}", header)?;
-        writeln!(stdout, "\r{}", Theme::separator(60))?;
+    'frames: loop {
+        let visible = visible_threads(&threads, &tags, tag_filter.as_deref());
+        selected = selected.min(visible.len().saturating_sub(1));
 
-        // Draw threads
-        for (i, thread) in threads.iter().enumerate() {
+        // Header
+        let mut header = Vec::new();
+        writeln!(header, "\r\n{}", inbox_header(false, tag_filter.as_deref()))?;
+        writeln!(header, "\r{}", Theme::separator(60))?;
+        canvas.set_output(REGION_HEADER, &header);
+
+        // Threads
+        let mut list = Vec::new();
+        if visible.is_empty() {
+            writeln!(list, "\r{}", Theme::muted("No conversations with this tag."))?;
+        }
+        for (i, &idx) in visible.iter().enumerate() {
             let is_selected = i == selected;
-            print_thread_interactive(&mut stdout, i + 1, thread, is_selected)?;
+            let is_marked = marked.contains(&threads[idx].id);
+            print_thread_interactive(&mut list, i + 1, &threads[idx], is_selected, is_marked, &tags)?;
+        }
+        canvas.set_output(REGION_LIST, &list);
+
+        // Preview of the selected conversation
+        let selected_id = visible.get(selected).map(|&idx| threads[idx].id.clone());
+        let mut preview = Vec::new();
+        if let Some(&idx) = visible.get(selected) {
+            writeln!(preview, "\r{}", Theme::separator(60))?;
+            draw_preview(&mut preview, &threads[idx], prefetch.get(&threads[idx].id).as_deref())?;
         }
+        canvas.set_output(REGION_PREVIEW, &preview);
 
         // Footer
-        writeln!(stdout, "\r{}", Theme::separator(60))?;
-        writeln!(
-            stdout,
-            "\r{}",
-            Theme::muted("↑/↓: Navigate  Enter: Open chat  q: Quit")
-        )?;
-
-        stdout.flush()?;
+        let mut footer = Vec::new();
+        writeln!(footer, "\r{}", Theme::separator(60))?;
+        if marked.is_empty() {
+            writeln!(
+                footer,
+                "\r{}",
+                Theme::muted("↑/↓: Navigate  Enter/1-9: Open chat  Space: Select  P: Pinned  t: Filter by tag  q: Quit")
+            )?;
+        } else {
+            writeln!(
+                footer,
+                "\r{} {}",
+                Theme::accent(&format!("{} selected", marked.len())),
+                Theme::muted("Space: Select  r: Mark read  m: Mute  a: Archive  T: Tag  Esc: Clear")
+            )?;
+        }
+        canvas.set_output(REGION_FOOTER, &footer);
+
+        canvas.draw(&mut stdout)?;
+        clock.drawn();
+
+        // Wait for a key, redrawing once the selected preview arrives
+        let preview_pending = selected_id.as_deref().is_some_and(|id| prefetch.get(id).is_none());
+        let mut next = loop {
+            if event::poll(Duration::from_millis(PREVIEW_POLL_MS))? {
+                match event::read()? {
+                    Event::Key(key_event) => break Some(key_event),
+                    Event::Resize(_, rows) => {
+                        canvas.resize(rows);
+                        break None;
+                    }
+                    _ => {}
+                }
+            } else if preview_pending && selected_id.as_deref().is_some_and(|id| prefetch.get(id).is_some()) {
+                break None;
+            }
+        };
 
-        // Handle input
-        if let Event::Key(key_event) = event::read()? {
-            if key_event.kind == KeyEventKind::Press {
+        // Handle the key, and any more that arrive before the next frame is
+        // due (e.g. a held arrow key), then draw once
+        loop {
+            if let Some(key_event) = next.take().filter(|k| k.kind == KeyEventKind::Press) {
+                let visible = visible_threads(&threads, &tags, tag_filter.as_deref());
                 match key_event.code {
                     KeyCode::Up | KeyCode::Char('k') => {
-                        if selected > 0 {
-                            selected -= 1;
+                        selected = selected.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') if selected + 1 < visible.len() => {
+                        selected += 1;
+                    }
+                    KeyCode::Enter if !visible.is_empty() => {
+                        should_open = Some(visible[selected]);
+                        break 'frames;
+                    }
+                    KeyCode::Char(c @ '1'..='9') => {
+                        let number = c as usize - '0' as usize;
+                        if let Some(&idx) = visible.get(number - 1) {
+                            should_open = Some(idx);
+                            break 'frames;
+                        }
+                    }
+                    KeyCode::Char('P') if !visible.is_empty() => {
+                        draw_pinned_view(&mut stdout, &threads[visible[selected]])?;
+                        canvas.invalidate();
+                        continue 'frames;
+                    }
+                    KeyCode::Char(' ') if !visible.is_empty() => {
+                        let id = &threads[visible[selected]].id;
+                        if !marked.remove(id) {
+                            marked.insert(id.clone());
                         }
+                        selected = (selected + 1).min(visible.len() - 1);
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if selected < threads.len() - 1 {
-                            selected += 1;
+                    KeyCode::Char(c @ ('r' | 'm' | 'a' | 'T')) if !marked.is_empty() => {
+                        let action = match c {
+                            'r' => BatchAction::MarkRead,
+                            'm' => BatchAction::Mute,
+                            'a' => BatchAction::Archive,
+                            _ => match prompt_tag(&mut stdout)? {
+                                Some(tag) => BatchAction::Tag(tag),
+                                None => {
+                                    canvas.invalidate();
+                                    continue 'frames;
+                                }
+                            },
+                        };
+                        // Marked threads in list order
+                        let batch: Vec<&Thread> = threads.iter().filter(|t| marked.contains(&t.id)).collect();
+                        let done: HashSet<String> = run_batch(&mut stdout, client, &action, &batch)
+                            .await?
+                            .into_iter()
+                            .collect();
+                        canvas.invalidate();
+
+                        match action {
+                            BatchAction::MarkRead => threads
+                                .iter_mut()
+                                .filter(|t| done.contains(&t.id))
+                                .for_each(|t| t.has_unread = Some(false)),
+                            BatchAction::Archive => threads.retain(|t| !done.contains(&t.id)),
+                            BatchAction::Mute => {}
+                            BatchAction::Tag(_) => {
+                                tags = Tags::load().unwrap_or_default();
+                                all_tags = tags.all().into_iter().collect();
+                            }
                         }
+                        // Failed ones stay marked for another try
+                        marked.retain(|id| !done.contains(id));
+                        continue 'frames;
                     }
-                    KeyCode::Enter => {
-                        should_open = Some(selected);
-                        break;
+                    KeyCode::Esc if !marked.is_empty() => {
+                        marked.clear();
+                    }
+                    KeyCode::Char('t') => {
+                        // Cycle: no filter -> each tag in turn -> no filter
+                        tag_filter = match &tag_filter {
+                            None => all_tags.first().cloned(),
+                            Some(current) => all_tags
+                                .iter()
+                                .skip_while(|t| *t != current)
+                                .nth(1)
+                                .cloned(),
+                        };
+                        selected = 0;
                     }
                     KeyCode::Char('q') | KeyCode::Esc => {
-                        break;
+                        break 'frames;
                     }
                     _ => {}
                 }
             }
+
+            let wait = clock.until_next();
+            if wait.is_zero() || !event::poll(wait)? {
+                break;
+            }
+            match event::read()? {
+                Event::Key(key_event) => next = Some(key_event),
+                Event::Resize(_, rows) => canvas.resize(rows),
+                _ => {}
+            }
         }
     }
 
@@ -615,25 +1350,110 @@ pub async fn show_inbox_interactive(client: &ApiClient, limit: u32) -> Result<()
             .flat_map(|t| t.users.iter().map(|u| u.username.clone()))
             .collect();
 
-        chat_with_user(client, username, usernames).await?;
+        // Usually already prefetched; otherwise fetch it now
+        let recent = match prefetch.get(&thread.id) {
+            Some(recent) => Some(recent),
+            None => {
+                let spinner = create_spinner("Opening chat...");
+                let recent = prefetch.get_or_fetch(&thread.id).await;
+                spinner.finish_and_clear();
+                recent
+            }
+        };
+        drop(prefetch);
+
+        chat_with_user(client, username, usernames, recent.as_deref()).await?;
     }
 
     Ok(())
 }
 
+/// Draw the last few messages of the selected thread under the list
+fn draw_preview(stdout: &mut impl Write, thread: &Thread, recent: Option<&Thread>) -> Result<()> {
+    let Some(recent) = recent else {
+        writeln!(stdout, "\r{}", Theme::muted("Loading preview..."))?;
+        return Ok(());
+    };
+
+    let messages = recent.messages.as_deref().unwrap_or_default();
+    if messages.is_empty() {
+        writeln!(stdout, "\r{}", Theme::muted("No messages yet."))?;
+        return Ok(());
+    }
+
+    for msg in messages.iter().take(PREVIEW_MESSAGES).rev() {
+        let sender = msg
+            .user_id
+            .as_ref()
+            .and_then(|uid| users::username(&thread.users, uid))
+            .map(|username| nicknames::name(&username))
+            .unwrap_or("You".to_string());
+        let text = msg.text.as_deref().unwrap_or("[media]");
+        writeln!(
+            stdout,
+            "\r  {} {}",
+            Theme::pink(&format!("{}:", sender)),
+            truncate(text, 50)
+        )?;
+    }
+    Ok(())
+}
+
+/// Show the pinned messages of a thread until a key is pressed
+fn draw_pinned_view(stdout: &mut io::Stdout, thread: &Thread) -> Result<()> {
+    let username = thread.users.first().map(|u| u.username.as_str()).unwrap_or("unknown");
+    let title = thread
+        .thread_title
+        .clone()
+        .unwrap_or_else(|| nicknames::name(username));
+
+    execute!(stdout, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+    writeln!(
+        stdout,
+        "\r\n{} {}",
+        Theme::header("Pinned in"),
+        Theme::orange(&title)
+    )?;
+    writeln!(stdout, "\r{}", Theme::separator(60))?;
+
+    let pins = Pins::load().unwrap_or_default();
+    let thread_pins = pins.for_thread(&thread.id);
+    if thread_pins.is_empty() {
+        writeln!(stdout, "\r{}", Theme::muted("No pinned messages. Pin one with: ig pin-msg <thread_id> <message_id>"))?;
+    } else {
+        for line in pin_lines(&thread_pins) {
+            writeln!(stdout, "\r{}", line)?;
+        }
+    }
+
+    writeln!(stdout, "\r{}", Theme::separator(60))?;
+    writeln!(stdout, "\r{}", Theme::muted("Press any key to return"))?;
+    stdout.flush()?;
+
+    loop {
+        if let Event::Key(key_event) = event::read()? {
+            if key_event.kind == KeyEventKind::Press {
+                return Ok(());
+            }
+        }
+    }
+}
+
 /// Print a thread summary for interactive view
 fn print_thread_interactive(
-    stdout: &mut io::Stdout,
+    stdout: &mut impl Write,
     index: usize,
     thread: &Thread,
     is_selected: bool,
+    is_marked: bool,
+    tags: &Tags,
 ) -> Result<()> {
     let username = thread.users.first().map(|u| u.username.as_str()).unwrap_or("unknown");
 
     let title = thread
         .thread_title
         .clone()
-        .unwrap_or_else(|| username.to_string());
+        .unwrap_or_else(|| nicknames::name(username));
 
     let preview = thread
         .last_message_text
@@ -641,15 +1461,11 @@ fn print_thread_interactive(
         .unwrap_or_else(|| "[media]".to_string());
 
     // Truncate preview
-    let preview = if preview.chars().count() > 35 {
-        format!("{}...", preview.chars().take(35).collect::<String>())
-    } else {
-        preview
-    };
+    let preview = ellipsize(&preview, 35);
 
     // Unread indicator
     let unread = if thread.has_unread.unwrap_or(false) {
-        format!("{}", Theme::unread_dot())
+        Theme::unread_marker()
     } else {
         " ".to_string()
     };
@@ -663,24 +1479,35 @@ fn print_thread_interactive(
 
     // Selection indicator and highlighting
     let (indicator, highlight_start, highlight_end) = if is_selected {
-        let (r, g, b) = instagram::PINK;
+        // The ► marker carries the selection even when colors are off
+        let highlight = if colors_enabled() {
+            "\x1b[48;2;60;60;60m".to_string() // Dark background for highlight
+        } else {
+            String::new()
+        };
         (
-            format!("\x1b[38;2;{};{};{}m►\x1b[0m", r, g, b),
-            format!("\x1b[48;2;60;60;60m"), // Dark background for highlight
-            "\x1b[0m".to_string(),
+            Theme::pink("►").to_string(),
+            highlight,
+            reset_escape().to_string(),
         )
     } else {
         (" ".to_string(), String::new(), String::new())
     };
 
+    // Marked for a batch action
+    let mark = if is_marked { Theme::check().to_string() } else { " ".to_string() };
+
     writeln!(
         stdout,
-        "\r{} {}{:>2}. {} {} {} {}{}",
+        "\r{}{}{}{:>2}. {}{} {} {}{} {}{}",
         indicator,
+        mark,
         highlight_start,
         index,
-        Theme::orange(&title),
+        avatar(thread, &title),
+        paint_title(thread, &title, |t| Theme::orange(t).to_string()),
         Theme::username(&format!("@{}", username)),
+        chips_suffix(tags, thread),
         time,  // Already colored
         unread,
         highlight_end
@@ -0,0 +1,89 @@
+//! Deep links to conversations (`ig link`, `ig open-uri`)
+//!
+//! Links look like `insta://thread/<thread_id>` or
+//! `insta://thread/<thread_id>/message/<message_id>`, so notes apps and
+//! scripts can keep a pointer that `ig open-uri` turns back into the
+//! conversation, scrolled to the message.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::client::ApiClient;
+use crate::commands::context::show_context;
+use crate::commands::inbox::show_thread;
+use crate::output::{print_json, OutputFormat};
+
+/// URI scheme of deep links
+const SCHEME: &str = "insta://";
+
+/// Messages shown either side of a linked message
+const CONTEXT: usize = 5;
+
+/// Messages shown for a link to a whole thread
+const THREAD_LIMIT: u32 = 20;
+
+/// A thread, or a message in one, as a deep link points at it
+#[derive(Debug, Serialize)]
+struct DeepLink {
+    uri: String,
+    thread_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_id: Option<String>,
+}
+
+impl DeepLink {
+    fn new(thread_id: &str, message_id: Option<&str>) -> Result<Self> {
+        for id in std::iter::once(thread_id).chain(message_id) {
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                anyhow::bail!("'{}' doesn't look like a thread or message ID", id);
+            }
+        }
+        let mut uri = format!("{}thread/{}", SCHEME, thread_id);
+        if let Some(message_id) = message_id {
+            uri.push_str(&format!("/message/{}", message_id));
+        }
+        Ok(Self {
+            uri,
+            thread_id: thread_id.to_string(),
+            message_id: message_id.map(str::to_string),
+        })
+    }
+
+    /// Read a link produced by `ig link`
+    fn parse(uri: &str) -> Result<Self> {
+        let invalid = || {
+            anyhow::anyhow!(
+                "Not an ig link: {} (expected {}thread/<id> or {}thread/<id>/message/<id>)",
+                uri,
+                SCHEME,
+                SCHEME
+            )
+        };
+        let path = uri.trim().strip_prefix(SCHEME).ok_or_else(invalid)?;
+        let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+        match parts.as_slice() {
+            ["thread", thread_id] => Self::new(thread_id, None),
+            ["thread", thread_id, "message", message_id] => Self::new(thread_id, Some(message_id)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Print the deep link for a thread or a message in it (`ig link`)
+pub fn print_link(thread_id: &str, message_id: Option<&str>, output: OutputFormat) -> Result<()> {
+    let link = DeepLink::new(thread_id, message_id)?;
+    if output == OutputFormat::Json {
+        return print_json(&link);
+    }
+    println!("{}", link.uri);
+    Ok(())
+}
+
+/// Show the conversation a deep link points at (`ig open-uri`)
+pub async fn open_uri(client: &ApiClient, uri: &str) -> Result<()> {
+    let link = DeepLink::parse(uri)?;
+    match &link.message_id {
+        Some(message_id) => show_context(client, &link.thread_id, message_id, CONTEXT).await,
+        None => show_thread(client, &link.thread_id, Some(THREAD_LIMIT), None, None).await,
+    }
+}
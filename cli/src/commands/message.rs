@@ -0,0 +1,163 @@
+//! Single message inspection
+
+use anyhow::Result;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::models::{Message, User};
+use crate::spinner::create_spinner;
+use crate::users;
+use crate::util::format_time_ago;
+
+/// How far back to look for the message (server maximum)
+pub(crate) const SEARCH_LIMIT: u32 = 100;
+
+/// A message located in a thread, with the thread's participants
+pub(crate) struct FoundMessage {
+    pub raw: serde_json::Value,
+    pub message: Message,
+    pub users: Vec<User>,
+}
+
+impl FoundMessage {
+    /// Display name of the sender ("You" for our own messages)
+    pub fn sender(&self) -> String {
+        if self.message.is_sent_by_viewer.unwrap_or(false) {
+            return "You".to_string();
+        }
+        self.message
+            .user_id
+            .as_ref()
+            .and_then(|uid| users::username(&self.users, uid))
+            .map(|username| format!("@{}", username))
+            .or_else(|| self.message.user_id.clone())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+/// Find a message by ID among the most recent messages of a thread
+pub(crate) async fn find_message(
+    client: &ApiClient,
+    thread_id: &str,
+    message_id: &str,
+) -> Result<Option<FoundMessage>> {
+    let response = client.get_thread_raw(thread_id, SEARCH_LIMIT).await?;
+
+    if response["success"].as_bool() != Some(true) {
+        let error = response["error"].as_str().unwrap_or("Failed to fetch thread");
+        anyhow::bail!(error.to_string());
+    }
+
+    let thread = &response["thread"];
+    let raw = thread["messages"]
+        .as_array()
+        .and_then(|messages| messages.iter().find(|m| m["id"].as_str() == Some(message_id)));
+
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+
+    let message: Message = serde_json::from_value(raw.clone())?;
+    let thread_users: Vec<User> = serde_json::from_value(thread["users"].clone()).unwrap_or_default();
+    users::remember(&thread_users);
+    if let Some(uid) = &message.user_id {
+        if users::username(&thread_users, uid).is_none() {
+            users::resolve(client, uid).await;
+            users::save();
+        }
+    }
+
+    Ok(Some(FoundMessage {
+        raw: raw.clone(),
+        message,
+        users: thread_users,
+    }))
+}
+
+/// Error for a message that could not be found
+pub(crate) fn message_not_found(message_id: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Message {} not found in the last {} messages of this thread",
+        message_id,
+        SEARCH_LIMIT
+    )
+}
+
+/// Show full metadata for a single message in a thread
+pub async fn show_message(
+    client: &ApiClient,
+    thread_id: &str,
+    message_id: &str,
+    raw: bool,
+) -> Result<()> {
+    let spinner = create_spinner("Fetching message...");
+
+    let found = find_message(client, thread_id, message_id).await;
+    spinner.finish_and_clear();
+
+    let found = found?.ok_or_else(|| message_not_found(message_id))?;
+
+    if raw {
+        println!("{}", serde_json::to_string_pretty(&found.raw)?);
+        return Ok(());
+    }
+
+    let sender = found.sender();
+    let message = found.message;
+
+    println!();
+    println!("{}", Theme::header("Message Details"));
+    println!("{}", Theme::separator(60));
+    println!("  {} {}", Theme::muted("ID:"), message.id);
+    println!("  {} {}", Theme::muted("Thread:"), thread_id);
+    println!("  {} {}", Theme::muted("From:"), Theme::username(&sender));
+
+    if let Some(timestamp) = &message.timestamp {
+        let ago = format_time_ago(timestamp);
+        if ago.is_empty() {
+            println!("  {} {}", Theme::muted("Timestamp:"), timestamp);
+        } else {
+            println!(
+                "  {} {} {}",
+                Theme::muted("Timestamp:"),
+                timestamp,
+                Theme::timestamp(&format!("({} ago)", ago))
+            );
+        }
+    }
+
+    println!(
+        "  {} {}",
+        Theme::muted("Type:"),
+        Theme::accent(message.item_type.as_deref().unwrap_or("unknown"))
+    );
+
+    if let Some(text) = &message.text {
+        println!("  {} {}", Theme::muted("Text:"), text);
+    }
+    if let Some(media_url) = &message.media_url {
+        let media_type = message.media_type.as_deref().unwrap_or("media");
+        println!("  {} {} {}", Theme::muted("Media:"), media_type, Theme::muted(media_url));
+    }
+    if let Some(link_url) = &message.link_url {
+        println!("  {} {}", Theme::muted("Link:"), link_url);
+        if let Some(title) = &message.link_title {
+            println!("  {} {}", Theme::muted("Link title:"), title);
+        }
+    }
+
+    let reactions = message.reactions.unwrap_or_default();
+    if reactions.is_empty() {
+        println!("  {} {}", Theme::muted("Reactions:"), Theme::muted("none"));
+    } else {
+        println!("  {}", Theme::muted("Reactions:"));
+        for reaction in &reactions {
+            println!("    {}", reaction);
+        }
+    }
+
+    println!("{}", Theme::separator(60));
+    println!("{}", Theme::muted("Use --raw to see the full JSON payload"));
+
+    Ok(())
+}
@@ -1,9 +1,105 @@
 //! CLI command implementations
 
+pub mod analytics;
+pub mod archive;
+pub mod assign;
+pub mod attach;
 pub mod auth;
+pub mod batch;
+pub mod bench;
+pub mod board;
+pub mod cache;
+pub mod catalog;
+pub mod chatsearch;
+pub mod context;
+pub mod debug;
+pub mod cooldown;
+pub mod devices;
+pub mod doctor;
+pub mod download;
+pub mod exclude;
+pub mod digest;
+pub mod export;
+pub mod followup;
+pub mod goto;
+pub mod heatmap;
+pub mod history;
 pub mod inbox;
+pub mod link;
+pub mod message;
+pub mod nick;
+pub mod notify;
+pub mod ocr;
+pub mod pages;
+pub mod paylink;
+pub mod pins;
+pub mod prefetch;
+pub mod presend;
+pub mod profiles;
+pub mod qrcode;
+pub mod react;
+pub mod remind;
+pub mod rename;
+pub mod report;
+pub mod searches;
 pub mod send;
+pub mod stats;
+pub mod summarize;
+pub mod tail;
+pub mod tags;
+pub mod tui;
+pub mod tutorial;
+pub mod voice;
+pub mod web;
 
+pub use analytics::*;
+pub use archive::*;
+pub use assign::*;
+pub use attach::*;
 pub use auth::*;
+pub use batch::*;
+pub use bench::*;
+pub use board::*;
+pub use cache::*;
+pub use catalog::*;
+pub use chatsearch::*;
+pub use context::*;
+pub use debug::*;
+pub use cooldown::*;
+pub use devices::*;
+pub use doctor::*;
+pub use download::*;
+pub use exclude::*;
+pub use digest::*;
+pub use export::*;
+pub use followup::*;
+pub use goto::*;
+pub use heatmap::*;
+pub use history::*;
 pub use inbox::*;
+pub use link::*;
+pub use message::*;
+pub use nick::*;
+pub use notify::*;
+pub use ocr::*;
+pub use pages::*;
+pub use paylink::*;
+pub use pins::*;
+pub use prefetch::*;
+pub use presend::*;
+pub use profiles::*;
+pub use qrcode::*;
+pub use react::*;
+pub use remind::*;
+pub use rename::*;
+pub use report::*;
+pub use searches::*;
 pub use send::*;
+pub use stats::*;
+pub use summarize::*;
+pub use tail::*;
+pub use tags::*;
+pub use tui::*;
+pub use tutorial::*;
+pub use voice::*;
+pub use web::*;
@@ -0,0 +1,74 @@
+//! `ig nick`: local display names for conversation partners
+
+use anyhow::Result;
+
+use crate::colors::Theme;
+use crate::nicknames::Nicknames;
+use crate::output::{print_json, OutputFormat, Table};
+
+/// Give a user a nickname, or with `clear` remove it
+/// (`ig nick @cryptic_handle_999 "Mom"`)
+pub fn set_nickname(target: &str, name: Option<&str>, clear: bool) -> Result<()> {
+    let username = target.trim_start_matches('@').to_lowercase();
+    let mut nicknames = Nicknames::load()?;
+
+    if clear {
+        if nicknames.by_user.remove(&username).is_none() {
+            println!("{}", Theme::muted(&format!("@{} has no nickname", username)));
+            return Ok(());
+        }
+        nicknames.save()?;
+        println!(
+            "{} {}",
+            Theme::check(),
+            Theme::success(&format!("@{} is shown by their handle again", username))
+        );
+        return Ok(());
+    }
+
+    let name = name.map(str::trim).unwrap_or_default();
+    if name.is_empty() {
+        anyhow::bail!("Give a nickname (ig nick @{} \"Name\") or pass --clear to remove one", username);
+    }
+    nicknames.by_user.insert(username.clone(), name.to_string());
+    nicknames.save()?;
+    println!(
+        "{} {} {}",
+        Theme::check(),
+        Theme::success(&format!("@{} will be shown as", username)),
+        Theme::username(name)
+    );
+    Ok(())
+}
+
+/// List nicknames and whose they are
+pub fn list_nicknames(output: OutputFormat) -> Result<()> {
+    let nicknames = Nicknames::load()?;
+
+    if output == OutputFormat::Json {
+        return print_json(&nicknames.by_user);
+    }
+
+    if nicknames.by_user.is_empty() {
+        println!("{}", Theme::muted("No nicknames yet. Add one with: ig nick @user \"Name\""));
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Nickname", "Username"]);
+        for (username, name) in &nicknames.by_user {
+            table.row([name.clone(), format!("@{}", username)]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Nicknames"));
+    println!("{}", Theme::separator(60));
+    for (username, name) in &nicknames.by_user {
+        println!("  {} {}", Theme::username(name), Theme::muted(&format!("@{}", username)));
+    }
+    println!("{}", Theme::separator(60));
+    Ok(())
+}
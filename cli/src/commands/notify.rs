@@ -0,0 +1,311 @@
+//! Notification watcher: desktop notifications for new messages and reminders
+//!
+//! With `--stdout` the watcher prints NDJSON events instead, for scripts
+//! that want to react to new messages themselves.
+//!
+//! Keywords under `[alerts]` in the config are checked against every
+//! incoming message; a match raises a high-priority alert and is written to
+//! the audit trail.
+//!
+//! During `[notify] quiet_hours` new messages are only logged in the
+//! terminal. Conversations set to `notify = "always"` under
+//! `[thread."<name>"]` still pop up then, and `notify = "never"` ones never
+//! do. Keyword alerts and `--stdout` events aren't affected.
+
+use anyhow::Result;
+use chrono::Local;
+use regex::Regex;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::audit;
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::cache::vacuum;
+use crate::commands::remind::{Reminder, Reminders};
+use crate::commands::web::{new_conversation_url, open_url, thread_url};
+use crate::config::{Config, NotifyMode};
+use crate::duration::Interval;
+use crate::models::{Event, Message, StreamEvent, Thread, User};
+use crate::nicknames;
+use crate::notify::{desktop_alert, desktop_notify, QuietHours};
+use crate::output::print_ndjson;
+use crate::sync::{SyncEvent, SyncHandle, SyncOptions};
+use crate::tasks::Supervisor;
+
+/// Number of inbox threads watched for new messages
+const WATCH_LIMIT: u32 = 20;
+
+/// How often retention settings are applied while watching
+const VACUUM_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Watch the inbox and fire desktop notifications (or with `stdout`, print
+/// NDJSON events) until Ctrl+C
+pub async fn run_notify(client: &ApiClient, interval: Interval, stdout: bool) -> Result<()> {
+    if !stdout {
+        println!("{}", Theme::header("Notifications"));
+        println!(
+            "{}",
+            Theme::muted(&format!(
+                "Watching inbox every {} and firing due reminders. Press Ctrl+C to stop.",
+                interval
+            ))
+        );
+        println!();
+    }
+
+    let config = Config::load()?;
+    let quiet = config.notify.quiet_hours.as_deref().map(QuietHours::parse).transpose()?;
+    if let (false, Some(quiet)) = (stdout, quiet) {
+        println!(
+            "{}",
+            Theme::muted(&format!("Quiet hours: {}-{}", quiet.start.format("%H:%M"), quiet.end.format("%H:%M")))
+        );
+        println!();
+    }
+
+    let keywords = alert_keywords(&config)?;
+    if !stdout && !keywords.is_empty() {
+        let words: Vec<&str> = keywords.iter().map(|(word, _)| word.as_str()).collect();
+        println!("{}", Theme::muted(&format!("Alerting on: {}", words.join(", "))));
+        println!();
+    }
+
+    // Keywords need the messages themselves, not just each thread's latest
+    let mut tasks = Supervisor::new();
+    tasks.stop_on_ctrl_c();
+    let sync = SyncHandle::spawn(
+        &mut tasks,
+        client,
+        SyncOptions {
+            interval: interval.0,
+            inbox_limit: WATCH_LIMIT,
+            inbox_messages: !keywords.is_empty(),
+            delta: false,
+            threads: Vec::new(),
+        },
+    );
+    let mut events = sync.subscribe();
+    let mut reminder_tick = tokio::time::interval(interval.0);
+    let mut vacuum_tick = tokio::time::interval(Duration::from_secs(VACUUM_INTERVAL_SECS));
+
+    loop {
+        tokio::select! {
+            _ = tasks.stopping() => break,
+            _ = reminder_tick.tick() => fire_due_reminders(stdout),
+            _ = vacuum_tick.tick() => match vacuum() {
+                Ok(Some(removed)) if !removed.is_empty() && !stdout => {
+                    log_event(&Theme::muted(&format!("Removed {} past retention", removed)));
+                }
+                Ok(_) => {}
+                Err(error) if stdout => eprintln!("{}", error),
+                Err(error) => log_event(&format!("{} {}", Theme::warn_icon(), Theme::warning(&error.to_string()))),
+            },
+            received = events.recv() => match received {
+                Ok(SyncEvent::ThreadUpdated { thread, new_activity }) => {
+                    if new_activity && thread.has_unread.unwrap_or(false) {
+                        notify_thread(&thread, &config, quiet, stdout)?;
+                    }
+                }
+                Ok(SyncEvent::NewMessage { thread_id, message, users }) => {
+                    if let Some(keyword) = matching_keyword(&keywords, &message) {
+                        alert_message(&thread_id, &message, &users, &keyword, stdout)?;
+                    }
+                }
+                Ok(event) if event.server_notice().is_some() => {
+                    let notice = event.server_notice().unwrap_or_default();
+                    if stdout {
+                        eprintln!("{}", notice);
+                    } else {
+                        log_event(&format!("{} {}", Theme::warn_icon(), Theme::warning(notice)));
+                    }
+                }
+                Ok(SyncEvent::PollFailed(error)) if stdout => eprintln!("{}", error),
+                Ok(SyncEvent::PollFailed(error)) => {
+                    log_event(&format!("{} {}", Theme::warn_icon(), Theme::warning(&error)));
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+    tasks.shutdown().await;
+
+    if !stdout {
+        println!();
+        println!("{}", Theme::muted("Stopped watching."));
+    }
+    Ok(())
+}
+
+/// Notify about new unread activity in a thread, on the desktop unless the
+/// thread's `notify` setting or quiet hours say otherwise
+fn notify_thread(thread: &Thread, config: &Config, quiet: Option<QuietHours>, stdout: bool) -> Result<()> {
+    if stdout {
+        return print_ndjson(&StreamEvent::new(Event::thread_update(thread)));
+    }
+
+    let username = thread
+        .users
+        .first()
+        .map(|u| u.username.as_str())
+        .unwrap_or("unknown");
+    let text = thread.last_message_text.as_deref().unwrap_or("[media]");
+
+    log_event(&format!("{} {}", Theme::username(&nicknames::display(username)), text));
+    let mode = config.thread_settings(thread).map(|t| t.notify).unwrap_or_default();
+    let quiet_now = quiet.is_some_and(|q| q.contains(Local::now().time()));
+    if should_notify(mode, quiet_now) {
+        desktop_notify(&nicknames::display(username), text);
+    }
+    Ok(())
+}
+
+/// Whether a thread with `mode` gets a desktop notification
+pub fn should_notify(mode: NotifyMode, quiet_now: bool) -> bool {
+    match mode {
+        NotifyMode::Always => true,
+        NotifyMode::Never => false,
+        NotifyMode::Default => !quiet_now,
+    }
+}
+
+/// `[alerts]` keywords with the whole-word, case-insensitive pattern for each
+fn alert_keywords(config: &Config) -> Result<Vec<(String, Regex)>> {
+    config
+        .alerts
+        .keywords
+        .iter()
+        .map(|word| word.trim())
+        .filter(|word| !word.is_empty())
+        .map(|word| Ok((word.to_string(), Regex::new(&format!(r"(?i)\b{}\b", regex::escape(word)))?)))
+        .collect()
+}
+
+/// The first keyword in an incoming message, if any
+fn matching_keyword(keywords: &[(String, Regex)], message: &Message) -> Option<String> {
+    if message.is_sent_by_viewer == Some(true) {
+        return None;
+    }
+    let text = message.text.as_deref()?;
+    keywords
+        .iter()
+        .find(|(_, re)| re.is_match(text))
+        .map(|(word, _)| word.clone())
+}
+
+/// Raise a high-priority alert for a message containing `keyword` and
+/// record it in the audit trail
+fn alert_message(thread_id: &str, message: &Message, users: &[User], keyword: &str, stdout: bool) -> Result<()> {
+    let sender = message
+        .user_id
+        .as_ref()
+        .and_then(|uid| crate::users::username(users, uid));
+    let text = message.text.clone().unwrap_or_default();
+
+    audit::record(
+        "keyword_alert",
+        serde_json::json!({
+            "thread_id": thread_id,
+            "message_id": message.id,
+            "sender": sender,
+            "keyword": keyword,
+            "text": text,
+        }),
+    )?;
+
+    if stdout {
+        return print_ndjson(&StreamEvent::new(Event::Alert {
+            thread_id: thread_id.to_string(),
+            message_id: message.id.clone(),
+            sender,
+            keyword: keyword.to_string(),
+            text,
+        }));
+    }
+
+    let from = sender.as_deref().map(nicknames::display).unwrap_or_else(|| "someone".to_string());
+    log_event(&format!(
+        "{} {} {} {}",
+        Theme::error("!"),
+        Theme::warning(&format!("\"{}\"", keyword)),
+        Theme::username(&from),
+        text
+    ));
+    desktop_alert(&format!("\"{}\" from {}", keyword, from), &text);
+    Ok(())
+}
+
+/// Fire every due reminder. Each is removed only once it has fired, and a
+/// failure is logged without stopping the watcher or the other reminders.
+fn fire_due_reminders(stdout: bool) {
+    let due = match Reminders::load() {
+        Ok(reminders) => reminders.due(),
+        Err(error) => return log_error(&error, stdout),
+    };
+    for reminder in due {
+        if let Err(error) = fire_reminder(&reminder, stdout).and_then(|()| Reminders::remove_fired(reminder.id)) {
+            log_error(&error.context(format!("Reminder #{}", reminder.id)), stdout);
+        }
+    }
+}
+
+/// Notify for a reminder, and open the conversation in the browser or app
+/// for reminders that ask to (in `stdout` mode reminders are only printed
+/// as events)
+fn fire_reminder(reminder: &Reminder, stdout: bool) -> Result<()> {
+    if stdout {
+        return print_ndjson(&StreamEvent::new(Event::Reminder {
+            id: reminder.id,
+            username: reminder.username.clone(),
+            thread_id: reminder.thread_id.clone(),
+            note: reminder.note.clone(),
+        }));
+    }
+
+    let title = format!("Reminder: {}", nicknames::display(&reminder.username));
+    let mut body = if reminder.note.is_empty() {
+        "Time to get back to this conversation".to_string()
+    } else {
+        reminder.note.clone()
+    };
+
+    log_event(&format!(
+        "{} {} {}",
+        Theme::accent("⏰"),
+        Theme::username(&nicknames::display(&reminder.username)),
+        body
+    ));
+    if reminder.open {
+        let url = match &reminder.thread_id {
+            Some(thread_id) => thread_url(thread_id),
+            None => new_conversation_url(&reminder.username),
+        };
+        // The reminder has fired either way; a failed opener isn't retried
+        match open_url(&url) {
+            Ok(()) => log_event(&Theme::muted(&format!("↳ Opened {}", url))),
+            Err(error) => log_error(&error, stdout),
+        }
+        body = format!("{}\nig chat @{} to reply here", body, reminder.username);
+    }
+    desktop_notify(&title, &body);
+    Ok(())
+}
+
+/// Report an error without stopping the watcher
+fn log_error(error: &anyhow::Error, stdout: bool) {
+    if stdout {
+        eprintln!("{:#}", error);
+    } else {
+        log_event(&format!("{} {}", Theme::warn_icon(), Theme::warning(&format!("{:#}", error))));
+    }
+}
+
+/// Print a timestamped line to the watcher log
+fn log_event(text: &str) {
+    println!(
+        "{} {}",
+        Theme::timestamp(&Local::now().format("%H:%M:%S").to_string()),
+        text
+    );
+}
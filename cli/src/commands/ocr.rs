@@ -0,0 +1,134 @@
+//! Text extraction from shared images (`ig ocr`)
+//!
+//! Runs the `tesseract` command locally so screenshots never leave the
+//! machine; it has to be installed separately (e.g. `apt install
+//! tesseract-ocr` or `brew install tesseract`).
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::message::{find_message, message_not_found};
+use crate::http;
+use crate::output::{print_json, OutputFormat};
+use crate::spinner::create_spinner;
+
+/// Give up on the image download after this long
+const DOWNLOAD_TIMEOUT_SECS: u64 = 60;
+
+/// Text found in a message's image
+#[derive(Debug, Serialize)]
+struct OcrResult {
+    thread_id: String,
+    message_id: String,
+    sender: String,
+    text: String,
+}
+
+/// Download the image attached to a message and print the text in it
+///
+/// `lang` is passed to tesseract as-is (e.g. "eng" or "eng+spa").
+pub async fn ocr_message(
+    client: &ApiClient,
+    thread_id: &str,
+    message_id: &str,
+    lang: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let spinner = create_spinner("Fetching message...");
+    let found = find_message(client, thread_id, message_id).await;
+    spinner.finish_and_clear();
+
+    let found = found?.ok_or_else(|| message_not_found(message_id))?;
+
+    let message = &found.message;
+    let Some(url) = message.media_url.as_deref().filter(|u| !u.is_empty()) else {
+        anyhow::bail!("Message {} has no image attached", message_id);
+    };
+    if message.media_type.as_deref() == Some("video") {
+        anyhow::bail!("Message {} is a video; only images can be read", message_id);
+    }
+
+    let spinner = create_spinner("Downloading image...");
+    let image = download(url).await;
+    spinner.finish_and_clear();
+    let image = image?;
+
+    let spinner = create_spinner("Reading text...");
+    let text = tesseract(&image, lang);
+    spinner.finish_and_clear();
+    let text = text?;
+
+    if output == OutputFormat::Json {
+        return print_json(&OcrResult {
+            thread_id: thread_id.to_string(),
+            message_id: message_id.to_string(),
+            sender: found.sender(),
+            text,
+        });
+    }
+
+    if text.is_empty() {
+        println!("{}", Theme::muted("No text found in the image."));
+    } else {
+        println!("{}", text);
+    }
+
+    Ok(())
+}
+
+/// Fetch a photo or video from Instagram's CDN
+pub(crate) async fn download(url: &str) -> Result<Vec<u8>> {
+    let client = http::with_network(reqwest::Client::builder())
+        .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .context("Failed to download the media")?;
+    if !resp.status().is_success() {
+        anyhow::bail!("Media download returned {}", resp.status());
+    }
+    let bytes = resp.bytes().await.context("Failed to download the media")?;
+    Ok(bytes.to_vec())
+}
+
+/// Run tesseract on the image and return the text it found
+fn tesseract(image: &[u8], lang: Option<&str>) -> Result<String> {
+    let mut command = Command::new("tesseract");
+    command.args(["stdin", "stdout"]);
+    if let Some(lang) = lang {
+        command.args(["-l", lang]);
+    }
+
+    let mut child = match command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            anyhow::bail!("tesseract is not installed (see https://tesseract-ocr.github.io/tessdoc/Installation.html)")
+        }
+        Err(e) => return Err(e).context("Failed to run tesseract"),
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // tesseract exits early on images it can't decode, closing the pipe
+        let _ = stdin.write_all(image);
+    }
+    let output = child.wait_with_output().context("Failed to run tesseract")?;
+    if !output.status.success() {
+        let detail = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("tesseract failed: {}", detail.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
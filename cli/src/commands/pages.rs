@@ -0,0 +1,178 @@
+//! Fetches that span more than one page
+//!
+//! The server returns at most [`PAGE_SIZE`] items per request. Larger limits
+//! and `--all` page through with cursors and show a progress bar instead of
+//! the spinner.
+
+use anyhow::Result;
+
+use crate::client::ApiClient;
+use crate::header;
+use crate::models::{InboxResponse, Message, ThreadResponse};
+use crate::spinner::{create_progress, create_spinner};
+
+/// Most items the server returns for one request
+pub const PAGE_SIZE: u32 = 100;
+
+/// Fetch `limit` inbox threads, or the whole inbox when `limit` is None
+pub async fn fetch_inbox(client: &ApiClient, limit: Option<u32>) -> Result<InboxResponse> {
+    if let Some(limit) = limit.filter(|&l| l <= PAGE_SIZE) {
+        let spinner = create_spinner("Fetching inbox...");
+        let response = client.get_inbox(limit).await;
+        spinner.finish_and_clear();
+        if let Some(threads) = response.as_ref().ok().and_then(|r| r.threads.as_deref()) {
+            header::remember_unread(threads);
+        }
+        return response;
+    }
+
+    let mut progress = create_progress("Fetching inbox", "threads", limit.map(u64::from));
+    let mut threads = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let response = match client.get_inbox_page(&cursor).await {
+            Ok(response) if response.success => response,
+            other => {
+                progress.finish_and_clear();
+                return other;
+            }
+        };
+
+        let page = response.threads.unwrap_or_default();
+        let fetched = page.len();
+        progress.page_done(fetched as u64);
+        threads.extend(page);
+
+        if limit.is_some_and(|l| threads.len() >= l as usize) {
+            threads.truncate(limit.unwrap_or_default() as usize);
+            break;
+        }
+        match response.next_cursor {
+            Some(next) if fetched > 0 => cursor = next,
+            _ => break,
+        }
+    }
+    progress.finish_and_clear();
+    header::remember_unread(&threads);
+
+    Ok(InboxResponse {
+        success: true,
+        threads: Some(threads),
+        ..Default::default()
+    })
+}
+
+/// Fetch the newest `limit` messages of a thread, or its whole history when
+/// `limit` is None
+pub async fn fetch_thread(
+    client: &ApiClient,
+    thread_id: &str,
+    limit: Option<u32>,
+) -> Result<ThreadResponse> {
+    if let Some(limit) = limit.filter(|&l| l <= PAGE_SIZE) {
+        let spinner = create_spinner("Fetching messages...");
+        let response = client.get_thread(thread_id, limit).await;
+        spinner.finish_and_clear();
+        return response;
+    }
+
+    let mut progress = create_progress("Fetching messages", "messages", limit.map(u64::from));
+    let mut thread = None;
+    let mut messages = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let remaining = limit.map_or(PAGE_SIZE, |l| l.saturating_sub(messages.len() as u32));
+        let response = match client
+            .get_thread_page(thread_id, remaining.min(PAGE_SIZE), &cursor)
+            .await
+        {
+            Ok(response) if response.success => response,
+            other => {
+                progress.finish_and_clear();
+                return other;
+            }
+        };
+
+        let Some(mut page) = response.thread else {
+            break;
+        };
+        // Pages go from newest to oldest, so appending keeps newest first
+        let page_messages = page.messages.take().unwrap_or_default();
+        let fetched = page_messages.len();
+        progress.page_done(fetched as u64);
+        messages.extend(page_messages);
+        thread.get_or_insert(page);
+
+        if limit.is_some_and(|l| messages.len() >= l as usize) {
+            messages.truncate(limit.unwrap_or_default() as usize);
+            break;
+        }
+        match response.next_cursor {
+            Some(next) if fetched > 0 => cursor = next,
+            _ => break,
+        }
+    }
+    progress.finish_and_clear();
+
+    let thread = thread.map(|mut thread| {
+        thread.messages = Some(messages);
+        thread
+    });
+    Ok(ThreadResponse {
+        success: true,
+        thread,
+        ..Default::default()
+    })
+}
+
+/// Page back through a thread, newest first, until `enough` says the
+/// messages fetched so far (newest first) go back far enough, or the start
+/// of the conversation is reached
+pub async fn fetch_thread_until(
+    client: &ApiClient,
+    thread_id: &str,
+    message: &str,
+    enough: impl Fn(&[Message]) -> bool,
+) -> Result<ThreadResponse> {
+    let mut progress = create_progress(message, "messages", None);
+    let mut thread = None;
+    let mut messages = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let response = match client.get_thread_page(thread_id, PAGE_SIZE, &cursor).await {
+            Ok(response) if response.success => response,
+            other => {
+                progress.finish_and_clear();
+                return other;
+            }
+        };
+
+        let Some(mut page) = response.thread else {
+            break;
+        };
+        let page_messages = page.messages.take().unwrap_or_default();
+        let fetched = page_messages.len();
+        progress.page_done(fetched as u64);
+        messages.extend(page_messages);
+        thread.get_or_insert(page);
+
+        if enough(&messages) {
+            break;
+        }
+        match response.next_cursor {
+            Some(next) if fetched > 0 => cursor = next,
+            _ => break,
+        }
+    }
+    progress.finish_and_clear();
+
+    let thread = thread.map(|mut thread| {
+        thread.messages = Some(messages);
+        thread
+    });
+    Ok(ThreadResponse {
+        success: true,
+        thread,
+        ..Default::default()
+    })
+}
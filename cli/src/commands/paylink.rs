@@ -0,0 +1,93 @@
+//! `ig paylink`: payment links from the `[paylink]` template
+//!
+//! `ig paylink 40 "Commission"` prints the configured payment link for that
+//! amount, `--to` sends it straight away, and `:paylink 40 Commission` in
+//! `ig chat` puts it in the input to add to before sending.
+
+use anyhow::Result;
+
+use crate::client::ApiClient;
+use crate::config::{Config, PaylinkConfig};
+use crate::commands::{send_to_thread, send_to_user};
+
+/// Payment link message for `amount` (and an optional `note`) from the
+/// `[paylink]` templates
+pub fn render_paylink(config: &PaylinkConfig, amount: &str, note: Option<&str>) -> Result<String> {
+    let Some(template) = config.url.as_deref().filter(|u| !u.trim().is_empty()) else {
+        anyhow::bail!(
+            "No payment link configured. Set one under [paylink] in config.toml, e.g. url = \"https://paypal.me/you/{{amount}}\""
+        );
+    };
+    let amount = parse_amount(amount)?;
+    let note = note.map(str::trim).filter(|n| !n.is_empty());
+    let currency = config.currency.as_deref().unwrap_or_default().trim();
+
+    let url = template
+        .trim()
+        .replace("{amount}", &amount)
+        .replace("{currency}", currency)
+        .replace("{note}", &percent_encode(note.unwrap_or_default()));
+    let message = match (config.message.as_deref(), note) {
+        (Some(message), _) => message,
+        (None, Some(_)) => "{note}: {url}",
+        (None, None) => "{url}",
+    };
+    Ok(message
+        .replace("{amount}", &amount)
+        .replace("{currency}", currency)
+        .replace("{note}", note.unwrap_or_default())
+        .replace("{url}", &url)
+        .trim()
+        .to_string())
+}
+
+/// `40`, `$40` or `12.50` as it goes in the link; anything else is an error
+fn parse_amount(amount: &str) -> Result<String> {
+    let trimmed = amount.trim().trim_start_matches(['$', '€', '£']);
+    let (whole, cents) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    let valid = !whole.is_empty()
+        && digits(whole)
+        && digits(cents)
+        && cents.len() <= 2
+        && !trimmed.ends_with('.')
+        && trimmed.chars().any(|c| ('1'..='9').contains(&c));
+    if !valid {
+        anyhow::bail!("'{}' isn't an amount (e.g. 40 or 12.50)", amount);
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Percent-encode everything but unreserved URL characters
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// `ig paylink`: print the payment link, or send it to `to` (a thread ID
+/// or @username) through the usual send checks
+pub async fn paylink(client: &ApiClient, amount: &str, note: Option<&str>, to: Option<&str>, yes: bool) -> Result<()> {
+    let text = render_paylink(&Config::load()?.paylink, amount, note)?;
+    match to {
+        None => {
+            println!("{}", text);
+            Ok(())
+        }
+        Some(to) => match to.strip_prefix('@') {
+            Some(username) => send_to_user(client, username, Some(&text), yes, false, false, true).await,
+            None => send_to_thread(client, to, Some(&text), false, false, true).await,
+        },
+    }
+}
+
+/// `:paylink <amount> [note]` in `ig chat`: the link to put in the input
+pub fn chat_paylink(arg: &str) -> Result<String> {
+    let arg = arg.trim();
+    let (amount, note) = arg.split_once(char::is_whitespace).unwrap_or((arg, ""));
+    let note = note.trim().trim_matches('"');
+    render_paylink(&Config::load()?.paylink, amount, Some(note))
+}
@@ -0,0 +1,169 @@
+//! Local message pinning
+//!
+//! Instagram DMs have no pin feature, so pinned messages are kept locally
+//! with a snapshot of their text for quick reference.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::message::{find_message, message_not_found};
+use crate::output::{print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+use crate::store;
+use crate::util::truncate;
+
+/// Data file holding pinned messages
+const PINS_FILE: &str = "pins";
+
+/// A pinned message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pin {
+    pub thread_id: String,
+    pub message_id: String,
+    pub sender: String,
+    pub text: Option<String>,
+    pub timestamp: Option<String>,
+    pub pinned_at: DateTime<Local>,
+}
+
+impl Pin {
+    /// One-line preview of the pinned message
+    pub fn preview(&self) -> String {
+        self.text.clone().unwrap_or_else(|| "[media]".to_string())
+    }
+}
+
+/// All pinned messages
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Pins {
+    pub items: Vec<Pin>,
+}
+
+impl Pins {
+    /// Load pins from disk
+    pub fn load() -> Result<Self> {
+        store::load(PINS_FILE)
+    }
+
+    /// Save pins to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(PINS_FILE, self)
+    }
+
+    /// Pins for one thread, oldest pin first
+    pub fn for_thread(&self, thread_id: &str) -> Vec<&Pin> {
+        self.items.iter().filter(|p| p.thread_id == thread_id).collect()
+    }
+}
+
+/// Pin a message (`ig pin-msg <thread_id> <message_id>`)
+pub async fn pin_message(client: &ApiClient, thread_id: &str, message_id: &str) -> Result<()> {
+    let mut pins = Pins::load()?;
+    if pins
+        .items
+        .iter()
+        .any(|p| p.thread_id == thread_id && p.message_id == message_id)
+    {
+        println!("{}", Theme::muted("Message is already pinned."));
+        return Ok(());
+    }
+
+    let spinner = create_spinner("Fetching message...");
+    let found = find_message(client, thread_id, message_id).await;
+    spinner.finish_and_clear();
+
+    let found = found?.ok_or_else(|| message_not_found(message_id))?;
+
+    let pin = Pin {
+        thread_id: thread_id.to_string(),
+        message_id: message_id.to_string(),
+        sender: found.sender(),
+        text: found.message.text.clone(),
+        timestamp: found.message.timestamp.clone(),
+        pinned_at: Local::now(),
+    };
+    println!(
+        "{} {} {}",
+        Theme::check(),
+        Theme::success("Pinned:"),
+        pin.preview()
+    );
+
+    pins.items.push(pin);
+    pins.save()
+}
+
+/// Unpin a message
+pub fn unpin_message(thread_id: &str, message_id: &str) -> Result<()> {
+    let mut pins = Pins::load()?;
+    let before = pins.items.len();
+    pins.items
+        .retain(|p| !(p.thread_id == thread_id && p.message_id == message_id));
+
+    if pins.items.len() == before {
+        println!("{}", Theme::muted("Message was not pinned."));
+        return Ok(());
+    }
+
+    pins.save()?;
+    println!("{} {}", Theme::check(), Theme::success("Message unpinned"));
+    Ok(())
+}
+
+/// List pinned messages for a thread (`ig pins <thread_id>`)
+pub fn list_pins(thread_id: &str, output: OutputFormat) -> Result<()> {
+    let pins = Pins::load()?;
+    let thread_pins = pins.for_thread(thread_id);
+
+    if output == OutputFormat::Json {
+        return print_json(&thread_pins);
+    }
+
+    if thread_pins.is_empty() {
+        println!("{}", Theme::muted("No pinned messages in this thread."));
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Message ID", "Sender", "Text", "Pinned"]);
+        for pin in &thread_pins {
+            table.row([
+                pin.message_id.clone(),
+                pin.sender.clone(),
+                truncate(&pin.preview(), 50),
+                pin.pinned_at.format("%Y-%m-%d %H:%M").to_string(),
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Pinned messages"));
+    println!("{}", Theme::separator(60));
+    for line in pin_lines(&thread_pins) {
+        println!("{}", line);
+    }
+    println!("{}", Theme::separator(60));
+
+    Ok(())
+}
+
+/// Render pins as display lines (shared by the thread header and the
+/// interactive inbox's `P` view)
+pub fn pin_lines(pins: &[&Pin]) -> Vec<String> {
+    pins.iter()
+        .map(|pin| {
+            format!(
+                "  {} {} {} {}",
+                Theme::accent("📌"),
+                Theme::pink(&pin.sender),
+                pin.preview(),
+                Theme::muted(&format!("({})", pin.message_id))
+            )
+        })
+        .collect()
+}
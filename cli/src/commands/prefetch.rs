@@ -0,0 +1,87 @@
+//! Background prefetch of recent messages
+//!
+//! As soon as the interactive inbox loads, the recent messages of the top
+//! threads are fetched a few at a time, so the preview pane fills in and
+//! pressing Enter opens a chat without waiting on the server.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+use crate::client::ApiClient;
+use crate::exclusions;
+use crate::models::Thread;
+use crate::tasks::Supervisor;
+
+/// How many threads from the top of the inbox to prefetch
+pub const PREFETCH_THREADS: usize = 10;
+
+/// Thread requests in flight at once
+const PREFETCH_CONCURRENCY: usize = 4;
+
+/// Messages fetched per thread
+const PREFETCH_MESSAGES: u32 = 20;
+
+/// Threads fetched in the background, keyed by thread ID
+///
+/// Outstanding fetches are cancelled when this is dropped.
+pub struct Prefetch {
+    client: ApiClient,
+    cache: Arc<Mutex<HashMap<String, Arc<Thread>>>>,
+    _tasks: Supervisor,
+}
+
+impl Prefetch {
+    /// Start fetching the given threads' recent messages
+    pub fn spawn(client: &ApiClient, thread_ids: impl IntoIterator<Item = String>) -> Self {
+        let cache: Arc<Mutex<HashMap<String, Arc<Thread>>>> = Arc::default();
+        let semaphore = Arc::new(Semaphore::new(PREFETCH_CONCURRENCY));
+        let mut tasks = Supervisor::new();
+        let excluded = exclusions::excluded_ids();
+
+        for thread_id in thread_ids.into_iter().filter(|id| !excluded.contains(id)) {
+            let client = client.clone();
+            let cache = cache.clone();
+            let semaphore = semaphore.clone();
+            tasks.spawn(async move {
+                let Ok(_permit) = semaphore.acquire().await else {
+                    return;
+                };
+                // Failures are left uncached; opening the chat fetches again
+                if let Some(thread) = fetch_recent(&client, &thread_id).await {
+                    if let Ok(mut cache) = cache.lock() {
+                        cache.insert(thread_id, Arc::new(thread));
+                    }
+                }
+            });
+        }
+
+        Self {
+            client: client.clone(),
+            cache,
+            _tasks: tasks,
+        }
+    }
+
+    /// A thread if its prefetch has finished
+    pub fn get(&self, thread_id: &str) -> Option<Arc<Thread>> {
+        self.cache.lock().ok()?.get(thread_id).cloned()
+    }
+
+    /// A thread from the cache, or fetched now if the prefetch hasn't got to it
+    pub async fn get_or_fetch(&self, thread_id: &str) -> Option<Arc<Thread>> {
+        match self.get(thread_id) {
+            Some(thread) => Some(thread),
+            None => fetch_recent(&self.client, thread_id).await.map(Arc::new),
+        }
+    }
+}
+
+/// A thread with its recent messages (None if it couldn't be fetched)
+pub async fn fetch_recent(client: &ApiClient, thread_id: &str) -> Option<Thread> {
+    let response = client.get_thread(thread_id, PREFETCH_MESSAGES).await.ok()?;
+    if !response.success {
+        return None;
+    }
+    response.thread
+}
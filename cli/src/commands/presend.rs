@@ -0,0 +1,350 @@
+//! Checks run before a message is sent
+//!
+//! All are off by default and set under `[send]` in config.toml:
+//!
+//! - `[send.filter]` lists words, regexes and/or an external command that
+//!   outgoing messages are checked against; a match either warns and asks
+//!   (`action = "warn"`) or refuses to send (`action = "block"`).
+//! - `typo_check = true` checks each message for common misspellings,
+//!   doubled words and an opening @mention of someone who isn't in the
+//!   conversation, and any warning asks for confirmation before sending.
+//! - `send_delay = "5s"` holds chat messages for that long with a countdown,
+//!   during which `u` takes the message back.
+//!
+//! Independently, `ig send` asks before messaging someone for the first time,
+//! and a `signature` (under `[send]` or the active profile) is added to
+//! messages once they've passed the checks.
+
+use anyhow::{Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use dialoguer::Confirm;
+use regex::Regex;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::inbox::find_thread_by_username;
+use crate::config::{Config, FilterAction, FilterConfig};
+use crate::duration::parse_duration;
+use crate::profile;
+use crate::spinner::create_spinner;
+use crate::users;
+
+/// Frequent misspellings and their corrections
+const COMMON_TYPOS: &[(&str, &str)] = &[
+    ("teh", "the"),
+    ("adn", "and"),
+    ("taht", "that"),
+    ("thier", "their"),
+    ("recieve", "receive"),
+    ("recieved", "received"),
+    ("becuase", "because"),
+    ("beacuse", "because"),
+    ("definately", "definitely"),
+    ("seperate", "separate"),
+    ("occured", "occurred"),
+    ("untill", "until"),
+    ("wierd", "weird"),
+    ("alot", "a lot"),
+    ("tommorow", "tomorrow"),
+    ("tomorow", "tomorrow"),
+    ("accomodate", "accommodate"),
+    ("wich", "which"),
+    ("whcih", "which"),
+    ("freind", "friend"),
+    ("beleive", "believe"),
+    ("calender", "calendar"),
+    ("goverment", "government"),
+    ("adress", "address"),
+    ("payed", "paid"),
+];
+
+/// Words that are often repeated on purpose
+const REPEATABLE: &[&str] = &["ha", "haha", "lol", "no", "yes", "so", "very", "really", "bye", "hey"];
+
+/// How often the undo countdown checks for a key press
+const UNDO_POLL_MS: u64 = 100;
+
+/// Whether pre-send checks are turned on in config
+pub fn checks_enabled() -> bool {
+    Config::load().map(|c| c.send.typo_check).unwrap_or(false)
+}
+
+/// Problems worth a second look before sending `text` to a conversation
+/// with `participants` (pass an empty slice if they aren't known)
+fn find_problems(text: &str, participants: &[String]) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    // Addressing someone who won't see it
+    if let Some(first) = text.split_whitespace().next() {
+        if let Some(name) = first.strip_prefix('@') {
+            let name = name.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_' && c != '.');
+            let known = participants.iter().any(|p| p.eq_ignore_ascii_case(name));
+            if !name.is_empty() && !participants.is_empty() && !known {
+                problems.push(format!("@{} is not in this conversation", name));
+            }
+        }
+    }
+
+    let words: Vec<String> = text
+        .split_whitespace()
+        .filter(|w| !w.starts_with('@') && !w.contains("://"))
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                .to_string()
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    for pair in words.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let repeatable = REPEATABLE.iter().any(|r| r.eq_ignore_ascii_case(a));
+        if a.eq_ignore_ascii_case(b) && a.chars().any(char::is_alphabetic) && !repeatable {
+            problems.push(format!("\"{} {}\" is repeated", a, b));
+        }
+    }
+
+    for word in &words {
+        let lower = word.to_lowercase();
+        if let Some((_, fix)) = COMMON_TYPOS.iter().find(|(typo, _)| *typo == lower) {
+            let problem = format!("\"{}\" — did you mean \"{}\"?", word, fix);
+            if !problems.contains(&problem) {
+                problems.push(problem);
+            }
+        }
+    }
+
+    problems
+}
+
+/// Content filter rules `text` breaks
+fn filter_matches(filter: &FilterConfig, text: &str) -> Result<Vec<String>> {
+    let mut matches = Vec::new();
+
+    for word in filter.words.iter().filter(|w| !w.trim().is_empty()) {
+        let re = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(word.trim())))?;
+        if re.is_match(text) {
+            matches.push(format!("\"{}\" is filtered", word.trim()));
+        }
+    }
+
+    for pattern in &filter.patterns {
+        let re = Regex::new(pattern)
+            .with_context(|| format!("Invalid [send.filter] pattern {:?}", pattern))?;
+        if let Some(found) = re.find(text) {
+            matches.push(format!("\"{}\" matches a filtered pattern", found.as_str()));
+        }
+    }
+
+    if let Some(command) = filter.command.as_deref().filter(|c| !c.trim().is_empty()) {
+        if let Some(reason) = run_filter_command(command, text)? {
+            matches.push(reason);
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Pipe `text` to the filter command; the reason it gave if it exited
+/// non-zero
+fn run_filter_command(command: &str, text: &str) -> Result<Option<String>> {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let mut child = Command::new(shell)
+        .args([flag, command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run filter command {:?}", command))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that exits without reading its input closes the pipe
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run filter command {:?}", command))?;
+    if output.status.success() {
+        return Ok(None);
+    }
+
+    let reason = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(if reason.is_empty() {
+        "Rejected by the filter command".to_string()
+    } else {
+        reason
+    }))
+}
+
+/// Run the pre-send checks before `text` goes out
+///
+/// A message the content filter blocks is never sent (an error outside a
+/// terminal). Otherwise anything flagged asks whether to send anyway;
+/// outside a terminal filter warnings are printed and the send goes ahead.
+pub fn confirm_send(text: &str, participants: &[String]) -> Result<bool> {
+    let config = Config::load()?.send;
+    let interactive = io::stdin().is_terminal();
+
+    let matches = filter_matches(&config.filter, text)?;
+    if !matches.is_empty() && config.filter.action == FilterAction::Block {
+        for reason in &matches {
+            eprintln!("{} {}", Theme::cross(), Theme::error(reason));
+        }
+        if !interactive {
+            anyhow::bail!("Message blocked by the content filter");
+        }
+        println!("{}", Theme::muted("Not sent."));
+        return Ok(false);
+    }
+
+    let mut problems = matches;
+    if config.typo_check && interactive {
+        problems.extend(find_problems(text, participants));
+    }
+    if problems.is_empty() {
+        return Ok(true);
+    }
+
+    for problem in &problems {
+        eprintln!("{} {}", Theme::warn_icon(), Theme::warning(problem));
+    }
+    if !interactive {
+        return Ok(true);
+    }
+    let send = Confirm::new()
+        .with_prompt("Send anyway?")
+        .default(false)
+        .interact()?;
+    if !send {
+        println!("{}", Theme::muted("Not sent."));
+    }
+    Ok(send)
+}
+
+/// What the pre-send checks say about `text`, for views that ask in their
+/// own way instead of prompting (`ig tui`)
+#[derive(Debug, Default)]
+pub struct Verdict {
+    /// The content filter refuses the message
+    pub blocked: bool,
+    /// Filter matches and typo check warnings
+    pub problems: Vec<String>,
+}
+
+/// Run the pre-send checks on `text` without asking anything
+pub fn check_message(text: &str, participants: &[String]) -> Result<Verdict> {
+    let config = Config::load()?.send;
+    let mut problems = filter_matches(&config.filter, text)?;
+    let blocked = !problems.is_empty() && config.filter.action == FilterAction::Block;
+    if config.typo_check && !blocked {
+        problems.extend(find_problems(text, participants));
+    }
+    Ok(Verdict { blocked, problems })
+}
+
+/// The configured send delay, if any
+pub fn send_delay() -> Option<Duration> {
+    let config = Config::load().ok()?;
+    let delay = parse_duration(config.send.send_delay.as_deref()?).ok()?;
+    delay.to_std().ok().filter(|d| !d.is_zero())
+}
+
+/// Count down before sending; false if the user pressed `u` (or Esc) to
+/// undo. Enter sends straight away. Always true outside a terminal.
+pub fn undo_window(delay: Duration) -> Result<bool> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Ok(true);
+    }
+
+    let deadline = Instant::now() + delay;
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    let result = (|| -> Result<bool> {
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(true);
+            }
+            write!(
+                stdout,
+                "\r{}\x1b[K",
+                Theme::muted(&format!(
+                    "Sending in {}s (press u to undo, Enter to send now)",
+                    left.as_secs() + 1
+                ))
+            )?;
+            stdout.flush()?;
+
+            if !event::poll(Duration::from_millis(UNDO_POLL_MS))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('u') | KeyCode::Esc => return Ok(false),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(false)
+                    }
+                    KeyCode::Enter => return Ok(true),
+                    _ => {}
+                }
+            }
+        }
+    })();
+    terminal::disable_raw_mode()?;
+    write!(stdout, "\r\x1b[K")?;
+    stdout.flush()?;
+    result
+}
+
+/// Ask before messaging someone there is no conversation with yet
+///
+/// Checks users seen in earlier fetches first and only searches the inbox
+/// when the name is new. Outside a terminal the send is refused (pass
+/// `--yes` to skip the check).
+pub async fn confirm_first_contact(client: &ApiClient, username: &str) -> Result<bool> {
+    let username = username.trim_start_matches('@');
+    if users::is_known(username) {
+        return Ok(true);
+    }
+    let spinner = create_spinner("Checking conversations...");
+    let thread = find_thread_by_username(client, username).await;
+    spinner.finish_and_clear();
+    if thread?.is_some() {
+        return Ok(true);
+    }
+
+    if !io::stdin().is_terminal() {
+        anyhow::bail!(
+            "You've never messaged @{}; pass --yes to send anyway",
+            username
+        );
+    }
+    let send = Confirm::new()
+        .with_prompt(format!("You've never messaged @{} — send anyway?", username))
+        .default(false)
+        .interact()?;
+    if !send {
+        println!("{}", Theme::muted("Not sent."));
+    }
+    Ok(send)
+}
+
+/// `text` with the signature on a line of its own after it: the active
+/// profile's, else `[send] signature`. Messages from `ig chat` (`in_chat`)
+/// are left alone when `signature_in_chat = false`.
+pub fn sign(text: &str, in_chat: bool) -> Result<String> {
+    let send = Config::load()?.send;
+    if in_chat && !send.signature_in_chat {
+        return Ok(text.to_string());
+    }
+    let signature = profile::active().signature.clone().or(send.signature);
+    Ok(match signature.as_deref().map(str::trim) {
+        Some(signature) if !signature.is_empty() => format!("{}\n\n{}", text.trim_end(), signature),
+        _ => text.to_string(),
+    })
+}
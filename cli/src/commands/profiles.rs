@@ -0,0 +1,124 @@
+//! One inbox across every profile (`ig inbox --all-profiles`)
+//!
+//! Each profile in config.toml is asked for its inbox at the same time,
+//! through its own server and session, and the conversations are listed
+//! together, newest first, each with its profile's name. The listing is
+//! saved with the profile of every conversation, so `ig open <n>` sends
+//! through the account it came from.
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio::task::JoinSet;
+
+use crate::aliases;
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::assign::Assignments;
+use crate::commands::export::InboxRow;
+use crate::commands::inbox::{filter_threads, inbox_header, print_thread_summary};
+use crate::commands::tags::Tags;
+use crate::config::Permission;
+use crate::models::Thread;
+use crate::output::{print_json, OutputFormat};
+use crate::profile;
+use crate::snapshot;
+use crate::spinner::create_spinner;
+use crate::util::parse_timestamp;
+
+/// `--output json` shape of a conversation in the merged inbox
+#[derive(Debug, Serialize)]
+struct ProfileRow {
+    profile: String,
+    #[serde(flatten)]
+    row: InboxRow,
+}
+
+/// Show the merged inbox of every profile that may read messages
+pub async fn show_inbox_all_profiles(
+    limit: u32,
+    unread_only: bool,
+    tag: Option<&str>,
+    compact: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let profiles: Vec<_> = profile::all()?
+        .into_iter()
+        .filter(|p| p.allows(Permission::Read))
+        .collect();
+    if profiles.is_empty() {
+        anyhow::bail!("No profiles to read from (add [profiles.<name>] sections to config.toml)");
+    }
+
+    let spinner = create_spinner(&format!("Fetching {} inboxes...", profiles.len()));
+    let mut tasks = JoinSet::new();
+    for profile in profiles {
+        tasks.spawn(async move {
+            let name = profile.name.unwrap_or_default();
+            let response = async {
+                let response = ApiClient::new(profile.server.as_deref())?.get_inbox(limit).await?;
+                if response.success {
+                    Ok(response.threads.unwrap_or_default())
+                } else {
+                    Err(anyhow::anyhow!(response.error.unwrap_or("Failed to fetch inbox".to_string())))
+                }
+            }
+            .await;
+            (name, response)
+        });
+    }
+
+    let tags = Tags::load().unwrap_or_default();
+    let mut threads: Vec<(String, Thread)> = Vec::new();
+    let mut failed: Vec<(String, anyhow::Error)> = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined? {
+            (name, Ok(fetched)) => {
+                let fetched = filter_threads(fetched, unread_only, tag, &tags);
+                threads.extend(fetched.into_iter().map(|t| (name.clone(), t)));
+            }
+            (name, Err(e)) => failed.push((name, e)),
+        }
+    }
+    spinner.finish_and_clear();
+    sort_merged(&mut threads);
+    threads.truncate(limit as usize);
+    failed.sort_by(|a, b| a.0.cmp(&b.0));
+
+    if output == OutputFormat::Json {
+        for (name, e) in &failed {
+            eprintln!("{}: {:#}", name, e);
+        }
+        let rows: Vec<ProfileRow> = threads
+            .iter()
+            .map(|(name, t)| ProfileRow { profile: name.clone(), row: InboxRow::new(t, &tags) })
+            .collect();
+        return print_json(&rows);
+    }
+
+    snapshot::record_merged(&threads);
+    if threads.is_empty() {
+        println!("{}", Theme::muted("No conversations found."));
+    } else {
+        println!();
+        println!("{} {}", inbox_header(unread_only, tag), Theme::blue("(all profiles)"));
+        println!("{}", Theme::separator(60));
+        let aliases = aliases::assign(threads.iter().map(|(_, t)| t.id.as_str()));
+        for (i, (name, thread)) in threads.iter().enumerate() {
+            let alias = aliases.get(&thread.id).map(String::as_str);
+            print_thread_summary(i + 1, thread, &tags, &Assignments::default(), alias, Some(name), compact);
+        }
+        println!("{}", Theme::separator(60));
+        println!("{}", Theme::muted(&format!("Showing {} conversations", threads.len())));
+    }
+    for (name, e) in &failed {
+        println!("{} {}", Theme::warn_icon(), Theme::warning(&format!("{}: {:#}", name, e)));
+    }
+    Ok(())
+}
+
+/// Newest activity first, whichever profile it's in
+fn sort_merged(threads: &mut [(String, Thread)]) {
+    threads.sort_by_cached_key(|(_, t)| {
+        std::cmp::Reverse(t.last_message_timestamp.as_deref().and_then(parse_timestamp))
+    });
+}
@@ -0,0 +1,46 @@
+//! Terminal QR codes (`ig qrcode`, `ig login --qr`)
+
+use anyhow::{Context, Result};
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::spinner::create_spinner;
+
+/// Render `data` as a QR code of half-block characters
+pub(crate) fn render_qr(data: &str) -> Result<String> {
+    let code = QrCode::new(data.as_bytes()).context("Failed to build the QR code")?;
+    // Light modules drawn dark so the code scans on dark terminals
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// Show a QR code linking to `target`'s profile (the logged-in user's if
+/// none is given)
+pub async fn show_profile_qr(client: &ApiClient, target: Option<&str>) -> Result<()> {
+    let username = match target {
+        Some(target) => target.trim_start_matches('@').to_string(),
+        None => {
+            let spinner = create_spinner("Loading profile...");
+            let health = client.health().await;
+            spinner.finish_and_clear();
+            health?
+                .username
+                .filter(|u| !u.is_empty())
+                .context("Not logged in. Use 'ig login' first, or pass a @username.")?
+        }
+    };
+
+    let url = format!("https://instagram.com/{}", username);
+    println!("{}", render_qr(&url)?);
+    println!(
+        "{} {}",
+        Theme::username(&format!("@{}", username)),
+        Theme::muted(&url)
+    );
+    Ok(())
+}
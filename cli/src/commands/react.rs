@@ -0,0 +1,116 @@
+//! Reacting to messages from `ig chat`
+//!
+//! `:react` reacts to a message on screen, counting from the newest:
+//! `:react` puts ❤️ on the last message, `:react 2 😂` puts 😂 on the one
+//! before it. The reaction is shown right away and the server's answer
+//! follows; if Instagram turns it down, a line says so.
+
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+
+use crate::client::{ApiClient, Feature};
+use crate::colors::Theme;
+use crate::emoji::emoji_only;
+use crate::models::Message;
+use crate::util::ellipsize;
+
+/// Reaction used when `:react` isn't given one
+pub const DEFAULT_REACTION: &str = "❤️";
+
+/// Messages remembered for `:react`; further back isn't on screen anyway
+const VISIBLE_KEEP: usize = 100;
+
+/// Width of the reacted-to message in the confirmation line
+const TEXT_WIDTH: usize = 40;
+
+/// A message shown in the chat
+#[derive(Debug, Clone)]
+pub struct Shown {
+    pub id: String,
+    pub sender: String,
+    pub text: String,
+}
+
+/// The messages shown in a chat so far, oldest first. Shared with the task
+/// printing incoming messages.
+#[derive(Debug, Clone, Default)]
+pub struct Visible(Arc<Mutex<Vec<Shown>>>);
+
+impl Visible {
+    /// Remember a message as it's printed
+    pub fn push(&self, message: &Message, sender: &str) {
+        if message.id.is_empty() {
+            return;
+        }
+        let mut shown = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        shown.push(Shown {
+            id: message.id.clone(),
+            sender: sender.to_string(),
+            text: message.text.clone().unwrap_or_else(|| "[media]".to_string()),
+        });
+        if shown.len() > VISIBLE_KEEP {
+            shown.remove(0);
+        }
+    }
+
+    /// The `n`th newest message shown (1 is the last)
+    pub fn nth_newest(&self, n: usize) -> Option<Shown> {
+        let shown = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        n.checked_sub(1).and_then(|i| shown.iter().rev().nth(i)).cloned()
+    }
+}
+
+/// Parse `:react` arguments: an optional position (default 1) and an
+/// optional emoji (default ❤️), e.g. "", "2", "😂" or "2 😂"
+pub fn parse_react(arg: &str) -> Result<(usize, String)> {
+    let arg = arg.trim();
+    let (first, rest) = arg.split_once(char::is_whitespace).unwrap_or((arg, ""));
+    let (n, emoji) = match first.parse::<usize>() {
+        Ok(n) => (n, rest.trim()),
+        Err(_) => (1, arg),
+    };
+    if n == 0 {
+        anyhow::bail!("messages are counted from 1 (the newest)");
+    }
+    let emoji = if emoji.is_empty() { DEFAULT_REACTION } else { emoji };
+    if emoji_only(emoji).is_none_or(|e| e.len() != 1) {
+        anyhow::bail!("'{}' isn't a single emoji (e.g. :react 2 😂)", emoji);
+    }
+    Ok((n, emoji.to_string()))
+}
+
+/// `:react [n] [emoji]` in chat
+pub async fn react_in_chat(client: &ApiClient, thread_id: Option<&str>, visible: &Visible, arg: &str) {
+    let (n, emoji) = match parse_react(arg) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            println!("{} {}", Theme::cross(), Theme::error(&format!("{:#}", e)));
+            return;
+        }
+    };
+    let (Some(thread_id), Some(message)) = (thread_id, visible.nth_newest(n)) else {
+        println!("{}", Theme::muted(&format!("No message {} on screen to react to.", n)));
+        return;
+    };
+    if !client.supports(Feature::Reactions).await {
+        println!("{} {}", Theme::cross(), Theme::error(&Feature::Reactions.unsupported()));
+        return;
+    }
+
+    // Shown before the server answers; a failure is reported after it
+    println!(
+        "  {} {}",
+        emoji,
+        Theme::muted(&format!("→ {}: {}", message.sender, ellipsize(&message.text, TEXT_WIDTH)))
+    );
+    let error = match client.react(thread_id, &message.id, &emoji).await {
+        Ok(response) if response.success => return,
+        Ok(response) => response.error.unwrap_or_else(|| "Instagram refused it".to_string()),
+        Err(e) => format!("{:#}", e),
+    };
+    println!(
+        "{} {}",
+        Theme::cross(),
+        Theme::error(&format!("Reaction {} not sent: {}", emoji, error))
+    );
+}
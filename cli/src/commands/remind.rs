@@ -0,0 +1,189 @@
+//! Read-later reminders tied to conversations
+//!
+//! Reminders are stored locally and fired by `ig notify` when they come due.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::inbox::find_thread_by_username;
+use crate::duration::Span;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+use crate::store;
+
+/// Data file holding reminders
+const REMINDERS_FILE: &str = "reminders";
+
+/// A reminder to come back to a conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: u32,
+    pub username: String,
+    pub thread_id: Option<String>,
+    pub note: String,
+    pub due: DateTime<Local>,
+    pub created: DateTime<Local>,
+    /// Open the conversation when the reminder fires
+    #[serde(default)]
+    pub open: bool,
+}
+
+/// All stored reminders
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Reminders {
+    pub next_id: u32,
+    pub items: Vec<Reminder>,
+}
+
+impl Reminders {
+    /// Load reminders from disk
+    pub fn load() -> Result<Self> {
+        store::load(REMINDERS_FILE)
+    }
+
+    /// Save reminders to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(REMINDERS_FILE, self)
+    }
+
+    /// All reminders that are due
+    pub fn due(&self) -> Vec<Reminder> {
+        let now = Local::now();
+        self.items.iter().filter(|r| r.due <= now).cloned().collect()
+    }
+
+    /// Remove a reminder once it has fired. Reloads first, so reminders
+    /// added in the meantime are kept.
+    pub fn remove_fired(id: u32) -> Result<()> {
+        let mut reminders = Self::load()?;
+        reminders.items.retain(|r| r.id != id);
+        reminders.save()
+    }
+}
+
+/// Create a reminder for a conversation (`ig remind @user 3h "reply to this"`)
+pub async fn add_reminder(
+    client: &ApiClient,
+    target: &str,
+    after: &Span,
+    note: Option<&str>,
+    open: bool,
+) -> Result<()> {
+    let username = target.trim_start_matches('@');
+    let due = Local::now() + after.duration;
+
+    // Tie the reminder to the thread when we can find it
+    let spinner = create_spinner(&format!("Finding conversation with @{}...", username));
+    let thread = find_thread_by_username(client, username).await;
+    spinner.finish_and_clear();
+    let thread_id = thread.ok().flatten().map(|t| t.id);
+
+    let mut reminders = Reminders::load()?;
+    reminders.next_id += 1;
+    let id = reminders.next_id;
+    reminders.items.push(Reminder {
+        id,
+        username: username.to_string(),
+        thread_id,
+        note: note.unwrap_or_default().to_string(),
+        due,
+        created: Local::now(),
+        open,
+    });
+    reminders.save()?;
+
+    println!(
+        "{} {} {} {}",
+        Theme::check(),
+        Theme::success(&format!("Reminder #{} set for", id)),
+        Theme::username(&format!("@{}", username)),
+        Theme::muted(&format!("at {}", due.format("%Y-%m-%d %H:%M")))
+    );
+    println!(
+        "{}",
+        Theme::muted("Reminders fire while `ig notify` is running.")
+    );
+
+    Ok(())
+}
+
+/// List pending reminders
+pub fn list_reminders(output: OutputFormat) -> Result<()> {
+    let mut reminders = Reminders::load()?;
+    reminders.items.sort_by_key(|r| r.due);
+
+    if output == OutputFormat::Json {
+        return print_json(&reminders.items);
+    }
+
+    if reminders.items.is_empty() {
+        println!("{}", Theme::muted("No reminders."));
+        return Ok(());
+    }
+
+    let now = Local::now();
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["ID", "Username", "Due", "Note"]);
+        for reminder in &reminders.items {
+            let mut due = reminder.due.format("%Y-%m-%d %H:%M").to_string();
+            if reminder.due <= now {
+                due.push_str(" (due)");
+            }
+            table.row([
+                reminder.id.to_string(),
+                format!("@{}", reminder.username),
+                due,
+                reminder.note.clone(),
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Reminders"));
+    println!("{}", Theme::separator(60));
+    for reminder in &reminders.items {
+        let due = reminder.due.format("%Y-%m-%d %H:%M").to_string();
+        let due = if reminder.due <= now {
+            Theme::warning(&format!("{} (due)", due))
+        } else {
+            Theme::timestamp(&due)
+        };
+        println!(
+            "  {} {} {}",
+            Theme::muted(&format!("#{}", reminder.id)),
+            Theme::username(&format!("@{}", reminder.username)),
+            due
+        );
+        if !reminder.note.is_empty() {
+            println!("     {} {}", Theme::muted("└"), reminder.note);
+        }
+    }
+    println!("{}", Theme::separator(60));
+
+    Ok(())
+}
+
+/// Cancel a reminder by ID
+pub fn cancel_reminder(id: u32) -> Result<()> {
+    let mut reminders = Reminders::load()?;
+    let before = reminders.items.len();
+    reminders.items.retain(|r| r.id != id);
+
+    if reminders.items.len() == before {
+        anyhow::bail!("No reminder #{}", id);
+    }
+
+    reminders.save()?;
+    println!(
+        "{} {}",
+        Theme::check(),
+        Theme::success(&format!("Reminder #{} cancelled", id))
+    );
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! `ig rename`: set a group conversation's title
+//!
+//! Instagram only lets groups have a title; a one-to-one conversation is
+//! always shown under the other person's name, so it's refused before
+//! anything is sent.
+
+use anyhow::Result;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::spinner::create_spinner;
+
+/// Rename a group thread to `title`
+pub async fn rename_thread(client: &ApiClient, thread_id: &str, title: &str) -> Result<()> {
+    let title = title.trim();
+    if title.is_empty() {
+        anyhow::bail!("The title can't be empty");
+    }
+
+    let spinner = create_spinner("Renaming...");
+    let result = rename(client, thread_id, title).await;
+    spinner.finish_and_clear();
+    let old = result?;
+
+    let renamed = match old {
+        Some(old) => format!("Renamed \"{}\" to \"{}\"", old, title),
+        None => format!("Named the group \"{}\"", title),
+    };
+    println!("{} {}", Theme::check(), Theme::success(&renamed));
+    Ok(())
+}
+
+/// Check that the thread is a group and rename it; the old title, if it had one
+async fn rename(client: &ApiClient, thread_id: &str, title: &str) -> Result<Option<String>> {
+    let response = client.get_thread(thread_id, 1).await?;
+    let Some(thread) = response.thread.filter(|_| response.success) else {
+        anyhow::bail!(response.error.unwrap_or(format!("Conversation {} not found", thread_id)));
+    };
+    if thread.users.len() < 2 {
+        let with = thread.users.first().map(|u| format!(" with @{}", u.username)).unwrap_or_default();
+        anyhow::bail!("Only group conversations can be renamed; this is a conversation{}", with);
+    }
+
+    let response = client.rename_thread(thread_id, title).await?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to rename the conversation".to_string()));
+    }
+    Ok(thread.thread_title)
+}
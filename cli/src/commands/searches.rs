@@ -0,0 +1,278 @@
+//! Saved message searches (`ig search-save`, `ig search-run`)
+//!
+//! Definitions live in the config file under `[searches.<name>]`; running
+//! one scans the recent messages of the latest conversations. The IDs of
+//! matches already seen are kept in the data directory so new ones stand
+//! out (and can raise a desktop notification).
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::digest::is_after;
+use crate::commands::message::SEARCH_LIMIT;
+use crate::config::{Config, SavedSearch};
+use crate::duration::parse_duration;
+use crate::exclusions;
+use crate::notify::desktop_notify;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+use crate::store;
+use crate::users;
+use crate::util::{format_time_ago, parse_timestamp, truncate};
+
+/// Data file holding the matches each search has already shown
+const SEEN_FILE: &str = "searches";
+
+/// Conversations from the top of the inbox that are searched
+const INBOX_SCAN_LIMIT: u32 = 50;
+
+/// Match IDs already reported, by search name
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Seen {
+    searches: HashMap<String, HashSet<String>>,
+}
+
+/// A message a search found
+#[derive(Debug, Serialize)]
+struct SearchMatch {
+    thread_id: String,
+    message_id: String,
+    sender: String,
+    text: String,
+    timestamp: Option<String>,
+    /// Not reported by an earlier run
+    new: bool,
+}
+
+/// Save a search definition to the config file (`ig search-save`)
+pub fn save_search(name: &str, search: SavedSearch) -> Result<()> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        anyhow::bail!("Search names may only use letters, digits, '-' and '_'");
+    }
+    if search.query.is_none() && search.from.is_none() {
+        anyhow::bail!("Give --query, --from or both");
+    }
+    if let Some(since) = &search.since {
+        parse_duration(since)?;
+    }
+    let search = SavedSearch {
+        from: search.from.map(|f| f.trim_start_matches('@').to_string()),
+        ..search
+    };
+
+    let replaced = Config::load()?.searches.contains_key(name);
+    search.save(name)?;
+    println!(
+        "{} {} {}",
+        Theme::check(),
+        Theme::success(if replaced { "Updated saved search" } else { "Saved search" }),
+        Theme::accent(name)
+    );
+    println!("{}", Theme::muted(&format!("Run it with `ig search-run {}`.", name)));
+    Ok(())
+}
+
+/// List saved searches (`ig search-run` without a name)
+pub fn list_searches(output: OutputFormat) -> Result<()> {
+    let searches = Config::load()?.searches;
+
+    if output == OutputFormat::Json {
+        let rows: Vec<_> = searches
+            .iter()
+            .map(|(name, s)| {
+                serde_json::json!({
+                    "name": name,
+                    "query": s.query,
+                    "from": s.from,
+                    "since": s.since,
+                    "notify": s.notify,
+                })
+            })
+            .collect();
+        return print_json(&rows);
+    }
+
+    if searches.is_empty() {
+        println!("{}", Theme::muted("No saved searches. Create one with `ig search-save`."));
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Name", "Search", "Notify"]);
+        for (name, search) in &searches {
+            table.row([
+                name.clone(),
+                describe(search),
+                if search.notify { "yes" } else { "" }.to_string(),
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Saved searches"));
+    println!("{}", Theme::separator(60));
+    for (name, search) in &searches {
+        let bell = if search.notify { " 🔔" } else { "" };
+        println!("  {}{} {}", Theme::accent(name), bell, Theme::muted(&describe(search)));
+    }
+    println!("{}", Theme::separator(60));
+    Ok(())
+}
+
+/// Run a saved search and show what it matches (`ig search-run`)
+pub async fn run_search(client: &ApiClient, name: &str, output: OutputFormat) -> Result<()> {
+    let Some(search) = Config::load()?.searches.remove(name) else {
+        anyhow::bail!("No saved search named '{}' (see `ig search-run` for the list)", name);
+    };
+    let cutoff = match &search.since {
+        Some(since) => Some(Local::now() - parse_duration(since)?),
+        None => None,
+    };
+
+    let spinner = create_spinner("Searching conversations...");
+    let matches = find_matches(client, &search, cutoff).await;
+    spinner.finish_and_clear();
+    let mut matches = matches?;
+
+    let mut seen: Seen = store::load(SEEN_FILE)?;
+    let known = seen.searches.entry(name.to_string()).or_default();
+    for m in &mut matches {
+        m.new = known.insert(m.message_id.clone());
+    }
+    store::save(SEEN_FILE, &seen)?;
+
+    let new: Vec<&SearchMatch> = matches.iter().filter(|m| m.new).collect();
+    if search.notify && !new.is_empty() {
+        let title = format!("ig: {} new match(es) for {}", new.len(), name);
+        desktop_notify(&title, &format!("{}: {}", new[0].sender, truncate(&new[0].text, 80)));
+    }
+
+    if output == OutputFormat::Json {
+        return print_json(&matches);
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Thread", "From", "When", "Message", "New"]);
+        for m in &matches {
+            table.row([
+                m.thread_id.clone(),
+                m.sender.clone(),
+                m.timestamp.as_deref().map(format_time_ago).unwrap_or_default(),
+                truncate(&m.text, 60),
+                if m.new { "new" } else { "" }.to_string(),
+            ]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{} {}", Theme::header(&format!("Saved search {}:", name)), Theme::muted(&describe(&search)));
+    println!("{}", Theme::separator(60));
+    if matches.is_empty() {
+        println!("{}", Theme::muted("No matches."));
+    }
+    for m in &matches {
+        let time = m.timestamp.as_deref().map(format_time_ago).unwrap_or_default();
+        let badge = if m.new { Theme::success(" new").to_string() } else { String::new() };
+        println!("{} {}{}", Theme::pink(&m.sender), Theme::timestamp(&time), badge);
+        println!("  {}", m.text);
+        println!("  {}", Theme::muted(&format!("└ ig msg {} {}", m.thread_id, m.message_id)));
+        println!();
+    }
+    println!("{}", Theme::separator(60));
+    println!(
+        "{}",
+        Theme::muted(&format!("{} match(es), {} new", matches.len(), new.len()))
+    );
+    Ok(())
+}
+
+/// Scan the latest conversations for messages matching `search`, newest first
+async fn find_matches(
+    client: &ApiClient,
+    search: &SavedSearch,
+    cutoff: Option<DateTime<Local>>,
+) -> Result<Vec<SearchMatch>> {
+    let response = client.get_inbox(INBOX_SCAN_LIMIT).await?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to fetch inbox".to_string()));
+    }
+    let query = search.query.as_ref().map(|q| q.to_lowercase());
+    let from = search.from.as_deref();
+    let excluded = exclusions::excluded_ids();
+
+    let mut matches = Vec::new();
+    for thread in response.threads.unwrap_or_default() {
+        if excluded.contains(&thread.id) {
+            continue;
+        }
+        if from.is_some_and(|f| !thread.users.iter().any(|u| u.username.eq_ignore_ascii_case(f))) {
+            continue;
+        }
+        if cutoff.is_some_and(|c| !is_after(thread.last_message_timestamp.as_deref(), c)) {
+            continue;
+        }
+
+        let Ok(resp) = client.get_thread(&thread.id, SEARCH_LIMIT).await else {
+            continue;
+        };
+        let Some(full) = resp.thread else {
+            continue;
+        };
+        users::remember(&full.users);
+
+        for message in full.messages.unwrap_or_default() {
+            let Some(text) = message.text.filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            if cutoff.is_some_and(|c| !is_after(message.timestamp.as_deref(), c)) {
+                continue;
+            }
+            if query.as_ref().is_some_and(|q| !text.to_lowercase().contains(q)) {
+                continue;
+            }
+            let sender = if message.is_sent_by_viewer == Some(true) {
+                None
+            } else {
+                message.user_id.as_ref().and_then(|uid| users::username(&full.users, uid))
+            };
+            if from.is_some_and(|f| !sender.as_deref().is_some_and(|s| s.eq_ignore_ascii_case(f))) {
+                continue;
+            }
+
+            matches.push(SearchMatch {
+                thread_id: thread.id.clone(),
+                message_id: message.id,
+                sender: sender.map(|s| format!("@{}", s)).unwrap_or("You".to_string()),
+                text,
+                timestamp: message.timestamp,
+                new: false,
+            });
+        }
+    }
+
+    matches.sort_by_key(|m| std::cmp::Reverse(m.timestamp.as_deref().and_then(parse_timestamp)));
+    Ok(matches)
+}
+
+/// One-line summary of what a search looks for
+fn describe(search: &SavedSearch) -> String {
+    let mut parts = Vec::new();
+    if let Some(query) = &search.query {
+        parts.push(format!("\"{}\"", query));
+    }
+    if let Some(from) = &search.from {
+        parts.push(format!("from @{}", from));
+    }
+    if let Some(since) = &search.since {
+        parts.push(format!("in the last {}", since));
+    }
+    parts.join(" ")
+}
@@ -1,30 +1,96 @@
 //! Send message commands
 
-use anyhow::Result;
-use dialoguer::Input;
+use anyhow::{Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use dialoguer::{Confirm, Editor};
 use rustyline::error::ReadlineError;
+use rustyline::{ExternalPrinter, KeyEvent};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
 
+use crate::catalog::Catalog;
 use crate::client::ApiClient;
+use crate::clipboard::{self, Paste};
 use crate::colors::Theme;
-use crate::completer::create_editor;
+use crate::commands::attach::{
+    dropped_files, format_size, is_photo, send_file, send_path, send_staged, staged_count, Recipient,
+};
+use crate::commands::chatsearch::search_chat;
+use crate::commands::followup::review_followups;
+use crate::commands::goto::{anchor, day_marker, fetch_around, message_day, parse_date};
+use crate::commands::{checks_enabled, confirm_first_contact, confirm_send, send_delay, sign, undo_window};
+use crate::commands::inbox::find_thread_by_username;
+use crate::commands::paylink::chat_paylink;
+use crate::commands::react::{react_in_chat, Visible};
+use crate::commands::summarize::transcript;
+use crate::completer::{bind_flag_key, bind_flag_key_on_empty, create_editor, prompt_line};
+use crate::config::{Config, Permission};
+use crate::emoji;
+use crate::interactions::record_message;
+use crate::language;
+use crate::markdown;
+use crate::models::{Message, Thread, ThreadResponse, User};
+use crate::nicknames;
+use crate::profile;
+use crate::render::FRAME;
 use crate::spinner::create_spinner;
+use crate::summarizer::{self, Summarizer};
+use crate::translate::{default_target, Provider, Translator};
+use crate::sync::{SyncEvent, SyncHandle, SyncOptions};
+use crate::tasks::Supervisor;
+use crate::transcript::Transcript;
+use crate::users;
+use crate::util::format_time_ago;
+
+/// Seconds between checks for incoming messages during a chat
+const CHAT_POLL_INTERVAL: u64 = 3;
+
+/// Recent messages shown when a chat opens with a prefetched thread
+const CHAT_HISTORY: usize = 5;
+
+/// Messages `:goto` shows around a date
+const CHAT_AROUND: u32 = 10;
+
+/// Recent messages the reply drafter sees
+const DRAFT_CONTEXT: u32 = 20;
 
 /// Send a message to a user (interactive or with provided message)
-pub async fn send_to_user(client: &ApiClient, username: &str, message: Option<&str>) -> Result<()> {
-    let text = match message {
-        Some(m) => m.to_string(),
-        None => {
-            // Interactive mode
-            Input::new()
-                .with_prompt(format!("Message to @{}", username))
-                .interact_text()?
-        }
+///
+/// Unless `yes` is set, asks first when there is no conversation with the
+/// user yet, so a typo in the username doesn't DM a stranger. `signature`
+/// false leaves the configured signature off.
+pub async fn send_to_user(
+    client: &ApiClient,
+    username: &str,
+    message: Option<&str>,
+    yes: bool,
+    markdown: bool,
+    editor: bool,
+    signature: bool,
+) -> Result<()> {
+    let username = username.trim_start_matches('@');
+    if !yes && !confirm_first_contact(client, username).await? {
+        return Ok(());
+    }
+
+    let prompt = format!("Message to @{}: ", username);
+    let Some(text) = compose(&prompt, message, markdown, editor)? else {
+        return Ok(());
     };
 
     if text.trim().is_empty() {
         println!("{}", Theme::warning("Message cannot be empty."));
         return Ok(());
     }
+    if !confirm_send(&text, &[username.to_string()])? {
+        return Ok(());
+    }
+    let text = if signature { sign(&text, false)? } else { text };
 
     let spinner = create_spinner(&format!("Sending to @{}...", username));
 
@@ -34,11 +100,13 @@ pub async fn send_to_user(client: &ApiClient, username: &str, message: Option<&s
     match result {
         Ok(response) => {
             if response.success {
+                record_message(username);
                 println!(
                     "{} {}",
                     Theme::check(),
                     Theme::success(&format!("Message sent to @{}", username))
                 );
+                send_staged(client, Recipient::User(username)).await?;
             } else {
                 println!(
                     "{} {}",
@@ -55,26 +123,74 @@ pub async fn send_to_user(client: &ApiClient, username: &str, message: Option<&s
     }
 }
 
+/// Send a photo to a user (`ig send @user --photo pic.jpg`)
+///
+/// Asks first when there is no conversation with the user yet, like
+/// [`send_to_user`], unless `yes` is set.
+pub async fn send_photo_to_user(client: &ApiClient, username: &str, photo: &Path, yes: bool) -> Result<()> {
+    let username = username.trim_start_matches('@');
+    if !is_photo(photo) {
+        anyhow::bail!("--photo takes a jpg, png or webp image (stage videos with ig attach add)");
+    }
+    if !photo.is_file() {
+        anyhow::bail!("Cannot read {}: no such file", photo.display());
+    }
+    if !yes && !confirm_first_contact(client, username).await? {
+        return Ok(());
+    }
+
+    let data = std::fs::read(photo).with_context(|| format!("Cannot read {}", photo.display()))?;
+    let name = photo
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| photo.display().to_string());
+    let spinner = create_spinner(&format!("Sending {}...", name));
+    let result = client.upload_photo_to_user(username, &name, data).await;
+    spinner.finish_and_clear();
+    let response = result.with_context(|| format!("Failed to send {} to @{}", name, username))?;
+    if !response.success {
+        anyhow::bail!(
+            "Failed to send {} to @{}: {}",
+            name,
+            username,
+            response.error.unwrap_or("unknown error".to_string())
+        );
+    }
+    println!("{} {}", Theme::check(), Theme::muted(&format!("Sent {}", name)));
+    record_message(username);
+    Ok(())
+}
+
 /// Send a message to an existing thread (interactive or with provided message)
 pub async fn send_to_thread(
     client: &ApiClient,
     thread_id: &str,
     message: Option<&str>,
+    markdown: bool,
+    editor: bool,
+    signature: bool,
 ) -> Result<()> {
-    let text = match message {
-        Some(m) => m.to_string(),
-        None => {
-            // Interactive mode
-            Input::new()
-                .with_prompt("Message")
-                .interact_text()?
-        }
+    let Some(text) = compose("Message: ", message, markdown, editor)? else {
+        return Ok(());
     };
 
     if text.trim().is_empty() {
         println!("{}", Theme::warning("Message cannot be empty."));
         return Ok(());
     }
+    // Participants are only needed to check an opening @mention
+    let participants = if checks_enabled() && text.starts_with('@') {
+        match client.get_thread(thread_id, 1).await {
+            Ok(ThreadResponse { thread: Some(thread), .. }) => thread_usernames(&thread),
+            _ => Vec::new(),
+        }
+    } else {
+        Vec::new()
+    };
+    if !confirm_send(&text, &participants)? {
+        return Ok(());
+    }
+    let text = if signature { sign(&text, false)? } else { text };
 
     let spinner = create_spinner("Sending message...");
 
@@ -85,6 +201,7 @@ pub async fn send_to_thread(
         Ok(response) => {
             if response.success {
                 println!("{} {}", Theme::check(), Theme::success("Message sent!"));
+                send_staged(client, Recipient::Thread(thread_id)).await?;
             } else {
                 println!(
                     "{} {}",
@@ -101,26 +218,242 @@ pub async fn send_to_thread(
     }
 }
 
+/// The message to send: `message` if given, otherwise written in $EDITOR
+/// or typed at `prompt`
+///
+/// Markdown (`--md`, or anything written in the editor) is converted to
+/// plain text and shown for a last look first. `None` if the user backs
+/// out.
+fn compose(prompt: &str, message: Option<&str>, markdown: bool, editor: bool) -> Result<Option<String>> {
+    let text = match message {
+        Some(m) => m.to_string(),
+        None if editor => match Editor::new().extension(".md").edit("")? {
+            Some(text) => text,
+            None => {
+                println!("{}", Theme::muted("Editor closed without saving; nothing sent."));
+                return Ok(None);
+            }
+        },
+        None => {
+            // Interactive mode
+            prompt_line(prompt)?
+        }
+    };
+    if !(markdown || editor) || text.trim().is_empty() {
+        return Ok(Some(text));
+    }
+
+    let rendered = markdown::render(&text, Config::load()?.send.markdown);
+    if rendered == text.trim() || !io::stdin().is_terminal() {
+        return Ok(Some(rendered));
+    }
+    println!();
+    println!("{}", Theme::header("Preview"));
+    println!("{}", Theme::separator(60));
+    println!("{}", rendered);
+    println!("{}", Theme::separator(60));
+    let send = Confirm::new().with_prompt("Send this?").default(true).interact()?;
+    if !send {
+        println!("{}", Theme::muted("Not sent."));
+        return Ok(None);
+    }
+    Ok(Some(rendered))
+}
+
 /// Interactive chat with a user by username (with tab completion for @mentions)
-pub async fn chat_with_user(client: &ApiClient, username: &str, usernames: Vec<String>) -> Result<()> {
-    println!("{} {}", Theme::header("Chat with"), Theme::username(&format!("@{}", username)));
+///
+/// `recent` is the conversation with its latest messages when the caller
+/// already has it (e.g. prefetched by the inbox): those messages are shown
+/// right away and the inbox lookup for the thread is skipped.
+pub async fn chat_with_user(
+    client: &ApiClient,
+    username: &str,
+    usernames: Vec<String>,
+    recent: Option<&Thread>,
+) -> Result<()> {
+    profile::require(Permission::Send)?;
+    println!("{} {}", Theme::header("Chat with"), Theme::username(&nicknames::display(username)));
     println!(
         "{}",
-        Theme::muted("Type your messages. Press Tab to autocomplete @usernames. Empty line to exit.")
+        Theme::muted("Type your messages. Type @ to mention someone (Tab or arrows to pick). Empty line to exit.")
     );
+    let drafting = summarizer::configured();
+    let mut keys = Vec::new();
+    if drafting {
+        keys.push("Ctrl-G drafts a reply for you to edit.");
+    }
+    keys.push("Ctrl-T translates incoming messages.");
+    keys.push("Ctrl-V pastes an image from the clipboard.");
+    keys.push("/ on an empty line searches the conversation, :goto 2024-12-25 jumps to a date.");
+    keys.push(":react 2 😂 reacts to the second newest message (:react alone puts ❤️ on the last).");
+    println!("{}", Theme::muted(&keys.join(" ")));
+    let staged = staged_count();
+    if staged > 0 {
+        println!(
+            "{}",
+            Theme::muted(&format!("📎 {} staged attachment(s) go with your next message.", staged))
+        );
+    }
     println!();
 
-    let mut editor = create_editor(usernames);
+    review_followups([username])?;
+
+    // What's on screen, for :react
+    let visible = Visible::default();
+    let (thread_id, participants) = match recent {
+        Some(thread) => {
+            print_recent_messages(thread, &visible);
+            (Some(thread.id.clone()), thread_usernames(thread))
+        }
+        None => match find_thread_by_username(client, username).await.ok().flatten() {
+            Some(thread) => (Some(thread.id.clone()), thread_usernames(&thread)),
+            None => (None, Vec::new()),
+        },
+    };
+
+    // The conversation's participants come first in @-completion
+    let mut participants = participants;
+    if !participants.iter().any(|p| p == username) {
+        participants.push(username.to_string());
+    }
+    let mut candidates = participants.clone();
+    for name in usernames {
+        if !candidates.contains(&name) {
+            candidates.push(name);
+        }
+    }
+    let mut editor = create_editor(candidates);
     let prompt = format!("{} ", Theme::prompt(">"));
+    let draft_requested = drafting.then(|| bind_flag_key(&mut editor, KeyEvent::ctrl('G')));
+    let translate_toggled = bind_flag_key(&mut editor, KeyEvent::ctrl('T'));
+    let paste_requested = bind_flag_key(&mut editor, KeyEvent::ctrl('V'));
+    let search_requested = bind_flag_key_on_empty(&mut editor, KeyEvent::from('/'));
+    // Language incoming messages are translated into, while turned on
+    let translate_to: Arc<Mutex<Option<String>>> = Arc::default();
+
+    // Show incoming messages above the prompt while typing
+    let mut tasks = Supervisor::new();
+    if let (Some(thread_id), Ok(printer)) = (thread_id.clone(), editor.create_external_printer()) {
+        print_incoming(
+            &mut tasks,
+            client,
+            &thread_id,
+            printer,
+            Arc::clone(&translate_to),
+            visible.clone(),
+        );
+    }
+
+    let delay = send_delay();
+    // An undone message goes back into the input for editing
+    let mut draft = String::new();
 
     loop {
-        match editor.readline(&prompt) {
+        let line = editor.readline_with_initial(&prompt, (&draft, ""));
+        draft.clear();
+        match line {
+            Ok(text) if translate_toggled.swap(false, Ordering::Relaxed) => {
+                let mut target = translate_to.lock().unwrap_or_else(|e| e.into_inner());
+                *target = match target.take() {
+                    Some(_) => {
+                        println!("{}", Theme::muted("Translation off."));
+                        None
+                    }
+                    None => {
+                        let language = default_target();
+                        println!(
+                            "{}",
+                            Theme::muted(&format!("Translating incoming messages into {} (Ctrl-T to stop).", language))
+                        );
+                        Some(language)
+                    }
+                };
+                draft = text;
+            }
+            Ok(text) if paste_requested.swap(false, Ordering::Relaxed) => {
+                draft = text;
+                match clipboard::paste() {
+                    Ok(Some(Paste::Image(png))) => {
+                        if confirm_image(png.len())? {
+                            if send_file(client, Recipient::User(username), "clipboard.png", &png).await {
+                                record_message(username);
+                            }
+                        } else {
+                            println!("{}", Theme::muted("Image not sent."));
+                        }
+                    }
+                    // The input is a single line
+                    Ok(Some(Paste::Text(pasted))) => draft.push_str(&pasted.trim_end().replace(['\r', '\n'], " ")),
+                    Ok(None) => println!("{}", Theme::muted("Nothing on the clipboard to paste.")),
+                    Err(e) => println!("{} {}", Theme::cross(), Theme::error(&format!("{:#}", e))),
+                }
+            }
+            Ok(_) if search_requested.swap(false, Ordering::Relaxed) => match thread_id.as_deref() {
+                Some(thread_id) => {
+                    if let Err(e) = search_chat(client, thread_id).await {
+                        println!("{} {}", Theme::cross(), Theme::error(&format!("{:#}", e)));
+                    }
+                }
+                None => println!("{}", Theme::muted("No messages yet to search.")),
+            },
+            Ok(text) if draft_requested.as_ref().is_some_and(|f| f.swap(false, Ordering::Relaxed)) => {
+                draft = match thread_id.as_deref() {
+                    Some(thread_id) => draft_reply(client, thread_id, &text).await,
+                    None => {
+                        println!("{}", Theme::muted("No messages yet to draft a reply from."));
+                        text
+                    }
+                };
+            }
             Ok(text) => {
                 if text.trim().is_empty() {
                     println!("{}", Theme::muted("Exiting chat mode."));
                     break;
                 }
 
+                if let Some(arg) = text.trim().strip_prefix(":goto ") {
+                    goto_date(client, thread_id.as_deref(), &visible, arg).await;
+                    continue;
+                }
+                if let Some(arg) = text.trim().strip_prefix(":react").filter(|a| a.is_empty() || a.starts_with(' ')) {
+                    react_in_chat(client, thread_id.as_deref(), &visible, arg).await;
+                    continue;
+                }
+                if let Some(arg) = text.trim().strip_prefix(":paylink ") {
+                    // Into the input, to add to or send as it is
+                    match chat_paylink(arg) {
+                        Ok(link) => draft = link,
+                        Err(e) => println!("{} {}", Theme::cross(), Theme::error(&format!("{:#}", e))),
+                    }
+                    continue;
+                }
+
+                // A file dragged into the terminal arrives as its path
+                if let Some(files) = dropped_files(&text) {
+                    if confirm_dropped(&files)? {
+                        for file in &files {
+                            if send_path(client, Recipient::User(username), file).await {
+                                record_message(username);
+                            }
+                        }
+                        continue;
+                    }
+                }
+
+                // Loaded per message so `ig catalog add` in another terminal applies at once
+                let text = Catalog::load()?.expand(&text);
+                if !confirm_send(&text, &participants)? {
+                    continue;
+                }
+                if let Some(delay) = delay {
+                    if !undo_window(delay)? {
+                        println!("{}", Theme::muted("Undone — edit the message or clear the line."));
+                        draft = text;
+                        continue;
+                    }
+                }
+
+                let text = sign(&text, true)?;
                 let spinner = create_spinner("Sending...");
                 let result = client.send_to_user(username, &text).await;
                 spinner.finish_and_clear();
@@ -128,7 +461,14 @@ pub async fn chat_with_user(client: &ApiClient, username: &str, usernames: Vec<S
                 match result {
                     Ok(response) => {
                         if response.success {
+                            record_message(username);
+                            if let Some(message) = &response.message {
+                                visible.push(message, "You");
+                            }
                             println!("{} {}", Theme::check(), Theme::muted("Sent"));
+                            if let Err(e) = send_staged(client, Recipient::User(username)).await {
+                                println!("{} {}", Theme::cross(), Theme::error(&format!("{}", e)));
+                            }
                         } else {
                             println!(
                                 "{} {}",
@@ -157,5 +497,303 @@ pub async fn chat_with_user(client: &ApiClient, username: &str, usernames: Vec<S
         }
     }
 
+    // Nothing may print above the prompt once the editor has let go of
+    // the terminal
+    tasks.shutdown().await;
+
     Ok(())
 }
+
+/// Ask whether dropped files should go as attachments rather than as text
+fn confirm_dropped(files: &[PathBuf]) -> Result<bool> {
+    let names: Vec<String> = files
+        .iter()
+        .map(|f| {
+            let name = f.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let size = f.metadata().map(|m| format_size(m.len())).unwrap_or_default();
+            format!("{} ({})", name, size)
+        })
+        .collect();
+    Ok(Confirm::new()
+        .with_prompt(format!(
+            "Send {} as {}? (no sends the path as text)",
+            names.join(", "),
+            if files.len() == 1 { "an attachment" } else { "attachments" }
+        ))
+        .default(true)
+        .interact()?)
+}
+
+/// Show the size of a pasted image and wait for Enter (send) or Esc (don't)
+fn confirm_image(bytes: usize) -> Result<bool> {
+    let mut stdout = io::stdout();
+    write!(
+        stdout,
+        "{}",
+        Theme::muted(&format!("[image {} — Enter to send, Esc to cancel]", format_size(bytes as u64)))
+    )?;
+    stdout.flush()?;
+
+    terminal::enable_raw_mode()?;
+    let answer = loop {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(e) => break Err(e.into()),
+        };
+        match key.code {
+            KeyCode::Enter => break Ok(true),
+            KeyCode::Esc => break Ok(false),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break Ok(false),
+            _ => {}
+        }
+    };
+    terminal::disable_raw_mode()?;
+    write!(stdout, "\r\x1b[K")?;
+    stdout.flush()?;
+    answer
+}
+
+/// Ask the summarizer for a reply to the latest messages, building on what
+/// was typed so far. On failure the typed text comes back unchanged.
+async fn draft_reply(client: &ApiClient, thread_id: &str, typed: &str) -> String {
+    let spinner = create_spinner("Drafting a reply...");
+    let result = async {
+        let summarizer = summarizer::from_config()?;
+        let response = client.get_thread(thread_id, DRAFT_CONTEXT).await?;
+        let thread = response
+            .thread
+            .ok_or_else(|| anyhow::anyhow!(response.error.unwrap_or("Thread not found".to_string())))?;
+        summarizer.draft_reply(&transcript(&thread), typed).await
+    }
+    .await;
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(reply) if !reply.is_empty() => reply,
+        Ok(_) => typed.to_string(),
+        Err(e) => {
+            println!("{} {}", Theme::cross(), Theme::error(&format!("{:#}", e)));
+            typed.to_string()
+        }
+    }
+}
+
+/// Muted translation line for an incoming message (None when it is already
+/// in `target`). Failures are shown in place of the translation.
+async fn translate_line(translator: &mut Option<Provider>, text: String, target: &str) -> Option<String> {
+    let result = async {
+        let provider = match translator {
+            Some(provider) => provider,
+            None => translator.insert(Provider::from_config()?),
+        };
+        provider.translate(&[text], target).await
+    }
+    .await;
+
+    match result {
+        Ok(translations) => translations
+            .into_iter()
+            .next()
+            .filter(|t| !t.is_same_language(target))
+            .map(|t| format!("  {}", Theme::muted(&format!("↳ {}", t.text)))),
+        Err(e) => Some(format!("  {}", Theme::warning(&format!("Translation failed: {:#}", e)))),
+    }
+}
+
+/// Usernames of a thread's participants
+fn thread_usernames(thread: &Thread) -> Vec<String> {
+    thread.users.iter().map(|u| u.username.clone()).collect()
+}
+
+/// Print the last few messages of a thread, oldest first
+fn print_recent_messages(thread: &Thread, visible: &Visible) {
+    let messages = thread.messages.as_deref().unwrap_or_default();
+    if messages.is_empty() {
+        return;
+    }
+
+    for msg in messages.iter().take(CHAT_HISTORY).rev() {
+        print_chat_message(&thread.users, msg, visible);
+    }
+    println!();
+}
+
+/// Print one message in the chat's compact one-line form
+fn print_chat_message(thread_users: &[User], msg: &Message, visible: &Visible) {
+    let username = msg
+        .user_id
+        .as_ref()
+        .and_then(|uid| users::username(thread_users, uid));
+    visible.push(msg, username.as_deref().unwrap_or("You"));
+    // Named the way live messages are
+    let name = username.as_deref().map(nicknames::display).unwrap_or("You".to_string());
+    let time = msg.timestamp.as_deref().map(format_time_ago).unwrap_or_default();
+    let label = msg.media_label();
+    let text = msg.text.as_deref().or(label.as_deref()).unwrap_or("[media]");
+    let tag = if msg.is_sent_by_viewer == Some(true) {
+        String::new()
+    } else {
+        language::tag(text)
+    };
+    match emoji::big(text) {
+        Some(big) => println!("{} {}\n\n{}\n", Theme::pink(&name), Theme::timestamp(&time), big),
+        None => println!(
+            "{} {} {}{}",
+            Theme::pink(&name),
+            Theme::timestamp(&time),
+            tag,
+            text
+        ),
+    }
+}
+
+/// `:goto <date>`: print the messages around the start of that day above
+/// the prompt
+async fn goto_date(client: &ApiClient, thread_id: Option<&str>, visible: &Visible, arg: &str) {
+    let Some(thread_id) = thread_id else {
+        println!("{}", Theme::muted("No messages yet to go through."));
+        return;
+    };
+    let result = async {
+        let date = parse_date(arg)?;
+        let response = fetch_around(client, thread_id, date, CHAT_AROUND).await?;
+        if !response.success {
+            anyhow::bail!(response.error.unwrap_or_else(|| "Failed to fetch messages".to_string()));
+        }
+        Ok((date, response.thread.unwrap_or_default()))
+    }
+    .await;
+    let (date, thread) = match result {
+        Ok(found) => found,
+        Err(e) => {
+            println!("{} {}", Theme::cross(), Theme::error(&format!("{:#}", e)));
+            return;
+        }
+    };
+
+    let messages = thread.messages.unwrap_or_default();
+    if messages.is_empty() {
+        println!("{}", Theme::muted("No messages in this conversation."));
+        return;
+    }
+    let at = anchor(&messages, date);
+    let reached = message_day(&messages[at]).is_some_and(|day| day >= date);
+    println!();
+    for (i, msg) in messages.iter().enumerate().rev() {
+        if reached && i == at {
+            println!("{}", day_marker(date, Some(msg)));
+        }
+        print_chat_message(&thread.users, msg, visible);
+    }
+    if !reached {
+        println!("{}", day_marker(date, None));
+    }
+    println!();
+}
+
+/// Follow a thread and print messages from the other side through the
+/// line editor, so they appear above the prompt without clobbering input.
+/// While `translate_to` holds a language, each message's translation is
+/// printed under it. Printed messages go into `visible` for `:react`.
+/// Polling and printing run on `tasks` and stop with it.
+fn print_incoming(
+    tasks: &mut Supervisor,
+    client: &ApiClient,
+    thread_id: &str,
+    mut printer: impl ExternalPrinter + Send + 'static,
+    translate_to: Arc<Mutex<Option<String>>>,
+    visible: Visible,
+) {
+    let sync = SyncHandle::spawn(
+        tasks,
+        client,
+        SyncOptions {
+            interval: Duration::from_secs(CHAT_POLL_INTERVAL),
+            inbox_limit: 0,
+            inbox_messages: false,
+            delta: true,
+            threads: vec![thread_id.to_string()],
+        },
+    );
+    let mut events = sync.subscribe();
+
+    tasks.spawn(async move {
+        // Set up on first use, so chats without translation never need it
+        let mut translator = None;
+        let mut transcript = Transcript::open().unwrap_or_else(|e| {
+            let _ = printer.print(format!("{} {}", Theme::warn_icon(), Theme::warning(&format!("{:#}", e))));
+            None
+        });
+        let mut closed = false;
+        while !closed {
+            // Messages arriving within a frame of the first are printed
+            // together, so a burst redraws the prompt once
+            let mut lines = Vec::new();
+            let mut deadline = None;
+            loop {
+                let event = match deadline {
+                    Some(deadline) => match tokio::time::timeout_at(deadline, events.recv()).await {
+                        Ok(event) => event,
+                        Err(_) => break,
+                    },
+                    None => events.recv().await,
+                };
+                let (message, users) = match event {
+                    Ok(SyncEvent::NewMessage { thread_id, message, users }) => {
+                        if let Some(transcript) = transcript.as_mut() {
+                            if let Err(e) = transcript.record(&thread_id, &message, &users) {
+                                let warning = Theme::warning(&format!("{:#}", e));
+                                let _ = printer.print(format!("{} {}", Theme::warn_icon(), warning));
+                            }
+                        }
+                        (message, users)
+                    }
+                    // Shown on their own, right away
+                    Ok(event) if event.server_notice().is_some() => {
+                        let notice = event.server_notice().unwrap_or_default();
+                        let _ = printer.print(format!("{} {}", Theme::warn_icon(), Theme::warning(notice)));
+                        continue;
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => {
+                        closed = true;
+                        break;
+                    }
+                };
+                if message.is_sent_by_viewer.unwrap_or(false) {
+                    continue;
+                }
+                let sender = message
+                    .user_id
+                    .as_ref()
+                    .and_then(|uid| users::username(&users, uid))
+                    .unwrap_or("unknown".to_string());
+                visible.push(&message, &sender);
+                let label = message.media_label();
+                let text = message.text.as_deref().or(label.as_deref()).unwrap_or("[media]");
+                let mut line = match emoji::big(text) {
+                    Some(big) => format!("{}\n\n{}\n", Theme::pink(&nicknames::display(&sender)), big),
+                    None => format!(
+                        "{} {}{}",
+                        Theme::pink(&nicknames::display(&sender)),
+                        language::tag(text),
+                        text
+                    ),
+                };
+                let target = translate_to.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                if let (Some(target), Some(original)) = (target, message.text.clone()) {
+                    if let Some(translated) = translate_line(&mut translator, original, &target).await {
+                        line.push('\n');
+                        line.push_str(&translated);
+                    }
+                }
+                lines.push(line);
+                deadline.get_or_insert_with(|| tokio::time::Instant::now() + FRAME);
+            }
+            if !lines.is_empty() && printer.print(lines.join("\n")).is_err() {
+                break;
+            }
+        }
+    });
+}
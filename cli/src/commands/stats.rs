@@ -0,0 +1,355 @@
+//! Statistics (`ig stats`)
+//!
+//! `ig stats --api` shows how much the CLI has been talking to Instagram:
+//! the last run, the past hour, day and week, and which commands sent the
+//! most requests. The numbers come from the usage log kept in the data
+//! directory (see [`crate::usage`]).
+//!
+//! `ig stats --response-times @user` measures how long each side of a
+//! conversation takes to answer: from the first message of one side's turn
+//! to the other side's next message.
+//!
+//! With `--output json` or `--output csv` both print their figures in a
+//! fixed layout for spreadsheets and dashboards (see [`STATS_SCHEMA`]).
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use serde::Serialize;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::attach::format_size;
+use crate::commands::inbox::find_thread_by_username;
+use crate::commands::pages::fetch_thread;
+use crate::duration::format_duration;
+use crate::nicknames;
+use crate::output::{print_csv, print_json, OutputFormat, Table};
+use crate::spinner::create_spinner;
+use crate::usage::{Session, Totals, Usage};
+use crate::util::parse_timestamp;
+
+/// Commands listed under "Busiest commands"
+const TOP_COMMANDS: usize = 5;
+
+/// Version of the JSON and CSV layouts of `ig stats` and `ig heatmap`.
+/// Fields may be added; renaming or removing one bumps it.
+pub const STATS_SCHEMA: u32 = 1;
+
+#[derive(Serialize)]
+struct ApiStats<'a> {
+    schema: u32,
+    last: Option<&'a Session>,
+    last_hour: Totals,
+    last_day: Totals,
+    last_week: Totals,
+    by_command: Vec<CommandRequests>,
+}
+
+#[derive(Serialize)]
+struct CommandRequests {
+    command: String,
+    requests: usize,
+}
+
+/// Show recorded API usage (`ig stats --api`)
+pub fn show_api_stats(output: OutputFormat) -> Result<()> {
+    let usage = Usage::load()?;
+    let now = Local::now();
+    let stats = ApiStats {
+        schema: STATS_SCHEMA,
+        last: usage.sessions.last(),
+        last_hour: usage.totals_since(now - Duration::hours(1)),
+        last_day: usage.totals_since(now - Duration::days(1)),
+        last_week: usage.totals_since(now - Duration::days(7)),
+        by_command: usage
+            .requests_by_command(now - Duration::days(1))
+            .into_iter()
+            .map(|(command, requests)| CommandRequests { command, requests })
+            .collect(),
+    };
+
+    match output {
+        OutputFormat::Json => return print_json(&stats),
+        OutputFormat::Csv => return print_api_csv(&stats),
+        _ => {}
+    }
+
+    let Some(last) = stats.last else {
+        println!("{}", Theme::muted("No API usage recorded yet."));
+        return Ok(());
+    };
+
+    println!();
+    println!("{}", Theme::header("API usage"));
+    println!("{}", Theme::separator(50));
+    println!(
+        "{} {} {}",
+        Theme::muted("Last run:"),
+        Theme::accent(&format!("ig {}", last.command)),
+        Theme::muted(&format!(
+            "· {} requests, {} sent, {} received, {} cache hits, {} retries · {}",
+            last.requests,
+            format_size(last.bytes_sent as u64),
+            format_size(last.bytes_received as u64),
+            last.cache_hits,
+            last.retries,
+            last.at.format("%H:%M")
+        ))
+    );
+    println!();
+
+    let mut table = Table::new(&["Period", "Runs", "Requests", "Sent", "Received", "Cache hits", "Retries"]);
+    for (label, totals) in [
+        ("Last hour", stats.last_hour),
+        ("Last 24h", stats.last_day),
+        ("Last 7 days", stats.last_week),
+    ] {
+        table.row([
+            label.to_string(),
+            totals.sessions.to_string(),
+            totals.requests.to_string(),
+            format_size(totals.bytes_sent as u64),
+            format_size(totals.bytes_received as u64),
+            totals.cache_hits.to_string(),
+            totals.retries.to_string(),
+        ]);
+    }
+    table.print();
+
+    if !stats.by_command.is_empty() {
+        println!();
+        println!("{}", Theme::muted("Busiest commands (last 24h):"));
+        for entry in stats.by_command.iter().take(TOP_COMMANDS) {
+            println!(
+                "  {} {}",
+                Theme::accent(&format!("{:<12}", entry.command)),
+                Theme::muted(&format!("{} requests", entry.requests))
+            );
+        }
+    }
+    Ok(())
+}
+
+/// One row per period (the last run, hour, day and week) and one per busy
+/// command of the last day, which only has a request count
+fn print_api_csv(stats: &ApiStats) -> Result<()> {
+    let headers = [
+        "period",
+        "command",
+        "runs",
+        "requests",
+        "bytes_sent",
+        "bytes_received",
+        "cache_hits",
+        "retries",
+    ];
+    let mut rows = Vec::new();
+    if let Some(last) = stats.last {
+        rows.push(vec![
+            "last_run".to_string(),
+            last.command.clone(),
+            "1".to_string(),
+            last.requests.to_string(),
+            last.bytes_sent.to_string(),
+            last.bytes_received.to_string(),
+            last.cache_hits.to_string(),
+            last.retries.to_string(),
+        ]);
+    }
+    for (period, totals) in [
+        ("last_hour", &stats.last_hour),
+        ("last_day", &stats.last_day),
+        ("last_week", &stats.last_week),
+    ] {
+        rows.push(vec![
+            period.to_string(),
+            String::new(),
+            totals.sessions.to_string(),
+            totals.requests.to_string(),
+            totals.bytes_sent.to_string(),
+            totals.bytes_received.to_string(),
+            totals.cache_hits.to_string(),
+            totals.retries.to_string(),
+        ]);
+    }
+    for entry in &stats.by_command {
+        let mut row = vec![String::new(); headers.len()];
+        row[0] = "last_day".to_string();
+        row[1] = entry.command.clone();
+        row[3] = entry.requests.to_string();
+        rows.push(row);
+    }
+    print_csv(&headers, rows)
+}
+
+/// Reply latencies of one side of a conversation
+#[derive(Serialize)]
+struct Latencies {
+    replies: usize,
+    median_secs: Option<i64>,
+    p90_secs: Option<i64>,
+    slowest_secs: Option<i64>,
+}
+
+impl Latencies {
+    fn new(mut secs: Vec<i64>) -> Self {
+        secs.sort_unstable();
+        Self {
+            replies: secs.len(),
+            median_secs: percentile(&secs, 50),
+            p90_secs: percentile(&secs, 90),
+            slowest_secs: secs.last().copied(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ResponseTimes {
+    schema: u32,
+    user: String,
+    messages: usize,
+    /// How long you took to answer them
+    you: Latencies,
+    /// How long they took to answer you
+    them: Latencies,
+}
+
+/// Show how quickly you and `target` (@user) answer each other, over the
+/// newest `limit` messages (the whole history when None)
+pub async fn show_response_times(
+    client: &ApiClient,
+    target: &str,
+    limit: Option<u32>,
+    output: OutputFormat,
+) -> Result<()> {
+    let username = target.trim_start_matches('@');
+    let spinner = create_spinner(&format!("Finding conversation with @{}...", username));
+    let thread = find_thread_by_username(client, username).await;
+    spinner.finish_and_clear();
+    let Some(thread) = thread? else {
+        anyhow::bail!("No conversation with @{} found", username);
+    };
+
+    let response = fetch_thread(client, &thread.id, limit).await?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to fetch thread".to_string()));
+    }
+    let mut messages: Vec<(DateTime<Local>, bool)> = response
+        .thread
+        .and_then(|t| t.messages)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|m| {
+            let time = m.timestamp.as_deref().and_then(parse_timestamp)?;
+            Some((time, m.is_sent_by_viewer.unwrap_or(false)))
+        })
+        .collect();
+    messages.sort_by_key(|(time, _)| *time);
+
+    let (you, them) = reply_latencies(&messages);
+    let times = ResponseTimes {
+        schema: STATS_SCHEMA,
+        user: username.to_string(),
+        messages: messages.len(),
+        you: Latencies::new(you),
+        them: Latencies::new(them),
+    };
+
+    match output {
+        OutputFormat::Json => return print_json(&times),
+        OutputFormat::Csv => {
+            let headers = ["side", "username", "replies", "median_secs", "p90_secs", "slowest_secs"];
+            let seconds = |secs: Option<i64>| secs.map(|s| s.to_string()).unwrap_or_default();
+            let rows = [("you", "", &times.you), ("them", username, &times.them)].map(|(side, user, l)| {
+                vec![
+                    side.to_string(),
+                    user.to_string(),
+                    l.replies.to_string(),
+                    seconds(l.median_secs),
+                    seconds(l.p90_secs),
+                    seconds(l.slowest_secs),
+                ]
+            });
+            return print_csv(&headers, rows);
+        }
+        _ => {}
+    }
+
+    let name = nicknames::display(username);
+    let mut table = Table::new(&["", "Replies", "Median", "90th percentile", "Slowest"]);
+    for (label, latencies) in [("You".to_string(), &times.you), (name.clone(), &times.them)] {
+        table.row([
+            label,
+            latencies.replies.to_string(),
+            latency(latencies.median_secs),
+            latency(latencies.p90_secs),
+            latency(latencies.slowest_secs),
+        ]);
+    }
+    if output == OutputFormat::Table {
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "{} {}",
+        Theme::header(&format!("Response times with {}", name)),
+        Theme::muted(&format!("(last {} messages)", times.messages))
+    );
+    println!("{}", Theme::separator(60));
+    if times.you.replies + times.them.replies == 0 {
+        println!("{}", Theme::muted("No replies to measure yet."));
+    } else {
+        table.print();
+    }
+    println!("{}", Theme::separator(60));
+    Ok(())
+}
+
+/// Seconds each side took to reply, as (yours, theirs), given messages
+/// oldest first as (time, sent by you). A reply is timed from the first
+/// message of the other side's turn, the way the person waiting sees it.
+fn reply_latencies(messages: &[(DateTime<Local>, bool)]) -> (Vec<i64>, Vec<i64>) {
+    let mut you = Vec::new();
+    let mut them = Vec::new();
+    let mut turn: Option<(DateTime<Local>, bool)> = None;
+    for &(time, from_me) in messages {
+        match turn {
+            Some((_, side)) if side == from_me => {}
+            Some((started, _)) => {
+                let secs = (time - started).num_seconds().max(0);
+                if from_me {
+                    you.push(secs);
+                } else {
+                    them.push(secs);
+                }
+                turn = Some((time, from_me));
+            }
+            None => turn = Some((time, from_me)),
+        }
+    }
+    (you, them)
+}
+
+/// The `pct`th percentile of sorted values (nearest rank)
+fn percentile(sorted: &[i64], pct: usize) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+/// A latency rounded for reading ("4m", "2h10m", "1d3h"), or "—"
+fn latency(secs: Option<i64>) -> String {
+    let Some(secs) = secs else {
+        return "—".to_string();
+    };
+    let rounded = match secs {
+        s if s >= 86_400 => s - s % 3_600,
+        s if s >= 3_600 => s - s % 60,
+        s => s,
+    };
+    format_duration(Duration::seconds(rounded))
+}
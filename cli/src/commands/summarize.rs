@@ -0,0 +1,110 @@
+//! Conversation summaries (`ig summarize`)
+
+use anyhow::Result;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::inbox::find_thread_by_username;
+use crate::commands::pages::fetch_thread;
+use crate::models::Thread;
+use crate::nicknames;
+use crate::output::{print_json, OutputFormat};
+use crate::spinner::create_spinner;
+use crate::summarizer::{self, Summarizer};
+use crate::users;
+use crate::util::parse_timestamp;
+
+/// Summarize the last `last` messages with `target` (@user) using the
+/// configured summarizer
+pub async fn summarize_conversation(
+    client: &ApiClient,
+    target: &str,
+    last: u32,
+    output: OutputFormat,
+) -> Result<()> {
+    let summarizer = summarizer::from_config()?;
+    let username = target.trim_start_matches('@');
+
+    let spinner = create_spinner(&format!("Finding conversation with @{}...", username));
+    let thread = find_thread_by_username(client, username).await;
+    spinner.finish_and_clear();
+    let Some(thread) = thread? else {
+        println!(
+            "{} {}",
+            Theme::warn_icon(),
+            Theme::warning(&format!("No conversation found with @{}", username))
+        );
+        return Ok(());
+    };
+
+    let response = fetch_thread(client, &thread.id, Some(last)).await?;
+    if !response.success {
+        anyhow::bail!(response.error.unwrap_or("Failed to fetch thread".to_string()));
+    }
+    let thread = response.thread.unwrap_or(thread);
+    let count = thread.messages.as_ref().map_or(0, Vec::len);
+    if count == 0 {
+        println!("{}", Theme::muted("No messages to summarize."));
+        return Ok(());
+    }
+
+    let spinner = create_spinner(&format!(
+        "Summarizing {} messages with {}...",
+        count,
+        summarizer.name()
+    ));
+    let summary = summarizer.summarize(&transcript(&thread)).await;
+    spinner.finish_and_clear();
+    let summary = summary?;
+
+    if output == OutputFormat::Json {
+        return print_json(&summary);
+    }
+
+    println!();
+    println!(
+        "{} {} {}",
+        Theme::header("Summary of"),
+        Theme::username(&nicknames::display(username)),
+        Theme::muted(&format!("(last {} messages)", count))
+    );
+    println!("{}", Theme::separator(60));
+    println!("{}", summary.summary);
+
+    if !summary.action_items.is_empty() {
+        println!();
+        println!("{}", Theme::header("Action items"));
+        for item in &summary.action_items {
+            println!("  {} {}", Theme::accent("•"), item);
+        }
+    }
+    println!("{}", Theme::separator(60));
+
+    Ok(())
+}
+
+/// A thread's messages as "[time] sender: text" lines, oldest first
+pub(crate) fn transcript(thread: &Thread) -> String {
+    // Messages come newest first
+    thread
+        .messages
+        .iter()
+        .flatten()
+        .rev()
+        .map(|msg| {
+            let sender = msg
+                .user_id
+                .as_ref()
+                .and_then(|uid| users::username(&thread.users, uid))
+                .unwrap_or("You".to_string());
+            let time = msg
+                .timestamp
+                .as_deref()
+                .and_then(parse_timestamp)
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            format!("[{}] {}: {}", time, sender, msg.text.as_deref().unwrap_or("[media]"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,182 @@
+//! Local conversation labels
+//!
+//! Tags are attached to usernames and shown as colored chips in the inbox,
+//! which makes it easy to triage business DMs (`ig inbox --tag work`).
+//! `ig board` also tags group chats by thread ID, so a group's column
+//! doesn't spill over onto its members' own conversations.
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::colors::{hashed_color, Theme};
+use crate::models::Thread;
+use crate::output::{print_json, OutputFormat, Table};
+use crate::store;
+
+/// Data file holding tags
+const TAGS_FILE: &str = "tags";
+
+/// Tags per username (lowercase), and per thread ID for tags on a single
+/// conversation
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Tags {
+    pub by_user: BTreeMap<String, BTreeSet<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub by_thread: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Tags {
+    /// Load tags from disk
+    pub fn load() -> Result<Self> {
+        store::load(TAGS_FILE)
+    }
+
+    /// Save tags to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(TAGS_FILE, self)
+    }
+
+    /// Tags of a single user
+    pub fn for_user(&self, username: &str) -> Option<&BTreeSet<String>> {
+        self.by_user.get(&username.to_lowercase())
+    }
+
+    /// Tags of everyone in a thread, and of the thread itself
+    pub fn for_thread(&self, thread: &Thread) -> BTreeSet<String> {
+        thread
+            .users
+            .iter()
+            .filter_map(|u| self.for_user(&u.username))
+            .chain(self.by_thread.get(&thread.id))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Whether the thread or anyone in it carries the tag
+    pub fn thread_has(&self, thread: &Thread, tag: &str) -> bool {
+        self.by_thread.get(&thread.id).is_some_and(|t| t.contains(tag))
+            || thread
+                .users
+                .iter()
+                .any(|u| self.for_user(&u.username).is_some_and(|t| t.contains(tag)))
+    }
+
+    /// Every tag in use, sorted
+    pub fn all(&self) -> BTreeSet<String> {
+        self.by_user.values().chain(self.by_thread.values()).flatten().cloned().collect()
+    }
+
+    /// Who carries a tag, for listing: "@alice, @bob, 2 group chats"
+    fn holders(&self, tag: &str) -> String {
+        let mut holders: Vec<String> = self
+            .by_user
+            .iter()
+            .filter(|(_, set)| set.contains(tag))
+            .map(|(user, _)| format!("@{}", user))
+            .collect();
+        match self.by_thread.values().filter(|set| set.contains(tag)).count() {
+            0 => {}
+            1 => holders.push("1 group chat".to_string()),
+            n => holders.push(format!("{} group chats", n)),
+        }
+        holders.join(", ")
+    }
+
+    /// Colored chips for a thread's tags (empty string when untagged)
+    pub fn chips(&self, thread: &Thread) -> String {
+        self.for_thread(thread)
+            .iter()
+            .map(|tag| tag_chip(tag))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Render a tag as a colored chip; each tag always gets the same color
+pub fn tag_chip(tag: &str) -> String {
+    let (r, g, b) = hashed_color(tag);
+    format!("[{}]", tag).truecolor(r, g, b).bold().to_string()
+}
+
+/// Add (or with `remove`, drop) a tag on a user (`ig tag @user work`)
+pub fn tag_user(target: &str, tag: &str, remove: bool) -> Result<()> {
+    let username = target.trim_start_matches('@').to_lowercase();
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        println!("{}", Theme::warning("Tag cannot be empty."));
+        return Ok(());
+    }
+
+    let mut tags = Tags::load()?;
+
+    if remove {
+        let removed = tags
+            .by_user
+            .get_mut(&username)
+            .is_some_and(|set| set.remove(&tag));
+        tags.by_user.retain(|_, set| !set.is_empty());
+
+        if !removed {
+            println!(
+                "{}",
+                Theme::muted(&format!("@{} is not tagged {}", username, tag))
+            );
+            return Ok(());
+        }
+        tags.save()?;
+        println!(
+            "{} {} {}",
+            Theme::check(),
+            Theme::success(&format!("Removed {} from", tag_chip(&tag))),
+            Theme::username(&format!("@{}", username))
+        );
+        return Ok(());
+    }
+
+    tags.by_user.entry(username.clone()).or_default().insert(tag.clone());
+    tags.save()?;
+    println!(
+        "{} {} {}",
+        Theme::check(),
+        Theme::success(&format!("Tagged @{}", username)),
+        tag_chip(&tag)
+    );
+    Ok(())
+}
+
+/// List all tags and who carries them
+pub fn list_tags(output: OutputFormat) -> Result<()> {
+    let tags = Tags::load()?;
+
+    if output == OutputFormat::Json {
+        return print_json(&tags.by_user);
+    }
+
+    if tags.by_user.is_empty() && tags.by_thread.is_empty() {
+        println!("{}", Theme::muted("No tags yet. Add one with: ig tag @user work"));
+        return Ok(());
+    }
+
+    if output == OutputFormat::Table {
+        let mut table = Table::new(&["Tag", "Users"]);
+        for tag in tags.all() {
+            let holders = tags.holders(&tag);
+            table.row([tag, holders]);
+        }
+        table.print();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", Theme::header("Tags"));
+    println!("{}", Theme::separator(60));
+    for tag in tags.all() {
+        println!("  {} {}", tag_chip(&tag), Theme::username(&tags.holders(&tag)));
+    }
+    println!("{}", Theme::separator(60));
+
+    Ok(())
+}
@@ -0,0 +1,200 @@
+//! Follow new messages as they arrive
+//!
+//! `ig tail` follows the whole inbox, `ig tail <thread_id|@user>` a single
+//! conversation. With `--output json` every event is printed as one NDJSON
+//! line (see [`StreamEvent`]) for downstream tools.
+
+use anyhow::Result;
+use chrono::Local;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::inbox::find_thread_by_username;
+use crate::duration::Interval;
+use crate::models::{Event, StreamEvent};
+use crate::nicknames;
+use crate::output::{print_ndjson, OutputFormat};
+use crate::sync::{SyncEvent, SyncHandle, SyncOptions};
+use crate::tasks::Supervisor;
+use crate::transcript::Transcript;
+
+/// Number of inbox threads followed when no target is given
+const INBOX_LIMIT: u32 = 20;
+
+/// Follow the inbox or a single thread until Ctrl+C
+pub async fn run_tail(
+    client: &ApiClient,
+    target: Option<&str>,
+    interval: Interval,
+    output: OutputFormat,
+) -> Result<()> {
+    let json = output == OutputFormat::Json;
+
+    let thread_id = match target {
+        Some(target) => match target.strip_prefix('@') {
+            Some(username) => match find_thread_by_username(client, username).await? {
+                Some(thread) => Some(thread.id),
+                None => anyhow::bail!("No conversation found with @{}", username),
+            },
+            None => Some(target.to_string()),
+        },
+        None => None,
+    };
+
+    if !json {
+        let what = match target {
+            Some(target) => format!("Following {}", target),
+            None => "Following inbox".to_string(),
+        };
+        println!("{}", Theme::header(&what));
+        println!("{}", Theme::muted("Press Ctrl+C to stop."));
+        println!();
+    }
+
+    let options = match thread_id {
+        Some(id) => SyncOptions {
+            interval: interval.0,
+            inbox_limit: 0,
+            inbox_messages: false,
+            delta: false,
+            threads: vec![id],
+        },
+        None => SyncOptions {
+            interval: interval.0,
+            inbox_limit: INBOX_LIMIT,
+            inbox_messages: true,
+            delta: false,
+            threads: Vec::new(),
+        },
+    };
+    let mut transcript = Transcript::open()?;
+    let mut tasks = Supervisor::new();
+    tasks.stop_on_ctrl_c();
+    let sync = SyncHandle::spawn(&mut tasks, client, options);
+    let mut events = sync.subscribe();
+
+    loop {
+        let received = tokio::select! {
+            _ = tasks.stopping() => break,
+            received = events.recv() => received,
+        };
+
+        match received {
+            Ok(SyncEvent::PollFailed(error)) => {
+                if json {
+                    eprintln!("{}", error);
+                } else {
+                    println!("{} {}", Theme::warn_icon(), Theme::warning(&error));
+                }
+            }
+            Ok(event) if event.server_notice().is_some() => {
+                let notice = event.server_notice().unwrap_or_default();
+                if json {
+                    eprintln!("{}", notice);
+                } else {
+                    println!("{} {}", Theme::warn_icon(), Theme::warning(notice));
+                }
+            }
+            Ok(event) => {
+                if let (Some(transcript), SyncEvent::NewMessage { thread_id, message, users }) =
+                    (transcript.as_mut(), &event)
+                {
+                    if let Err(e) = transcript.record(thread_id, message, users) {
+                        eprintln!("{} {}", Theme::warn_icon(), Theme::warning(&format!("{:#}", e)));
+                    }
+                }
+                if let Some(event) = stream_event(event) {
+                    emit(event, json)?;
+                }
+            }
+            Err(RecvError::Lagged(missed)) => eprintln!("Missed {} events", missed),
+            Err(RecvError::Closed) => break,
+        }
+    }
+    tasks.shutdown().await;
+
+    Ok(())
+}
+
+/// The NDJSON event for a sync event, if it has one
+fn stream_event(event: SyncEvent) -> Option<Event> {
+    match event {
+        SyncEvent::NewMessage { thread_id, message, users } => {
+            Some(Event::message(&thread_id, &message, &users))
+        }
+        SyncEvent::ReactionsChanged { thread_id, message_id, reactions } => Some(Event::Reaction {
+            thread_id,
+            message_id,
+            reactions,
+        }),
+        SyncEvent::ThreadUpdated { thread, .. } => Some(Event::thread_update(&thread)),
+        SyncEvent::PresenceChanged { username, online } => Some(Event::Presence { username, online }),
+        SyncEvent::PollFailed(_)
+        | SyncEvent::ServerUnavailable(_)
+        | SyncEvent::ServerRestarted
+        | SyncEvent::SessionLost
+        | SyncEvent::SessionRestored => None,
+    }
+}
+
+/// Print an event as NDJSON or as a human-readable line
+fn emit(event: Event, json: bool) -> Result<()> {
+    if json {
+        return print_ndjson(&StreamEvent::new(event));
+    }
+
+    let line = match &event {
+        Event::Message { sender, user_id, from_me, text, .. } => {
+            let sender = match (from_me, sender, user_id) {
+                (true, _, _) => "You".to_string(),
+                (false, Some(sender), _) => nicknames::display(sender),
+                (false, None, user_id) => user_id.clone().unwrap_or("unknown".to_string()),
+            };
+            format!(
+                "{} {}",
+                Theme::pink(&sender),
+                text.as_deref().unwrap_or("[media]")
+            )
+        }
+        Event::Reaction { message_id, reactions, .. } => Theme::muted(&format!(
+            "Reactions on {} changed ({})",
+            message_id,
+            reactions.len()
+        ))
+        .to_string(),
+        Event::ThreadUpdate { participants, unread, .. } => {
+            let who = participants
+                .iter()
+                .map(|p| nicknames::display(p))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let state = if *unread { Theme::unread_marker() } else { String::new() };
+            format!("{} {} {}", Theme::muted("Updated:"), Theme::username(&who), state)
+        }
+        Event::Presence { username, online } => {
+            let state = if *online { "is online" } else { "went offline" };
+            format!("{} {}", Theme::username(&nicknames::display(username)), Theme::muted(state))
+        }
+        Event::Reminder { username, note, .. } => {
+            format!("{} {} {}", Theme::accent("⏰"), Theme::username(&nicknames::display(username)), note)
+        }
+        Event::Alert { sender, keyword, text, .. } => {
+            let from = sender.as_deref().map(nicknames::display).unwrap_or_else(|| "someone".to_string());
+            format!(
+                "{} {} {} {}",
+                Theme::error("!"),
+                Theme::warning(&format!("\"{}\"", keyword)),
+                Theme::username(&from),
+                text
+            )
+        }
+    };
+
+    println!(
+        "{} {}",
+        Theme::timestamp(&Local::now().format("%H:%M:%S").to_string()),
+        line
+    );
+    Ok(())
+}
@@ -0,0 +1,502 @@
+//! `ig tui`: a full-screen inbox and conversation view
+//!
+//! The inbox stays on the left and the selected conversation on the right,
+//! so switching threads never leaves the app. One sync task keeps both
+//! panes current: threads with new activity move to the top of the list and
+//! their messages appear as they arrive. Drawn with ratatui, which takes
+//! care of layout, wrapping and redrawing only what changed.
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::catalog::Catalog;
+use crate::client::ApiClient;
+use crate::colors::{colors_enabled, palette, Theme};
+use crate::commands::{check_message, fetch_inbox, fetch_recent, sign};
+use crate::interactions::record_message;
+use crate::models::{Message, Thread};
+use crate::nicknames;
+use crate::sync::{SyncEvent, SyncHandle, SyncOptions};
+use crate::tasks::Supervisor;
+use crate::users;
+use crate::util::{ellipsize, format_time_ago};
+
+/// Seconds between sync polls
+const TUI_POLL_INTERVAL: u64 = 3;
+
+/// How long to wait for a key before looking for sync events
+const TICK: Duration = Duration::from_millis(100);
+
+/// Lines PageUp/PageDown scroll the conversation by
+const SCROLL_PAGE: u16 = 10;
+
+/// Key help shown in the status line when there's nothing to report
+const HELP: &str = "↑/↓: Select  Enter: Write  Tab: Switch pane  PgUp/PgDn: Scroll  r: Refresh  q: Quit";
+
+/// Which pane keys go to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Inbox,
+    Input,
+}
+
+/// How a status line message is colored
+#[derive(Debug, Clone, Copy)]
+enum Tone {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Everything on screen
+struct App {
+    threads: Vec<Arc<Thread>>,
+    list: ListState,
+    /// Messages per thread, oldest first, once fetched
+    conversations: HashMap<String, Vec<Arc<Message>>>,
+    focus: Focus,
+    input: String,
+    /// Lines scrolled up from the newest message
+    scroll: u16,
+    /// A message the pre-send checks flagged, sent on a second Enter
+    confirming: Option<String>,
+    status: Option<(String, Tone)>,
+}
+
+/// Open the full-screen view on the `limit` most recent conversations
+pub async fn run_tui(client: &ApiClient, limit: u32) -> Result<()> {
+    if !io::stdout().is_terminal() {
+        anyhow::bail!("ig tui needs a terminal");
+    }
+
+    let response = fetch_inbox(client, Some(limit)).await?;
+    if !response.success {
+        println!(
+            "{} {}",
+            Theme::cross(),
+            Theme::error(&response.error.unwrap_or("Failed to fetch inbox".to_string()))
+        );
+        return Ok(());
+    }
+
+    let mut tasks = Supervisor::new();
+    let sync = SyncHandle::spawn(
+        &mut tasks,
+        client,
+        SyncOptions {
+            interval: Duration::from_secs(TUI_POLL_INTERVAL),
+            inbox_limit: limit,
+            inbox_messages: true,
+            delta: false,
+            threads: Vec::new(),
+        },
+    );
+    let mut events = sync.subscribe();
+
+    let mut app = App::new(response.threads.unwrap_or_default());
+    let mut terminal = ratatui::try_init()?;
+    let result = app.run(&mut terminal, client, &mut events, limit).await;
+    ratatui::restore();
+    tasks.shutdown().await;
+    result
+}
+
+impl App {
+    fn new(threads: Vec<Thread>) -> Self {
+        let mut list = ListState::default();
+        if !threads.is_empty() {
+            list.select(Some(0));
+        }
+        Self {
+            threads: threads.into_iter().map(Arc::new).collect(),
+            list,
+            conversations: HashMap::new(),
+            focus: Focus::Inbox,
+            input: String::new(),
+            scroll: 0,
+            confirming: None,
+            status: None,
+        }
+    }
+
+    async fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        client: &ApiClient,
+        events: &mut broadcast::Receiver<SyncEvent>,
+        limit: u32,
+    ) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Drawn with "Loading…" first, so the switch shows at once
+            if let Some(thread_id) = self.unloaded() {
+                self.load(client, &thread_id).await;
+                continue;
+            }
+
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !self.handle_key(key, client, limit).await {
+                        return Ok(());
+                    }
+                }
+            }
+
+            loop {
+                match events.try_recv() {
+                    Ok(event) => self.apply(event),
+                    Err(TryRecvError::Lagged(_)) => continue,
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+
+    fn selected(&self) -> Option<&Arc<Thread>> {
+        self.threads.get(self.list.selected()?)
+    }
+
+    /// The selected thread, if its messages haven't been fetched yet
+    fn unloaded(&self) -> Option<String> {
+        self.selected()
+            .filter(|t| !self.conversations.contains_key(&t.id))
+            .map(|t| t.id.clone())
+    }
+
+    async fn load(&mut self, client: &ApiClient, thread_id: &str) {
+        let messages = match fetch_recent(client, thread_id).await {
+            Some(thread) => thread.messages.unwrap_or_default().into_iter().rev().map(Arc::new).collect(),
+            None => {
+                self.status = Some(("Couldn't load the conversation (r to retry)".to_string(), Tone::Error));
+                Vec::new()
+            }
+        };
+        self.conversations.insert(thread_id.to_string(), messages);
+    }
+
+    /// Handle a key press; false to quit
+    async fn handle_key(&mut self, key: KeyEvent, client: &ApiClient, limit: u32) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return false;
+        }
+        match key.code {
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Inbox if self.selected().is_some() => Focus::Input,
+                    _ => Focus::Inbox,
+                };
+                return true;
+            }
+            KeyCode::PageUp => {
+                self.scroll = self.scroll.saturating_add(SCROLL_PAGE);
+                return true;
+            }
+            KeyCode::PageDown => {
+                self.scroll = self.scroll.saturating_sub(SCROLL_PAGE);
+                return true;
+            }
+            _ => {}
+        }
+
+        match self.focus {
+            Focus::Inbox => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.select_offset(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.select_offset(1),
+                KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') if self.selected().is_some() => {
+                    self.focus = Focus::Input;
+                }
+                KeyCode::Char('r') => self.refresh(client, limit).await,
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                _ => {}
+            },
+            Focus::Input => match key.code {
+                KeyCode::Esc => self.focus = Focus::Inbox,
+                KeyCode::Enter => {
+                    if let Err(e) = self.send(client).await {
+                        self.status = Some((format!("{:#}", e), Tone::Error));
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                    self.confirming = None;
+                }
+                KeyCode::Char(c) => {
+                    self.input.push(c);
+                    self.confirming = None;
+                }
+                _ => {}
+            },
+        }
+        true
+    }
+
+    /// Move the selection up or down the list, to a thread at the bottom
+    /// of its conversation
+    fn select_offset(&mut self, offset: isize) {
+        let Some(current) = self.list.selected() else {
+            return;
+        };
+        let next = current.saturating_add_signed(offset).min(self.threads.len().saturating_sub(1));
+        if next != current {
+            self.list.select(Some(next));
+            self.scroll = 0;
+            self.confirming = None;
+            self.status = None;
+        }
+    }
+
+    /// Fetch the inbox again, and the selected conversation with it
+    async fn refresh(&mut self, client: &ApiClient, limit: u32) {
+        let selected_id = self.selected().map(|t| t.id.clone());
+        match client.get_inbox(limit).await {
+            Ok(response) if response.success => {
+                self.threads = response.threads.unwrap_or_default().into_iter().map(Arc::new).collect();
+                self.reselect(selected_id.as_deref());
+                self.conversations.clear();
+                self.status = None;
+            }
+            Ok(response) => {
+                let error = response.error.unwrap_or("Failed to fetch inbox".to_string());
+                self.status = Some((error, Tone::Error));
+            }
+            Err(e) => self.status = Some((format!("{:#}", e), Tone::Error)),
+        }
+    }
+
+    /// Select the thread with `thread_id` again after the list changed
+    fn reselect(&mut self, thread_id: Option<&str>) {
+        let index = thread_id
+            .and_then(|id| self.threads.iter().position(|t| t.id == id))
+            .or(if self.threads.is_empty() { None } else { Some(0) });
+        self.list.select(index);
+    }
+
+    /// Send what's in the input to the selected conversation
+    ///
+    /// `#CODE`s from the catalog are expanded and the pre-send checks run
+    /// first; a message they flag is sent on a second Enter.
+    async fn send(&mut self, client: &ApiClient) -> Result<()> {
+        let Some(thread) = self.selected().cloned() else {
+            return Ok(());
+        };
+        let text = Catalog::load()?.expand(self.input.trim());
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        if self.confirming.as_deref() != Some(text.as_str()) {
+            let participants: Vec<String> = thread.users.iter().map(|u| u.username.clone()).collect();
+            let verdict = check_message(&text, &participants)?;
+            if verdict.blocked {
+                self.status = Some((format!("Not sent: {}", verdict.problems.join("; ")), Tone::Error));
+                return Ok(());
+            }
+            if !verdict.problems.is_empty() {
+                let warning = format!("{} — Enter again to send anyway", verdict.problems.join("; "));
+                self.status = Some((warning, Tone::Warning));
+                self.confirming = Some(text);
+                return Ok(());
+            }
+        }
+        self.confirming = None;
+
+        let response = client.send_to_thread(&thread.id, &sign(&text, true)?).await?;
+        if !response.success {
+            anyhow::bail!(response.error.unwrap_or("Failed to send message".to_string()));
+        }
+        for user in &thread.users {
+            record_message(&user.username);
+        }
+        if let Some(message) = response.message {
+            self.push_message(&thread.id, Arc::new(message));
+        }
+        self.input.clear();
+        self.scroll = 0;
+        self.status = Some(("Sent".to_string(), Tone::Info));
+        Ok(())
+    }
+
+    /// Add a message to a conversation that has been fetched, unless it's
+    /// already there (our own messages come back from the sync task too)
+    fn push_message(&mut self, thread_id: &str, message: Arc<Message>) {
+        if let Some(messages) = self.conversations.get_mut(thread_id) {
+            if !messages.iter().any(|m| m.id == message.id) {
+                messages.push(message);
+            }
+        }
+    }
+
+    fn apply(&mut self, event: SyncEvent) {
+        match event {
+            SyncEvent::ThreadUpdated { thread, new_activity } => {
+                let selected_id = self.selected().map(|t| t.id.clone());
+                let position = self.threads.iter().position(|t| t.id == thread.id);
+                if let Some(position) = position {
+                    self.threads.remove(position);
+                }
+                let position = position.filter(|_| !new_activity).unwrap_or(0);
+                self.threads.insert(position.min(self.threads.len()), thread);
+                self.reselect(selected_id.as_deref());
+            }
+            SyncEvent::NewMessage { thread_id, message, users } => {
+                users::remember(&users);
+                self.push_message(&thread_id, message);
+            }
+            SyncEvent::PollFailed(error) => self.status = Some((error, Tone::Warning)),
+            event => {
+                if let Some(notice) = event.server_notice() {
+                    self.status = Some((notice.to_string(), Tone::Warning));
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [inbox, right] = Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)]).areas(main);
+        let [conversation, input] = Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(right);
+
+        self.draw_inbox(frame, inbox);
+        self.draw_conversation(frame, conversation);
+        self.draw_input(frame, input);
+
+        let line = match &self.status {
+            Some((text, Tone::Info)) => Line::styled(text.as_str(), fg(palette().green)),
+            Some((text, Tone::Warning)) => Line::styled(format!("⚠ {}", text), fg(palette().yellow)),
+            Some((text, Tone::Error)) => Line::styled(format!("✗ {}", text), fg(palette().red)),
+            None => Line::styled(HELP, fg(palette().gray)),
+        };
+        frame.render_widget(Paragraph::new(line), status);
+    }
+
+    fn draw_inbox(&mut self, frame: &mut Frame, area: Rect) {
+        let width = area.width.saturating_sub(8) as usize;
+        let items: Vec<ListItem> = self
+            .threads
+            .iter()
+            .map(|thread| {
+                let unread = thread.has_unread.unwrap_or(false);
+                let mut title_style = fg(palette().orange);
+                if unread {
+                    title_style = title_style.add_modifier(Modifier::BOLD);
+                }
+                let time = thread.last_message_timestamp.as_deref().map(format_time_ago).unwrap_or_default();
+                let preview = thread.last_message_text.as_deref().unwrap_or("[media]");
+                ListItem::new(vec![
+                    Line::from(vec![
+                        Span::styled(if unread { "● " } else { "  " }, fg(palette().blue)),
+                        Span::styled(thread_title(thread), title_style),
+                        Span::styled(format!("  {}", time), fg(palette().gray)),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("  └ ", fg(palette().gray)),
+                        Span::raw(ellipsize(&preview.replace('\n', " "), width)),
+                    ]),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(pane(" Inbox ", self.focus == Focus::Inbox))
+            .highlight_style(highlight())
+            .highlight_symbol("►");
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_conversation(&self, frame: &mut Frame, area: Rect) {
+        let Some(thread) = self.selected() else {
+            let empty = Paragraph::new(Line::styled("No conversations found.", fg(palette().gray)));
+            frame.render_widget(empty.block(pane(" Conversation ", false)), area);
+            return;
+        };
+
+        let mut lines = Vec::new();
+        match self.conversations.get(&thread.id) {
+            None => lines.push(Line::styled("Loading…", fg(palette().gray))),
+            Some(messages) if messages.is_empty() => lines.push(Line::styled("No messages yet.", fg(palette().gray))),
+            Some(messages) => {
+                for msg in messages {
+                    let sender = msg
+                        .user_id
+                        .as_ref()
+                        .and_then(|uid| users::username(&thread.users, uid))
+                        .map(|username| nicknames::name(&username))
+                        .unwrap_or("You".to_string());
+                    let time = msg.timestamp.as_deref().map(format_time_ago).unwrap_or_default();
+                    lines.push(Line::from(vec![
+                        Span::styled(sender, fg(palette().pink).add_modifier(Modifier::BOLD)),
+                        Span::styled(format!(" {}", time), fg(palette().gray)),
+                    ]));
+                    let label = msg.media_label();
+                    let text = msg.text.as_deref().or(label.as_deref()).unwrap_or("[media]");
+                    lines.extend(text.lines().map(|line| Line::raw(format!("  {}", line))));
+                    lines.push(Line::default());
+                }
+            }
+        }
+
+        let title = format!(" {} ", thread_title(thread));
+        let paragraph = Paragraph::new(lines).block(pane(&title, false)).wrap(Wrap { trim: false });
+        // Pinned to the newest message unless scrolled up
+        let total = paragraph.line_count(area.width.saturating_sub(2)) as u16;
+        let bottom = total.saturating_sub(area.height);
+        let offset = bottom.saturating_sub(self.scroll);
+        frame.render_widget(paragraph.scroll((offset, 0)), area);
+    }
+
+    fn draw_input(&self, frame: &mut Frame, area: Rect) {
+        let focused = self.focus == Focus::Input;
+        let title = if focused { " Message (Enter: Send  Esc: Inbox) " } else { " Message " };
+        // Keep the end of a long message in view
+        let width = area.width.saturating_sub(3) as usize;
+        let skip = self.input.chars().count().saturating_sub(width);
+        let visible: String = self.input.chars().skip(skip).collect();
+        let cursor = area.x + 1 + visible.chars().count() as u16;
+        frame.render_widget(Paragraph::new(visible).block(pane(title, focused)), area);
+        if focused {
+            frame.set_cursor_position((cursor, area.y + 1));
+        }
+    }
+}
+
+/// A thread's title, or the nickname/username of the person in it
+pub(crate) fn thread_title(thread: &Thread) -> String {
+    let username = thread.users.first().map(|u| u.username.as_str()).unwrap_or("unknown");
+    thread.thread_title.clone().unwrap_or_else(|| nicknames::name(username))
+}
+
+/// A bordered pane, its border in the accent color when it has focus
+pub(crate) fn pane(title: &str, focused: bool) -> Block<'static> {
+    let border = if focused { fg(palette().pink) } else { fg(palette().gray) };
+    Block::bordered().title(title.to_string()).border_style(border)
+}
+
+/// Text in a palette color, or unstyled when colors are off
+pub(crate) fn fg((r, g, b): (u8, u8, u8)) -> Style {
+    if colors_enabled() {
+        Style::new().fg(Color::Rgb(r, g, b))
+    } else {
+        Style::new()
+    }
+}
+
+/// The selected inbox entry: the interactive inbox's dark background, or
+/// reversed when colors are off
+pub(crate) fn highlight() -> Style {
+    if colors_enabled() {
+        Style::new().bg(Color::Rgb(60, 60, 60))
+    } else {
+        Style::new().add_modifier(Modifier::REVERSED)
+    }
+}
@@ -0,0 +1,271 @@
+//! Guided tour (`ig tutorial`)
+//!
+//! Walks through the everyday workflow (status → login → inbox → open →
+//! send) on a made-up account. Each step explains a command and waits for
+//! it to be typed; the answer is checked and what the real command would
+//! print for the sample data is shown. Nothing reaches the server and your
+//! own conversations, tags and reminders aren't touched, so it's safe to run
+//! before logging in.
+
+use anyhow::Result;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use crate::colors::Theme;
+
+/// A conversation in the sample inbox
+struct Sample {
+    username: &'static str,
+    name: &'static str,
+    ago: &'static str,
+    unread: bool,
+    /// Messages, oldest first: (from them, text)
+    messages: &'static [(bool, &'static str)],
+}
+
+const SAMPLE_INBOX: [Sample; 3] = [
+    Sample {
+        username: "alice",
+        name: "Alice Andrews",
+        ago: "5m",
+        unread: true,
+        messages: &[(false, "are we still on for friday?"), (true, "yes! 7pm at the usual place?")],
+    },
+    Sample {
+        username: "bob",
+        name: "Bob Brown",
+        ago: "2h",
+        unread: false,
+        messages: &[(true, "sent you the photos"), (false, "thanks, they look great")],
+    },
+    Sample {
+        username: "carol",
+        name: "Carol Chen",
+        ago: "3d",
+        unread: false,
+        messages: &[(true, "happy birthday!! 🎉")],
+    },
+];
+
+/// Steps of the tutorial, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Status,
+    Login,
+    Inbox,
+    Open,
+    Send,
+}
+
+impl Step {
+    pub const ALL: [Step; 5] = [Step::Status, Step::Login, Step::Inbox, Step::Open, Step::Send];
+
+    fn title(self) -> &'static str {
+        match self {
+            Step::Status => "Check the server",
+            Step::Login => "Log in",
+            Step::Inbox => "See your inbox",
+            Step::Open => "Open a conversation",
+            Step::Send => "Send a message",
+        }
+    }
+
+    fn explain(self) -> &'static str {
+        match self {
+            Step::Status => "ig talks to Instagram through a small local server. `ig status` shows whether it is \
+                             running and who is logged in.",
+            Step::Login => "`ig login -u <username>` logs in; the password is asked for and encrypted before \
+                            it leaves your machine. Here any username works and no password is needed.",
+            Step::Inbox => "`ig inbox` lists your conversations, newest first. ● marks unread ones.",
+            Step::Open => "Conversations are numbered in the last listing; `ig open <number>` shows one.",
+            Step::Send => "`ig send @user \"message\"` sends a message without opening the chat.",
+        }
+    }
+
+    /// Command to suggest when stuck
+    fn example(self) -> &'static str {
+        match self {
+            Step::Status => "ig status",
+            Step::Login => "ig login -u yourname",
+            Step::Inbox => "ig inbox",
+            Step::Open => "ig open 1",
+            Step::Send => "ig send @alice \"see you friday\"",
+        }
+    }
+
+    fn command(self) -> &'static str {
+        match self {
+            Step::Status => "status",
+            Step::Login => "login",
+            Step::Inbox => "inbox",
+            Step::Open => "open",
+            Step::Send => "send",
+        }
+    }
+}
+
+/// A command typed at a step that checks out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Status,
+    Login(Option<String>),
+    Inbox,
+    /// 1-based position in the sample inbox
+    Open(usize),
+    Send { username: String, text: String },
+}
+
+/// Check a line typed at `step`: the command it stands for, or what's wrong
+/// with it
+pub fn check(step: Step, line: &str) -> std::result::Result<Answer, String> {
+    let line = line.trim();
+    let line = line.strip_prefix("ig ").unwrap_or(line).trim_start();
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+
+    if command != step.command() {
+        let later = Step::ALL.iter().any(|s| s.command() == command);
+        return Err(if later {
+            format!("`ig {}` comes in another step. Here, try: {}", command, step.example())
+        } else {
+            format!("Not quite. Try: {}", step.example())
+        });
+    }
+
+    match step {
+        Step::Status | Step::Inbox if !rest.is_empty() => {
+            Err(format!("`ig {}` needs nothing else here. Try: {}", command, step.example()))
+        }
+        Step::Status => Ok(Answer::Status),
+        Step::Inbox => Ok(Answer::Inbox),
+        Step::Login => match rest.split_whitespace().collect::<Vec<_>>()[..] {
+            [] => Ok(Answer::Login(None)),
+            ["-u" | "--username", username] => Ok(Answer::Login(Some(username.trim_start_matches('@').to_string()))),
+            ref words if words.iter().any(|w| *w == "-p" || *w == "--password") => {
+                Err("Leave the password out: it is asked for separately, so it doesn't end up in your shell history."
+                    .to_string())
+            }
+            _ => Err(format!("The username goes after -u. Try: {}", step.example())),
+        },
+        Step::Open => match rest.parse::<usize>() {
+            Ok(n) if (1..=SAMPLE_INBOX.len()).contains(&n) => Ok(Answer::Open(n)),
+            Ok(_) => Err(format!("The sample inbox has {} conversations.", SAMPLE_INBOX.len())),
+            Err(_) => Err("Which conversation? Give its number, e.g. ig open 1".to_string()),
+        },
+        Step::Send => {
+            let (username, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let username = username.trim_start_matches('@');
+            let text = text.trim().trim_matches(|c| c == '"' || c == '\'').trim();
+            if !SAMPLE_INBOX.iter().any(|s| s.username == username) {
+                let names: Vec<String> = SAMPLE_INBOX.iter().map(|s| format!("@{}", s.username)).collect();
+                return Err(format!("Send to someone in the sample inbox: {}", names.join(", ")));
+            }
+            if text.is_empty() {
+                return Err(format!("Add the message after the username, e.g. ig send @{} \"hi\"", username));
+            }
+            Ok(Answer::Send { username: username.to_string(), text: text.to_string() })
+        }
+    }
+}
+
+/// Print what the real command would print for the sample account
+fn show(answer: &Answer) {
+    match answer {
+        Answer::Status => {
+            println!("{}", Theme::header("Server Status"));
+            println!("{}", Theme::separator(40));
+            println!("  {} {}", Theme::muted("Server:"), Theme::success("ok"));
+            println!("  {} {}", Theme::muted("Status:"), Theme::warning("Not authenticated"));
+        }
+        Answer::Login(username) => {
+            println!("{} {}", Theme::check(), Theme::success("Login successful!"));
+            println!(
+                "  {} {} (Practice account)",
+                Theme::muted("Logged in as:"),
+                Theme::username(username.as_deref().unwrap_or("you"))
+            );
+        }
+        Answer::Inbox => {
+            println!("{}", Theme::header("Inbox"));
+            println!("{}", Theme::separator(60));
+            for (i, sample) in SAMPLE_INBOX.iter().enumerate() {
+                let unread = if sample.unread { Theme::unread_marker() } else { " ".to_string() };
+                println!(
+                    "{:>3}. {} {} {} {}",
+                    Theme::muted(&(i + 1).to_string()),
+                    Theme::orange(sample.name),
+                    Theme::username(&format!("@{}", sample.username)),
+                    Theme::timestamp(sample.ago),
+                    unread
+                );
+                let (_, last) = sample.messages[sample.messages.len() - 1];
+                println!("     {}", Theme::muted(last));
+            }
+            println!("{}", Theme::separator(60));
+        }
+        Answer::Open(n) => {
+            let sample = &SAMPLE_INBOX[n - 1];
+            println!("{} {}", Theme::header("Conversation with"), Theme::orange(sample.name));
+            println!("{}", Theme::separator(60));
+            for (from_them, text) in sample.messages {
+                let sender = if *from_them { format!("@{}", sample.username) } else { "You".to_string() };
+                println!("  {} {}", Theme::username(&format!("{}:", sender)), text);
+            }
+            println!("{}", Theme::separator(60));
+        }
+        Answer::Send { username, text } => {
+            println!("{} {}", Theme::check(), Theme::success(&format!("Message sent to @{}", username)));
+            println!("  {}", Theme::muted(text));
+        }
+    }
+}
+
+/// Run the tutorial (`ig tutorial`). `q` or Ctrl-D stops it; an empty line
+/// or `?` shows the example for the current step.
+pub fn run_tutorial() -> Result<()> {
+    let mut editor = DefaultEditor::new()?;
+    println!();
+    println!("{}", Theme::header("ig tutorial"));
+    println!(
+        "{}",
+        Theme::muted("A practice run on a sample account: nothing is sent and nothing is saved.")
+    );
+    println!("{}", Theme::muted("Type each command as shown. Enter on its own shows a hint, q quits."));
+
+    for (i, step) in Step::ALL.iter().enumerate() {
+        println!();
+        println!("{}", Theme::accent(&format!("Step {}/{}: {}", i + 1, Step::ALL.len(), step.title())));
+        println!("{}", step.explain());
+        loop {
+            let line = match editor.readline(&format!("{} ", Theme::prompt("$"))) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted | ReadlineError::Eof) => return stopped(),
+                Err(e) => return Err(e.into()),
+            };
+            match line.trim() {
+                "q" | "quit" | "exit" => return stopped(),
+                "" | "?" | "hint" => println!("{} {}", Theme::muted("Try:"), Theme::accent(step.example())),
+                typed => match check(*step, typed) {
+                    Ok(answer) => {
+                        println!();
+                        show(&answer);
+                        break;
+                    }
+                    Err(message) => println!("{} {}", Theme::cross(), Theme::warning(&message)),
+                },
+            }
+        }
+    }
+
+    println!();
+    println!("{} {}", Theme::check(), Theme::success("That's the whole workflow!"));
+    println!("{}", Theme::muted("For real: start the server, then `ig login`. Next, try `ig chat @user` for a"));
+    println!("{}", Theme::muted("live conversation, `ig inbox -i` to browse with the keyboard, and `ig --help`."));
+    Ok(())
+}
+
+fn stopped() -> Result<()> {
+    println!();
+    println!("{}", Theme::muted("Tutorial stopped. Run `ig tutorial` to start over."));
+    Ok(())
+}
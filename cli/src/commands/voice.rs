@@ -0,0 +1,40 @@
+//! Voice messages (`ig voice`)
+
+use anyhow::Result;
+use std::time::Duration;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::attach::{format_size, send_file, Recipient};
+use crate::interactions::record_message;
+use crate::voice;
+
+/// Record up to `seconds` from the microphone and send it to `target`
+/// (thread ID or @username) as a voice message
+pub async fn send_voice(client: &ApiClient, target: &str, seconds: u64) -> Result<()> {
+    let recipient = match target.strip_prefix('@') {
+        Some(username) => Recipient::User(username),
+        None => Recipient::Thread(target),
+    };
+
+    let Some(recording) = voice::record(Duration::from_secs(seconds))? else {
+        println!("{}", Theme::muted("Cancelled; nothing sent."));
+        return Ok(());
+    };
+    println!(
+        "{}",
+        Theme::muted(&format!(
+            "Recorded {:.1}s ({}) for {}",
+            recording.duration.as_secs_f64(),
+            format_size(recording.wav.len() as u64),
+            target
+        ))
+    );
+
+    if send_file(client, recipient, "voice.wav", &recording.wav).await {
+        if let Recipient::User(username) = recipient {
+            record_message(username);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Open conversations on instagram.com (`ig web`)
+//!
+//! For whatever the terminal can't show yet (media, calls, some message
+//! types), this hands the conversation to the browser, or to the Instagram
+//! app where the system routes instagram.com links to it.
+
+use anyhow::{Context, Result};
+use std::process::Command;
+
+use crate::client::ApiClient;
+use crate::colors::Theme;
+use crate::commands::inbox::find_thread_by_username;
+use crate::spinner::create_spinner;
+
+/// Open the conversation for `target` (thread ID or @username), or just
+/// print its URL with `print_only`
+pub async fn open_in_browser(client: &ApiClient, target: &str, print_only: bool) -> Result<()> {
+    let url = match target.strip_prefix('@') {
+        Some(username) => {
+            let spinner = create_spinner(&format!("Finding conversation with @{}...", username));
+            let thread = find_thread_by_username(client, username).await;
+            spinner.finish_and_clear();
+            match thread? {
+                Some(thread) => thread_url(&thread.id),
+                None => new_conversation_url(username),
+            }
+        }
+        None => thread_url(target),
+    };
+
+    if print_only {
+        println!("{}", url);
+        return Ok(());
+    }
+
+    open_url(&url)?;
+    println!("{} {} {}", Theme::check(), Theme::success("Opened"), Theme::muted(&url));
+    Ok(())
+}
+
+/// instagram.com address of a DM thread
+pub(crate) fn thread_url(thread_id: &str) -> String {
+    format!("https://www.instagram.com/direct/t/{}/", thread_id)
+}
+
+/// Address that starts a conversation with `username` (ig.me)
+pub(crate) fn new_conversation_url(username: &str) -> String {
+    format!("https://ig.me/m/{}", username)
+}
+
+/// Open a URL with the platform's opener (`xdg-open` on Linux, `open` on
+/// macOS, `start` on Windows)
+pub(crate) fn open_url(url: &str) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).status()
+    } else if cfg!(windows) {
+        // The empty argument is start's window title
+        Command::new("cmd").args(["/C", "start", "", url]).status()
+    } else {
+        Command::new("xdg-open").arg(url).status()
+    };
+
+    let status = status.context("Failed to start the system opener; use --print to get the URL")?;
+    if !status.success() {
+        anyhow::bail!("The system opener failed; use --print to get the URL");
+    }
+    Ok(())
+}
@@ -1,28 +1,93 @@
-//! Tab completion for usernames from recent conversations
+//! Tab completion for usernames
+//!
+//! Candidates come from the caller (e.g. the inbox on screen) followed by
+//! every username in the user registry, which is filled from each inbox and
+//! thread fetch and kept between runs. Matches are ranked by how often and
+//! how recently each user was messaged (see [`crate::interactions`]).
+//!
+//! Typing `@` lists the top candidates inline after the cursor; Tab cycles
+//! through them (as do Up/Down) and Right arrow accepts the top match.
+//!
+//! Every keystroke runs the hinter and asks the highlighter whether the
+//! line must be redrawn, so both stay cheap: names are lowercased once up
+//! front, and a redraw is only requested while an @mention is being typed.
+//! Otherwise rustyline just echoes the typed character (it keeps the
+//! terminal width itself and updates it on resize).
 
 use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::{CmdKind, Highlighter};
-use rustyline::hint::Hinter;
+use rustyline::hint::{Hint, Hinter};
 use rustyline::history::DefaultHistory;
 use rustyline::validate::Validator;
-use rustyline::{Context, Editor, Helper};
+use rustyline::{
+    Cmd, ConditionalEventHandler, Context, Editor, Event, EventContext, EventHandler, Helper,
+    KeyCode, KeyEvent, Modifiers, RepeatCount,
+};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use crate::colors::instagram;
+use anyhow::Result;
 
-/// Username completer that suggests usernames from recent conversations
+use crate::colors::{fg_escape, palette, reset_escape};
+use crate::interactions::Interactions;
+use crate::users;
+
+/// Candidates shown inline while typing an @mention
+const MENTION_LIST: usize = 5;
+
+/// Username completer that suggests known usernames
 #[derive(Clone)]
 pub struct UsernameCompleter {
     usernames: Vec<String>,
+    /// `usernames` lowercased, for prefix matching
+    lowered: Vec<String>,
+    /// Interaction score per username (missing means never messaged)
+    scores: HashMap<String, f64>,
 }
 
 impl UsernameCompleter {
     pub fn new(usernames: Vec<String>) -> Self {
-        Self { usernames }
+        let interactions = Interactions::load().unwrap_or_default();
+        let scores = usernames
+            .iter()
+            .map(|name| (name.clone(), interactions.score(name)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        let lowered = usernames.iter().map(|name| name.to_lowercase()).collect();
+        Self { usernames, lowered, scores }
+    }
+
+    /// Usernames starting with `prefix`, most messaged first (ties keep
+    /// their original order)
+    fn matches(&self, prefix: &str) -> Vec<&String> {
+        let prefix = prefix.to_lowercase();
+        let mut matches: Vec<&String> = self
+            .usernames
+            .iter()
+            .zip(&self.lowered)
+            .filter(|(_, lowered)| lowered.starts_with(&prefix))
+            .map(|(name, _)| name)
+            .collect();
+        let score = |name: &String| self.scores.get(name).copied().unwrap_or(0.0);
+        matches.sort_by(|a, b| score(b).total_cmp(&score(a)));
+        matches
     }
 }
 
+/// The word being typed at the end of `input`: its byte offset, its text
+/// (without a leading `@`) and whether it is an @mention
+fn current_word(input: &str) -> (usize, &str, bool) {
+    let start = input
+        .rfind(|c: char| c == '@' || c.is_whitespace())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let mention = start > 0 && input[..start].ends_with('@');
+    (start, &input[start..], mention)
+}
+
 impl Completer for UsernameCompleter {
     type Candidate = Pair;
 
@@ -32,24 +97,16 @@ impl Completer for UsernameCompleter {
         pos: usize,
         _ctx: &Context<'_>,
     ) -> Result<(usize, Vec<Pair>), ReadlineError> {
-        let input = &line[..pos];
+        let (word_start, prefix, mention) = current_word(&line[..pos]);
 
-        // Find the start of the current word (after @ or space)
-        let word_start = input
-            .rfind(|c: char| c == '@' || c.is_whitespace())
-            .map(|i| i + 1)
-            .unwrap_or(0);
-
-        let prefix = &input[word_start..];
-
-        if prefix.is_empty() {
+        // A bare `@` offers everyone; elsewhere an empty word offers nothing
+        if prefix.is_empty() && !mention {
             return Ok((pos, vec![]));
         }
 
         let matches: Vec<Pair> = self
-            .usernames
-            .iter()
-            .filter(|name| name.to_lowercase().starts_with(&prefix.to_lowercase()))
+            .matches(prefix)
+            .into_iter()
             .map(|name| Pair {
                 display: format!("@{}", name),
                 replacement: name.clone(),
@@ -60,43 +117,135 @@ impl Completer for UsernameCompleter {
     }
 }
 
+/// Inline hint: the rest of the top match, followed for an @mention by the
+/// next few candidates
+pub struct MentionHint {
+    display: String,
+    completion: String,
+}
+
+impl Hint for MentionHint {
+    fn display(&self) -> &str {
+        &self.display
+    }
+
+    fn completion(&self) -> Option<&str> {
+        (!self.completion.is_empty()).then_some(self.completion.as_str())
+    }
+}
+
 impl Hinter for UsernameCompleter {
-    type Hint = String;
+    type Hint = MentionHint;
 
-    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<String> {
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<MentionHint> {
         if line.is_empty() || pos < line.len() {
             return None;
         }
 
-        // Find the start of the current word
-        let word_start = line
-            .rfind(|c: char| c == '@' || c.is_whitespace())
-            .map(|i| i + 1)
-            .unwrap_or(0);
+        let (_, prefix, mention) = current_word(line);
+        if prefix.is_empty() && !mention {
+            return None;
+        }
 
-        let prefix = &line[word_start..];
+        let matches = self.matches(prefix);
+        let (top, others) = matches.split_first()?;
+        let completion = top[prefix.len()..].to_string();
+        let mut display = completion.clone();
+        if mention && !others.is_empty() {
+            let others: Vec<String> = others
+                .iter()
+                .take(MENTION_LIST - 1)
+                .map(|name| format!("@{}", name))
+                .collect();
+            display.push_str(&format!("   {}", others.join("  ")));
+        }
+        Some(MentionHint { display, completion })
+    }
+}
 
-        if prefix.is_empty() {
+/// Up/Down while typing an @mention cycle through the candidates like
+/// Tab / Shift-Tab; elsewhere they browse history as usual
+struct MentionCycler {
+    completer: UsernameCompleter,
+    forward: bool,
+}
+
+impl ConditionalEventHandler for MentionCycler {
+    fn handle(&self, _evt: &Event, _n: RepeatCount, _positive: bool, ctx: &EventContext) -> Option<Cmd> {
+        let (_, word, mention) = current_word(&ctx.line()[..ctx.pos()]);
+        if !mention {
             return None;
         }
+        // Stepping back only works once a candidate is in the line (i.e.
+        // while cycling); before that both keys start the cycle
+        let cycling = self.completer.usernames.iter().any(|name| name == word);
+        Some(if self.forward || !cycling {
+            Cmd::Complete
+        } else {
+            Cmd::CompleteBackward
+        })
+    }
+}
 
-        // Find first matching username
-        self.usernames
-            .iter()
-            .find(|name| name.to_lowercase().starts_with(&prefix.to_lowercase()))
-            .map(|name| name[prefix.len()..].to_string())
+/// Ends the line like Enter but raises a flag, so the caller can tell the
+/// line was handed back for something else (e.g. drafting a reply)
+struct AcceptWithFlag {
+    flag: Arc<AtomicBool>,
+    /// Only on an empty line; otherwise the key does what it normally does
+    empty_only: bool,
+}
+
+impl ConditionalEventHandler for AcceptWithFlag {
+    fn handle(&self, _evt: &Event, _n: RepeatCount, _positive: bool, ctx: &EventContext) -> Option<Cmd> {
+        if self.empty_only && !ctx.line().is_empty() {
+            return None;
+        }
+        self.flag.store(true, Ordering::Relaxed);
+        Some(Cmd::AcceptLine)
     }
 }
 
+/// Make `key` end the line and set the returned flag (the caller clears it)
+pub fn bind_flag_key(
+    editor: &mut Editor<UsernameCompleter, DefaultHistory>,
+    key: KeyEvent,
+) -> Arc<AtomicBool> {
+    bind_accept(editor, key, false)
+}
+
+/// Like [`bind_flag_key`], but only on an empty line, so a printable key
+/// (e.g. `/`) can still be typed within a message
+pub fn bind_flag_key_on_empty(
+    editor: &mut Editor<UsernameCompleter, DefaultHistory>,
+    key: KeyEvent,
+) -> Arc<AtomicBool> {
+    bind_accept(editor, key, true)
+}
+
+fn bind_accept(
+    editor: &mut Editor<UsernameCompleter, DefaultHistory>,
+    key: KeyEvent,
+    empty_only: bool,
+) -> Arc<AtomicBool> {
+    let flag = Arc::new(AtomicBool::new(false));
+    let handler = AcceptWithFlag {
+        flag: Arc::clone(&flag),
+        empty_only,
+    };
+    editor.bind_sequence(key, EventHandler::Conditional(Box::new(handler)));
+    flag
+}
+
 impl Highlighter for UsernameCompleter {
     fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
         // Gray color for hints
-        Cow::Owned(format!("\x1b[38;2;142;142;142m{}\x1b[0m", hint))
+        Cow::Owned(format!("{}{}{}", fg_escape(palette().gray), hint, reset_escape()))
     }
 
     fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
         // Highlight @usernames in pink
-        let (r, g, b) = instagram::PINK;
+        let pink = fg_escape(palette().pink);
+        let reset = reset_escape();
         let mut result = String::new();
         let mut in_username = false;
         let mut current_word = String::new();
@@ -109,7 +258,7 @@ impl Highlighter for UsernameCompleter {
                 current_word.push(c);
             } else {
                 if in_username && !current_word.is_empty() {
-                    result.push_str(&format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, current_word));
+                    result.push_str(&format!("{}{}{}", pink, current_word, reset));
                     current_word.clear();
                 }
                 in_username = false;
@@ -119,7 +268,7 @@ impl Highlighter for UsernameCompleter {
 
         // Handle trailing username
         if in_username && !current_word.is_empty() {
-            result.push_str(&format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, current_word));
+            result.push_str(&format!("{}{}{}", pink, current_word, reset));
         }
 
         if result.is_empty() {
@@ -129,19 +278,58 @@ impl Highlighter for UsernameCompleter {
         }
     }
 
-    fn highlight_char(&self, _line: &str, _pos: usize, _kind: CmdKind) -> bool {
-        true
+    /// Only typing inside an @mention changes the colors; anything else
+    /// (including cursor moves) can skip the full redraw
+    fn highlight_char(&self, line: &str, pos: usize, kind: CmdKind) -> bool {
+        kind == CmdKind::Other && in_mention(&line[..pos.min(line.len())])
     }
 }
 
+/// Whether `before` (the line up to the cursor) ends inside an @mention as
+/// colored by [`UsernameCompleter::highlight`]
+pub fn in_mention(before: &str) -> bool {
+    before
+        .rfind('@')
+        .is_some_and(|at| before[at + 1..].chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
 impl Validator for UsernameCompleter {}
 
 impl Helper for UsernameCompleter {}
 
 /// Create a readline editor with username completion
-pub fn create_editor(usernames: Vec<String>) -> Editor<UsernameCompleter, DefaultHistory> {
+///
+/// `usernames` are suggested first, then the rest of the known users.
+pub fn create_editor(mut usernames: Vec<String>) -> Editor<UsernameCompleter, DefaultHistory> {
+    for username in users::usernames() {
+        if !usernames.contains(&username) {
+            usernames.push(username);
+        }
+    }
     let completer = UsernameCompleter::new(usernames);
     let mut editor = Editor::new().expect("Failed to create editor");
+
+    for (key, forward) in [(KeyCode::Down, true), (KeyCode::Up, false)] {
+        let cycler = MentionCycler {
+            completer: completer.clone(),
+            forward,
+        };
+        editor.bind_sequence(
+            KeyEvent(key, Modifiers::NONE),
+            EventHandler::Conditional(Box::new(cycler)),
+        );
+    }
+
     editor.set_helper(Some(completer));
     editor
 }
+
+/// Read one line with username completion (empty on Ctrl-C / Ctrl-D)
+pub fn prompt_line(prompt: &str) -> Result<String> {
+    let mut editor = create_editor(Vec::new());
+    match editor.readline(prompt) {
+        Ok(line) => Ok(line),
+        Err(ReadlineError::Interrupted | ReadlineError::Eof) => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,670 @@
+//! User configuration
+//!
+//! Read from `config.toml` in the platform config directory
+//! (e.g. `~/.config/insta-cli/config.toml` on Linux). Every setting is
+//! optional; a missing file means defaults.
+//!
+//! ```toml
+//! profile = "viewer"
+//!
+//! [profiles.viewer]
+//! permissions = ["read"]
+//!
+//! [profiles.work]
+//! server = "http://localhost:8001"
+//! signature = "— Acme support"
+//!
+//! [device]
+//! name = "work-laptop"
+//! user_agent_seed = "any stable string"
+//!
+//! [http]
+//! pool_max_idle_per_host = 4
+//! pool_idle_timeout_secs = 90
+//! connect_timeout_secs = 10
+//! tcp_keepalive_secs = 60
+//! proxy = "socks5h://127.0.0.1:9050"
+//! ip_version = "ipv4"
+//! dns_servers = ["1.1.1.1"]
+//!
+//! [http.hosts]
+//! localhost = "127.0.0.1"
+//!
+//! [summarizer]
+//! url = "http://localhost:11434/v1"
+//! model = "llama3.1"
+//! api_key_env = "OPENAI_API_KEY"
+//!
+//! [language]
+//! untagged = ["en", "my"]
+//!
+//! [display]
+//! big_emoji = false
+//! header = true
+//! avatars = true
+//!
+//! [translate]
+//! provider = "deepl"
+//! api_key_env = "DEEPL_API_KEY"
+//! target = "en"
+//!
+//! [send]
+//! typo_check = true
+//! send_delay = "5s"
+//! markdown = "unicode"   # or "plain"; how `--md` messages look
+//! signature = "— sent from insta_cli"
+//! signature_in_chat = false
+//!
+//! [send.filter]
+//! action = "block"
+//! words = ["confidential"]
+//! patterns = ['\b\d{16}\b']
+//! command = "my-checker"
+//!
+//! [paylink]
+//! url = "https://paypal.me/acme/{amount}{currency}"
+//! currency = "USD"
+//! message = "{note} — {amount} {currency}: {url}"
+//!
+//! [team]
+//! operator = "alice"
+//!
+//! [cache]
+//! keep_messages = "180d"
+//! keep_media = "30d"
+//!
+//! [alerts]
+//! keywords = ["urgent", "invoice"]
+//!
+//! [notify]
+//! quiet_hours = "22:00-07:00"
+//!
+//! [thread."bestfriend"]
+//! color = "yellow"
+//! notify = "always"
+//!
+//! [thread."client"]
+//! transcript = true
+//!
+//! [transcript]
+//! dir = "~/records/instagram"
+//! format = "jsonl"
+//! keep = "730d"
+//!
+//! [usage]
+//! track = false
+//!
+//! [alias]
+//! ii = "inbox -i"
+//!
+//! [searches.invoices]
+//! query = "invoice"
+//! from = "client"
+//! since = "30d"
+//! notify = true
+//! ```
+
+use anyhow::{Context, Result};
+use colored::ColoredString;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::colors::Theme;
+use crate::dns::IpVersion;
+use crate::models::{DeviceInfo, Thread, User};
+use crate::store;
+
+/// Directory name under the platform config directory
+const APP_DIR: &str = "insta-cli";
+
+/// Data file holding the generated device seed
+const DEVICE_FILE: &str = "device";
+
+/// Contents of `config.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Profile used when `--profile` isn't given
+    pub profile: Option<String>,
+    pub profiles: HashMap<String, ProfileConfig>,
+    pub device: DeviceConfig,
+    pub http: HttpConfig,
+    pub send: SendConfig,
+    pub paylink: PaylinkConfig,
+    pub team: TeamConfig,
+    pub summarizer: SummarizerConfig,
+    pub translate: TranslateConfig,
+    pub language: LanguageConfig,
+    pub display: DisplayConfig,
+    pub alerts: AlertsConfig,
+    pub notify: NotifyConfig,
+    pub cache: CacheConfig,
+    pub transcript: TranscriptConfig,
+    /// Per-conversation settings, keyed by @username, thread ID or group title
+    pub thread: HashMap<String, ThreadConfig>,
+    pub usage: UsageConfig,
+    /// Shorthands for command lines, e.g. `ii = "inbox -i"` makes `ig ii`
+    /// run `ig inbox -i`
+    pub alias: BTreeMap<String, String>,
+    /// Saved message searches, by name
+    pub searches: BTreeMap<String, SavedSearch>,
+}
+
+/// `[profiles.<name>]` section: a named setup selected with `--profile`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// Server this profile talks to
+    pub server: Option<String>,
+    /// What commands run under this profile may do (default: everything)
+    pub permissions: Vec<Permission>,
+    /// Signature for messages sent under this profile, instead of
+    /// `[send] signature` ("" for none)
+    pub signature: Option<String>,
+}
+
+impl Default for ProfileConfig {
+    fn default() -> Self {
+        Self {
+            server: None,
+            permissions: Permission::ALL.to_vec(),
+            signature: None,
+        }
+    }
+}
+
+/// Kind of action a profile can be allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// View the inbox, threads and users
+    Read,
+    /// Send messages
+    Send,
+    /// Log in and out and manage devices
+    Manage,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 3] = [Permission::Read, Permission::Send, Permission::Manage];
+}
+
+/// `[device]` section: how this CLI presents itself at login
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DeviceConfig {
+    /// Name shown in the server's device list
+    pub name: Option<String>,
+    /// Seed the server derives device IDs and user agent from. Keeping it
+    /// stable makes every login look like the same phone to Instagram.
+    pub user_agent_seed: Option<String>,
+}
+
+/// `[send]` section: checks before a message goes out, and its signature
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SendConfig {
+    /// Flag likely typos and mentions of people not in the conversation,
+    /// and ask before sending
+    pub typo_check: bool,
+    /// How long chat waits before sending, so a message can be undone
+    /// (e.g. "5s")
+    pub send_delay: Option<String>,
+    /// How `--md` messages show bold, italic and code
+    pub markdown: MarkdownStyle,
+    /// Rules outgoing messages are checked against
+    pub filter: FilterConfig,
+    /// Line added to the end of every message sent
+    pub signature: Option<String>,
+    /// Sign messages sent from `ig chat` too
+    pub signature_in_chat: bool,
+}
+
+impl Default for SendConfig {
+    fn default() -> Self {
+        Self {
+            typo_check: false,
+            send_delay: None,
+            markdown: MarkdownStyle::default(),
+            filter: FilterConfig::default(),
+            signature: None,
+            signature_in_chat: true,
+        }
+    }
+}
+
+/// How Markdown emphasis looks once converted to plain text
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkdownStyle {
+    /// Unicode bold/italic/monospace letters (𝗯𝗼𝗹𝗱, 𝘪𝘵𝘢𝘭𝘪𝘤)
+    #[default]
+    Unicode,
+    /// Markers removed, headings in capitals
+    Plain,
+}
+
+/// `[send.filter]` section: rules that flag outgoing messages
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FilterConfig {
+    /// What happens to a message that matches
+    pub action: FilterAction,
+    /// Words matched case-insensitively as whole words
+    pub words: Vec<String>,
+    /// Regular expressions matched anywhere in the message
+    pub patterns: Vec<String>,
+    /// Shell command given the message on stdin. A non-zero exit flags the
+    /// message, with its output as the reason.
+    pub command: Option<String>,
+}
+
+/// What the content filter does with a matching message
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// Show the match and ask before sending
+    #[default]
+    Warn,
+    /// Refuse to send
+    Block,
+}
+
+/// `[paylink]` section: the payment link `ig paylink` fills in
+///
+/// `{amount}`, `{currency}` and `{note}` are replaced in both templates
+/// (the note URL-encoded in `url`), and `{url}` in `message`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PaylinkConfig {
+    /// Payment URL, e.g. "https://paypal.me/acme/{amount}"
+    pub url: Option<String>,
+    /// Currency code for `{currency}` (default: empty)
+    pub currency: Option<String>,
+    /// Message around the link (default: "{note}: {url}", or just the URL
+    /// without a note)
+    pub message: Option<String>,
+}
+
+/// `[team]` section: for several people answering one shared account
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TeamConfig {
+    /// Your name on the team, recorded on the assignments you make; when
+    /// set, `ig inbox` shows who each thread is assigned to
+    pub operator: Option<String>,
+}
+
+/// `[summarizer]` section: the language model `ig summarize` uses
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SummarizerConfig {
+    pub backend: SummarizerBackend,
+    /// Base URL of the API (e.g. "https://api.openai.com/v1")
+    pub url: Option<String>,
+    /// Model name passed to the API
+    pub model: Option<String>,
+    /// Environment variable holding the API key (none for local servers)
+    pub api_key_env: Option<String>,
+}
+
+/// Kind of API the summarizer talks to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummarizerBackend {
+    /// OpenAI-compatible chat completions (OpenAI, Ollama, llama.cpp, ...)
+    #[default]
+    OpenAi,
+}
+
+/// `[language]` section: tags showing what language a message is in
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LanguageConfig {
+    /// Show language tags at all
+    pub tags: bool,
+    /// Languages never tagged, as ISO 639-1 or 639-3 codes ("en" or "eng")
+    pub untagged: Vec<String>,
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        Self {
+            tags: true,
+            untagged: vec!["en".to_string()],
+        }
+    }
+}
+
+/// `[display]` section: how messages and command output look
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// Show messages of just 1-3 emoji spaced out on a line of their own
+    pub big_emoji: bool,
+    /// Start every command with a line of account and server context
+    pub header: bool,
+    /// Put a colored two-letter badge before each conversation in the inbox
+    pub avatars: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            big_emoji: true,
+            header: false,
+            avatars: false,
+        }
+    }
+}
+
+/// `[translate]` section: where `--translate` sends messages
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TranslateConfig {
+    pub provider: TranslateProvider,
+    /// API base URL (default: the provider's public API)
+    pub url: Option<String>,
+    /// Environment variable holding the API key
+    pub api_key_env: Option<String>,
+    /// Language chat translates into when toggled on (default: "en")
+    pub target: Option<String>,
+}
+
+/// Translation service to use
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslateProvider {
+    /// LibreTranslate (self-hostable)
+    #[default]
+    LibreTranslate,
+    /// DeepL API
+    DeepL,
+}
+
+/// `[alerts]` section: words `ig notify` always raises an alert for
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AlertsConfig {
+    /// Words matched case-insensitively as whole words in incoming
+    /// messages, in any conversation
+    pub keywords: Vec<String>,
+}
+
+/// `[usage]` section: local counting of commands run (`ig usage`)
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct UsageConfig {
+    /// Count each run's subcommand and flags
+    pub track: bool,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self { track: true }
+    }
+}
+
+/// `[notify]` section: when `ig notify` stays quiet
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Local time range with no desktop notifications, e.g. "22:00-07:00"
+    pub quiet_hours: Option<String>,
+}
+
+/// `[thread."<name>"]` section: settings for one conversation
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThreadConfig {
+    /// Color of the conversation's name in the inbox
+    pub color: Option<AccentColor>,
+    pub notify: NotifyMode,
+    /// Keep a transcript of the conversation (see [`TranscriptConfig`])
+    pub transcript: bool,
+}
+
+/// When `ig notify` shows desktop notifications for a conversation
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyMode {
+    /// Except during quiet hours
+    #[default]
+    Default,
+    /// Even during quiet hours
+    Always,
+    /// Never (keyword alerts still go through)
+    Never,
+}
+
+/// Colors a conversation can be given, from the theme's palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccentColor {
+    Yellow,
+    Orange,
+    Pink,
+    Purple,
+    Blue,
+    Red,
+    Green,
+}
+
+impl AccentColor {
+    pub fn paint(self, text: &str) -> ColoredString {
+        match self {
+            AccentColor::Yellow => Theme::yellow(text),
+            AccentColor::Orange => Theme::orange(text),
+            AccentColor::Pink => Theme::pink(text),
+            AccentColor::Purple => Theme::purple(text),
+            AccentColor::Blue => Theme::blue(text),
+            AccentColor::Red => Theme::red(text),
+            AccentColor::Green => Theme::green(text),
+        }
+    }
+}
+
+/// `[cache]` section: how long local copies of messages are kept
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Remove stored message text older than this (e.g. "180d"); unset
+    /// keeps everything. Transcripts are records kept on purpose and only
+    /// follow [`TranscriptConfig::keep`].
+    pub keep_messages: Option<String>,
+    /// Remove files `ig download` saved longer ago than this (e.g. "30d");
+    /// unset keeps them. Nothing else in those directories is touched.
+    pub keep_media: Option<String>,
+}
+
+/// `[transcript]` section: where and how conversations with
+/// `transcript = true` (or all of them) are recorded
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TranscriptConfig {
+    /// Record every conversation, not only those opted in
+    pub all: bool,
+    /// Directory for the transcripts (default: `transcripts/` in the data
+    /// directory); a leading `~/` is the home directory
+    pub dir: Option<String>,
+    pub format: TranscriptFormat,
+    /// Remove daily files older than this (e.g. "730d"); unset keeps
+    /// everything. Applied when a chat or `ig tail` opens the transcript,
+    /// never by `[cache] keep_messages` or `ig cache vacuum`.
+    pub keep: Option<String>,
+}
+
+/// How transcript lines are written
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    /// `2026-10-01 18:00:00 @alice: see you at 6`
+    #[default]
+    Text,
+    /// One JSON object per message
+    Jsonl,
+}
+
+/// `[searches.<name>]` section: a message search saved with `ig search-save`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SavedSearch {
+    /// Text to look for (case-insensitive)
+    pub query: Option<String>,
+    /// Only messages from this user
+    pub from: Option<String>,
+    /// Only messages newer than this (e.g. "30d")
+    pub since: Option<String>,
+    /// Show a desktop notification when `ig search-run` finds new matches
+    pub notify: bool,
+}
+
+impl SavedSearch {
+    /// Write this search to the config file as `[searches.<name>]`,
+    /// replacing one with the same name and keeping the rest of the file
+    /// (comments included) as it was
+    pub fn save(&self, name: &str) -> Result<()> {
+        let path = Config::path()?;
+        let data = if path.exists() {
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+        } else {
+            String::new()
+        };
+        let mut doc: toml_edit::DocumentMut = data
+            .parse()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let searches = doc
+            .entry("searches")
+            .or_insert(toml_edit::table())
+            .as_table_mut()
+            .context("`searches` in the config file is not a table")?;
+        searches.set_implicit(true);
+
+        let mut table = toml_edit::Table::new();
+        for (key, value) in [("query", &self.query), ("from", &self.from), ("since", &self.since)] {
+            if let Some(value) = value {
+                table[key] = toml_edit::value(value.as_str());
+            }
+        }
+        if self.notify {
+            table["notify"] = toml_edit::value(true);
+        }
+        searches[name] = toml_edit::Item::Table(table);
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(&path, doc.to_string()).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// `[http]` section: connection pool tuning (unset means reqwest's default),
+/// proxy and name resolution
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Idle connections kept open to the server
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle connection is kept before closing it
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// Give up connecting to the server after this long
+    pub connect_timeout_secs: Option<u64>,
+    /// TCP keepalive interval for open connections
+    pub tcp_keepalive_secs: Option<u64>,
+    /// Proxy for requests to the server and media downloads, e.g.
+    /// "socks5h://127.0.0.1:9050" (http, https, socks5 and socks5h URLs)
+    pub proxy: Option<String>,
+    /// Connect over "ipv4" or "ipv6" only
+    pub ip_version: Option<IpVersion>,
+    /// DNS servers to resolve hostnames with instead of the system's, e.g.
+    /// "1.1.1.1" or "10.0.0.2:5353"
+    pub dns_servers: Vec<String>,
+    /// Fixed addresses for hostnames, e.g. `localhost = "127.0.0.1"`
+    pub hosts: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Path of the config file
+    pub fn path() -> Result<PathBuf> {
+        Ok(dirs::config_dir()
+            .context("Could not determine the config directory")?
+            .join(APP_DIR)
+            .join("config.toml"))
+    }
+
+    /// Load the config file (defaults if it doesn't exist)
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Settings for a conversation from its `[thread."<name>"]` section,
+    /// matched case-insensitively against its ID, participants' usernames
+    /// (with or without @) and group title
+    pub fn thread_settings(&self, thread: &Thread) -> Option<&ThreadConfig> {
+        self.thread_settings_by(&thread.id, &thread.users, thread.thread_title.as_deref())
+    }
+
+    /// [`Config::thread_settings`] for a conversation known only by its
+    /// parts (e.g. from a sync event, which has no title)
+    pub fn thread_settings_by(&self, id: &str, users: &[User], title: Option<&str>) -> Option<&ThreadConfig> {
+        self.thread.iter().find_map(|(key, settings)| {
+            let key = key.trim().trim_start_matches('@');
+            let matches = key == id
+                || users.iter().any(|u| u.username.eq_ignore_ascii_case(key))
+                || title.is_some_and(|t| t.eq_ignore_ascii_case(key));
+            matches.then_some(settings)
+        })
+    }
+}
+
+/// Seed generated on first login when none is configured
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GeneratedDevice {
+    seed: Option<String>,
+}
+
+/// Device metadata sent with login requests
+///
+/// Uses the configured name and seed; without a configured seed a random one
+/// is generated once and kept in the data directory.
+pub fn device_info() -> Result<DeviceInfo> {
+    let config = Config::load()?.device;
+
+    let seed = match config.user_agent_seed {
+        Some(seed) => seed,
+        None => {
+            let mut generated: GeneratedDevice = store::load(DEVICE_FILE)?;
+            match generated.seed {
+                Some(seed) => seed,
+                None => {
+                    let mut bytes = [0u8; 16];
+                    rand::thread_rng().fill_bytes(&mut bytes);
+                    let seed: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                    generated.seed = Some(seed.clone());
+                    store::save(DEVICE_FILE, &generated)?;
+                    seed
+                }
+            }
+        }
+    };
+
+    let name = config
+        .name
+        .unwrap_or_else(|| format!("insta-cli ({})", std::env::consts::OS));
+
+    Ok(DeviceInfo {
+        name,
+        user_agent_seed: seed,
+    })
+}
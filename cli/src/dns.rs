@@ -0,0 +1,148 @@
+//! Name resolution for requests to the server
+//!
+//! Hostnames are normally resolved by the system, like for any other
+//! program. Where that goes wrong (a corporate DNS that answers for
+//! `localhost`, a network with a broken IPv6 route), the `[http]` section
+//! can pin hostnames to addresses (`hosts`), send lookups to particular DNS
+//! servers (`dns_servers`) and keep to one address family (`ip_version`,
+//! or `-4`/`-6` for one run).
+
+use anyhow::{bail, Context, Result};
+use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+use hickory_resolver::TokioAsyncResolver;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+
+use crate::config::HttpConfig;
+
+/// Port for DNS servers given without one
+const DNS_PORT: u16 = 53;
+
+/// Address family to connect over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpVersion {
+    Ipv4,
+    Ipv6,
+}
+
+impl IpVersion {
+    fn allows(self, ip: &IpAddr) -> bool {
+        match self {
+            IpVersion::Ipv4 => ip.is_ipv4(),
+            IpVersion::Ipv6 => ip.is_ipv6(),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            IpVersion::Ipv4 => "IPv4",
+            IpVersion::Ipv6 => "IPv6",
+        }
+    }
+}
+
+/// Address family given on the command line, over the config's
+static IP_VERSION_OVERRIDE: OnceLock<IpVersion> = OnceLock::new();
+
+/// Connect over `version` only for this run (from `-4`/`-6`)
+pub fn use_ip_version(version: IpVersion) {
+    let _ = IP_VERSION_OVERRIDE.set(version);
+}
+
+/// Resolver following the `[http]` settings
+#[derive(Clone)]
+pub struct Resolver {
+    hosts: HashMap<String, IpAddr>,
+    /// Lookups go to these servers instead of the system's
+    servers: Option<TokioAsyncResolver>,
+    ip_version: Option<IpVersion>,
+}
+
+impl Resolver {
+    /// The resolver for `config`; None when name resolution is left to the
+    /// system. Errors for an address that doesn't parse.
+    pub fn new(config: &HttpConfig) -> Result<Option<Self>> {
+        let ip_version = IP_VERSION_OVERRIDE.get().copied().or(config.ip_version);
+        if config.hosts.is_empty() && config.dns_servers.is_empty() && ip_version.is_none() {
+            return Ok(None);
+        }
+
+        let hosts = config
+            .hosts
+            .iter()
+            .map(|(host, ip)| {
+                let ip = ip
+                    .parse()
+                    .with_context(|| format!("Invalid address '{}' for {} under [http.hosts]", ip, host))?;
+                Ok((host.to_lowercase(), ip))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        let servers = if config.dns_servers.is_empty() {
+            None
+        } else {
+            let mut group = NameServerConfigGroup::new();
+            for server in &config.dns_servers {
+                let address = parse_server(server)?;
+                group.merge(NameServerConfigGroup::from_ips_clear(&[address.ip()], address.port(), true));
+            }
+            let config = ResolverConfig::from_parts(None, vec![], group);
+            Some(TokioAsyncResolver::tokio(config, ResolverOpts::default()))
+        };
+
+        Ok(Some(Self { hosts, servers, ip_version }))
+    }
+
+    /// Addresses for `host`, in the order to try them
+    pub async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>> {
+        if let Some(ip) = self.hosts.get(&host.to_lowercase()) {
+            return Ok(vec![*ip]);
+        }
+        let mut addresses: Vec<IpAddr> = match &self.servers {
+            Some(servers) => servers
+                .lookup_ip(host)
+                .await
+                .with_context(|| format!("Could not resolve {}", host))?
+                .iter()
+                .collect(),
+            None => tokio::net::lookup_host((host, 0))
+                .await
+                .with_context(|| format!("Could not resolve {}", host))?
+                .map(|a| a.ip())
+                .collect(),
+        };
+        if let Some(version) = self.ip_version {
+            addresses.retain(|ip| version.allows(ip));
+            if addresses.is_empty() {
+                bail!("{} has no {} address", host, version.label());
+            }
+        }
+        Ok(addresses)
+    }
+}
+
+impl Resolve for Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addresses = resolver.lookup(name.as_str()).await?;
+            // The port comes from the URL, not from here
+            let addrs: Addrs = Box::new(addresses.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+/// A DNS server as "1.1.1.1", "1.1.1.1:5353" or "[2606:4700::1111]:53"
+fn parse_server(server: &str) -> Result<SocketAddr> {
+    if let Ok(ip) = server.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, DNS_PORT));
+    }
+    server
+        .parse()
+        .with_context(|| format!("Invalid DNS server '{}' (expected an IP address, optionally with a port)", server))
+}
@@ -0,0 +1,170 @@
+//! Human-friendly durations for flags and config values
+//!
+//! Everything that takes a length of time (`--since`, `--interval`,
+//! reminders, follow-ups, `keep_messages`, `keep_media`, ...) goes through
+//! [`parse`], so they all accept the same forms: `45s`, `2m`, `1h30m`,
+//! `7d`, `2w` (long unit names like `90 minutes` work too). [`Span`] and [`Interval`] plug
+//! into clap, so bad values are rejected before a command runs.
+
+use chrono::Duration;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// What a bare number without a unit means
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BareUnit {
+    Seconds,
+    Hours,
+}
+
+/// The longest duration accepted (100 years): anything longer would run
+/// past the dates chrono can represent once added to or taken from now
+pub const MAX_DAYS: i64 = 36_500;
+
+/// Why a duration couldn't be parsed
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DurationError {
+    #[error("empty duration (expected e.g. 45s, 2m, 1h30m, 7d)")]
+    Empty,
+    #[error("invalid duration '{0}' (expected e.g. 45s, 2m, 1h30m, 7d)")]
+    Invalid(String),
+    #[error("unknown unit '{unit}' in '{value}' (use s, m, h, d or w)")]
+    UnknownUnit { unit: String, value: String },
+    #[error("'{0}' needs a unit after every number (e.g. 1h30m)")]
+    MissingUnit(String),
+    #[error("duration '{0}' is too long")]
+    TooLong(String),
+    #[error("interval must be at least 1s")]
+    Zero,
+}
+
+/// Parse a duration like "45s", "2m", "1h30m" or "7d"; `bare` says what a
+/// lone number ("30") means
+pub fn parse(value: &str, bare: BareUnit) -> Result<Duration, DurationError> {
+    let text = value.trim();
+    if text.is_empty() {
+        return Err(DurationError::Empty);
+    }
+    let invalid = || DurationError::Invalid(text.to_string());
+    let too_long = || DurationError::TooLong(text.to_string());
+
+    let mut total = Duration::zero();
+    let mut rest = text;
+    let mut parts = 0;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            return Err(invalid());
+        }
+        let amount: i64 = rest[..digits].parse().map_err(|_| too_long())?;
+        rest = rest[digits..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = rest[..unit_len].to_ascii_lowercase();
+        rest = rest[unit_len..].trim_start();
+
+        let seconds = match unit.as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3_600,
+            "d" | "day" | "days" => 86_400,
+            "w" | "wk" | "week" | "weeks" => 604_800,
+            "" if parts == 0 && rest.is_empty() => match bare {
+                BareUnit::Seconds => 1,
+                BareUnit::Hours => 3_600,
+            },
+            "" if !rest.is_empty() && !rest.starts_with(|c: char| c.is_ascii_digit()) => return Err(invalid()),
+            "" => return Err(DurationError::MissingUnit(text.to_string())),
+            _ => {
+                return Err(DurationError::UnknownUnit {
+                    unit,
+                    value: text.to_string(),
+                })
+            }
+        };
+        let part = amount
+            .checked_mul(seconds)
+            .and_then(Duration::try_seconds)
+            .ok_or_else(too_long)?;
+        total = total.checked_add(&part).ok_or_else(too_long)?;
+        parts += 1;
+    }
+    if total > Duration::days(MAX_DAYS) {
+        return Err(too_long());
+    }
+    Ok(total)
+}
+
+/// Parse a duration for a time window or delay (bare numbers are hours)
+pub fn parse_duration(value: &str) -> anyhow::Result<Duration> {
+    Ok(parse(value, BareUnit::Hours)?)
+}
+
+/// Short form of a duration, largest units first ("1h30m", "45s")
+pub fn format_duration(duration: Duration) -> String {
+    let mut secs = duration.num_seconds().max(0);
+    if secs == 0 {
+        return "0s".to_string();
+    }
+    let mut out = String::new();
+    for (size, unit) in [(86_400, "d"), (3_600, "h"), (60, "m"), (1, "s")] {
+        if secs >= size {
+            out.push_str(&format!("{}{}", secs / size, unit));
+            secs %= size;
+        }
+    }
+    out
+}
+
+/// A time window or delay given on the command line (`--since 7d`,
+/// `ig remind @user 1h30m`); keeps the text as written for messages and
+/// the config file
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub text: String,
+    pub duration: Duration,
+}
+
+impl FromStr for Span {
+    type Err = DurationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            text: value.trim().to_string(),
+            duration: parse(value, BareUnit::Hours)?,
+        })
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// How often to poll (`--interval 30s`); bare numbers are seconds, and it
+/// can't be zero
+#[derive(Debug, Clone, Copy)]
+pub struct Interval(pub std::time::Duration);
+
+impl FromStr for Interval {
+    type Err = DurationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let duration = parse(value, BareUnit::Seconds)?;
+        match duration.to_std() {
+            Ok(duration) if !duration.is_zero() => Ok(Self(duration)),
+            _ => Err(DurationError::Zero),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let duration = Duration::from_std(self.0).unwrap_or(Duration::MAX);
+        f.write_str(&format_duration(duration))
+    }
+}
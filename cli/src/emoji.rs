@@ -0,0 +1,66 @@
+//! Big emoji
+//!
+//! Like the official apps, a message of just one to three emoji (❤️, 😂😂,
+//! 👍🏽🎉🔥) stands out from the text around it. A terminal can't change the
+//! font size, so such messages go on a line of their own, spaced out and
+//! set off by blank lines. `[display] big_emoji = false` in config.toml
+//! turns it off.
+
+use std::sync::OnceLock;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::config::Config;
+
+/// Most emoji a message can have and still be shown big
+const MAX_BIG: usize = 3;
+
+/// Spaces between big emoji
+const GAP: &str = "   ";
+
+/// Indent of a big emoji line
+const INDENT: &str = "    ";
+
+fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| Config::load().map(|c| c.display.big_emoji).unwrap_or(true))
+}
+
+/// Whether a character starts an emoji presentation
+fn is_emoji_char(c: char) -> bool {
+    matches!(
+        u32::from(c),
+        0x1F000..=0x1FAFF // pictographs, emoticons, flags, symbols
+            | 0x2600..=0x27BF // miscellaneous symbols, dingbats
+            | 0x2300..=0x23FF // ⌚ ⏰ ...
+            | 0x2B00..=0x2BFF // ⬆ ⭐ ...
+            | 0x2190..=0x21FF // arrows
+            | 0x3030 | 0x303D | 0x3297 | 0x3299
+            | 0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139
+    )
+}
+
+/// Whether a grapheme cluster is one emoji (including skin tones, ZWJ
+/// sequences, flags and keycaps such as 1️⃣)
+fn is_emoji(grapheme: &str) -> bool {
+    let Some(first) = grapheme.chars().next() else {
+        return false;
+    };
+    is_emoji_char(first) || grapheme.contains('\u{20E3}')
+}
+
+/// The emoji of a message made of only 1-3 of them (spaces allowed)
+pub fn emoji_only(text: &str) -> Option<Vec<&str>> {
+    let graphemes: Vec<&str> = text.graphemes(true).filter(|g| !g.trim().is_empty()).collect();
+    let fits = (1..=MAX_BIG).contains(&graphemes.len()) && graphemes.iter().all(|g| is_emoji(g));
+    fits.then_some(graphemes)
+}
+
+/// `text` as an indented, spaced-out line, if it's an emoji-only message
+/// and big emoji are on. Callers set it off with blank lines.
+pub fn big(text: &str) -> Option<String> {
+    if !enabled() {
+        return None;
+    }
+    let emoji = emoji_only(text)?;
+    Some(format!("{}{}", INDENT, emoji.join(GAP)))
+}
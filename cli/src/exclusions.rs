@@ -0,0 +1,60 @@
+//! Threads excluded from syncing (`ig sync exclude`)
+//!
+//! Background work never touches an excluded conversation: the sync engine
+//! skips it when watching the inbox (`ig notify`, `ig tail`), its responses
+//! are not cached or prefetched, and saved searches and exports leave it
+//! out. Opening it directly with `ig thread` or `ig chat` still works,
+//! live updates included.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::store;
+
+/// Data file holding the exclusion list
+const EXCLUSIONS_FILE: &str = "exclusions";
+
+/// An excluded conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Excluded {
+    pub thread_id: String,
+    /// Who the conversation is with, for listings
+    pub label: String,
+    pub added: DateTime<Local>,
+}
+
+/// All excluded conversations
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Exclusions {
+    pub threads: Vec<Excluded>,
+}
+
+impl Exclusions {
+    /// Load the exclusion list from disk
+    pub fn load() -> Result<Self> {
+        store::load(EXCLUSIONS_FILE)
+    }
+
+    /// Save the exclusion list to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(EXCLUSIONS_FILE, self)
+    }
+
+    pub fn contains(&self, thread_id: &str) -> bool {
+        self.threads.iter().any(|t| t.thread_id == thread_id)
+    }
+}
+
+/// IDs of excluded threads (empty if the list can't be read)
+pub fn excluded_ids() -> HashSet<String> {
+    Exclusions::load()
+        .map(|e| e.threads.into_iter().map(|t| t.thread_id).collect())
+        .unwrap_or_default()
+}
+
+/// Whether a thread is excluded from syncing
+pub fn is_excluded(thread_id: &str) -> bool {
+    Exclusions::load().is_ok_and(|e| e.contains(thread_id))
+}
@@ -0,0 +1,108 @@
+//! Startup header
+//!
+//! With `[display] header = true` in config.toml, every command starts with
+//! one line of context: `@me • 3 unread • server ok • profile: work`. It is
+//! put together from what earlier runs saw (the account from login and
+//! `ig status`, unread conversations from the last inbox fetch, whether the
+//! server answered last time), so it never costs a request. Parts that
+//! aren't known yet are left out.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::{self, IsTerminal};
+
+use crate::colors::Theme;
+use crate::config::Config;
+use crate::http::HttpStats;
+use crate::models::Thread;
+use crate::output::OutputFormat;
+use crate::store;
+
+/// Data file holding what the header shows
+const HEADER_FILE: &str = "header";
+
+/// What earlier runs learned about the account and server
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Ambient {
+    /// Logged-in account
+    pub username: Option<String>,
+    /// Unread conversations in the last inbox fetch
+    pub unread: Option<usize>,
+    /// Whether the server answered every request of the last run that sent any
+    pub server_ok: Option<bool>,
+}
+
+impl Ambient {
+    pub fn load() -> Result<Self> {
+        store::load(HEADER_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        store::save(HEADER_FILE, self)
+    }
+
+    /// The header line, or None if nothing is known yet
+    pub fn line(&self, profile: Option<&str>) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(username) = &self.username {
+            parts.push(Theme::username(&format!("@{}", username)).to_string());
+        }
+        match self.unread {
+            Some(0) => parts.push(Theme::muted("no unread").to_string()),
+            Some(n) => parts.push(Theme::accent(&format!("{} unread", n)).to_string()),
+            None => {}
+        }
+        match self.server_ok {
+            Some(true) => parts.push(Theme::success("server ok").to_string()),
+            Some(false) => parts.push(Theme::error("server unreachable").to_string()),
+            None => {}
+        }
+        if let Some(profile) = profile {
+            parts.push(Theme::muted(&format!("profile: {}", profile)).to_string());
+        }
+        if parts.is_empty() {
+            return None;
+        }
+        Some(parts.join(&Theme::muted(" • ").to_string()))
+    }
+}
+
+/// Update the saved state. Best effort: the header is only a convenience,
+/// so a data directory that can't be written never fails the command.
+fn update(change: impl FnOnce(&mut Ambient)) {
+    let Ok(mut ambient) = Ambient::load() else {
+        return;
+    };
+    change(&mut ambient);
+    let _ = ambient.save();
+}
+
+/// Remember the logged-in account (None after logging out)
+pub fn remember_user(username: Option<&str>) {
+    update(|a| a.username = username.map(str::to_string));
+}
+
+/// Remember how many of the fetched conversations are unread
+pub fn remember_unread(threads: &[Thread]) {
+    let unread = threads.iter().filter(|t| t.has_unread.unwrap_or(false)).count();
+    update(|a| a.unread = Some(unread));
+}
+
+/// Remember whether the server answered this run's requests
+pub fn remember_server(stats: &HttpStats) {
+    if stats.requests() == 0 {
+        return;
+    }
+    update(|a| a.server_ok = Some(stats.failures() == 0));
+}
+
+/// Print the header if it's turned on and the output is for a person
+pub fn print(output: OutputFormat, profile: Option<&str>) {
+    let enabled = Config::load().is_ok_and(|c| c.display.header);
+    if !enabled || output != OutputFormat::Plain || !io::stdout().is_terminal() {
+        return;
+    }
+    if let Some(line) = Ambient::load().ok().and_then(|a| a.line(profile)) {
+        println!("{}", line);
+    }
+}
@@ -0,0 +1,349 @@
+//! Shared HTTP plumbing for [`ApiClient`](crate::client::ApiClient)
+//!
+//! Every `ApiClient` clone shares one [`Http`]: one reqwest client (and so
+//! one connection pool), one response cache and one set of counters. The
+//! pool is tuned from the `[http]` section of the config file, and the
+//! counters show how well connections are reused and how much was sent and
+//! received (`ig --verbose`, `ig stats --api`).
+//!
+//! With a proxy (`proxy` under `[http]`, or `--tor`), requests to the
+//! server and media downloads go through it; HTTP and SOCKS5 proxies work.
+//! Hostnames are resolved by the system unless `[http]` says otherwise (see
+//! [`dns`](crate::dns)).
+//!
+//! Requests go out through [`CountedRequest`] and come back as
+//! [`CountedResponse`], thin wrappers over reqwest's that keep the counters
+//! up to date, so no call site has to remember to.
+
+use anyhow::Context as _;
+use reqwest::header::{HeaderMap, CONTENT_LENGTH};
+use reqwest::multipart::Form;
+use reqwest::{Client, ClientBuilder, Proxy, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::config::{Config, HttpConfig};
+use crate::dns::Resolver;
+use crate::responses::ResponseCache;
+
+/// Tor's SOCKS port on this machine; `socks5h` so hostnames are resolved
+/// by Tor too rather than leaking through local DNS
+pub const TOR_PROXY: &str = "socks5h://127.0.0.1:9050";
+
+/// Proxy given on the command line, over the config's
+static PROXY_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Route this run through `url` (from `--tor`), whatever the config says
+pub fn use_proxy(url: &str) {
+    let _ = PROXY_OVERRIDE.set(url.to_string());
+}
+
+/// The proxy requests go through, if any
+pub fn proxy_url() -> Option<String> {
+    PROXY_OVERRIDE
+        .get()
+        .cloned()
+        .or_else(|| Config::load().ok().and_then(|c| c.http.proxy))
+}
+
+/// The proxy as reqwest takes it; an error for a malformed proxy URL
+pub fn proxy() -> anyhow::Result<Option<Proxy>> {
+    proxy_url()
+        .map(|url| Proxy::all(&url).with_context(|| format!("Invalid proxy URL '{}'", url)))
+        .transpose()
+}
+
+/// Host and port a proxy URL points at (SOCKS proxies default to 1080)
+pub fn proxy_address(url: &str) -> Option<(String, u16)> {
+    let url = Url::parse(url).ok()?;
+    let port = url.port_or_known_default().unwrap_or(1080);
+    Some((url.host_str()?.to_string(), port))
+}
+
+/// The resolver for the `[http]` name resolution settings, if any; an
+/// error for an address that doesn't parse
+pub fn resolver() -> anyhow::Result<Option<Resolver>> {
+    Resolver::new(&Config::load().map(|c| c.http).unwrap_or_default())
+}
+
+/// Apply the proxy and name resolution settings to a client builder.
+/// Malformed settings are rejected when the command starts, so they're
+/// skipped here.
+pub fn with_network(mut builder: ClientBuilder) -> ClientBuilder {
+    if let Ok(Some(proxy)) = proxy() {
+        builder = builder.proxy(proxy);
+    }
+    if let Ok(Some(resolver)) = resolver() {
+        builder = builder.dns_resolver(Arc::new(resolver));
+    }
+    builder
+}
+
+/// State shared by all clones of an `ApiClient`
+pub struct Http {
+    pub client: Client,
+    /// Last 200 response per URL for polled endpoints
+    pub cache: Mutex<ResponseCache>,
+    pub stats: Arc<HttpStats>,
+}
+
+impl Http {
+    /// Build the shared client, with or without response compression. An
+    /// error when the client can't be built, rather than falling back to
+    /// one that ignores the proxy.
+    pub fn new(compression: bool) -> anyhow::Result<Self> {
+        let config = Config::load().map(|c| c.http).unwrap_or_default();
+        let stats = Arc::new(HttpStats::default());
+
+        let builder = with_network(Client::builder())
+            .gzip(compression)
+            .connector_layer(CountConnections(Arc::clone(&stats)));
+        let client = apply_pool_config(builder, &config)
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            cache: Mutex::default(),
+            stats,
+        })
+    }
+}
+
+/// Apply the `[http]` config to a client builder
+fn apply_pool_config(mut builder: ClientBuilder, config: &HttpConfig) -> ClientBuilder {
+    if let Some(max) = config.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max);
+    }
+    if let Some(secs) = config.pool_idle_timeout_secs {
+        builder = builder.pool_idle_timeout(Duration::from_secs(secs));
+    }
+    if let Some(secs) = config.connect_timeout_secs {
+        builder = builder.connect_timeout(Duration::from_secs(secs));
+    }
+    if let Some(secs) = config.tcp_keepalive_secs {
+        builder = builder.tcp_keepalive(Duration::from_secs(secs));
+    }
+    builder
+}
+
+/// Request, connection and traffic counters
+#[derive(Debug, Default)]
+pub struct HttpStats {
+    requests: AtomicUsize,
+    connections: AtomicUsize,
+    bytes_sent: AtomicUsize,
+    bytes_received: AtomicUsize,
+    cache_hits: AtomicUsize,
+    failures: AtomicUsize,
+    retries: AtomicUsize,
+}
+
+impl HttpStats {
+    /// Count a request about to be sent, with `body` bytes of payload
+    pub fn record_request(&self, body: usize) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(body, Ordering::Relaxed);
+    }
+
+    /// Count `bytes` of response body read
+    pub fn record_received(&self, bytes: usize) {
+        self.bytes_received.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Count a 304 answered from the response cache
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a request that got no response (connection refused, timeout)
+    pub fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a poll repeated right away after a failure (the sync task
+    /// catching up once the server is back)
+    pub fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Requests sent so far
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// New connections opened so far (the rest of the requests reused one)
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// Request body bytes sent so far
+    pub fn bytes_sent(&self) -> usize {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    /// Response body bytes read so far (after decompression)
+    pub fn bytes_received(&self) -> usize {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Requests the server answered with 304 Not Modified
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// Requests that got no response so far
+    pub fn failures(&self) -> usize {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Polls retried after a failure so far
+    pub fn retries(&self) -> usize {
+        self.retries.load(Ordering::Relaxed)
+    }
+}
+
+/// A request being built, counted when it is sent
+pub struct CountedRequest {
+    builder: RequestBuilder,
+    stats: Arc<HttpStats>,
+}
+
+impl CountedRequest {
+    pub fn new(builder: RequestBuilder, stats: Arc<HttpStats>) -> Self {
+        Self { builder, stats }
+    }
+
+    pub fn header(self, name: impl AsRef<str>, value: impl ToString) -> Self {
+        let builder = self.builder.header(name.as_ref(), value.to_string());
+        Self { builder, ..self }
+    }
+
+    pub fn query<T: Serialize + ?Sized>(self, query: &T) -> Self {
+        let builder = self.builder.query(query);
+        Self { builder, ..self }
+    }
+
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> Self {
+        let builder = self.builder.json(json);
+        Self { builder, ..self }
+    }
+
+    pub fn multipart(self, form: Form) -> Self {
+        let builder = self.builder.multipart(form);
+        Self { builder, ..self }
+    }
+
+    /// Send the request, counting it and its body
+    ///
+    /// Streamed bodies (multipart uploads) are counted by the Content-Length
+    /// reqwest sets for them.
+    pub async fn send(self) -> reqwest::Result<CountedResponse> {
+        let (client, request) = self.builder.build_split();
+        let request = request?;
+        let body = match request.body().and_then(|b| b.as_bytes()) {
+            Some(bytes) => bytes.len(),
+            None => request
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+        };
+        self.stats.record_request(body);
+        let response = client.execute(request).await.inspect_err(|_| self.stats.record_failure())?;
+        Ok(CountedResponse {
+            response,
+            stats: self.stats,
+        })
+    }
+}
+
+/// A response whose body is counted as it is read
+pub struct CountedResponse {
+    response: Response,
+    stats: Arc<HttpStats>,
+}
+
+impl CountedResponse {
+    pub fn status(&self) -> StatusCode {
+        self.response.status()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    /// Body size, when the server sent a Content-Length
+    pub fn content_length(&self) -> Option<u64> {
+        self.response.content_length()
+    }
+
+    /// Read the next piece of the body; None once it has all been read
+    pub async fn chunk(&mut self) -> reqwest::Result<Option<Vec<u8>>> {
+        let chunk = self.response.chunk().await?;
+        if let Some(chunk) = &chunk {
+            self.stats.record_received(chunk.len());
+        }
+        Ok(chunk.map(|c| c.to_vec()))
+    }
+
+    /// Read the whole body
+    pub async fn bytes(self) -> reqwest::Result<Vec<u8>> {
+        let body = self.response.bytes().await?;
+        self.stats.record_received(body.len());
+        Ok(body.to_vec())
+    }
+
+    /// Read the body as JSON
+    pub async fn json<T: DeserializeOwned>(self) -> anyhow::Result<T> {
+        let body = self.bytes().await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+/// Connector layer that counts every new connection
+#[derive(Clone)]
+struct CountConnections(Arc<HttpStats>);
+
+impl<S> Layer<S> for CountConnections {
+    type Service = CountingConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CountingConnector {
+            inner,
+            stats: Arc::clone(&self.0),
+        }
+    }
+}
+
+/// Connector wrapper created by [`CountConnections`]
+#[derive(Clone)]
+struct CountingConnector<S> {
+    inner: S,
+    stats: Arc<HttpStats>,
+}
+
+impl<S, R> Service<R> for CountingConnector<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        self.stats.connections.fetch_add(1, Ordering::Relaxed);
+        self.inner.call(request)
+    }
+}
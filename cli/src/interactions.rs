@@ -0,0 +1,74 @@
+//! Who we message, how often and how recently
+//!
+//! Every sent message bumps a per-user score that halves every
+//! [`HALF_LIFE_DAYS`], so completions can put the people messaged most and
+//! most recently first.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::store;
+
+/// Data file holding interaction scores
+const INTERACTIONS_FILE: &str = "interactions";
+
+/// Days for an interaction's weight to halve
+const HALF_LIFE_DAYS: f64 = 14.0;
+
+/// Decayed message count for one user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    /// Score as of `last`
+    score: f64,
+    last: DateTime<Local>,
+}
+
+impl Interaction {
+    /// Score decayed to `now`
+    fn score_at(&self, now: DateTime<Local>) -> f64 {
+        let days = (now - self.last).num_seconds().max(0) as f64 / 86_400.0;
+        self.score * 0.5f64.powf(days / HALF_LIFE_DAYS)
+    }
+}
+
+/// Interaction scores keyed by lowercase username
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Interactions {
+    users: HashMap<String, Interaction>,
+}
+
+impl Interactions {
+    /// Load scores from disk
+    pub fn load() -> Result<Self> {
+        store::load(INTERACTIONS_FILE)
+    }
+
+    /// Save scores to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(INTERACTIONS_FILE, self)
+    }
+
+    /// Count one message to `username`
+    pub fn record(&mut self, username: &str) {
+        let now = Local::now();
+        let key = username.trim_start_matches('@').to_lowercase();
+        let score = self.users.get(&key).map_or(0.0, |i| i.score_at(now));
+        self.users.insert(key, Interaction { score: score + 1.0, last: now });
+    }
+
+    /// Current score for `username` (0 if never messaged)
+    pub fn score(&self, username: &str) -> f64 {
+        self.users
+            .get(&username.to_lowercase())
+            .map_or(0.0, |i| i.score_at(Local::now()))
+    }
+}
+
+/// Count one message to `username` on disk (best effort)
+pub fn record_message(username: &str) {
+    let mut interactions = Interactions::load().unwrap_or_default();
+    interactions.record(username);
+    let _ = interactions.save();
+}
@@ -0,0 +1,126 @@
+//! Language tags for incoming messages
+//!
+//! Messages are run through offline language detection (whatlang) and get a
+//! small tag such as `[pt]` when the detection is confident enough, so it's clear
+//! before translating what a message is in. Languages listed under
+//! `[language] untagged` in config.toml (English by default) are left alone.
+
+use std::sync::OnceLock;
+use whatlang::Lang;
+
+use crate::colors::Theme;
+use crate::config::{Config, LanguageConfig};
+
+/// Detection confidence needed to tag a message. whatlang's own
+/// "reliable" bar rejects most chat-length messages, while below this short
+/// messages ("ok see you") are often misdetected.
+const MIN_CONFIDENCE: f64 = 0.3;
+
+/// ISO 639-1 codes for the languages whatlang detects (it reports 639-3)
+const TWO_LETTER_CODES: &[(Lang, &str)] = &[
+    (Lang::Epo, "eo"),
+    (Lang::Eng, "en"),
+    (Lang::Rus, "ru"),
+    (Lang::Cmn, "zh"),
+    (Lang::Spa, "es"),
+    (Lang::Por, "pt"),
+    (Lang::Ita, "it"),
+    (Lang::Ben, "bn"),
+    (Lang::Fra, "fr"),
+    (Lang::Deu, "de"),
+    (Lang::Ukr, "uk"),
+    (Lang::Kat, "ka"),
+    (Lang::Ara, "ar"),
+    (Lang::Hin, "hi"),
+    (Lang::Jpn, "ja"),
+    (Lang::Heb, "he"),
+    (Lang::Yid, "yi"),
+    (Lang::Pol, "pl"),
+    (Lang::Amh, "am"),
+    (Lang::Jav, "jv"),
+    (Lang::Kor, "ko"),
+    (Lang::Nob, "nb"),
+    (Lang::Dan, "da"),
+    (Lang::Swe, "sv"),
+    (Lang::Fin, "fi"),
+    (Lang::Tur, "tr"),
+    (Lang::Nld, "nl"),
+    (Lang::Hun, "hu"),
+    (Lang::Ces, "cs"),
+    (Lang::Ell, "el"),
+    (Lang::Bul, "bg"),
+    (Lang::Bel, "be"),
+    (Lang::Mar, "mr"),
+    (Lang::Kan, "kn"),
+    (Lang::Ron, "ro"),
+    (Lang::Slv, "sl"),
+    (Lang::Hrv, "hr"),
+    (Lang::Srp, "sr"),
+    (Lang::Mkd, "mk"),
+    (Lang::Lit, "lt"),
+    (Lang::Lav, "lv"),
+    (Lang::Est, "et"),
+    (Lang::Tam, "ta"),
+    (Lang::Vie, "vi"),
+    (Lang::Urd, "ur"),
+    (Lang::Tha, "th"),
+    (Lang::Guj, "gu"),
+    (Lang::Uzb, "uz"),
+    (Lang::Pan, "pa"),
+    (Lang::Aze, "az"),
+    (Lang::Ind, "id"),
+    (Lang::Tel, "te"),
+    (Lang::Pes, "fa"),
+    (Lang::Mal, "ml"),
+    (Lang::Ori, "or"),
+    (Lang::Mya, "my"),
+    (Lang::Nep, "ne"),
+    (Lang::Sin, "si"),
+    (Lang::Khm, "km"),
+    (Lang::Tuk, "tk"),
+    (Lang::Aka, "ak"),
+    (Lang::Zul, "zu"),
+    (Lang::Sna, "sn"),
+    (Lang::Afr, "af"),
+    (Lang::Lat, "la"),
+    (Lang::Slk, "sk"),
+    (Lang::Cat, "ca"),
+    (Lang::Tgl, "tl"),
+    (Lang::Hye, "hy"),
+];
+
+fn config() -> &'static LanguageConfig {
+    static CONFIG: OnceLock<LanguageConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| Config::load().map(|c| c.language).unwrap_or_default())
+}
+
+/// Short code for a language ("pt"), falling back to its 639-3 code
+fn short_code(lang: Lang) -> &'static str {
+    TWO_LETTER_CODES
+        .iter()
+        .find(|(l, _)| *l == lang)
+        .map_or(lang.code(), |(_, code)| code)
+}
+
+/// Language code to tag `text` with, if it's confidently detected and not
+/// in the untagged list
+pub fn detect(text: &str) -> Option<&'static str> {
+    let config = config();
+    if !config.tags {
+        return None;
+    }
+    let info = whatlang::detect(text).filter(|info| info.confidence() >= MIN_CONFIDENCE)?;
+    let code = short_code(info.lang());
+    let untagged = config
+        .untagged
+        .iter()
+        .any(|u| u.eq_ignore_ascii_case(code) || u.eq_ignore_ascii_case(info.lang().code()));
+    (!untagged).then_some(code)
+}
+
+/// Muted `[pt] ` tag for `text`, or nothing
+pub fn tag(text: &str) -> String {
+    detect(text)
+        .map(|code| format!("{} ", Theme::muted(&format!("[{}]", code))))
+        .unwrap_or_default()
+}
@@ -0,0 +1,49 @@
+//! Instagram DM CLI library
+//!
+//! Everything behind the `ig` binary: the API client for the local
+//! Python/FastAPI server, the commands, local storage and terminal output.
+//! Split out of the binary so integration tests can drive it directly.
+
+pub mod aliases;
+pub mod analytics;
+pub mod audit;
+pub mod banner;
+pub mod catalog;
+pub mod checkpoint;
+pub mod client;
+pub mod clipboard;
+pub mod colors;
+pub mod commands;
+pub mod completer;
+pub mod config;
+pub mod crypto;
+pub mod dns;
+pub mod duration;
+pub mod emoji;
+pub mod exclusions;
+pub mod header;
+pub mod http;
+pub mod interactions;
+pub mod language;
+pub mod markdown;
+pub mod models;
+pub mod nicknames;
+pub mod notify;
+pub mod output;
+pub mod profile;
+pub mod reconcile;
+pub mod render;
+pub mod responses;
+pub mod snapshot;
+pub mod spinner;
+pub mod store;
+pub mod summarizer;
+pub mod sync;
+pub mod tasks;
+pub mod transcript;
+pub mod translate;
+pub mod usage;
+pub mod users;
+pub mod util;
+pub mod verbose;
+pub mod voice;
@@ -3,21 +3,26 @@
 //! A command-line interface for Instagram Direct Messages.
 //! Communicates with a local Python/FastAPI server that handles Instagram API.
 
-mod client;
-mod colors;
-mod commands;
-mod completer;
-mod crypto;
-mod models;
-mod spinner;
-
 use anyhow::Result;
-use clap::{CommandFactory, Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use clap_complete::{generate, Shell};
+use std::ffi::OsString;
 use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use client::ApiClient;
-use colors::Theme;
+use insta_cli::aliases;
+use insta_cli::banner::{self, BannerFont};
+use insta_cli::client::ApiClient;
+use insta_cli::colors::{self, Theme, ThemeName};
+use insta_cli::commands;
+use insta_cli::config::{Config, Permission, SavedSearch};
+use insta_cli::dns::{self, IpVersion};
+use insta_cli::duration::{Interval, Span};
+use insta_cli::output::OutputFormat;
+use insta_cli::snapshot::InboxSnapshot;
+use insta_cli::{analytics, header, http, nicknames, profile, usage, verbose};
 
 /// Instagram DM CLI - Manage your Instagram DMs from the terminal
 #[derive(Parser)]
@@ -28,14 +33,57 @@ struct Cli {
     #[arg(short, long, global = true)]
     server: Option<String>,
 
+    /// Profile from config.toml to use (server, permissions and signature)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Color theme (default, high-contrast, colorblind)
+    #[arg(long, global = true, value_enum, default_value_t = ThemeName::Default)]
+    theme: ThemeName,
+
+    /// Disable colored output for this command
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Output format for listings (plain, table, json; csv for ig stats and ig heatmap)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Plain)]
+    output: OutputFormat,
+
+    /// Print diagnostics (HTTP connection reuse and traffic, malformed data) to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Send requests through Tor (a SOCKS proxy on 127.0.0.1:9050)
+    #[arg(long, global = true)]
+    tor: bool,
+
+    /// Show usernames instead of nicknames for this command
+    #[arg(long, global = true)]
+    real_names: bool,
+
+    /// Connect to the server over IPv4 only
+    #[arg(short = '4', long, global = true, conflicts_with = "ipv6")]
+    ipv4: bool,
+
+    /// Connect to the server over IPv6 only
+    #[arg(short = '6', long, global = true)]
+    ipv6: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Show the IG DM CLI banner
-    Banner,
+    /// Show the IG DM CLI banner, or render your own text (ig banner "hello")
+    Banner {
+        /// Text to render (default: the classic banner)
+        text: Option<String>,
+
+        /// Font for custom text
+        #[arg(long, value_enum, default_value_t = BannerFont::Block)]
+        font: BannerFont,
+    },
 
     /// Login to Instagram (interactive prompts for credentials)
     Login {
@@ -46,6 +94,15 @@ enum Commands {
         /// Password (optional - will prompt securely if not provided)
         #[arg(short, long)]
         password: Option<String>,
+
+        /// Log in even if Instagram recently asked us to wait
+        #[arg(long)]
+        force: bool,
+
+        /// Show a QR code to approve the login from the Instagram app
+        /// (hidden until a server backend can hand logins off to the app)
+        #[arg(long, hide = true, conflicts_with_all = ["username", "password"])]
+        qr: bool,
     },
 
     /// Logout from Instagram
@@ -54,14 +111,40 @@ enum Commands {
     /// Check server status and authentication
     Status,
 
+    /// Diagnose server, session and local setup problems
+    Doctor,
+
+    /// Bundle diagnostics (redacted) into a tarball to attach to a bug report
+    Report {
+        /// Where to write the tarball (default: ig-report-<time>.tar.gz here)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Manage devices that have logged in through the server
+    Devices {
+        #[command(subcommand)]
+        action: Option<DevicesAction>,
+    },
+
     /// Show current logged-in user info
     Me,
 
+    /// Show a QR code linking to a profile (yours by default)
+    Qrcode {
+        /// Username to link to (default: the logged-in user)
+        target: Option<String>,
+    },
+
     /// Show inbox (list of conversations)
     Inbox {
-        /// Number of threads to show (default: 20)
-        #[arg(short, long, default_value = "20")]
-        limit: u32,
+        /// Number of threads to show (default: as many as fit the terminal)
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Page through the whole inbox (ignores --limit)
+        #[arg(long, conflicts_with = "watch")]
+        all: bool,
 
         /// Show only unread conversations
         #[arg(short = 'u', long)]
@@ -71,9 +154,46 @@ enum Commands {
         #[arg(short, long)]
         interactive: bool,
 
-        /// Watch mode - auto-refresh every N seconds (default: 5)
+        /// Watch mode - auto-refresh every interval (e.g. 10s, 1m; default: 5s)
         #[arg(short, long)]
-        watch: Option<Option<u64>>,
+        watch: Option<Option<Interval>>,
+
+        /// Show only conversations with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Show the inbox as a table (same as --output table)
+        #[arg(long)]
+        table: bool,
+
+        /// Group the listing under Unread, Today, This week and Older headers
+        #[arg(long, value_enum, default_value = "none")]
+        group_by: commands::GroupBy,
+
+        /// Export inbox metadata instead of displaying it (csv or json)
+        #[arg(long, value_enum)]
+        export: Option<commands::ExportFormat>,
+
+        /// Merge the inboxes of every configured profile
+        #[arg(long, conflicts_with_all = ["all", "interactive", "watch", "table", "group_by", "export"])]
+        all_profiles: bool,
+    },
+
+    /// Summarize recent inbox activity (pipe-friendly)
+    Digest {
+        /// Time window to summarize (e.g. 30m, 24h, 1h30m, 7d, 2w)
+        #[arg(long, default_value = "24h")]
+        since: Span,
+    },
+
+    /// Heatmap of message activity per day and hour (eg: ig heatmap @alice --weeks 12)
+    Heatmap {
+        /// Username (@user); the whole inbox when left out
+        target: Option<String>,
+
+        /// Weeks to show, counting this one
+        #[arg(long, default_value = "12", value_parser = clap::value_parser!(u32).range(1..=52))]
+        weeks: u32,
     },
 
     /// Open chat by inbox number (eg: ig open 1)
@@ -82,40 +202,320 @@ enum Commands {
         number: usize,
     },
 
+    /// Open a conversation on instagram.com (by thread ID or @username)
+    Web {
+        /// Thread ID, alias (t12) or @username
+        #[arg(value_parser = aliases::target_arg)]
+        target: String,
+
+        /// Print the URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Print an insta:// link to a thread or a message in it
+    Link {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// Message ID (links straight to the message)
+        message_id: Option<String>,
+    },
+
+    /// Show the conversation an insta:// link points at
+    OpenUri {
+        /// Link from `ig link` (insta://thread/...)
+        uri: String,
+    },
+
     /// Search for a user
     Search {
-        /// Username to search for
-        query: String,
+        /// Username to search for (optional - will prompt with completion if not provided)
+        query: Option<String>,
+    },
+
+    /// Save a message search to run later with `ig search-run`
+    SearchSave {
+        /// Name to save the search under
+        name: String,
+
+        /// Text to look for (case-insensitive)
+        #[arg(short, long)]
+        query: Option<String>,
+
+        /// Only messages from this user
+        #[arg(short, long)]
+        from: Option<String>,
+
+        /// Only messages newer than this (e.g. 24h, 30d)
+        #[arg(long)]
+        since: Option<Span>,
+
+        /// Show a desktop notification when a run finds new matches
+        #[arg(short, long)]
+        notify: bool,
+    },
+
+    /// Run a saved search (lists saved searches without a name)
+    SearchRun {
+        /// Name of the saved search
+        name: Option<String>,
     },
 
     /// Show messages in a thread (by ID or @username)
     Thread {
-        /// Thread ID or @username
+        /// Thread ID, alias (t12) or @username
+        #[arg(value_parser = aliases::target_arg)]
         target: String,
 
         /// Number of messages to show (default: 20)
         #[arg(short, long, default_value = "20")]
         limit: u32,
+
+        /// Fetch the whole message history (ignores --limit)
+        #[arg(long)]
+        all: bool,
+
+        /// Show each message translated into this language (e.g. en)
+        #[arg(long, value_name = "LANG")]
+        translate: Option<String>,
+
+        /// Jump to a date: show --limit messages around it (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE", value_parser = commands::goto::date_arg, conflicts_with = "all")]
+        around: Option<NaiveDate>,
+    },
+
+    /// Timeline of messages, pins, follow-ups and reminders with someone
+    History {
+        /// Username (@user)
+        target: String,
+
+        /// Page to show (1 is the most recent activity)
+        #[arg(short, long, default_value = "1")]
+        page: usize,
+
+        /// Entries per page
+        #[arg(long, default_value = "20")]
+        per_page: usize,
+
+        /// Number of messages to fetch (default: 100)
+        #[arg(short, long, default_value = "100")]
+        limit: u32,
+
+        /// Fetch the whole message history (ignores --limit)
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Summarize a conversation with the configured language model
+    Summarize {
+        /// Username (@user)
+        target: String,
+
+        /// Number of recent messages to summarize
+        #[arg(long, default_value = "100")]
+        last: u32,
+    },
+
+    /// Show full details for a single message
+    Msg {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// Message ID
+        message_id: String,
+
+        /// Print the raw JSON payload from the server
+        #[arg(long)]
+        raw: bool,
+    },
+
+    /// Show the messages before and after a message
+    Context {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// Message ID
+        message_id: String,
+
+        /// Messages to show before and after it
+        #[arg(short = 'C', long, default_value = "5")]
+        context: usize,
+    },
+
+    /// Save the photos, videos and voice clips in a conversation
+    Download {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// Only this message's media (default: every one in the last 100 messages)
+        #[arg(short, long)]
+        message_id: Option<String>,
+
+        /// Directory to save into (default: the current one)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Read the text in a message's image (needs tesseract installed)
+    Ocr {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// Message ID
+        message_id: String,
+
+        /// Tesseract language(s), e.g. "eng" or "eng+spa"
+        #[arg(short, long)]
+        lang: Option<String>,
+    },
+
+    /// Pin a message locally (shown in the thread header)
+    PinMsg {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// Message ID
+        message_id: String,
+
+        /// Unpin the message instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// List pinned messages in a thread
+    Pins {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
     },
 
     /// Send a message to a user by username
     Send {
-        /// Username to send to (without @)
+        /// Username to send to (with or without @)
         username: String,
 
         /// Message text (optional - will prompt if not provided)
         #[arg(short, long)]
         message: Option<String>,
+
+        /// Send even if you've never messaged this user before
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Treat the message as Markdown and convert its formatting
+        #[arg(long)]
+        md: bool,
+
+        /// Write the message (as Markdown) in $EDITOR
+        #[arg(short, long, conflicts_with = "message")]
+        editor: bool,
+
+        /// Leave the configured signature off this message
+        #[arg(long)]
+        no_signature: bool,
+
+        /// Send this photo (jpg, png or webp) instead of a text message
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["message", "md", "editor"])]
+        photo: Option<PathBuf>,
+    },
+
+    /// Assign a thread to a team member sharing the account (eg: ig assign t3 alice)
+    Assign {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// Team member to hand it to ("me" for [team] operator); without
+        /// one, shows who has it
+        assignee: Option<String>,
+
+        /// Handoff note for whoever picks it up
+        #[arg(short, long)]
+        note: Option<String>,
+
+        /// Unassign the thread
+        #[arg(long, conflicts_with_all = ["assignee", "note"])]
+        clear: bool,
+    },
+
+    /// Set a group conversation's title
+    Rename {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// New title
+        title: String,
     },
 
     /// Reply to a thread
     Reply {
-        /// Thread ID
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
         thread_id: String,
 
         /// Message text (optional - will prompt if not provided)
         #[arg(short, long)]
         message: Option<String>,
+
+        /// Treat the message as Markdown and convert its formatting
+        #[arg(long)]
+        md: bool,
+
+        /// Write the message (as Markdown) in $EDITOR
+        #[arg(short, long, conflicts_with = "message")]
+        editor: bool,
+
+        /// Leave the configured signature off this message
+        #[arg(long)]
+        no_signature: bool,
+    },
+
+    /// Stage photos/videos to go with the next message
+    Attach {
+        #[command(subcommand)]
+        action: AttachAction,
+    },
+
+    /// Payment link for an amount from the [paylink] template (eg: ig paylink 40 "Commission")
+    Paylink {
+        /// Amount, e.g. 40 or 12.50
+        amount: String,
+
+        /// What it's for
+        note: Option<String>,
+
+        /// Send it to this thread ID, alias (t12) or @username instead of printing it
+        #[arg(long, value_parser = aliases::target_arg)]
+        to: Option<String>,
+
+        /// Send even if you've never messaged this user before
+        #[arg(short, long, requires = "to")]
+        yes: bool,
+    },
+
+    /// Saved replies sent by typing #CODE in ig chat
+    Catalog {
+        #[command(subcommand)]
+        action: CatalogAction,
+    },
+
+    /// Record a voice message and send it (by thread ID or @username)
+    Voice {
+        /// Thread ID, alias (t12) or @username
+        #[arg(value_parser = aliases::target_arg)]
+        target: String,
+
+        /// Longest recording in seconds (Enter stops early)
+        #[arg(long, default_value = "30", value_parser = clap::value_parser!(u64).range(1..))]
+        seconds: u64,
     },
 
     /// Start interactive chat with a user
@@ -124,6 +524,176 @@ enum Commands {
         username: String,
     },
 
+    /// Full-screen inbox and conversation, updating live
+    Tui {
+        /// Number of conversations to list (default: 20)
+        #[arg(short, long)]
+        limit: Option<u32>,
+    },
+
+    /// Triage conversations on a board: Inbox / Waiting on them / Waiting on me / Done
+    Board {
+        /// Number of conversations to show (default: 20)
+        #[arg(short, long)]
+        limit: Option<u32>,
+    },
+
+    /// Tag a conversation partner (eg: ig tag @user work)
+    Tag {
+        /// Username to tag (@user)
+        target: String,
+
+        /// Tag name
+        tag: String,
+
+        /// Remove the tag instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// List all tags
+    Tags,
+
+    /// Show someone under a nickname instead of their handle
+    /// (eg: ig nick @cryptic_handle_999 "Mom"); lists nicknames without arguments
+    Nick {
+        /// Username (@user)
+        target: Option<String>,
+
+        /// Name to show for them
+        name: Option<String>,
+
+        /// Remove their nickname instead
+        #[arg(long, requires = "target", conflicts_with = "name")]
+        clear: bool,
+    },
+
+    /// Track a follow-up with someone (eg: ig followup @user --in 2d --note "send quote")
+    #[command(args_conflicts_with_subcommands = true)]
+    Followup {
+        #[command(subcommand)]
+        action: Option<FollowupAction>,
+
+        /// Username to follow up with (@user)
+        target: Option<String>,
+
+        /// When the follow-up is due (e.g. 4h, 2d, 1w)
+        #[arg(long = "in", default_value = "1d")]
+        due_in: Span,
+
+        /// What needs doing
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+
+    /// Remind me to get back to a conversation (eg: ig remind @user 3h "reply")
+    #[command(args_conflicts_with_subcommands = true)]
+    Remind {
+        #[command(subcommand)]
+        action: Option<RemindAction>,
+
+        /// Username to be reminded about (@user)
+        target: Option<String>,
+
+        /// When to remind (e.g. 30m, 1h30m, 2d)
+        after: Option<Span>,
+
+        /// What to remember
+        note: Option<String>,
+
+        /// Open the conversation in the browser or app when the reminder fires
+        #[arg(long)]
+        open: bool,
+    },
+
+    /// Watch the inbox and show desktop notifications (also fires reminders)
+    Notify {
+        /// Time between inbox checks (e.g. 45s, 2m)
+        #[arg(short, long, default_value = "30s")]
+        interval: Interval,
+
+        /// Print NDJSON events to stdout instead of desktop notifications
+        #[arg(long)]
+        stdout: bool,
+    },
+
+    /// Follow new messages in the inbox or a thread (NDJSON with --output json)
+    Tail {
+        /// Thread ID, alias (t12) or @username (default: whole inbox)
+        #[arg(value_parser = aliases::target_arg)]
+        target: Option<String>,
+
+        /// Time between checks (e.g. 5s, 1m)
+        #[arg(short, long, default_value = "5s")]
+        interval: Interval,
+    },
+
+    /// Benchmark the backend (/health and /inbox latency)
+    Bench {
+        /// Requests per endpoint
+        #[arg(short, long, default_value = "50")]
+        requests: usize,
+
+        /// Requests in flight at once
+        #[arg(short, long, default_value = "5")]
+        concurrency: usize,
+    },
+
+    /// Back up every conversation, with media and a manifest of hashes
+    ExportAll {
+        /// Directory to write to (an interrupted export resumes there)
+        #[arg(long)]
+        out: PathBuf,
+
+        /// How conversations are written
+        #[arg(long, value_enum, default_value = "json")]
+        format: commands::ArchiveFormat,
+    },
+
+    /// Show usage statistics
+    Stats {
+        /// Requests and traffic sent to Instagram (last run, hour, day, week)
+        #[arg(long)]
+        api: bool,
+
+        /// How quickly you and @user reply to each other (median, 90th percentile)
+        #[arg(long, value_name = "@USER", conflicts_with = "api")]
+        response_times: Option<String>,
+
+        /// Messages to measure response times over (default: 500)
+        #[arg(short, long, requires = "response_times", conflicts_with = "api")]
+        limit: Option<u32>,
+
+        /// Measure over the whole message history
+        #[arg(long, requires = "response_times", conflicts_with_all = ["api", "limit"])]
+        all: bool,
+    },
+
+    /// Show the commands you run most, with alias suggestions (kept locally)
+    Usage,
+
+    /// Manage locally stored data
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Keep conversations out of syncing, caching, searches and exports
+    Sync {
+        #[command(subcommand)]
+        action: SyncAction,
+    },
+
+    /// Tools for debugging sync and caching
+    #[command(hide = true)]
+    Debug {
+        #[command(subcommand)]
+        action: DebugAction,
+    },
+
+    /// Practice the basic workflow on a sample account (nothing is sent)
+    Tutorial,
+
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
@@ -132,19 +702,229 @@ enum Commands {
     },
 }
 
+impl Commands {
+    /// Whether the command can print `--output csv`
+    fn supports_csv(&self) -> bool {
+        matches!(self, Commands::Stats { .. } | Commands::Heatmap { .. })
+    }
+
+    /// What the active profile must allow for this command to run
+    fn permission(&self) -> Option<Permission> {
+        match self {
+            Commands::Banner { .. } | Commands::Completions { .. } | Commands::Tutorial => None,
+            Commands::Attach { .. }
+            | Commands::Catalog { .. }
+            | Commands::Link { .. }
+            | Commands::SearchSave { .. }
+            | Commands::Nick { .. }
+            | Commands::SearchRun { name: None }
+            | Commands::Sync { action: SyncAction::Include { .. } | SyncAction::List }
+            | Commands::Cache { .. }
+            | Commands::Stats { response_times: None, .. }
+            | Commands::Usage => None,
+            Commands::Status | Commands::Doctor | Commands::Report { .. } => None,
+            Commands::Login { .. } | Commands::Logout => Some(Permission::Manage),
+            Commands::Devices { action: Some(DevicesAction::Forget { .. }) } => Some(Permission::Manage),
+            Commands::Paylink { to: None, .. } => None,
+            Commands::Assign { assignee: None, note: None, clear: false, .. } => Some(Permission::Read),
+            Commands::Send { .. }
+            | Commands::Paylink { .. }
+            | Commands::Reply { .. }
+            | Commands::Rename { .. }
+            | Commands::Assign { .. }
+            | Commands::Chat { .. }
+            | Commands::Open { .. }
+            | Commands::Voice { .. } => Some(Permission::Send),
+            _ => Some(Permission::Read),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum DevicesAction {
+    /// List devices (default)
+    List,
+
+    /// Forget a device and its saved session
+    Forget {
+        /// Device ID (from `ig devices list`)
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Remove stored messages and downloads older than `keep_messages` and `keep_media` allow
+    Vacuum,
+}
+
+#[derive(Subcommand)]
+enum SyncAction {
+    /// Exclude a conversation (by thread ID or @username)
+    Exclude {
+        /// Thread ID, alias (t12) or @username
+        #[arg(value_parser = aliases::target_arg)]
+        target: String,
+    },
+
+    /// Sync an excluded conversation again
+    Include {
+        /// Thread ID, alias (t12) or @username
+        #[arg(value_parser = aliases::target_arg)]
+        target: String,
+    },
+
+    /// List excluded conversations
+    List,
+}
+
+#[derive(Subcommand)]
+enum DebugAction {
+    /// Fetch a thread twice and show added, removed and changed messages
+    ThreadDiff {
+        /// Thread ID (or alias, e.g. t12)
+        #[arg(value_parser = aliases::thread_arg)]
+        thread_id: String,
+
+        /// Messages to fetch each time
+        #[arg(short, long, default_value = "20")]
+        limit: u32,
+
+        /// Time between the two fetches (e.g. 5s, 1m)
+        #[arg(short, long, default_value = "5s")]
+        wait: Interval,
+
+        /// Compare the response cache (what polling sees) with a fresh fetch
+        #[arg(long)]
+        cached: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AttachAction {
+    /// Stage files for the next message
+    Add {
+        /// Photos (jpg, png, webp) or videos (mp4, mov)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// List staged attachments
+    List,
+
+    /// Unstage all attachments
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum CatalogAction {
+    /// Save a reply under a shortcode (eg: ig catalog add SKU123 "Price is $40, ships in 2 days")
+    Add {
+        /// Shortcode, typed as #CODE in ig chat
+        code: String,
+
+        /// Reply to send in its place
+        text: String,
+    },
+
+    /// Remove a shortcode
+    Remove {
+        /// Shortcode to remove
+        code: String,
+    },
+
+    /// List saved replies
+    List,
+}
+
+#[derive(Subcommand)]
+enum RemindAction {
+    /// List pending reminders
+    List,
+
+    /// Cancel a reminder
+    Cancel {
+        /// Reminder ID (from `ig remind list`)
+        id: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum FollowupAction {
+    /// List overdue and upcoming follow-ups
+    List,
+
+    /// Mark a follow-up as done
+    Done {
+        /// Username (@user)
+        target: String,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
-    let client = ApiClient::new(cli.server.as_deref());
+    commands::install_panic_hook();
+    let matches = Cli::command().get_matches_from(expand_alias(std::env::args_os().collect()));
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // Only the subcommand's name goes in the usage log, never its arguments
+    let command = matches.subcommand_name().unwrap_or_default().to_string();
+    let shape = analytics::shape(&Cli::command(), &matches);
 
-    match cli.command {
-        Commands::Banner => {
-            colors::print_gradient_banner();
+    colors::set_theme(cli.theme);
+    if cli.no_color {
+        colors::disable_colors();
+    }
+    if cli.verbose {
+        verbose::enable();
+    }
+    if cli.real_names {
+        nicknames::show_real_names();
+    }
+    if cli.tor {
+        http::use_proxy(http::TOR_PROXY);
+    }
+    http::proxy()?;
+    if cli.ipv4 {
+        dns::use_ip_version(IpVersion::Ipv4);
+    } else if cli.ipv6 {
+        dns::use_ip_version(IpVersion::Ipv6);
+    }
+    http::resolver()?;
+
+    // `ig open` after `ig inbox --all-profiles` uses the profile the conversation was listed from
+    let listed_profile = match cli.command {
+        Commands::Open { number } if cli.profile.is_none() => {
+            InboxSnapshot::load().ok().and_then(|s| s.get(number).and_then(|l| l.profile.clone()))
+        }
+        _ => None,
+    };
+    let profile = profile::activate(cli.profile.as_deref().or(listed_profile.as_deref()))?;
+    if cli.output == OutputFormat::Csv && !cli.command.supports_csv() {
+        anyhow::bail!("--output csv is only available for ig stats and ig heatmap");
+    }
+    if let Some(permission) = cli.command.permission() {
+        profile::require(permission)?;
+    }
+    let server = cli.server.as_deref().or(profile.server.as_deref());
+    let client = ApiClient::new(server)?;
+    if !matches!(cli.command, Commands::Completions { .. }) {
+        header::print(cli.output, profile.name.as_deref());
+    }
+
+    let result = match cli.command {
+        Commands::Banner { text, font } => {
+            match text {
+                Some(text) => banner::print_text_banner(&text, font),
+                None => banner::print_gradient_banner(),
+            }
             Ok(())
         }
 
-        Commands::Login { username, password } => {
-            if let (Some(u), Some(p)) = (username.as_ref(), password.as_ref()) {
+        Commands::Login { username, password, force, qr } => {
+            commands::check_login_cooldown(force)?;
+            if qr {
+                commands::login_qr(&client).await
+            } else if let (Some(u), Some(p)) = (username.as_ref(), password.as_ref()) {
                 // Non-interactive mode with provided credentials
                 commands::login_with_credentials(&client, u, p).await
             } else if let Some(u) = username.as_ref() {
@@ -173,56 +953,294 @@ async fn main() -> Result<()> {
 
         Commands::Status => commands::status(&client).await,
 
+        Commands::Doctor => commands::run_doctor(&client, cli.output).await,
+        Commands::Report { out } => commands::create_report(&client, out).await,
+
+        Commands::Devices { action } => match action {
+            Some(DevicesAction::Forget { id }) => commands::forget_device(&client, &id).await,
+            Some(DevicesAction::List) | None => commands::list_devices(&client, cli.output).await,
+        },
+
         Commands::Me => commands::show_me(&client).await,
 
-        Commands::Inbox { limit, unread, interactive, watch } => {
-            if interactive {
-                commands::show_inbox_interactive(&client, limit).await
+        Commands::Qrcode { target } => commands::show_profile_qr(&client, target.as_deref()).await,
+
+        Commands::Inbox { limit, all, unread, interactive, watch, tag, table, group_by, export, all_profiles } => {
+            let output = if table { OutputFormat::Table } else { cli.output };
+            // Fit the terminal only where the listing is what ends up on it
+            let fits_screen = export.is_none() && !interactive && output == OutputFormat::Plain;
+            let fitted = match limit {
+                None if fits_screen && !all => commands::default_inbox_limit(group_by),
+                _ => None,
+            };
+            // A terminal-sized list keeps each conversation to two lines
+            let compact = fitted.is_some();
+            let limit = limit.or(fitted).unwrap_or(commands::INBOX_LIMIT);
+            let fetch_limit = if all { None } else { Some(limit) };
+            let tag = tag.as_deref().map(str::to_lowercase);
+            let tag = tag.as_deref();
+            if all_profiles {
+                commands::show_inbox_all_profiles(limit, unread, tag, compact, output).await
+            } else if let Some(format) = export {
+                commands::export_inbox(&client, fetch_limit, unread, tag, format).await
+            } else if interactive {
+                commands::show_inbox_interactive(&client, fetch_limit, tag).await
             } else if let Some(interval) = watch {
                 // Watch mode: default to 5 seconds if no value provided
-                let seconds = interval.unwrap_or(5);
-                commands::show_inbox_watch(&client, limit, unread, seconds).await
+                let interval = interval.unwrap_or(Interval(Duration::from_secs(5)));
+                commands::show_inbox_watch(&client, limit, unread, tag, group_by, compact, interval).await
             } else {
-                commands::show_inbox(&client, limit, unread).await
+                commands::show_inbox(&client, fetch_limit, unread, tag, group_by, compact, output).await
             }
         }
 
+        Commands::ExportAll { out, format } => commands::export_all(&client, &out, format).await,
+
+        Commands::Digest { since } => commands::show_digest(&client, &since).await,
+
+        Commands::Heatmap { target, weeks } => {
+            commands::show_heatmap(&client, target.as_deref(), weeks, cli.output).await
+        }
+
         Commands::Open { number } => commands::open_by_number(&client, number).await,
 
-        Commands::Search { query } => commands::search_user(&client, &query).await,
+        Commands::Web { target, print } => commands::open_in_browser(&client, &target, print).await,
+
+        Commands::Link { thread_id, message_id } => {
+            commands::print_link(&thread_id, message_id.as_deref(), cli.output)
+        }
+
+        Commands::OpenUri { uri } => commands::open_uri(&client, &uri).await,
+
+        Commands::Search { query } => commands::search_user(&client, query.as_deref()).await,
+
+        Commands::SearchSave { name, query, from, since, notify } => {
+            commands::save_search(&name, SavedSearch { query, from, since: since.map(|s| s.text), notify })
+        }
+
+        Commands::SearchRun { name } => match name {
+            Some(name) => commands::run_search(&client, &name, cli.output).await,
+            None => commands::list_searches(cli.output),
+        },
+
+        Commands::Thread { target, limit, all, translate, around } => {
+            let limit = if all { None } else { Some(limit) };
+            commands::show_thread_or_user(&client, &target, limit, translate.as_deref(), around).await
+        }
+
+        Commands::History { target, page, per_page, limit, all } => {
+            let limit = if all { None } else { Some(limit) };
+            commands::show_history(&client, &target, limit, page, per_page, cli.output).await
+        }
+
+        Commands::Summarize { target, last } => {
+            commands::summarize_conversation(&client, &target, last, cli.output).await
+        }
+
+        Commands::Msg { thread_id, message_id, raw } => {
+            commands::show_message(&client, &thread_id, &message_id, raw).await
+        }
+
+        Commands::Context { thread_id, message_id, context } => {
+            commands::show_context(&client, &thread_id, &message_id, context).await
+        }
+
+        Commands::Download { thread_id, message_id, out } => {
+            commands::download_thread_media(&client, &thread_id, message_id.as_deref(), out.as_deref(), cli.output).await
+        }
+
+        Commands::Ocr { thread_id, message_id, lang } => {
+            commands::ocr_message(&client, &thread_id, &message_id, lang.as_deref(), cli.output).await
+        }
+
+        Commands::PinMsg { thread_id, message_id, remove } => {
+            if remove {
+                commands::unpin_message(&thread_id, &message_id)
+            } else {
+                commands::pin_message(&client, &thread_id, &message_id).await
+            }
+        }
+
+        Commands::Pins { thread_id } => commands::list_pins(&thread_id, cli.output),
 
-        Commands::Thread { target, limit } => {
-            commands::show_thread_or_user(&client, &target, limit).await
+        Commands::Send { username, message, yes, md, editor, no_signature, photo } => match photo {
+            Some(photo) => commands::send_photo_to_user(&client, &username, &photo, yes).await,
+            None => {
+                let message = message.as_deref();
+                commands::send_to_user(&client, &username, message, yes, md, editor, !no_signature).await
+            }
+        },
+
+        Commands::Reply { thread_id, message, md, editor, no_signature } => {
+            commands::send_to_thread(&client, &thread_id, message.as_deref(), md, editor, !no_signature).await
         }
 
-        Commands::Send { username, message } => {
-            commands::send_to_user(&client, &username, message.as_deref()).await
+        Commands::Rename { thread_id, title } => commands::rename_thread(&client, &thread_id, &title).await,
+
+        Commands::Assign { thread_id, assignee, note, clear } => {
+            commands::assign(&client, &thread_id, assignee.as_deref(), note.as_deref(), clear, cli.output).await
         }
 
-        Commands::Reply { thread_id, message } => {
-            commands::send_to_thread(&client, &thread_id, message.as_deref()).await
+        Commands::Attach { action } => match action {
+            AttachAction::Add { files } => commands::add_attachments(&files),
+            AttachAction::List => commands::list_attachments(cli.output),
+            AttachAction::Clear => commands::clear_attachments(),
+        },
+
+        Commands::Tui { limit } => commands::run_tui(&client, limit.unwrap_or(commands::INBOX_LIMIT)).await,
+
+        Commands::Board { limit } => commands::run_board(&client, limit.unwrap_or(commands::INBOX_LIMIT)).await,
+
+        Commands::Paylink { amount, note, to, yes } => {
+            commands::paylink(&client, &amount, note.as_deref(), to.as_deref(), yes).await
         }
 
+        Commands::Catalog { action } => match action {
+            CatalogAction::Add { code, text } => commands::add_catalog_item(&code, &text),
+            CatalogAction::Remove { code } => commands::remove_catalog_item(&code),
+            CatalogAction::List => commands::list_catalog(cli.output),
+        },
+
+        Commands::Voice { target, seconds } => commands::send_voice(&client, &target, seconds).await,
+
         Commands::Chat { username } => {
-            // Fetch usernames from recent conversations for tab completion
-            let usernames = match client.get_inbox(20).await {
-                Ok(inbox) => {
-                    inbox.threads
-                        .unwrap_or_default()
-                        .iter()
-                        .flat_map(|t| t.users.iter().map(|u| u.username.clone()))
-                        .collect()
+            // Tab completion draws on every known username
+            commands::chat_with_user(&client, &username, Vec::new(), None).await
+        }
+
+        Commands::Tag { target, tag, remove } => commands::tag_user(&target, &tag, remove),
+
+        Commands::Tags => commands::list_tags(cli.output),
+
+        Commands::Nick { target, name, clear } => match target {
+            Some(target) => commands::set_nickname(&target, name.as_deref(), clear),
+            None => commands::list_nicknames(cli.output),
+        },
+
+        Commands::Followup { action, target, due_in, note } => match action {
+            Some(FollowupAction::List) => commands::list_followups(cli.output),
+            Some(FollowupAction::Done { target }) => commands::clear_followup(&target),
+            None => match target {
+                Some(target) => commands::add_followup(&target, &due_in, note.as_deref()),
+                None => commands::list_followups(cli.output),
+            },
+        },
+
+        Commands::Remind { action, target, after, note, open } => match action {
+            Some(RemindAction::List) => commands::list_reminders(cli.output),
+            Some(RemindAction::Cancel { id }) => commands::cancel_reminder(id),
+            None => match (target, after) {
+                (Some(target), Some(after)) => {
+                    commands::add_reminder(&client, &target, &after, note.as_deref(), open).await
                 }
-                Err(_) => Vec::new(), // Fall back to empty list if can't fetch
-            };
-            commands::chat_with_user(&client, &username, usernames).await
+                _ => anyhow::bail!("Usage: ig remind @user <when> [note]  (or: ig remind list)"),
+            },
+        },
+
+        Commands::Notify { interval, stdout } => commands::run_notify(&client, interval, stdout).await,
+
+        Commands::Tail { target, interval } => {
+            commands::run_tail(&client, target.as_deref(), interval, cli.output).await
+        }
+
+        Commands::Bench { requests, concurrency } => {
+            commands::run_bench(&client, requests, concurrency, cli.output).await
+        }
+
+        Commands::Stats { api, response_times, limit, all } => {
+            if api {
+                commands::show_api_stats(cli.output)
+            } else if let Some(target) = response_times {
+                let limit = if all { None } else { Some(limit.unwrap_or(500)) };
+                commands::show_response_times(&client, &target, limit, cli.output).await
+            } else {
+                anyhow::bail!("Usage: ig stats --api | --response-times @user")
+            }
         }
 
+        Commands::Usage => commands::show_command_usage(cli.output),
+
+        Commands::Cache { action } => match action {
+            CacheAction::Vacuum => commands::vacuum_cache(),
+        },
+
+        Commands::Sync { action } => match action {
+            SyncAction::Exclude { target } => commands::exclude_thread(&client, &target).await,
+            SyncAction::Include { target } => commands::include_thread(&target),
+            SyncAction::List => commands::list_exclusions(cli.output),
+        },
+
+        Commands::Debug { action } => match action {
+            DebugAction::ThreadDiff { thread_id, limit, wait, cached } => {
+                commands::thread_diff(&client, &thread_id, limit, wait.0, cached).await
+            }
+        },
+
+        Commands::Tutorial => commands::run_tutorial(),
+
         Commands::Completions { shell } => {
             let mut cmd = Cli::command();
             let name = cmd.get_name().to_string();
             generate(shell, &mut cmd, name, &mut io::stdout());
             Ok(())
         }
+    };
+
+    log_http_stats(&client);
+    usage::record(&command, client.stats());
+    header::remember_server(client.stats());
+    if let Some(shape) = shape {
+        analytics::record(&shape);
+    }
+    result
+}
+
+/// The command line with an `[alias]` from config.toml in place of its name
+/// (`ig -s <url> ii` → `ig -s <url> inbox -i`). Only the first argument after
+/// the global options is looked up, and built-in subcommands can't be
+/// overridden.
+fn expand_alias(mut args: Vec<OsString>) -> Vec<OsString> {
+    let cli = Cli::command();
+    let mut i = 1;
+    while let Some(arg) = args.get(i).and_then(|a| a.to_str()).filter(|a| a.starts_with('-')) {
+        let takes_value = cli.get_arguments().any(|a| {
+            a.get_action().takes_values()
+                && (a.get_long().is_some_and(|l| arg == format!("--{}", l))
+                    || a.get_short().is_some_and(|s| arg == format!("-{}", s)))
+        });
+        i += if takes_value { 2 } else { 1 };
+    }
+    let Some(name) = args.get(i).and_then(|a| a.to_str()) else {
+        return args;
+    };
+    if cli.find_subcommand(name).is_some() {
+        return args;
     }
+    // A broken config.toml is reported once the profile is loaded
+    let Some(expansion) = Config::load().ok().and_then(|c| c.alias.get(name).cloned()) else {
+        return args;
+    };
+    args.splice(i..=i, expansion.split_whitespace().map(OsString::from));
+    args
+}
+
+/// Requests sent, how many reused a pooled connection, and the traffic
+/// (`--verbose`)
+fn log_http_stats(client: &ApiClient) {
+    let stats = client.stats();
+    let requests = stats.requests();
+    let connections = stats.connections();
+    let reused = requests.saturating_sub(connections);
+    let pct = if requests == 0 { 0.0 } else { reused as f64 / requests as f64 * 100.0 };
+    verbose::log(&format!(
+        "HTTP: {} requests, {} connections opened, {} reused ({:.0}%)",
+        requests, connections, reused, pct
+    ));
+    verbose::log(&format!(
+        "HTTP: {} bytes sent, {} bytes received, {} cache hits (304), {} retries",
+        stats.bytes_sent(),
+        stats.bytes_received(),
+        stats.cache_hits(),
+        stats.retries()
+    ));
 }
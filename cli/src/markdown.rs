@@ -0,0 +1,307 @@
+//! Markdown for outgoing messages
+//!
+//! Instagram DMs are plain text, so Markdown is turned into text that reads
+//! well as-is: bullets for lists, and either Unicode "styled" letters
+//! (𝗯𝗼𝗹𝗱, 𝘪𝘵𝘢𝘭𝘪𝘤, 𝚌𝚘𝚍𝚎) or no markers at all for emphasis. Only the common
+//! subset is understood; line breaks are kept as written, since that's how
+//! people lay out messages.
+
+use crate::config::MarkdownStyle;
+
+/// Line drawn for a horizontal rule
+const RULE: &str = "──────────";
+
+/// Inline formatting in effect
+#[derive(Debug, Default, Clone, Copy)]
+struct Format {
+    bold: bool,
+    italic: bool,
+    code: bool,
+    strike: bool,
+}
+
+/// Convert Markdown to the plain text that gets sent
+pub fn render(text: &str, style: MarkdownStyle) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_fence = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            lines.push(line.to_string());
+            continue;
+        }
+        if trimmed.is_empty() {
+            // Collapse runs of blank lines
+            if lines.last().is_some_and(|l| !l.is_empty()) {
+                lines.push(String::new());
+            }
+            continue;
+        }
+        lines.push(render_line(line, style));
+    }
+
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// One line outside a code block
+fn render_line(line: &str, style: MarkdownStyle) -> String {
+    let indent = line.len() - line.trim_start().len();
+    let trimmed = line.trim();
+
+    if let Some(heading) = heading(trimmed) {
+        return match style {
+            MarkdownStyle::Unicode => inline(heading, Format { bold: true, ..Format::default() }, style),
+            MarkdownStyle::Plain => inline(heading, Format::default(), style).to_uppercase(),
+        };
+    }
+    if is_rule(trimmed) {
+        return RULE.to_string();
+    }
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        return format!("▎ {}", inline(quote.trim_start(), Format::default(), style));
+    }
+
+    let pad = " ".repeat(indent / 2 * 2);
+    if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|m| trimmed.strip_prefix(m)) {
+        let (marker, item) = if let Some(rest) = item.strip_prefix("[ ] ") {
+            ("☐", rest)
+        } else if let Some(rest) = item.strip_prefix("[x] ").or_else(|| item.strip_prefix("[X] ")) {
+            ("☑", rest)
+        } else if indent >= 2 {
+            ("◦", item)
+        } else {
+            ("•", item)
+        };
+        return format!("{}{} {}", pad, marker, inline(item.trim_start(), Format::default(), style));
+    }
+    if let Some((number, item)) = ordered_item(trimmed) {
+        return format!("{}{}. {}", pad, number, inline(item, Format::default(), style));
+    }
+
+    inline(trimmed, Format::default(), style)
+}
+
+/// Text of an ATX heading (`# Title`)
+fn heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some(rest.trim().trim_end_matches('#').trim_end())
+}
+
+/// `---`, `***` or `___` on a line of its own
+fn is_rule(line: &str) -> bool {
+    let marks: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&m| marks.chars().all(|c| c == m))
+}
+
+/// Number and text of an ordered list item (`1. Item` or `1) Item`)
+fn ordered_item(line: &str) -> Option<(&str, &str)> {
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let rest = line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))?;
+    Some((&line[..digits], rest.trim_start()))
+}
+
+/// Render inline Markdown (emphasis, code, links) with `format` applied
+fn inline(text: &str, format: Format, style: MarkdownStyle) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    spans(&chars, format, style, &mut out);
+    out
+}
+
+fn spans(chars: &[char], format: Format, style: MarkdownStyle, out: &mut String) {
+    let mut i = 0;
+    'outer: while i < chars.len() {
+        let c = chars[i];
+        let rest = &chars[i..];
+
+        if c == '\\' && chars.get(i + 1).is_some_and(|n| n.is_ascii_punctuation()) {
+            push(out, chars[i + 1], format, style);
+            i += 2;
+            continue;
+        }
+
+        // URLs and @mentions go out untouched so they still work
+        if starts_with(rest, "http://") || starts_with(rest, "https://") {
+            let end = rest.iter().position(|c| c.is_whitespace()).unwrap_or(rest.len());
+            out.extend(&rest[..end]);
+            i += end;
+            continue;
+        }
+        if c == '@' && (i == 0 || !chars[i - 1].is_alphanumeric()) {
+            let end = 1 + rest[1..]
+                .iter()
+                .position(|&c| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(rest.len() - 1);
+            out.extend(&rest[..end]);
+            i += end;
+            continue;
+        }
+
+        if c == '`' {
+            if let Some(end) = rest[1..].iter().position(|&c| c == '`') {
+                let code = Format { code: true, ..format };
+                for &ch in &rest[1..end + 1] {
+                    push(out, ch, code, style);
+                }
+                i += end + 2;
+                continue;
+            }
+        }
+
+        for (delim, apply) in [
+            ("***", Format { bold: true, italic: true, ..format }),
+            ("**", Format { bold: true, ..format }),
+            ("__", Format { bold: true, ..format }),
+            ("~~", Format { strike: true, ..format }),
+        ] {
+            if !starts_with(rest, delim) || (delim == "__" && i > 0 && chars[i - 1].is_alphanumeric()) {
+                continue;
+            }
+            let width = delim.len();
+            if let Some(end) = closing(&rest[width..], delim) {
+                let inner = &rest[width..width + end];
+                wrap(out, inner, apply, style);
+                i += width + end + width;
+                continue 'outer;
+            }
+        }
+
+        if (c == '*' || c == '_') && !(c == '_' && i > 0 && chars[i - 1].is_alphanumeric()) {
+            if let Some(end) = closing_single(&rest[1..], c) {
+                wrap(out, &rest[1..end + 1], Format { italic: true, ..format }, style);
+                i += end + 2;
+                continue;
+            }
+        }
+
+        if c == '[' {
+            if let Some((label, url, len)) = link(rest) {
+                spans(label, format, style, out);
+                let label: String = label.iter().collect();
+                if label != url {
+                    out.push_str(&format!(" ({})", url));
+                }
+                i += len;
+                continue;
+            }
+        }
+
+        push(out, c, format, style);
+        i += 1;
+    }
+}
+
+/// Render a formatted span. Plain style drops emphasis markers but keeps
+/// strikethrough visible as `~text~`.
+fn wrap(out: &mut String, inner: &[char], format: Format, style: MarkdownStyle) {
+    let plain_strike = style == MarkdownStyle::Plain && format.strike;
+    if plain_strike {
+        out.push('~');
+    }
+    spans(inner, format, style, out);
+    if plain_strike {
+        out.push('~');
+    }
+}
+
+/// Where a `delim` span that starts before `chars` ends
+fn closing(chars: &[char], delim: &str) -> Option<usize> {
+    if chars.first().is_none_or(|c| c.is_whitespace()) {
+        return None;
+    }
+    (1..chars.len()).find(|&j| starts_with(&chars[j..], delim) && !chars[j - 1].is_whitespace())
+}
+
+/// Where a single `*` or `_` span ends, skipping doubled markers inside it
+fn closing_single(chars: &[char], marker: char) -> Option<usize> {
+    if chars.first().is_none_or(|c| c.is_whitespace()) {
+        return None;
+    }
+    let mut j = 1;
+    while j < chars.len() {
+        if chars[j] == marker {
+            if chars.get(j + 1) == Some(&marker) {
+                j += 2;
+                continue;
+            }
+            let boundary = marker == '*' || chars.get(j + 1).is_none_or(|c| !c.is_alphanumeric());
+            if !chars[j - 1].is_whitespace() && boundary {
+                return Some(j);
+            }
+        }
+        j += 1;
+    }
+    None
+}
+
+/// `[label](url)` at the start of `chars`: label, url and length
+fn link(chars: &[char]) -> Option<(&[char], String, usize)> {
+    let close = chars.iter().position(|&c| c == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = close + 2 + chars[close + 2..].iter().position(|&c| c == ')')?;
+    let url: String = chars[close + 2..end].iter().collect();
+    Some((&chars[1..close], url.trim().to_string(), end + 1))
+}
+
+fn starts_with(chars: &[char], prefix: &str) -> bool {
+    prefix.chars().enumerate().all(|(i, p)| chars.get(i) == Some(&p))
+}
+
+/// Append `c` as it looks with `format`
+fn push(out: &mut String, c: char, format: Format, style: MarkdownStyle) {
+    if style == MarkdownStyle::Plain {
+        out.push(c);
+        return;
+    }
+    out.push(styled(c, format));
+    if format.strike && !c.is_whitespace() {
+        out.push('\u{0336}');
+    }
+}
+
+/// The Mathematical Alphanumeric Symbols letter for `c`, if there is one
+fn styled(c: char, format: Format) -> char {
+    // First capital, first small letter and first digit of each alphabet
+    let (upper, lower, digit) = match (format.code, format.bold, format.italic) {
+        (true, _, _) => (0x1D670, 0x1D68A, Some(0x1D7F6)),
+        (false, true, true) => (0x1D63C, 0x1D656, Some(0x1D7EC)),
+        (false, true, false) => (0x1D5D4, 0x1D5EE, Some(0x1D7EC)),
+        (false, false, true) => (0x1D608, 0x1D622, None),
+        (false, false, false) => return c,
+    };
+    let code = match c {
+        'A'..='Z' => upper + (c as u32 - 'A' as u32),
+        'a'..='z' => lower + (c as u32 - 'a' as u32),
+        '0'..='9' => match digit {
+            Some(digit) => digit + (c as u32 - '0' as u32),
+            None => return c,
+        },
+        _ => return c,
+    };
+    char::from_u32(code).unwrap_or(c)
+}
@@ -1,4 +1,7 @@
 //! Data models matching the server API
+//!
+//! Response models use `#[serde(default)]` and ignore unknown fields, so the
+//! server can add or drop fields without breaking JSON parsing in the CLI.
 
 #![allow(dead_code)]
 
@@ -12,36 +15,128 @@ pub struct LoginRequest {
     pub password: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub encrypted_password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<DeviceInfo>,
+}
+
+/// Device metadata forwarded to the server at login
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceInfo {
+    pub name: String,
+    pub user_agent_seed: String,
+}
+
+/// A device that has logged in through the server
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Device {
+    pub id: String,
+    pub name: String,
+    pub last_login: Option<String>,
+    /// Whether the server's current session belongs to this device
+    pub current: bool,
+}
+
+/// Device list response
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DevicesResponse {
+    pub success: bool,
+    pub devices: Vec<Device>,
+    pub error: Option<String>,
 }
 
 /// Login response from server
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct LoginResponse {
     pub success: bool,
     pub user: Option<User>,
     pub message: Option<String>,
 }
 
+/// Request body to start a QR code login
+#[derive(Debug, Serialize)]
+pub struct QrLoginRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<DeviceInfo>,
+}
+
+/// State of a QR code login handoff
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct QrLoginResponse {
+    pub success: bool,
+    /// One-time handoff token to poll with
+    pub token: Option<String>,
+    /// What the QR code encodes
+    pub url: Option<String>,
+    /// "pending", "approved" or "expired"
+    pub status: String,
+    /// Seconds until the token expires
+    pub expires_in: Option<u64>,
+    pub user: Option<User>,
+    pub error: Option<String>,
+}
+
 /// Public key response for encryption
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct PublicKeyResponse {
     pub public_key: String,
 }
 
 /// User info
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct User {
     pub pk: String,
     pub username: String,
     pub full_name: Option<String>,
+    /// Whether they're online now, from servers that report presence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+}
+
+impl From<UserDetails> for User {
+    fn from(details: UserDetails) -> Self {
+        Self {
+            pk: details.pk,
+            username: details.username,
+            full_name: details.full_name,
+            is_active: None,
+        }
+    }
 }
 
 /// Health check response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct HealthResponse {
     pub status: String,
     pub authenticated: bool,
     pub username: Option<String>,
+    /// API version spoken by the server (missing on servers that predate versioning)
+    pub api_version: Option<u32>,
+    /// Oldest CLI API version the server still supports
+    pub min_client_api_version: Option<u32>,
+    /// When the server's current session logged in (ISO 8601)
+    pub session_started_at: Option<String>,
+    /// When the server process started (ISO 8601); changes on a restart
+    pub started_at: Option<String>,
+}
+
+/// Optional features a server supports (`GET /capabilities`); anything it
+/// doesn't mention is unsupported
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Capabilities {
+    pub reactions: bool,
+    pub media: bool,
+    pub presence: bool,
+    pub websocket: bool,
+    pub assignments: bool,
+    pub qr_login: bool,
 }
 
 /// Send message request
@@ -50,32 +145,92 @@ pub struct SendMessageRequest {
     pub text: String,
 }
 
+/// React to a message request body
+#[derive(Debug, Serialize)]
+pub struct ReactRequest {
+    pub message_id: String,
+    pub emoji: String,
+}
+
+/// Rename a group thread request body
+#[derive(Debug, Serialize)]
+pub struct RenameThreadRequest {
+    pub title: String,
+}
+
+/// Assign a thread to a team member request body (no assignee unassigns)
+#[derive(Debug, Serialize)]
+pub struct AssignRequest {
+    pub assignee: Option<String>,
+    /// Handoff note for whoever picks the thread up
+    pub note: Option<String>,
+    /// Team member making the change (`[team] operator`)
+    pub assigned_by: Option<String>,
+}
+
+/// Who on the team is answering a thread
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Assignment {
+    pub thread_id: String,
+    pub assignee: String,
+    pub note: Option<String>,
+    pub assigned_by: Option<String>,
+    /// When it was assigned (ISO 8601)
+    pub assigned_at: Option<String>,
+}
+
+/// Thread assignments shared through the server
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssignmentsResponse {
+    pub success: bool,
+    pub assignments: Vec<Assignment>,
+    pub error: Option<String>,
+}
+
 /// Send message response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct SendMessageResponse {
     pub success: bool,
     pub message: Option<Message>,
     pub error: Option<String>,
 }
 
+/// Result of marking, muting or archiving a thread
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ThreadActionResponse {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
 /// Inbox response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct InboxResponse {
     pub success: bool,
     pub threads: Option<Vec<Thread>>,
+    /// Cursor of the next page (paged requests only; None on the last page)
+    pub next_cursor: Option<String>,
     pub error: Option<String>,
 }
 
 /// Thread response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct ThreadResponse {
     pub success: bool,
     pub thread: Option<Thread>,
+    /// Cursor of the next page (paged requests only; None on the last page)
+    pub next_cursor: Option<String>,
     pub error: Option<String>,
 }
 
 /// A conversation thread
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct Thread {
     pub id: String,
     pub users: Vec<User>,
@@ -84,33 +239,63 @@ pub struct Thread {
     pub last_message_text: Option<String>,
     pub last_message_timestamp: Option<String>,
     pub has_unread: Option<bool>,
+    /// Messages since the viewer last read the thread, among the few the
+    /// inbox includes (inbox only; older servers leave it out)
+    pub unseen_count: Option<u32>,
+    /// Up to two of them, newest first
+    pub unseen_messages: Option<Vec<Message>>,
 }
 
 /// A direct message
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Message {
     pub id: String,
     pub text: Option<String>,
     pub timestamp: Option<String>,
     pub user_id: Option<String>,
     pub item_type: Option<String>,
+    pub is_sent_by_viewer: Option<bool>,
+    pub media_url: Option<String>,
+    pub media_type: Option<String>,
+    pub link_url: Option<String>,
+    pub link_title: Option<String>,
+    pub reactions: Option<Vec<serde_json::Value>>,
 }
 
-/// Error response from server
+impl Message {
+    /// File name of the photo or video, from the end of its URL
+    pub fn media_filename(&self) -> Option<&str> {
+        let url = self.media_url.as_deref().filter(|u| !u.is_empty())?;
+        let path = url.split(['?', '#']).next()?;
+        path.rsplit('/').next().filter(|name| !name.is_empty())
+    }
+
+    /// `[photo: 1234_n.jpg]` for a photo or video message
+    pub fn media_label(&self) -> Option<String> {
+        let name = self.media_filename()?;
+        Some(format!("[{}: {}]", self.media_type.as_deref().unwrap_or("media"), name))
+    }
+}
+
+/// Error response from server (bodies without a `detail` don't parse, so
+/// callers fall back to a generic message)
 #[derive(Debug, Deserialize)]
 pub struct ErrorResponse {
     pub detail: String,
 }
 
 /// Search user response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct SearchUserResponse {
     pub success: bool,
     pub user: Option<UserDetails>,
 }
 
 /// Detailed user info from search
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 pub struct UserDetails {
     pub pk: String,
     pub username: String,
@@ -121,3 +306,119 @@ pub struct UserDetails {
     pub follower_count: Option<u64>,
     pub following_count: Option<u64>,
 }
+
+/// Version of the NDJSON event schema emitted by `ig tail` and
+/// `ig notify --stdout`. Adding fields is compatible; removing or renaming
+/// one bumps the version.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// One line of the NDJSON event stream
+#[derive(Debug, Serialize)]
+pub struct StreamEvent {
+    pub schema_version: u32,
+    /// When the CLI observed the event (RFC 3339)
+    pub emitted_at: String,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+impl StreamEvent {
+    /// Wrap an event with the schema version and the current time
+    pub fn new(event: Event) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            emitted_at: chrono::Local::now().to_rfc3339(),
+            event,
+        }
+    }
+}
+
+/// Event payload, tagged by `type`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// A new message in a thread
+    Message {
+        thread_id: String,
+        message_id: String,
+        /// Sender username (None for our own messages)
+        sender: Option<String>,
+        user_id: Option<String>,
+        from_me: bool,
+        item_type: Option<String>,
+        text: Option<String>,
+        timestamp: Option<String>,
+    },
+    /// The reactions on a message changed
+    Reaction {
+        thread_id: String,
+        message_id: String,
+        reactions: Vec<serde_json::Value>,
+    },
+    /// A thread's latest activity or unread state changed
+    ThreadUpdate {
+        thread_id: String,
+        title: Option<String>,
+        participants: Vec<String>,
+        unread: bool,
+        last_message: Option<String>,
+        last_timestamp: Option<String>,
+    },
+    /// A participant came online or went offline
+    Presence {
+        username: String,
+        online: bool,
+    },
+    /// A local reminder fired
+    Reminder {
+        id: u32,
+        username: String,
+        thread_id: Option<String>,
+        note: String,
+    },
+    /// An incoming message contained an `[alerts]` keyword
+    Alert {
+        thread_id: String,
+        message_id: String,
+        sender: Option<String>,
+        keyword: String,
+        text: String,
+    },
+}
+
+impl Event {
+    /// Message event for a message in a thread with the given participants
+    pub fn message(thread_id: &str, message: &Message, users: &[User]) -> Self {
+        let from_me = message.is_sent_by_viewer.unwrap_or(false);
+        let sender = if from_me {
+            None
+        } else {
+            message
+                .user_id
+                .as_ref()
+                .and_then(|uid| crate::users::username(users, uid))
+        };
+        Event::Message {
+            thread_id: thread_id.to_string(),
+            message_id: message.id.clone(),
+            sender,
+            user_id: message.user_id.clone(),
+            from_me,
+            item_type: message.item_type.clone(),
+            text: message.text.clone(),
+            timestamp: message.timestamp.clone(),
+        }
+    }
+
+    /// Thread update event from an inbox thread
+    pub fn thread_update(thread: &Thread) -> Self {
+        Event::ThreadUpdate {
+            thread_id: thread.id.clone(),
+            title: thread.thread_title.clone(),
+            participants: thread.users.iter().map(|u| u.username.clone()).collect(),
+            unread: thread.has_unread.unwrap_or(false),
+            last_message: thread.last_message_text.clone(),
+            last_timestamp: thread.last_message_timestamp.clone(),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Local nicknames for conversation partners
+//!
+//! `ig nick @cryptic_handle_999 "Mom"` shows "Mom" wherever that person is
+//! named: the inbox, threads, live chat, `ig tail` and notifications.
+//! Nicknames never leave this machine (they're kept in the data directory),
+//! JSON output keeps the real usernames, and `--real-names` shows handles
+//! instead for one run.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::store;
+
+/// Data file holding nicknames
+const NICKNAMES_FILE: &str = "nicknames";
+
+static REAL_NAMES: AtomicBool = AtomicBool::new(false);
+
+/// Show handles rather than nicknames for this run (from `--real-names`)
+pub fn show_real_names() {
+    REAL_NAMES.store(true, Ordering::Relaxed);
+}
+
+/// Nicknames per username (lowercase)
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Nicknames {
+    pub by_user: BTreeMap<String, String>,
+}
+
+impl Nicknames {
+    /// Load nicknames from disk
+    pub fn load() -> Result<Self> {
+        store::load(NICKNAMES_FILE)
+    }
+
+    /// Save nicknames to disk
+    pub fn save(&self) -> Result<()> {
+        store::save(NICKNAMES_FILE, self)
+    }
+
+    /// The nickname given to `username`
+    pub fn get(&self, username: &str) -> Option<&str> {
+        self.by_user.get(&username.to_lowercase()).map(String::as_str)
+    }
+}
+
+/// The nickname to show for `username`, unless `--real-names` was given
+pub fn nickname(username: &str) -> Option<String> {
+    static NICKNAMES: OnceLock<Nicknames> = OnceLock::new();
+    if REAL_NAMES.load(Ordering::Relaxed) {
+        return None;
+    }
+    let nicknames = NICKNAMES.get_or_init(|| Nicknames::load().unwrap_or_default());
+    nicknames.get(username).map(str::to_string)
+}
+
+/// How to name someone: their nickname, or "@username"
+pub fn display(username: &str) -> String {
+    nickname(username).unwrap_or_else(|| format!("@{}", username))
+}
+
+/// Their nickname, or the bare username (for titles and sender labels
+/// that don't use an @)
+pub fn name(username: &str) -> String {
+    nickname(username).unwrap_or_else(|| username.to_string())
+}
@@ -0,0 +1,78 @@
+//! Desktop notifications
+//!
+//! Uses the platform's notification tool (`notify-send` on Linux,
+//! `osascript` on macOS) so no extra system libraries are needed.
+//!
+//! `[notify] quiet_hours` in the config holds them back for part of the day.
+
+use anyhow::{Context, Result};
+use chrono::NaiveTime;
+use std::process::Command;
+
+/// A daily time range without desktop notifications, e.g. "22:00-07:00".
+/// The start is included, the end isn't; ranges may wrap past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parse "HH:MM-HH:MM"
+    pub fn parse(range: &str) -> Result<Self> {
+        let (start, end) = range
+            .split_once('-')
+            .with_context(|| format!("Quiet hours '{}' should look like 22:00-07:00", range))?;
+        let time = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .with_context(|| format!("Invalid time '{}' in quiet hours (use HH:MM)", t.trim()))
+        };
+        Ok(Self { start: time(start)?, end: time(end)? })
+    }
+
+    /// Whether `time` falls within the quiet hours
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Show a desktop notification. Returns false if no notifier is available.
+pub fn desktop_notify(title: &str, body: &str) -> bool {
+    show(title, body, false)
+}
+
+/// Show a high-priority notification: critical urgency on Linux (stays
+/// until dismissed), with a sound on macOS
+pub fn desktop_alert(title: &str, body: &str) -> bool {
+    show(title, body, true)
+}
+
+fn show(title: &str, body: &str, urgent: bool) -> bool {
+    let status = if cfg!(target_os = "macos") {
+        let mut script = format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        );
+        if urgent {
+            script.push_str(" sound name \"Sosumi\"");
+        }
+        Command::new("osascript").args(["-e", &script]).status()
+    } else {
+        let urgency = if urgent { "--urgency=critical" } else { "--urgency=normal" };
+        Command::new("notify-send")
+            .args(["--app-name=ig", urgency, title, body])
+            .status()
+    };
+
+    status.map(|s| s.success()).unwrap_or(false)
+}
+
+/// Quote a string for AppleScript
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
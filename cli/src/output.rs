@@ -0,0 +1,108 @@
+//! Output formatting layer
+//!
+//! Commands that list things render through here so `--output table|plain|json`
+//! behaves the same everywhere: `plain` is each command's own styled listing,
+//! `table` is an aligned ASCII table and `json` is machine-readable. `csv` is
+//! only offered by the statistics commands (`ig stats`, `ig heatmap`), whose
+//! figures are meant for spreadsheets and dashboards.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement};
+use serde::Serialize;
+use std::io::{self, Write};
+
+use crate::colors::{colors_enabled, palette};
+
+/// How list output is rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Styled listing (default)
+    #[default]
+    Plain,
+    /// Aligned table
+    Table,
+    /// JSON
+    Json,
+    /// CSV (`ig stats` and `ig heatmap`)
+    Csv,
+}
+
+/// A table with a styled header row
+pub struct Table {
+    inner: comfy_table::Table,
+}
+
+impl Table {
+    /// Create a table with the given column headers
+    pub fn new(headers: &[&str]) -> Self {
+        let mut inner = comfy_table::Table::new();
+        inner
+            .load_preset(UTF8_FULL_CONDENSED)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+
+        if !colors_enabled() {
+            inner.force_no_tty();
+        }
+
+        let (r, g, b) = palette().purple;
+        inner.set_header(headers.iter().map(|h| {
+            Cell::new(h)
+                .fg(Color::Rgb { r, g, b })
+                .add_attribute(Attribute::Bold)
+        }));
+
+        Self { inner }
+    }
+
+    /// Append a row
+    pub fn row<I, S>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inner.add_row(cells.into_iter().map(|c| Cell::new(c.into())));
+    }
+
+    /// Print the table to stdout
+    pub fn print(&self) {
+        println!("{}", self.inner);
+    }
+}
+
+/// Print a value as pretty JSON
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print a value as a single NDJSON line, flushed immediately so consumers
+/// reading a pipe see each event as it happens
+pub fn print_ndjson<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer(&mut stdout, value)?;
+    writeln!(stdout)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Print rows as CSV with a header line
+pub fn print_csv(headers: &[&str], rows: impl IntoIterator<Item = Vec<String>>) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", headers.join(","))?;
+    for row in rows {
+        let line: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        writeln!(stdout, "{}", line.join(","))?;
+    }
+    Ok(())
+}
+
+/// Quote a CSV field when it contains separators, quotes or newlines (RFC 4180)
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
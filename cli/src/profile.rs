@@ -0,0 +1,102 @@
+//! The active profile and what it may do
+//!
+//! A profile is a `[profiles.<name>]` section in config.toml, chosen with
+//! `--profile` or the top-level `profile` setting. Its permissions are
+//! checked before each command runs and again in the API client before
+//! anything is sent or the account is touched, so a read-only profile can't
+//! send from any code path. Without a profile everything is allowed.
+
+use anyhow::{Context, Result};
+use std::sync::OnceLock;
+
+use crate::config::{Config, Permission, ProfileConfig};
+
+/// Profile selected for this run
+#[derive(Debug)]
+pub struct Profile {
+    /// Profile name (None when no profile is configured)
+    pub name: Option<String>,
+    /// Server URL from the profile
+    pub server: Option<String>,
+    /// Signature from the profile, overriding `[send] signature`
+    pub signature: Option<String>,
+    permissions: Vec<Permission>,
+}
+
+impl Profile {
+    fn unrestricted() -> Self {
+        Self {
+            name: None,
+            server: None,
+            signature: None,
+            permissions: Permission::ALL.to_vec(),
+        }
+    }
+
+    fn configured(name: &str, section: &ProfileConfig) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            server: section.server.clone(),
+            signature: section.signature.clone(),
+            permissions: section.permissions.clone(),
+        }
+    }
+
+    /// Whether this profile may do `permission`
+    pub fn allows(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+static ACTIVE: OnceLock<Profile> = OnceLock::new();
+
+/// Select the profile named on the command line, or the configured default
+pub fn activate(name: Option<&str>) -> Result<&'static Profile> {
+    let config = Config::load()?;
+    let profile = match name.map(str::to_string).or(config.profile) {
+        None => Profile::unrestricted(),
+        Some(name) => {
+            let section = config.profiles.get(&name).with_context(|| {
+                format!("No profile named '{}' (add [profiles.{}] to config.toml)", name, name)
+            })?;
+            Profile::configured(&name, section)
+        }
+    };
+    Ok(ACTIVE.get_or_init(|| profile))
+}
+
+/// Every profile in config.toml, by name
+pub fn all() -> Result<Vec<Profile>> {
+    let config = Config::load()?;
+    let mut profiles: Vec<Profile> = config
+        .profiles
+        .iter()
+        .map(|(name, section)| Profile::configured(name, section))
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+/// The active profile
+pub fn active() -> &'static Profile {
+    ACTIVE.get_or_init(Profile::unrestricted)
+}
+
+/// Fail unless the active profile allows `permission`
+pub fn require(permission: Permission) -> Result<()> {
+    let profile = active();
+    if profile.allows(permission) {
+        return Ok(());
+    }
+
+    let action = match permission {
+        Permission::Read => "read messages",
+        Permission::Send => "send messages",
+        Permission::Manage => "manage the account",
+    };
+    anyhow::bail!(
+        "Profile '{}' is not allowed to {}",
+        profile.name.as_deref().unwrap_or_default(),
+        action
+    )
+}
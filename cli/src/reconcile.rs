@@ -0,0 +1,112 @@
+//! Repairing partial thread data
+//!
+//! Instagram sometimes hands back threads whose users have no username or
+//! whose last-message timestamp is missing. Before anything is rendered,
+//! missing users are filled in from the user registry (see [`crate::users`]),
+//! missing timestamps and previews are taken from the thread's messages, and
+//! threads that still can't be shown properly are reported under `--verbose`
+//! rather than rendered as "unknown".
+
+use std::collections::HashSet;
+
+use crate::client::ApiClient;
+use crate::models::{InboxResponse, Thread, ThreadResponse, User};
+use crate::users;
+use crate::verbose;
+
+/// Repair every thread in an inbox, dropping threads that can't be shown
+pub async fn reconcile_inbox(client: &ApiClient, response: &mut InboxResponse) {
+    let Some(threads) = response.threads.as_mut() else {
+        return;
+    };
+    for thread in threads.iter_mut() {
+        repair(client, thread).await;
+    }
+    threads.retain(|thread| match malformed(thread) {
+        Some(reason) => {
+            verbose::log(&format!("Skipping thread {:?}: {}", thread.id, reason));
+            false
+        }
+        None => true,
+    });
+    users::save();
+}
+
+/// Repair a single thread (kept even if malformed, since it was asked for)
+pub async fn reconcile_thread(client: &ApiClient, response: &mut ThreadResponse) {
+    let Some(thread) = response.thread.as_mut() else {
+        return;
+    };
+    repair(client, thread).await;
+    if let Some(reason) = malformed(thread) {
+        verbose::log(&format!("Thread {:?} is incomplete: {}", thread.id, reason));
+    }
+    users::save();
+}
+
+/// Why a repaired thread still can't be rendered meaningfully
+fn malformed(thread: &Thread) -> Option<&'static str> {
+    if thread.id.is_empty() {
+        Some("no thread ID")
+    } else if thread.users.is_empty() && thread.thread_title.is_none() {
+        Some("no participants or title")
+    } else {
+        None
+    }
+}
+
+async fn repair(client: &ApiClient, thread: &mut Thread) {
+    users::remember(&thread.users);
+
+    // Senders of messages from people not in the thread's user list
+    // (e.g. members who left a group)
+    let mut seen = HashSet::new();
+    let senders: Vec<String> = thread
+        .messages
+        .iter()
+        .flatten()
+        .filter(|m| m.is_sent_by_viewer != Some(true))
+        .filter_map(|m| m.user_id.clone())
+        .filter(|pk| seen.insert(pk.clone()))
+        .collect();
+
+    // No users at all: recover participants from who sent the messages
+    if thread.users.is_empty() {
+        thread.users = senders
+            .iter()
+            .map(|pk| User { pk: pk.clone(), ..Default::default() })
+            .collect();
+    }
+
+    for user in thread.users.iter_mut().filter(|u| u.username.is_empty()) {
+        if let Some(known) = users::resolve(client, &user.pk).await {
+            *user = known;
+        }
+    }
+    let missing = thread.users.iter().filter(|u| u.username.is_empty()).count();
+    if missing > 0 {
+        verbose::log(&format!(
+            "Thread {:?}: {} participant(s) could not be identified",
+            thread.id, missing
+        ));
+        thread.users.retain(|u| !u.username.is_empty());
+    }
+
+    for pk in senders.iter().filter(|pk| !thread.users.iter().any(|u| &u.pk == *pk)) {
+        if users::resolve(client, pk).await.is_none() {
+            verbose::log(&format!("Thread {:?}: unknown sender {}", thread.id, pk));
+        }
+    }
+
+    // Messages come newest first
+    let newest = thread.messages.iter().flatten().next();
+    if thread.last_message_timestamp.is_none() {
+        thread.last_message_timestamp = newest.and_then(|m| m.timestamp.clone());
+    }
+    if thread.last_message_text.is_none() {
+        thread.last_message_text = newest.and_then(|m| m.text.clone());
+    }
+    if thread.last_message_timestamp.is_none() && thread.messages.is_none() {
+        verbose::log(&format!("Thread {:?}: no last message time", thread.id));
+    }
+}
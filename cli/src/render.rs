@@ -0,0 +1,163 @@
+//! Flicker-free redraws for the full-screen views
+//!
+//! The interactive inbox and `ig inbox -w` draw into a [`Canvas`] made of
+//! regions (header, list, preview, footer). It remembers what is on screen
+//! and only rewrites lines that changed, moving the cursor to them instead
+//! of clearing the screen, so nothing blinks when a single row changes. A
+//! [`FrameClock`] caps redraws at about 30 per second: events that arrive
+//! within one frame (a held arrow key, a burst of new messages) are
+//! handled together and drawn once.
+//!
+//! Each update is put together in an offscreen buffer and handed to the
+//! terminal in a single write, so it never shows a half-drawn frame.
+
+use crossterm::{
+    cursor, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// Shortest time between two redraws (~30 fps)
+pub const FRAME: Duration = Duration::from_millis(33);
+
+/// A screen split into regions stacked top to bottom
+pub struct Canvas {
+    regions: Vec<Region>,
+    /// Lines currently on the terminal
+    shown: Vec<String>,
+    /// Terminal height; lines below it are not drawn
+    height: usize,
+    /// Whether the next draw has to start from a cleared screen
+    invalidated: bool,
+}
+
+#[derive(Default)]
+struct Region {
+    lines: Vec<String>,
+    dirty: bool,
+}
+
+impl Canvas {
+    /// A canvas with `regions` empty regions, sized to the terminal
+    pub fn new(regions: usize) -> Self {
+        // Some terminals (and ptys) report a height of 0 when they don't know
+        let height = terminal::size()
+            .ok()
+            .filter(|&(_, rows)| rows > 0)
+            .map(|(_, rows)| rows as usize)
+            .unwrap_or(usize::MAX);
+        Self {
+            regions: (0..regions).map(|_| Region::default()).collect(),
+            shown: Vec::new(),
+            height,
+            invalidated: true,
+        }
+    }
+
+    /// Replace a region's lines; it is only redrawn if they changed
+    pub fn set(&mut self, region: usize, lines: Vec<String>) {
+        let region = &mut self.regions[region];
+        if region.lines != lines {
+            region.lines = lines;
+            region.dirty = true;
+        }
+    }
+
+    /// Replace a region's lines with text written for a raw-mode terminal
+    /// (`\r` line starts are dropped)
+    pub fn set_output(&mut self, region: usize, output: &[u8]) {
+        let lines = String::from_utf8_lossy(output)
+            .lines()
+            .map(|line| line.trim_matches('\r').to_string())
+            .collect();
+        self.set(region, lines);
+    }
+
+    /// The terminal was resized: redraw everything at the new size
+    pub fn resize(&mut self, rows: u16) {
+        self.height = rows as usize;
+        self.invalidate();
+    }
+
+    /// Something else drew over the screen: redraw everything next time
+    pub fn invalidate(&mut self) {
+        self.invalidated = true;
+    }
+
+    /// Put the cursor on the last line drawn, so output after leaving the
+    /// view starts below it
+    pub fn move_below(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, cursor::MoveTo(0, self.shown.len().saturating_sub(1) as u16))?;
+        out.flush()
+    }
+
+    /// Bring the terminal up to date, writing only the lines that changed
+    /// (in one write)
+    pub fn draw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if !self.invalidated && !self.regions.iter().any(|r| r.dirty) {
+            return Ok(());
+        }
+
+        let frame: Vec<String> = self
+            .regions
+            .iter()
+            .flat_map(|r| r.lines.iter().cloned())
+            .take(self.height)
+            .collect();
+
+        let mut buffer = Vec::new();
+        if self.invalidated {
+            queue!(buffer, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+            self.shown.clear();
+        }
+        for (row, line) in frame.iter().enumerate() {
+            if self.shown.get(row) == Some(line) {
+                continue;
+            }
+            queue!(
+                buffer,
+                cursor::MoveTo(0, row as u16),
+                Print(line),
+                terminal::Clear(ClearType::UntilNewLine)
+            )?;
+        }
+        if frame.len() < self.shown.len() {
+            queue!(
+                buffer,
+                cursor::MoveTo(0, frame.len() as u16),
+                terminal::Clear(ClearType::FromCursorDown)
+            )?;
+        }
+        out.write_all(&buffer)?;
+        out.flush()?;
+
+        self.shown = frame;
+        self.invalidated = false;
+        for region in &mut self.regions {
+            region.dirty = false;
+        }
+        Ok(())
+    }
+}
+
+/// Paces redraws to at most one per [`FRAME`]
+#[derive(Default)]
+pub struct FrameClock {
+    last: Option<Instant>,
+}
+
+impl FrameClock {
+    /// Record that a frame was just drawn
+    pub fn drawn(&mut self) {
+        self.last = Some(Instant::now());
+    }
+
+    /// How long until the next frame may be drawn (zero if it's due)
+    pub fn until_next(&self) -> Duration {
+        self.last
+            .map(|last| FRAME.saturating_sub(last.elapsed()))
+            .unwrap_or_default()
+    }
+}
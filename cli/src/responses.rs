@@ -0,0 +1,154 @@
+//! Response cache for conditional requests
+//!
+//! The last 200 response of each polled URL (`/inbox`, `/thread/{id}`) is
+//! kept with its ETag / Last-Modified, in memory and as a file under
+//! `responses/` in the data directory, so the next `ig` run can ask "has
+//! this changed?" and get a `304 Not Modified` instead of the whole body.
+//! The files hold message text, so `keep_messages` retention removes them
+//! too (see [`crate::commands::cache`]).
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use crate::store;
+
+/// Directory in the data directory holding the cached responses
+const RESPONSES_DIR: &str = "responses";
+
+/// A response body kept for conditional requests
+#[derive(Clone)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// A cached response as stored on disk
+#[derive(Serialize, Deserialize)]
+struct StoredResponse {
+    /// The URL it answered, since file names are only a hash of it
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Base64
+    body: String,
+}
+
+impl StoredResponse {
+    fn response(self) -> Option<CachedResponse> {
+        Some(CachedResponse {
+            etag: self.etag,
+            last_modified: self.last_modified,
+            body: STANDARD.decode(self.body).ok()?,
+        })
+    }
+}
+
+/// Last 200 response per URL, backed by files in the data directory
+///
+/// Disk errors are ignored: a response that can't be stored or read only
+/// means a full download next time.
+#[derive(Default)]
+pub struct ResponseCache {
+    memory: HashMap<String, CachedResponse>,
+}
+
+impl ResponseCache {
+    /// The cached response for `url`, read from disk if this run hasn't
+    /// seen it yet
+    pub fn get(&mut self, url: &str) -> Option<&CachedResponse> {
+        if !self.memory.contains_key(url) {
+            let stored = fs::read(file_for(url)?)
+                .ok()
+                .and_then(|data| serde_json::from_slice::<StoredResponse>(&data).ok())
+                .filter(|stored| stored.url == url)?
+                .response()?;
+            self.memory.insert(url.to_string(), stored);
+        }
+        self.memory.get(url)
+    }
+
+    pub fn insert(&mut self, url: &str, response: CachedResponse) {
+        if let Some(path) = file_for(url) {
+            let stored = StoredResponse {
+                url: url.to_string(),
+                etag: response.etag.clone(),
+                last_modified: response.last_modified.clone(),
+                body: STANDARD.encode(&response.body),
+            };
+            if let Ok(data) = serde_json::to_vec(&stored) {
+                let _ = fs::write(path, data);
+            }
+        }
+        self.memory.insert(url.to_string(), response);
+    }
+
+    pub fn remove(&mut self, url: &str) {
+        self.memory.remove(url);
+        if let Some(path) = file_for(url) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Keep only the responses whose URL passes `keep`, on disk too
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.memory.retain(|url, _| keep(url));
+        for (path, stored) in stored_files() {
+            if !keep(&stored.url) {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// Remove cached responses stored before `cutoff`; returns how many went
+pub fn prune(cutoff: DateTime<Local>) -> usize {
+    let Some(dir) = dir() else {
+        return 0;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let modified = entry.metadata().and_then(|m| m.modified()).map(DateTime::<Local>::from);
+        if modified.is_ok_and(|at| at < cutoff) && fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// The cache directory, created if needed
+fn dir() -> Option<PathBuf> {
+    let dir = store::data_dir().ok()?.join(RESPONSES_DIR);
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+/// File holding the response for `url`
+fn file_for(url: &str) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    Some(dir()?.join(format!("{:016x}.json", hasher.finish())))
+}
+
+/// Every readable stored response with its file
+fn stored_files() -> Vec<(PathBuf, StoredResponse)> {
+    let Some(entries) = dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let stored = serde_json::from_slice(&fs::read(entry.path()).ok()?).ok()?;
+            Some((entry.path(), stored))
+        })
+        .collect()
+}
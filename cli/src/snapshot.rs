@@ -0,0 +1,89 @@
+//! The last inbox listing shown (`ig inbox`)
+//!
+//! `ig open 3` means "the third conversation I just saw", not whatever is
+//! third by the time the command runs. Every numbered listing is saved
+//! here so `ig open` can pick from the same list, even if new messages
+//! have reordered the inbox since.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Thread;
+use crate::store;
+
+/// Data file holding the last listing
+const SNAPSHOT_FILE: &str = "inbox_snapshot";
+
+/// Age after which `ig open` warns that the listing may be out of date
+pub const STALE_AFTER_MINUTES: i64 = 10;
+
+/// A conversation as it was listed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Listed {
+    pub thread_id: String,
+    /// Usernames of the participants, first one is who the chat opens with
+    pub usernames: Vec<String>,
+    /// Profile it was listed from (`ig inbox --all-profiles`), so `ig open`
+    /// talks to the right account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+impl Listed {
+    fn new(thread: &Thread, profile: Option<&str>) -> Self {
+        Self {
+            thread_id: thread.id.clone(),
+            usernames: thread.users.iter().map(|u| u.username.clone()).collect(),
+            profile: profile.map(str::to_string),
+        }
+    }
+}
+
+/// The numbered conversations of the last listing, in order
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InboxSnapshot {
+    pub taken_at: Option<DateTime<Local>>,
+    pub threads: Vec<Listed>,
+}
+
+impl InboxSnapshot {
+    /// Load the last listing from disk
+    pub fn load() -> Result<Self> {
+        store::load(SNAPSHOT_FILE)
+    }
+
+    /// Conversation at a 1-based position
+    pub fn get(&self, number: usize) -> Option<&Listed> {
+        number.checked_sub(1).and_then(|i| self.threads.get(i))
+    }
+
+    /// How long ago the listing was shown
+    pub fn age(&self) -> Option<Duration> {
+        self.taken_at.map(|at| Local::now().signed_duration_since(at))
+    }
+
+    /// Whether the listing is older than [`STALE_AFTER_MINUTES`]
+    pub fn is_stale(&self) -> bool {
+        self.age().is_some_and(|age| age > Duration::minutes(STALE_AFTER_MINUTES))
+    }
+}
+
+/// Remember the threads just listed. Best effort: a failed write only
+/// means `ig open` falls back to fetching the inbox.
+pub fn record(threads: &[Thread]) {
+    save(threads.iter().map(|t| Listed::new(t, None)).collect());
+}
+
+/// Remember a listing merged from several profiles
+pub fn record_merged(threads: &[(String, Thread)]) {
+    save(threads.iter().map(|(profile, t)| Listed::new(t, Some(profile))).collect());
+}
+
+fn save(threads: Vec<Listed>) {
+    let snapshot = InboxSnapshot {
+        taken_at: Some(Local::now()),
+        threads,
+    };
+    let _ = store::save(SNAPSHOT_FILE, &snapshot);
+}
@@ -1,23 +1,19 @@
 //! Instagram-themed loading spinner animations
 //!
 //! Provides animated spinners that match Instagram's brand colors,
-//! cycling through the iconic gradient: Purple → Pink → Orange → Yellow
+//! cycling through the iconic gradient: Purple → Pink → Orange → Yellow,
+//! and progress bars for fetches that span many pages and for large
+//! downloads.
 
-use std::io::{self, Write};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
-use crate::colors::instagram;
-
-/// Instagram gradient colors for the spinner
-const GRADIENT: [(u8, u8, u8); 4] = [
-    instagram::PURPLE,  // #833AB4
-    instagram::PINK,    // #E1306C
-    instagram::ORANGE,  // #F77737
-    instagram::YELLOW,  // #FCAF45
-];
+use crate::colors::{fg_escape, gradient_text, palette, reset_escape, Theme};
 
 /// Spinner characters for smooth animation
 const SPINNER_CHARS: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
@@ -29,6 +25,8 @@ const SPINNER_CHARS: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '
 pub struct Spinner {
     running: Arc<AtomicBool>,
     handle: Option<thread::JoinHandle<()>>,
+    /// False when stdout is not a terminal (nothing is drawn)
+    active: bool,
 }
 
 impl Spinner {
@@ -38,18 +36,22 @@ impl Spinner {
         if let Some(handle) = self.handle.take() {
             let _ = handle.join();
         }
-        // Clear the line and show cursor
-        print!("\r\x1b[K\x1b[?25h");
-        let _ = io::stdout().flush();
+        if self.active {
+            // Clear the line and show cursor
+            print!("\r\x1b[K\x1b[?25h");
+            let _ = io::stdout().flush();
+        }
     }
 }
 
 impl Drop for Spinner {
     fn drop(&mut self) {
         self.running.store(false, Ordering::SeqCst);
-        // Ensure cursor is shown if dropped unexpectedly
-        print!("\x1b[?25h");
-        let _ = io::stdout().flush();
+        if self.active {
+            // Ensure cursor is shown if dropped unexpectedly
+            print!("\x1b[?25h");
+            let _ = io::stdout().flush();
+        }
     }
 }
 
@@ -60,13 +62,29 @@ impl Drop for Spinner {
 /// - Gradient colors: Purple → Pink → Orange → Yellow
 /// - Trailing dots: . → .. → ...
 ///
+/// When stdout is not a terminal (piped or redirected output) nothing is drawn,
+/// so machine-readable output stays clean.
+///
 /// # Example
-/// ```
+/// ```no_run
+/// # use insta_cli::{client::ApiClient, spinner::create_spinner};
+/// # async fn example(client: &ApiClient) {
 /// let spinner = create_spinner("Fetching inbox");
-/// let result = client.get_inbox().await;
+/// let result = client.get_inbox(20).await;
 /// spinner.finish_and_clear();
+/// # }
 /// ```
 pub fn create_spinner(message: &str) -> Spinner {
+    let running = Arc::new(AtomicBool::new(true));
+
+    if !io::stdout().is_terminal() {
+        return Spinner {
+            running,
+            handle: None,
+            active: false,
+        };
+    }
+
     // Remove trailing dots from message (we'll animate them)
     let base_message = message.trim_end_matches('.').to_string();
 
@@ -74,9 +92,11 @@ pub fn create_spinner(message: &str) -> Spinner {
     print!("\x1b[?25l");
     let _ = io::stdout().flush();
 
-    let running = Arc::new(AtomicBool::new(true));
     let running_clone = running.clone();
 
+    // Gradient colors from the active theme (Instagram's by default)
+    let gradient = palette().gradient();
+
     let handle = thread::spawn(move || {
         // Pad dots to same length to avoid leftover characters
         let dots = [".  ", ".. ", "..."];
@@ -87,16 +107,17 @@ pub fn create_spinner(message: &str) -> Spinner {
 
         while running_clone.load(Ordering::SeqCst) {
             // Get current color from gradient
-            let (r, g, b) = GRADIENT[color_index];
+            let color = fg_escape(gradient[color_index]);
 
             // Get current spinner character
             let spinner_char = SPINNER_CHARS[spinner_index];
 
             // Build the line with colored spinner
             let line = format!(
-                "\r\x1b[38;2;{};{};{}m{}\x1b[0m {}{}",
-                r, g, b,
+                "\r{}{}{} {}{}",
+                color,
                 spinner_char,
+                reset_escape(),
                 base_message,
                 dots[dot_index]
             );
@@ -110,7 +131,7 @@ pub fn create_spinner(message: &str) -> Spinner {
             // Advance color (every 2 spinner frames for smooth gradient)
             tick_count += 1;
             if tick_count % 2 == 0 {
-                color_index = (color_index + 1) % GRADIENT.len();
+                color_index = (color_index + 1) % gradient.len();
             }
 
             // Advance dots every ~320ms (4 ticks * 80ms)
@@ -125,5 +146,116 @@ pub fn create_spinner(message: &str) -> Spinner {
     Spinner {
         running,
         handle: Some(handle),
+        active: true,
+    }
+}
+
+/// Width of the progress bar in cells
+const PROGRESS_BAR_WIDTH: usize = 30;
+
+/// Progress bar for multi-page fetches
+///
+/// Shows pages fetched, items collected and, when the total is known, a
+/// gradient bar with an ETA. Like the spinner, nothing is drawn when stdout
+/// is not a terminal.
+pub struct Progress {
+    bar: ProgressBar,
+    message: String,
+    pages: u64,
+}
+
+impl Progress {
+    /// Record a fetched page holding `items` more items
+    pub fn page_done(&mut self, items: u64) {
+        self.pages += 1;
+        self.bar.inc(items);
+        self.bar.set_message(format!(
+            "{} · page {}",
+            self.message,
+            self.pages
+        ));
+    }
+
+    /// Set how much has been done so far (bytes, for a download)
+    pub fn set_position(&self, done: u64) {
+        self.bar.set_position(done);
+    }
+
+    /// Finish and clear the progress bar from the terminal
+    pub fn finish_and_clear(self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+/// Create a progress bar counting `unit` (e.g. "messages")
+///
+/// With a `total` the bar fills up and shows an ETA; without one it shows
+/// how many items have been collected so far and the elapsed time.
+///
+/// # Example
+/// ```
+/// # use insta_cli::spinner::create_progress;
+/// let mut progress = create_progress("Fetching history", "messages", None);
+/// progress.page_done(100);
+/// progress.finish_and_clear();
+/// ```
+pub fn create_progress(message: &str, unit: &str, total: Option<u64>) -> Progress {
+    let message = message.trim_end_matches('.').to_string();
+    let template = match total {
+        Some(_) => format!("{{icon}} {{msg}} {{gradient_bar}} {{pos}}/{{len}} {} · ETA {{eta}}", unit),
+        None => format!("{{icon}} {{msg}} · {{pos}} {} · {{elapsed}}", unit),
+    };
+    themed_progress(message, &template, total)
+}
+
+/// Create a progress bar for downloading a file of `total` bytes (or of
+/// unknown size), moved along with [`Progress::set_position`]
+pub fn create_download_progress(message: &str, total: Option<u64>) -> Progress {
+    let message = message.trim_end_matches('.').to_string();
+    let template = match total {
+        Some(_) => "{icon} {msg} {gradient_bar} {bytes}/{total_bytes} · {bytes_per_sec} · ETA {eta}",
+        None => "{icon} {msg} · {bytes} · {bytes_per_sec}",
+    };
+    themed_progress(message, template, total)
+}
+
+/// A progress bar in the spinner's colors, drawn only on a terminal
+fn themed_progress(message: String, template: &str, total: Option<u64>) -> Progress {
+    let target = if io::stdout().is_terminal() {
+        ProgressDrawTarget::stdout()
+    } else {
+        ProgressDrawTarget::hidden()
+    };
+
+    let gradient = palette().gradient();
+    let style = ProgressStyle::with_template(template)
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .with_key("icon", move |state: &ProgressState, w: &mut dyn fmt::Write| {
+            // Same frames and colors as the spinner, driven by elapsed time
+            let ticks = state.elapsed().as_millis() / 80;
+            let spinner_char = SPINNER_CHARS[ticks as usize % SPINNER_CHARS.len()];
+            let color = fg_escape(gradient[(ticks / 2) as usize % gradient.len()]);
+            let _ = write!(w, "{}{}{}", color, spinner_char, reset_escape());
+        })
+        .with_key("gradient_bar", |state: &ProgressState, w: &mut dyn fmt::Write| {
+            let filled = (state.fraction() * PROGRESS_BAR_WIDTH as f32).round() as usize;
+            let filled = filled.min(PROGRESS_BAR_WIDTH);
+            let _ = write!(
+                w,
+                "{}{}",
+                gradient_text(&"━".repeat(filled)),
+                Theme::muted(&"─".repeat(PROGRESS_BAR_WIDTH - filled))
+            );
+        });
+
+    let bar = ProgressBar::with_draw_target(total, target);
+    bar.set_style(style);
+    bar.set_message(message.clone());
+    bar.enable_steady_tick(Duration::from_millis(80));
+
+    Progress {
+        bar,
+        message,
+        pages: 0,
     }
 }
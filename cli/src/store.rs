@@ -0,0 +1,52 @@
+//! Local data storage
+//!
+//! Small JSON files under the user's data directory
+//! (e.g. `~/.local/share/insta-cli/` on Linux) hold state that only exists
+//! on this machine: reminders, pins, tags and so on.
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory name under the platform data directory
+const APP_DIR: &str = "insta-cli";
+
+/// Directory holding all local data files
+pub fn data_dir() -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .context("Could not determine the data directory")?
+        .join(APP_DIR);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create data directory {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// Path of a named data file
+pub fn path(name: &str) -> Result<PathBuf> {
+    Ok(data_dir()?.join(format!("{}.json", name)))
+}
+
+/// Load a named data file, falling back to the default if it doesn't exist yet
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> Result<T> {
+    let path = path(name)?;
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let data = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Save a named data file
+pub fn save<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    let path = path(name)?;
+    let data = serde_json::to_string_pretty(value)?;
+
+    // Write to a temp file first so a crash never leaves a half-written file
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
@@ -0,0 +1,193 @@
+//! Language model help with conversations: summaries and reply drafts
+//!
+//! Backends implement [`Summarizer`] and are picked with `[summarizer]
+//! backend` in config.toml. The only backend so far speaks the OpenAI chat
+//! completions API, which local servers (Ollama, llama.cpp, LM Studio) offer
+//! too, so messages never have to leave the machine.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::config::{Config, SummarizerBackend, SummarizerConfig};
+use crate::http;
+
+/// Give up on the model after this long (local models can be slow)
+const REQUEST_TIMEOUT_SECS: u64 = 180;
+
+/// Instructions sent ahead of the transcript
+const SYSTEM_PROMPT: &str = "You summarize Instagram direct message conversations. \
+The transcript has one message per line as \"[time] sender: text\", oldest first; \
+\"You\" is the person asking. Reply with only a JSON object: \
+{\"summary\": \"a few sentences on what was discussed and where it stands\", \
+\"action_items\": [\"things You still need to do or reply to\"]}. \
+Use an empty list when nothing is left to do.";
+
+/// Instructions for drafting the next message
+const DRAFT_PROMPT: &str = "You help \"You\" reply in an Instagram direct message conversation. \
+The transcript has one message per line as \"[time] sender: text\", oldest first. \
+Write the next message You should send: short, casual unless the conversation is formal, \
+and in the conversation's language. Reply with only the message text.";
+
+/// What a summarizer returns
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Summary {
+    pub summary: String,
+    #[serde(default)]
+    pub action_items: Vec<String>,
+}
+
+/// A backend that can summarize a conversation
+pub trait Summarizer {
+    /// Backend description shown while it works (e.g. the model name)
+    fn name(&self) -> String;
+
+    /// Summarize a transcript of "[time] sender: text" lines, oldest first
+    fn summarize(&self, transcript: &str) -> impl Future<Output = Result<Summary>> + Send;
+
+    /// Draft the next message to send, building on `start` if it isn't empty
+    fn draft_reply(&self, transcript: &str, start: &str) -> impl Future<Output = Result<String>> + Send;
+}
+
+/// The summarizer set up in config.toml
+pub fn from_config() -> Result<impl Summarizer> {
+    let config = Config::load()?.summarizer;
+    match config.backend {
+        SummarizerBackend::OpenAi => OpenAiSummarizer::from_config(&config),
+    }
+}
+
+/// Whether a summarizer is set up (so reply drafting can be offered)
+pub fn configured() -> bool {
+    Config::load().is_ok_and(|c| c.summarizer.url.is_some())
+}
+
+/// OpenAI-compatible `/chat/completions` endpoint
+pub struct OpenAiSummarizer {
+    client: Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiSummarizer {
+    /// Build from the `[summarizer]` config
+    pub fn from_config(config: &SummarizerConfig) -> Result<Self> {
+        let url = config
+            .url
+            .clone()
+            .context("No summarizer configured; set `url` under [summarizer] in config.toml")?;
+        let model = config
+            .model
+            .clone()
+            .context("Set `model` under [summarizer] in config.toml")?;
+        let api_key = match &config.api_key_env {
+            Some(var) => Some(
+                std::env::var(var)
+                    .with_context(|| format!("Environment variable {} (api_key_env) is not set", var))?,
+            ),
+            None => None,
+        };
+        let client = http::with_network(Client::builder())
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            model,
+            api_key,
+        })
+    }
+
+    /// Send one system + user exchange and return the model's answer
+    async fn complete(&self, system: &str, user: &str) -> Result<String> {
+        let url = format!("{}/chat/completions", self.url);
+        let body = json!({
+            "model": self.model,
+            "temperature": 0.2,
+            "messages": [
+                {"role": "system", "content": system},
+                {"role": "user", "content": user},
+            ],
+        });
+
+        let mut request = self.client.post(&url).json(&body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let resp = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach the summarizer at {}", self.url))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let detail = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Summarizer returned {}: {}", status, detail.trim());
+        }
+
+        let completion: CompletionResponse = resp
+            .json()
+            .await
+            .context("Failed to parse summarizer response")?;
+        completion
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .context("Summarizer returned no answer")
+    }
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChoiceMessage {
+    content: Option<String>,
+}
+
+impl Summarizer for OpenAiSummarizer {
+    fn name(&self) -> String {
+        self.model.clone()
+    }
+
+    async fn summarize(&self, transcript: &str) -> Result<Summary> {
+        let content = self.complete(SYSTEM_PROMPT, transcript).await?;
+        Ok(parse_summary(&content))
+    }
+
+    async fn draft_reply(&self, transcript: &str, start: &str) -> Result<String> {
+        let mut input = transcript.to_string();
+        if !start.trim().is_empty() {
+            input.push_str(&format!("\n\nStart the reply from this draft: {}", start.trim()));
+        }
+        let content = self.complete(DRAFT_PROMPT, &input).await?;
+        // Models like to wrap the message in quotes
+        Ok(content.trim().trim_matches('"').trim().to_string())
+    }
+}
+
+/// Read the model's answer, falling back to the raw text when it didn't
+/// reply with the requested JSON
+fn parse_summary(content: &str) -> Summary {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => "",
+    };
+    serde_json::from_str(json).unwrap_or_else(|_| Summary {
+        summary: content.trim().to_string(),
+        action_items: Vec::new(),
+    })
+}
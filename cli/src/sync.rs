@@ -0,0 +1,389 @@
+//! Inbox sync: one polling task, many subscribers
+//!
+//! Features that react to new activity (live chat, `ig notify`, `ig tail`)
+//! subscribe to a [`SyncHandle`] instead of running their own polling loop.
+//! The sync task diffs each poll against what it saw last and broadcasts
+//! typed [`SyncEvent`]s.
+//!
+//! When a poll fails, the task asks `/health` what happened. A server that
+//! is down, came back (or restarted in between, which its `started_at`
+//! shows) or is no longer logged in is reported once, not on every poll.
+//! After a restart it polls again right away: the server may reload its
+//! session lazily, and messages that arrived in between are caught up.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::client::ApiClient;
+use crate::exclusions;
+use crate::models::{Message, Thread, User};
+use crate::tasks::Supervisor;
+
+/// Events buffered per subscriber before slow ones start missing events
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Messages fetched when looking for new messages in a thread
+const THREAD_LIMIT: u32 = 20;
+
+/// Something that changed since the previous poll
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    /// A message we had not seen before
+    NewMessage {
+        thread_id: String,
+        message: Arc<Message>,
+        /// Participants of the thread, to resolve the sender
+        users: Arc<Vec<User>>,
+    },
+    /// The reactions on a known message changed
+    ReactionsChanged {
+        thread_id: String,
+        message_id: String,
+        reactions: Vec<serde_json::Value>,
+    },
+    /// A thread's latest activity or unread state changed
+    ThreadUpdated {
+        thread: Arc<Thread>,
+        /// Whether the last message changed (as opposed to only the unread flag)
+        new_activity: bool,
+    },
+    /// A participant came online or went offline (only from servers that
+    /// report presence)
+    PresenceChanged { username: String, online: bool },
+    /// A poll failed; the task keeps polling
+    PollFailed(String),
+    /// The server stopped answering (sent once until it's back)
+    ServerUnavailable(String),
+    /// The server came back or restarted; polling catches up on what was missed
+    ServerRestarted,
+    /// The server is up but no longer logged in (sent once until it is)
+    SessionLost,
+    /// The server is logged in again after [`SyncEvent::SessionLost`]
+    SessionRestored,
+}
+
+impl SyncEvent {
+    /// One-line notice for the server events, worded the same in every mode
+    pub fn server_notice(&self) -> Option<&'static str> {
+        match self {
+            SyncEvent::ServerUnavailable(_) => Some("Lost connection to the server, retrying…"),
+            SyncEvent::ServerRestarted => Some("Server restarted, re-syncing…"),
+            SyncEvent::SessionLost => Some("The server is no longer logged in. Run `ig login`; this resumes by itself."),
+            SyncEvent::SessionRestored => Some("Logged in again, re-syncing…"),
+            _ => None,
+        }
+    }
+}
+
+/// What the sync task polls
+pub struct SyncOptions {
+    /// Time between polls
+    pub interval: Duration,
+    /// Inbox threads watched for updates (0 to skip the inbox)
+    pub inbox_limit: u32,
+    /// Fetch new messages for inbox threads with new activity
+    pub inbox_messages: bool,
+    /// Poll `threads` for new messages only, instead of re-fetching recent
+    /// history (cheaper, but reaction changes on older messages are missed)
+    pub delta: bool,
+    /// Threads polled directly for new messages and reactions
+    pub threads: Vec<String>,
+}
+
+/// Handle to a running sync task; the task stops with the [`Supervisor`]
+/// it was spawned on
+pub struct SyncHandle {
+    sender: broadcast::Sender<SyncEvent>,
+}
+
+impl SyncHandle {
+    /// Start polling. The first poll only records the current state, so
+    /// subscribers only see what changes after they subscribed.
+    pub fn spawn(tasks: &mut Supervisor, client: &ApiClient, options: SyncOptions) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let mut poller = Poller {
+            client: client.clone(),
+            sender: sender.clone(),
+            threads: HashMap::new(),
+            presence: HashMap::new(),
+            first_poll: true,
+            delta_supported: true,
+            started_at: None,
+            outage: Outage::None,
+        };
+
+        tasks.spawn(async move {
+            // Which server instance this is, to tell a restart from other errors
+            poller.started_at = poller.client.health().await.ok().and_then(|h| h.started_at);
+            loop {
+                match poller.poll(&options).await {
+                    Ok(()) => poller.resumed(),
+                    Err(e) => poller.recover(e, &options).await,
+                }
+                poller.first_poll = false;
+                tokio::time::sleep(options.interval).await;
+            }
+        });
+
+        Self { sender }
+    }
+
+    /// Receive every event from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<SyncEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// What was last seen in a thread
+#[derive(Default)]
+struct ThreadState {
+    last_timestamp: Option<String>,
+    unread: bool,
+    /// Whether the thread's messages have been fetched before
+    fetched: bool,
+    /// Reactions per known message ID, serialized for cheap comparison
+    /// (the newest `THREAD_LIMIT` only)
+    messages: HashMap<String, String>,
+    /// Known message IDs, oldest first, to drop the oldest
+    order: VecDeque<String>,
+    /// ID of the newest message seen
+    newest_id: Option<String>,
+}
+
+/// Why polls are failing, while they are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outage {
+    None,
+    /// The server doesn't answer
+    Unreachable,
+    /// The server answers but isn't logged in
+    LoggedOut,
+}
+
+/// State owned by the sync task
+struct Poller {
+    client: ApiClient,
+    sender: broadcast::Sender<SyncEvent>,
+    threads: HashMap<String, ThreadState>,
+    /// Last reported online state per username
+    presence: HashMap<String, bool>,
+    first_poll: bool,
+    /// Cleared once the server turns out not to support delta fetches
+    delta_supported: bool,
+    /// `started_at` of the server instance last seen
+    started_at: Option<String>,
+    outage: Outage,
+}
+
+impl Poller {
+    /// Polls work again: say so if they didn't before
+    fn resumed(&mut self) {
+        let event = match self.outage {
+            Outage::None => return,
+            Outage::Unreachable => SyncEvent::ServerRestarted,
+            Outage::LoggedOut => SyncEvent::SessionRestored,
+        };
+        self.outage = Outage::None;
+        let _ = self.sender.send(event);
+    }
+
+    /// Find out why a poll failed and tell subscribers once per outage
+    async fn recover(&mut self, error: anyhow::Error, options: &SyncOptions) {
+        let health = match self.client.health().await {
+            Ok(health) => health,
+            Err(_) => {
+                if self.outage != Outage::Unreachable {
+                    self.outage = Outage::Unreachable;
+                    let _ = self.sender.send(SyncEvent::ServerUnavailable(format!("{:#}", error)));
+                }
+                return;
+            }
+        };
+
+        let restarted =
+            self.started_at.is_some() && health.started_at.is_some() && health.started_at != self.started_at;
+        self.started_at = health.started_at;
+        if !health.authenticated {
+            if self.outage != Outage::LoggedOut {
+                self.outage = Outage::LoggedOut;
+                let _ = self.sender.send(SyncEvent::SessionLost);
+            }
+            return;
+        }
+
+        match self.outage {
+            Outage::None if !restarted => {
+                let _ = self.sender.send(SyncEvent::PollFailed(format!("{:#}", error)));
+                return;
+            }
+            Outage::None => {
+                let _ = self.sender.send(SyncEvent::ServerRestarted);
+            }
+            Outage::Unreachable | Outage::LoggedOut => self.resumed(),
+        }
+        // The session may only just have been reloaded: try again right away
+        self.client.stats().record_retry();
+        if let Err(e) = self.poll(options).await {
+            let _ = self.sender.send(SyncEvent::PollFailed(format!("{:#}", e)));
+        }
+    }
+
+    async fn poll(&mut self, options: &SyncOptions) -> Result<()> {
+        // Re-read every poll so `ig sync exclude` applies to running watchers.
+        // Threads opened directly (`ig chat`) are still polled; the client
+        // doesn't cache their responses.
+        let excluded = exclusions::excluded_ids();
+        self.threads
+            .retain(|thread_id, _| !excluded.contains(thread_id) || options.threads.contains(thread_id));
+
+        if options.inbox_limit > 0 {
+            self.poll_inbox(options, &excluded).await?;
+        }
+        for thread_id in &options.threads {
+            self.poll_thread(thread_id, None, options.delta).await?;
+        }
+        Ok(())
+    }
+
+    /// Broadcast presence changes among `users`. A user's first reported
+    /// state is only recorded.
+    fn observe_presence(&mut self, users: &[User]) {
+        for user in users {
+            let Some(online) = user.is_active else {
+                continue;
+            };
+            let previous = self.presence.insert(user.username.clone(), online);
+            if previous.is_some_and(|previous| previous != online) {
+                let _ = self.sender.send(SyncEvent::PresenceChanged { username: user.username.clone(), online });
+            }
+        }
+    }
+
+    /// Broadcast updates for inbox threads that changed
+    async fn poll_inbox(&mut self, options: &SyncOptions, excluded: &HashSet<String>) -> Result<()> {
+        let response = self.client.get_inbox(options.inbox_limit).await?;
+        if !response.success {
+            anyhow::bail!(response.error.unwrap_or("Failed to fetch inbox".to_string()));
+        }
+
+        for thread in response.threads.unwrap_or_default() {
+            if excluded.contains(&thread.id) {
+                continue;
+            }
+            self.observe_presence(&thread.users);
+            let state = self.threads.entry(thread.id.clone()).or_default();
+            let unread = thread.has_unread.unwrap_or(false);
+            let new_activity = state.last_timestamp != thread.last_message_timestamp;
+            let changed = new_activity || state.unread != unread;
+
+            let previous_timestamp =
+                std::mem::replace(&mut state.last_timestamp, thread.last_message_timestamp.clone());
+            state.unread = unread;
+            if self.first_poll || !changed {
+                continue;
+            }
+
+            let thread_id = thread.id.clone();
+            let _ = self.sender.send(SyncEvent::ThreadUpdated {
+                thread: Arc::new(thread),
+                new_activity,
+            });
+
+            // Directly polled threads report their own messages
+            if new_activity && options.inbox_messages && !options.threads.contains(&thread_id) {
+                self.poll_thread(&thread_id, previous_timestamp, false).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Broadcast new messages and reaction changes in a thread
+    ///
+    /// The first time a thread is fetched its messages are only recorded,
+    /// except those newer than `since` (the last activity seen in the inbox).
+    /// With `delta`, later polls only ask for messages after the newest seen.
+    async fn poll_thread(&mut self, thread_id: &str, since: Option<String>, delta: bool) -> Result<()> {
+        let newest_id = self
+            .threads
+            .get(thread_id)
+            .and_then(|state| state.newest_id.clone())
+            .filter(|_| delta && self.delta_supported);
+        let delta_response = match &newest_id {
+            Some(newest_id) => self.client.get_thread_since(thread_id, newest_id).await?,
+            None => None,
+        };
+        if newest_id.is_some() && delta_response.is_none() {
+            self.delta_supported = false;
+        }
+        let response = match delta_response {
+            Some(response) => response,
+            None => self.client.get_thread(thread_id, THREAD_LIMIT).await?,
+        };
+        if !response.success {
+            anyhow::bail!(response.error.unwrap_or("Failed to fetch thread".to_string()));
+        }
+        let Some(thread) = response.thread else {
+            anyhow::bail!("Thread {} not found", thread_id);
+        };
+
+        self.observe_presence(&thread.users);
+        let users = Arc::new(thread.users);
+        let first_poll = self.first_poll;
+        let state = self.threads.entry(thread_id.to_string()).or_default();
+        let known = state.fetched;
+        state.fetched = true;
+
+        // Oldest first. Anything before the newest known message is older
+        // history coming back into view (e.g. after an unsend), not new.
+        let messages: Vec<Message> = thread.messages.unwrap_or_default().into_iter().rev().collect();
+        let last_known = messages.iter().rposition(|m| state.messages.contains_key(&m.id));
+
+        for (i, message) in messages.into_iter().enumerate() {
+            let reactions = message.reactions.clone().unwrap_or_default();
+            let snapshot = serde_json::to_string(&reactions)?;
+            let previous = state.messages.insert(message.id.clone(), snapshot.clone());
+            if previous.is_none() {
+                state.order.push_back(message.id.clone());
+                while state.order.len() > THREAD_LIMIT as usize {
+                    if let Some(oldest) = state.order.pop_front() {
+                        state.messages.remove(&oldest);
+                    }
+                }
+            }
+            state.newest_id = Some(message.id.clone());
+
+            if first_poll {
+                continue;
+            }
+            let is_new = match &since {
+                _ if known => last_known.is_none_or(|k| i > k),
+                Some(since) => message.timestamp.as_deref() > Some(since.as_str()),
+                None => false,
+            };
+            match previous {
+                None if !is_new => {}
+                None => {
+                    let _ = self.sender.send(SyncEvent::NewMessage {
+                        thread_id: thread_id.to_string(),
+                        message: Arc::new(message),
+                        users: Arc::clone(&users),
+                    });
+                }
+                Some(previous) if previous != snapshot => {
+                    let _ = self.sender.send(SyncEvent::ReactionsChanged {
+                        thread_id: thread_id.to_string(),
+                        message_id: message.id.clone(),
+                        reactions,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+//! Background tasks and how they stop
+//!
+//! Long-running commands (`ig chat`, `ig tail`, `ig notify`, the
+//! interactive inbox) poll, print and prefetch in the background. Each
+//! command spawns those tasks on its own [`Supervisor`], which stops them
+//! together: when the command finishes, on Ctrl+C and when it fails with
+//! an error. [`Supervisor::shutdown`] waits for the tasks to stop, so none
+//! of them is still printing once the terminal has been restored.
+
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+use crate::verbose;
+
+/// How long stopping tasks get to finish before they're aborted
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+/// Owns a command's background tasks
+///
+/// Dropping it (e.g. when the command returns early with an error) cancels
+/// the tasks without waiting for them.
+pub struct Supervisor {
+    tasks: JoinSet<()>,
+    token: CancellationToken,
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self {
+            tasks: JoinSet::new(),
+            token: CancellationToken::new(),
+        }
+    }
+
+    /// Run `task` in the background until it finishes or the supervisor
+    /// stops
+    pub fn spawn(&mut self, task: impl Future<Output = ()> + Send + 'static) {
+        let token = self.token.clone();
+        self.tasks.spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = task => {}
+            }
+        });
+    }
+
+    /// Stop everything on Ctrl+C (for commands that aren't reading from
+    /// the terminal themselves)
+    pub fn stop_on_ctrl_c(&mut self) {
+        let token = self.token.clone();
+        self.spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                token.cancel();
+            }
+        });
+    }
+
+    /// Completes once the supervisor is stopping, so a foreground loop can
+    /// stop with its tasks
+    pub async fn stopping(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Stop every task and wait for them to finish. Tasks still running
+    /// after a short grace period are aborted.
+    pub async fn shutdown(mut self) {
+        self.token.cancel();
+        let finished = tokio::time::timeout(SHUTDOWN_GRACE, async {
+            while let Some(joined) = self.tasks.join_next().await {
+                if joined.is_err_and(|e| e.is_panic()) {
+                    verbose::log("A background task panicked");
+                }
+            }
+        })
+        .await;
+        if finished.is_err() {
+            verbose::log(&format!("Aborted {} background task(s) that didn't stop in time", self.tasks.len()));
+            self.tasks.abort_all();
+            while self.tasks.join_next().await.is_some() {}
+        }
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        // The JoinSet aborts whatever is left when it's dropped
+        self.token.cancel();
+    }
+}
@@ -0,0 +1,162 @@
+//! Chat transcripts
+//!
+//! For people who have to keep records of business conversations: every
+//! message `ig chat` and `ig tail` see in a conversation with
+//! `transcript = true` under its `[thread."<name>"]` section (or in every
+//! conversation with `all = true` under `[transcript]`) is appended to a
+//! file for that conversation and day, `<dir>/<thread id>/2026-10-01.txt`
+//! (`.jsonl` in JSON format). Nothing is recorded unless configured,
+//! conversations excluded with `ig sync exclude` never are, and files are
+//! only removed once older than `keep`.
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, TranscriptFormat};
+use crate::duration::parse_duration;
+use crate::exclusions;
+use crate::models::{Message, User};
+use crate::store;
+use crate::users;
+use crate::util::parse_timestamp;
+
+/// Records messages of the conversations configured for it
+pub struct Transcript {
+    config: Config,
+    dir: PathBuf,
+    /// Messages already written by this run
+    written: HashSet<String>,
+}
+
+impl Transcript {
+    /// The transcript set up in config.toml; None when no conversation is
+    /// recorded
+    pub fn open() -> Result<Option<Self>> {
+        Self::new(Config::load()?)
+    }
+
+    /// A transcript for `config`. Daily files past `keep` are removed.
+    pub fn new(config: Config) -> Result<Option<Self>> {
+        if !config.transcript.all && !config.thread.values().any(|t| t.transcript) {
+            return Ok(None);
+        }
+        let dir = match config.transcript.dir.as_deref() {
+            Some(dir) => match dir.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir().context("Could not determine the home directory")?.join(rest),
+                None => PathBuf::from(dir),
+            },
+            None => store::data_dir()?.join("transcripts"),
+        };
+        if let Some(keep) = config.transcript.keep.as_deref() {
+            let cutoff = (Local::now() - parse_duration(keep)?).date_naive();
+            prune(&dir, cutoff);
+        }
+        Ok(Some(Self { config, dir, written: HashSet::new() }))
+    }
+
+    /// Append `message` to its conversation's transcript, if that
+    /// conversation is recorded and not excluded from syncing
+    pub fn record(&mut self, thread_id: &str, message: &Message, participants: &[User]) -> Result<()> {
+        let recorded = self.config.transcript.all
+            || self
+                .config
+                .thread_settings_by(thread_id, participants, None)
+                .is_some_and(|t| t.transcript);
+        if !recorded || self.written.contains(&message.id) || exclusions::is_excluded(thread_id) {
+            return Ok(());
+        }
+
+        let at = message.timestamp.as_deref().and_then(parse_timestamp).unwrap_or_else(Local::now);
+        let extension = match self.config.transcript.format {
+            TranscriptFormat::Text => "txt",
+            TranscriptFormat::Jsonl => "jsonl",
+        };
+        let dir = self.dir.join(file_safe(thread_id));
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(format!("{}.{}", at.format("%Y-%m-%d"), extension));
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let line = line(self.config.transcript.format, thread_id, message, participants);
+        writeln!(file, "{}", line).with_context(|| format!("Failed to write {}", path.display()))?;
+        self.written.insert(message.id.clone());
+        Ok(())
+    }
+}
+
+/// How `message` appears in a transcript
+pub fn line(format: TranscriptFormat, thread_id: &str, message: &Message, participants: &[User]) -> String {
+    let from_me = message.is_sent_by_viewer.unwrap_or(false);
+    let sender = message
+        .user_id
+        .as_deref()
+        .filter(|_| !from_me)
+        .and_then(|uid| users::username(participants, uid));
+    let text = match (&message.text, &message.media_url) {
+        (Some(text), _) => text.clone(),
+        (None, Some(url)) => format!("[{}] {}", message.media_type.as_deref().unwrap_or("media"), url),
+        (None, None) => format!("[{}]", message.item_type.as_deref().unwrap_or("media")),
+    };
+
+    match format {
+        TranscriptFormat::Text => {
+            let at = message.timestamp.as_deref().unwrap_or_default().replace('T', " ");
+            let sender = match (from_me, sender) {
+                (true, _) => "You".to_string(),
+                (false, Some(sender)) => format!("@{}", sender),
+                (false, None) => message.user_id.clone().unwrap_or("unknown".to_string()),
+            };
+            // Later lines of a message are indented, so each entry still
+            // starts with its time
+            format!("{} {}: {}", at, sender, text.replace('\n', "\n    "))
+        }
+        TranscriptFormat::Jsonl => json!({
+            "thread_id": thread_id,
+            "message_id": message.id,
+            "timestamp": message.timestamp,
+            "sender": sender,
+            "user_id": message.user_id,
+            "from_me": from_me,
+            "text": text,
+        })
+        .to_string(),
+    }
+}
+
+/// A thread ID as a directory name
+fn file_safe(thread_id: &str) -> String {
+    thread_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// Remove daily files from before `cutoff`. Best effort: files that can't
+/// be removed are left for the next time.
+fn prune(dir: &Path, cutoff: NaiveDate) {
+    let Ok(threads) = fs::read_dir(dir) else {
+        return;
+    };
+    for thread in threads.flatten() {
+        let Ok(days) = fs::read_dir(thread.path()) else {
+            continue;
+        };
+        for day in days.flatten() {
+            let path = day.path();
+            let date = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+            if date.is_some_and(|d| d < cutoff) {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+}
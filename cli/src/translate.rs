@@ -0,0 +1,229 @@
+//! Message translation
+//!
+//! Providers implement [`Translator`]; `[translate] provider` in config.toml
+//! picks LibreTranslate (the default, and self-hostable) or DeepL.
+
+use anyhow::{Context, Result};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::json;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::config::{Config, TranslateConfig, TranslateProvider};
+use crate::http;
+
+/// Public LibreTranslate instance (needs an API key)
+const LIBRETRANSLATE_URL: &str = "https://libretranslate.com";
+
+/// DeepL API for paid keys
+const DEEPL_URL: &str = "https://api.deepl.com";
+
+/// DeepL API for free keys (which end in ":fx")
+const DEEPL_FREE_URL: &str = "https://api-free.deepl.com";
+
+/// Language chat translates into when none is configured
+const DEFAULT_TARGET: &str = "en";
+
+/// Give up on the provider after this long
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// A translated text
+#[derive(Debug, Clone)]
+pub struct Translation {
+    pub text: String,
+    /// Language the provider detected the original to be in
+    pub source: Option<String>,
+}
+
+impl Translation {
+    /// Whether the original was already in `target` (nothing worth showing)
+    pub fn is_same_language(&self, target: &str) -> bool {
+        self.source
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case(target))
+    }
+}
+
+/// A service that can translate text
+pub trait Translator {
+    /// Translate each text into `target` (a language code like "en")
+    fn translate(
+        &self,
+        texts: &[String],
+        target: &str,
+    ) -> impl Future<Output = Result<Vec<Translation>>> + Send;
+}
+
+/// The translator set up in config.toml
+pub enum Provider {
+    LibreTranslate(LibreTranslate),
+    DeepL(DeepL),
+}
+
+impl Provider {
+    /// Build the configured provider
+    pub fn from_config() -> Result<Self> {
+        let config = Config::load()?.translate;
+        let api_key = api_key(&config)?;
+        let client = http::with_network(Client::builder())
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(match config.provider {
+            TranslateProvider::LibreTranslate => Provider::LibreTranslate(LibreTranslate {
+                client,
+                url: base_url(config.url.as_deref(), LIBRETRANSLATE_URL),
+                api_key,
+            }),
+            TranslateProvider::DeepL => {
+                let api_key = api_key.context("DeepL needs an API key; set api_key_env under [translate]")?;
+                let default = if api_key.ends_with(":fx") { DEEPL_FREE_URL } else { DEEPL_URL };
+                Provider::DeepL(DeepL {
+                    client,
+                    url: base_url(config.url.as_deref(), default),
+                    api_key,
+                })
+            }
+        })
+    }
+}
+
+impl Translator for Provider {
+    async fn translate(&self, texts: &[String], target: &str) -> Result<Vec<Translation>> {
+        match self {
+            Provider::LibreTranslate(p) => p.translate(texts, target).await,
+            Provider::DeepL(p) => p.translate(texts, target).await,
+        }
+    }
+}
+
+/// Language chat translates into when toggled on
+pub fn default_target() -> String {
+    Config::load()
+        .ok()
+        .and_then(|c| c.translate.target)
+        .unwrap_or(DEFAULT_TARGET.to_string())
+}
+
+fn api_key(config: &TranslateConfig) -> Result<Option<String>> {
+    match &config.api_key_env {
+        Some(var) => std::env::var(var)
+            .map(Some)
+            .with_context(|| format!("Environment variable {} (api_key_env) is not set", var)),
+        None => Ok(None),
+    }
+}
+
+fn base_url(configured: Option<&str>, default: &str) -> String {
+    configured.unwrap_or(default).trim_end_matches('/').to_string()
+}
+
+/// Send a request and fail with the provider's message on an error status
+async fn send(request: RequestBuilder, provider: &str) -> Result<reqwest::Response> {
+    let resp = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", provider))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let detail = resp.text().await.unwrap_or_default();
+        anyhow::bail!("{} returned {}: {}", provider, status, detail.trim());
+    }
+    Ok(resp)
+}
+
+/// LibreTranslate `/translate` endpoint
+pub struct LibreTranslate {
+    client: Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibreResponse {
+    translated_text: String,
+    detected_language: Option<LibreDetected>,
+}
+
+#[derive(Deserialize)]
+struct LibreDetected {
+    language: String,
+}
+
+impl Translator for LibreTranslate {
+    async fn translate(&self, texts: &[String], target: &str) -> Result<Vec<Translation>> {
+        let mut translations = Vec::with_capacity(texts.len());
+        for text in texts {
+            let body = json!({
+                "q": text,
+                "source": "auto",
+                "target": target,
+                "format": "text",
+                "api_key": self.api_key,
+            });
+            let request = self.client.post(format!("{}/translate", self.url)).json(&body);
+            let resp: LibreResponse = send(request, "LibreTranslate")
+                .await?
+                .json()
+                .await
+                .context("Failed to parse LibreTranslate response")?;
+            translations.push(Translation {
+                text: resp.translated_text,
+                source: resp.detected_language.map(|d| d.language),
+            });
+        }
+        Ok(translations)
+    }
+}
+
+/// DeepL `/v2/translate` endpoint
+pub struct DeepL {
+    client: Client,
+    url: String,
+    api_key: String,
+}
+
+#[derive(Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeepLTranslation {
+    text: String,
+    detected_source_language: Option<String>,
+}
+
+impl Translator for DeepL {
+    async fn translate(&self, texts: &[String], target: &str) -> Result<Vec<Translation>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        // DeepL takes every text in one request
+        let body = json!({
+            "text": texts,
+            "target_lang": target.to_uppercase(),
+        });
+        let request = self
+            .client
+            .post(format!("{}/v2/translate", self.url))
+            .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+            .json(&body);
+        let resp: DeepLResponse = send(request, "DeepL")
+            .await?
+            .json()
+            .await
+            .context("Failed to parse DeepL response")?;
+        Ok(resp
+            .translations
+            .into_iter()
+            .map(|t| Translation {
+                text: t.text,
+                source: t.detected_source_language,
+            })
+            .collect())
+    }
+}
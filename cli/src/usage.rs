@@ -0,0 +1,126 @@
+//! API usage per invocation
+//!
+//! Each run of `ig` that talks to the server appends its counters (requests,
+//! bytes sent and received, 304 cache hits, retries) to `api_usage.json` in the data
+//! directory, so `ig stats --api` can show how busy the last hour or day
+//! was. Instagram doesn't publish its limits; staying well under a few
+//! hundred requests an hour is the usual advice. Only the subcommand name is
+//! kept, never its arguments, and entries older than a week are dropped.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::http::HttpStats;
+use crate::store;
+
+/// Data file holding the recorded sessions
+const USAGE_FILE: &str = "api_usage";
+
+/// How long sessions are kept
+const KEEP_DAYS: i64 = 7;
+
+/// Counters of one invocation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    pub at: DateTime<Local>,
+    /// Subcommand name (e.g. "inbox")
+    pub command: String,
+    pub requests: usize,
+    pub bytes_sent: usize,
+    pub bytes_received: usize,
+    pub cache_hits: usize,
+    /// Missing in runs recorded before retries were counted
+    #[serde(default)]
+    pub retries: usize,
+}
+
+impl Session {
+    /// The counters of the current run
+    pub fn from_stats(command: &str, stats: &HttpStats) -> Self {
+        Self {
+            at: Local::now(),
+            command: command.to_string(),
+            requests: stats.requests(),
+            bytes_sent: stats.bytes_sent(),
+            bytes_received: stats.bytes_received(),
+            cache_hits: stats.cache_hits(),
+            retries: stats.retries(),
+        }
+    }
+}
+
+/// Sums over a time window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Totals {
+    pub sessions: usize,
+    pub requests: usize,
+    pub bytes_sent: usize,
+    pub bytes_received: usize,
+    pub cache_hits: usize,
+    pub retries: usize,
+}
+
+/// Recorded sessions, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Usage {
+    pub sessions: Vec<Session>,
+}
+
+impl Usage {
+    pub fn load() -> Result<Self> {
+        store::load(USAGE_FILE)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        store::save(USAGE_FILE, self)
+    }
+
+    /// Add a session and drop the ones past the retention period
+    pub fn push(&mut self, session: Session) {
+        let cutoff = session.at - Duration::days(KEEP_DAYS);
+        self.sessions.retain(|s| s.at >= cutoff);
+        self.sessions.push(session);
+    }
+
+    /// Totals of the sessions since `since`
+    pub fn totals_since(&self, since: DateTime<Local>) -> Totals {
+        self.sessions
+            .iter()
+            .filter(|s| s.at >= since)
+            .fold(Totals::default(), |t, s| Totals {
+                sessions: t.sessions + 1,
+                requests: t.requests + s.requests,
+                bytes_sent: t.bytes_sent + s.bytes_sent,
+                bytes_received: t.bytes_received + s.bytes_received,
+                cache_hits: t.cache_hits + s.cache_hits,
+                retries: t.retries + s.retries,
+            })
+    }
+
+    /// Requests per command since `since`, busiest first
+    pub fn requests_by_command(&self, since: DateTime<Local>) -> Vec<(String, usize)> {
+        let mut by_command: Vec<(String, usize)> = Vec::new();
+        for session in self.sessions.iter().filter(|s| s.at >= since) {
+            match by_command.iter_mut().find(|(c, _)| *c == session.command) {
+                Some((_, requests)) => *requests += session.requests,
+                None => by_command.push((session.command.clone(), session.requests)),
+            }
+        }
+        by_command.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        by_command
+    }
+}
+
+/// Record the counters of this run, if it sent anything. Best effort: a
+/// data directory that can't be written never fails the command.
+pub fn record(command: &str, stats: &HttpStats) {
+    if stats.requests() == 0 {
+        return;
+    }
+    let Ok(mut usage) = Usage::load() else {
+        return;
+    };
+    usage.push(Session::from_stats(command, stats));
+    let _ = usage.save();
+}
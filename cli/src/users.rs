@@ -0,0 +1,125 @@
+//! Registry of known users
+//!
+//! Maps user IDs (pks) to users. It learns from every thread the server
+//! returns and is kept in the data directory, so a message's sender can be
+//! named even when they aren't in the thread's user list (e.g. someone who
+//! has left a group), and every username in it is offered for @-completion.
+//! Unknown IDs are looked up on the server once per run.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use crate::client::ApiClient;
+use crate::models::User;
+use crate::store;
+use crate::verbose;
+
+/// Data file holding known users
+const USERS_FILE: &str = "users";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct Registry {
+    users: HashMap<String, User>,
+    /// IDs the server couldn't find this run
+    #[serde(skip)]
+    missing: HashSet<String>,
+    /// Whether users were learned since the last save
+    #[serde(skip)]
+    dirty: bool,
+}
+
+static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+fn registry() -> MutexGuard<'static, Registry> {
+    let registry = REGISTRY.get_or_init(|| Mutex::new(store::load(USERS_FILE).unwrap_or_default()));
+    // The registry is only a cache, so a poisoned lock is still usable
+    registry.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Add users with a known username to the registry
+pub fn remember(users: &[User]) {
+    let mut registry = registry();
+    for user in users.iter().filter(|u| !u.pk.is_empty() && !u.username.is_empty()) {
+        let changed = registry
+            .users
+            .get(&user.pk)
+            .is_none_or(|known| known.username != user.username || known.full_name != user.full_name);
+        if changed {
+            registry.users.insert(user.pk.clone(), user.clone());
+            registry.dirty = true;
+        }
+    }
+}
+
+/// A known user by ID
+pub fn get(pk: &str) -> Option<User> {
+    registry().users.get(pk).cloned()
+}
+
+/// Username of a message sender, checking the thread's users first and then
+/// the registry
+pub fn username(thread_users: &[User], pk: &str) -> Option<String> {
+    thread_users
+        .iter()
+        .find(|u| u.pk == pk && !u.username.is_empty())
+        .map(|u| u.username.clone())
+        .or_else(|| get(pk).map(|u| u.username))
+}
+
+/// Whether a username has been seen in any thread
+pub fn is_known(username: &str) -> bool {
+    let username = username.trim_start_matches('@');
+    registry()
+        .users
+        .values()
+        .any(|u| u.username.eq_ignore_ascii_case(username))
+}
+
+/// Every known username, sorted (for @-completion)
+pub fn usernames() -> Vec<String> {
+    let mut usernames: Vec<String> = registry().users.values().map(|u| u.username.clone()).collect();
+    usernames.sort_unstable();
+    usernames.dedup();
+    usernames
+}
+
+/// A user from the registry, or looked up on the server and remembered
+pub async fn resolve(client: &ApiClient, pk: &str) -> Option<User> {
+    if pk.is_empty() || registry().missing.contains(pk) {
+        return None;
+    }
+    if let Some(user) = get(pk) {
+        return Some(user);
+    }
+
+    match client.get_user_by_pk(pk).await {
+        Ok(Some(details)) => {
+            let user = User::from(details);
+            remember(std::slice::from_ref(&user));
+            Some(user)
+        }
+        Ok(None) => {
+            registry().missing.insert(pk.to_string());
+            None
+        }
+        Err(e) => {
+            verbose::log(&format!("Looking up user {} failed: {:#}", pk, e));
+            registry().missing.insert(pk.to_string());
+            None
+        }
+    }
+}
+
+/// Write newly learned users to disk
+pub fn save() {
+    let mut registry = registry();
+    if !registry.dirty {
+        return;
+    }
+    match store::save(USERS_FILE, &*registry) {
+        Ok(()) => registry.dirty = false,
+        Err(e) => verbose::log(&format!("Could not save users: {:#}", e)),
+    }
+}
@@ -0,0 +1,93 @@
+//! Formatting helpers shared by the commands
+//!
+//! Relative times ("5m", "3d"), compact counts ("1.2K") and text
+//! truncation. They take whatever the server or the user sends, so they
+//! must never panic; `tests/util.rs` checks that with arbitrary input.
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+
+/// Time age categories for coloring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeAge {
+    Now,
+    Minutes,
+    Hours,
+    Days,
+    Unknown,
+}
+
+/// Parse a server timestamp ("2026-01-24T16:07:11", ISO 8601 without timezone)
+/// as local time
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Local>> {
+    let naive = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S").ok()?;
+    Local.from_local_datetime(&naive).single()
+}
+
+/// Parse ISO timestamp to relative time with age category
+pub fn parse_time_ago(timestamp: &str) -> (String, TimeAge) {
+    let msg_time = match parse_timestamp(timestamp) {
+        Some(dt) => dt,
+        None => return (String::new(), TimeAge::Unknown),
+    };
+
+    let now = Local::now();
+    let duration = now.signed_duration_since(msg_time);
+    let secs = duration.num_seconds();
+
+    if secs < 0 {
+        // Future timestamp (shouldn't happen, but handle gracefully)
+        return ("now".to_string(), TimeAge::Now);
+    }
+
+    let secs = secs as u64;
+    if secs < 60 {
+        ("now".to_string(), TimeAge::Now)
+    } else if secs < 3600 {
+        (format!("{}m", secs / 60), TimeAge::Minutes)
+    } else if secs < 86400 {
+        (format!("{}h", secs / 3600), TimeAge::Hours)
+    } else {
+        (format!("{}d", secs / 86400), TimeAge::Days)
+    }
+}
+
+/// Format ISO timestamp to relative time (plain string)
+pub fn format_time_ago(timestamp: &str) -> String {
+    let (text, _) = parse_time_ago(timestamp);
+    text
+}
+
+/// Format large numbers (1000 -> 1.0K, 1000000 -> 1.0M)
+///
+/// Rounds to one decimal in integer arithmetic and moves to the next unit
+/// when rounding reaches 1000, so 999,950 is "1.0M" rather than "1000.0K".
+pub fn format_count(n: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1_000, "K"), (1_000_000, "M"), (1_000_000_000, "B")];
+
+    if n < 1_000 {
+        return n.to_string();
+    }
+    let mut shown = String::new();
+    for (size, suffix) in UNITS {
+        let tenths = (u128::from(n) * 10 + u128::from(size / 2)) / u128::from(size);
+        shown = format!("{}.{}{}", tenths / 10, tenths % 10, suffix);
+        if tenths < 10_000 {
+            break;
+        }
+    }
+    shown
+}
+
+/// Shorten text to `max` characters, marking the cut with "..."
+pub fn ellipsize(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        format!("{}...", text.chars().take(max).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Truncate text to `max` characters for table cells (first line only)
+pub fn truncate(text: &str, max: usize) -> String {
+    ellipsize(text.lines().next().unwrap_or_default(), max)
+}
@@ -0,0 +1,26 @@
+//! Diagnostics printed with `--verbose`
+//!
+//! Messages go to stderr so they never mix with JSON or piped output.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::colors::Theme;
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Turn verbose output on (from `--verbose`)
+pub fn enable() {
+    VERBOSE.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--verbose` was passed
+pub fn enabled() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Print a diagnostic line when verbose output is on
+pub fn log(message: &str) {
+    if enabled() {
+        eprintln!("{}", Theme::muted(&format!("[verbose] {}", message)));
+    }
+}
@@ -0,0 +1,197 @@
+//! Microphone recording for voice messages
+//!
+//! Recording uses cpal and is only compiled with the `voice` feature, since
+//! on Linux it needs the ALSA development headers (`libasound2-dev`) to
+//! build. Audio is kept as 16-bit mono WAV; the server transcodes it to the
+//! format Instagram wants.
+
+use std::time::Duration;
+
+/// A finished recording
+pub struct Recording {
+    /// WAV file contents
+    pub wav: Vec<u8>,
+    pub duration: Duration,
+}
+
+#[cfg(feature = "voice")]
+pub use recorder::record;
+
+/// Record from the default microphone (not available in this build)
+#[cfg(not(feature = "voice"))]
+pub fn record(_max: Duration) -> anyhow::Result<Option<Recording>> {
+    anyhow::bail!(
+        "This build can't record audio; rebuild with `cargo build --features voice` \
+         (on Linux this needs the ALSA headers, e.g. libasound2-dev)"
+    )
+}
+
+#[cfg(feature = "voice")]
+mod recorder {
+    use anyhow::{Context, Result};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use cpal::{Device, FromSample, SampleFormat, SizedSample, Stream, StreamConfig};
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal;
+    use std::io::{self, Cursor, Write};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use super::Recording;
+    use crate::colors::Theme;
+
+    /// How often the level meter redraws
+    const METER_INTERVAL_MS: u64 = 100;
+
+    /// Cells in the level meter
+    const METER_WIDTH: usize = 20;
+
+    /// Samples and the latest peak level, shared with the audio thread
+    #[derive(Default)]
+    struct Capture {
+        samples: Mutex<Vec<i16>>,
+        peak: AtomicU32,
+        error: Mutex<Option<String>>,
+    }
+
+    /// Record from the default microphone until Enter or `max` elapses,
+    /// showing a level meter. `None` if cancelled with Esc.
+    pub fn record(max: Duration) -> Result<Option<Recording>> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .context("No microphone found")?;
+        let supported = device
+            .default_input_config()
+            .context("Failed to read the microphone's settings")?;
+        let format = supported.sample_format();
+        let config: StreamConfig = supported.into();
+
+        let capture = Arc::new(Capture::default());
+        let stream = match format {
+            SampleFormat::F32 => build_stream::<f32>(&device, &config, Arc::clone(&capture))?,
+            SampleFormat::I16 => build_stream::<i16>(&device, &config, Arc::clone(&capture))?,
+            SampleFormat::U16 => build_stream::<u16>(&device, &config, Arc::clone(&capture))?,
+            other => anyhow::bail!("Unsupported microphone sample format {}", other),
+        };
+        stream.play().context("Failed to start recording")?;
+        println!("{}", Theme::header("Recording — speak now"));
+
+        let started = Instant::now();
+        terminal::enable_raw_mode()?;
+        let keep = meter_loop(&capture, started, max);
+        terminal::disable_raw_mode()?;
+        let duration = started.elapsed().min(max);
+        drop(stream);
+        let mut stdout = io::stdout();
+        write!(stdout, "\r\x1b[K")?;
+        stdout.flush()?;
+
+        if let Some(error) = capture.error.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            anyhow::bail!("Recording failed: {}", error);
+        }
+        if !keep? {
+            return Ok(None);
+        }
+
+        let samples = std::mem::take(&mut *capture.samples.lock().unwrap_or_else(|e| e.into_inner()));
+        Ok(Some(Recording {
+            wav: encode_wav(&samples, config.sample_rate.0)?,
+            duration,
+        }))
+    }
+
+    /// Redraw the meter until Enter (keep), Esc/Ctrl-C (discard) or time's up
+    fn meter_loop(capture: &Capture, started: Instant, max: Duration) -> Result<bool> {
+        let mut stdout = io::stdout();
+        loop {
+            let elapsed = started.elapsed();
+            if elapsed >= max {
+                return Ok(true);
+            }
+
+            let level = capture.peak.load(Ordering::Relaxed) as f64 / i16::MAX as f64;
+            // Square root so normal speech fills a fair part of the meter
+            let filled = ((level.sqrt() * METER_WIDTH as f64).round() as usize).min(METER_WIDTH);
+            write!(
+                stdout,
+                "\r{} {} {}{} {}\x1b[K",
+                Theme::error("●"),
+                Theme::timestamp(&format!("{} / {}", clock(elapsed), clock(max))),
+                Theme::success(&"▮".repeat(filled)),
+                Theme::muted(&"▯".repeat(METER_WIDTH - filled)),
+                Theme::muted("Enter to send, Esc to cancel")
+            )?;
+            stdout.flush()?;
+
+            if !event::poll(Duration::from_millis(METER_INTERVAL_MS))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Enter => return Ok(true),
+                    KeyCode::Esc => return Ok(false),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(false),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Open an input stream that mixes down to mono i16 into `capture`
+    fn build_stream<T>(device: &Device, config: &StreamConfig, capture: Arc<Capture>) -> Result<Stream>
+    where
+        T: SizedSample,
+        i16: FromSample<T>,
+    {
+        let channels = config.channels.max(1) as usize;
+        let errors = Arc::clone(&capture);
+        let stream = device
+            .build_input_stream(
+                config,
+                move |data: &[T], _: &cpal::InputCallbackInfo| {
+                    let mut samples = capture.samples.lock().unwrap_or_else(|e| e.into_inner());
+                    let mut peak = 0;
+                    for frame in data.chunks(channels) {
+                        let sum: i32 = frame.iter().map(|s| s.to_sample::<i16>() as i32).sum();
+                        let sample = (sum / frame.len() as i32) as i16;
+                        peak = peak.max(sample.unsigned_abs() as u32);
+                        samples.push(sample);
+                    }
+                    capture.peak.store(peak, Ordering::Relaxed);
+                },
+                move |err| {
+                    *errors.error.lock().unwrap_or_else(|e| e.into_inner()) = Some(err.to_string());
+                },
+                None,
+            )
+            .context("Failed to open the microphone")?;
+        Ok(stream)
+    }
+
+    /// 16-bit mono WAV file contents
+    fn encode_wav(samples: &[i16], sample_rate: u32) -> Result<Vec<u8>> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut wav, spec).context("Failed to encode the recording")?;
+        for &sample in samples {
+            writer.write_sample(sample)?;
+        }
+        writer.finalize().context("Failed to encode the recording")?;
+        Ok(wav.into_inner())
+    }
+
+    /// "0:07"
+    fn clock(duration: Duration) -> String {
+        let secs = duration.as_secs();
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
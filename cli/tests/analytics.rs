@@ -0,0 +1,78 @@
+//! Local command usage: what is counted, and which aliases are suggested
+
+mod common;
+
+use chrono::Local;
+use clap::{Arg, ArgAction, Command};
+use insta_cli::analytics::{alias_name, shape, CommandUsage, SUGGEST_AFTER};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+fn cli() -> Command {
+    Command::new("ig")
+        .arg(Arg::new("server").short('s').long("server").global(true))
+        .subcommand(
+            Command::new("send")
+                .arg(Arg::new("username"))
+                .arg(Arg::new("message"))
+                .arg(Arg::new("yes").short('y').long("yes").action(ArgAction::SetTrue))
+                .arg(Arg::new("editor").long("editor").action(ArgAction::SetTrue)),
+        )
+        .subcommand(Command::new("remind").subcommand(Command::new("list")))
+}
+
+#[test]
+fn shape_keeps_subcommands_and_flags_but_no_values() {
+    let matches = cli().get_matches_from(["ig", "-s", "http://x", "send", "@alice", "secret", "-y"]);
+    assert_eq!(shape(&cli(), &matches).as_deref(), Some("send -y"));
+
+    let matches = cli().get_matches_from(["ig", "send", "@alice", "--editor"]);
+    assert_eq!(shape(&cli(), &matches).as_deref(), Some("send --editor"));
+
+    let matches = cli().get_matches_from(["ig", "remind", "list"]);
+    assert_eq!(shape(&cli(), &matches).as_deref(), Some("remind list"));
+}
+
+#[test]
+fn often_run_command_lines_get_an_alias_suggestion() {
+    let mut usage = CommandUsage::default();
+    for _ in 0..SUGGEST_AFTER {
+        usage.count("inbox -i", Local::now());
+        usage.count("tags", Local::now());
+        usage.count("remind list", Local::now());
+    }
+    usage.count("send -y", Local::now());
+
+    assert_eq!(alias_name("inbox -i"), "ii");
+    let suggested: Vec<(String, &str)> = usage.suggestions(&BTreeMap::new());
+    assert_eq!(suggested, [("ii".to_string(), "inbox -i"), ("rl".to_string(), "remind list")]);
+
+    // Not for command lines that already have an alias
+    let aliases = BTreeMap::from([("in".to_string(), "inbox  -i".to_string())]);
+    assert_eq!(usage.suggestions(&aliases), [("rl".to_string(), "remind list")]);
+}
+
+#[tokio::test]
+async fn aliases_expand_and_runs_are_counted() {
+    let (server, home) = (MockServer::start().await, TempDir::new().unwrap());
+    std::fs::create_dir_all(home.path().join("config/insta-cli")).unwrap();
+    std::fs::write(home.path().join("config/insta-cli/config.toml"), "[alias]\nt = \"tags\"\n").unwrap();
+
+    let output = ig(&server, home.path(), &["t"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    ig(&server, home.path(), &["tags"]);
+
+    let path = home.path().join("data/insta-cli/command_usage.json");
+    let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["commands"]["tags"]["runs"], 2);
+
+    // Opting out stops the counting
+    std::fs::write(home.path().join("config/insta-cli/config.toml"), "[usage]\ntrack = false\n").unwrap();
+    ig(&server, home.path(), &["tags"]);
+    let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["commands"]["tags"]["runs"], 2);
+}
@@ -0,0 +1,172 @@
+//! `ig export-all`: conversations, media and manifest on disk, and resuming
+//! an export that didn't finish
+
+mod common;
+
+use serde_json::{json, Value};
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+const PHOTO: &[u8] = b"\x89PNG not really a photo";
+
+/// Alice's conversation has a photo whose first download fails; bob's is text only
+async fn setup() -> (MockServer, TempDir) {
+    let server = MockServer::start().await;
+    mount_get(&server, "/inbox", inbox()).await;
+
+    let mut photo = message("m3", "2", "", "2026-10-01T17:00:00");
+    photo["text"] = Value::Null;
+    photo["item_type"] = json!("media");
+    photo["media_type"] = json!("image");
+    photo["media_url"] = json!(format!("{}/cdn/photo.png?sig=abc", server.uri()));
+    let alice = thread(
+        "101",
+        user("2", "alice"),
+        vec![message("m2", "2", "see you at 6", "2026-10-01T18:00:00"), photo],
+    );
+    let bob = thread("102", user("3", "bob"), vec![message("m1", "1", "thanks!", "2026-09-30T09:30:00")]);
+    mount_get(&server, "/thread/101", json!({ "success": true, "thread": alice })).await;
+    mount_get(&server, "/thread/102", json!({ "success": true, "thread": bob })).await;
+
+    Mock::given(method("GET"))
+        .and(path("/cdn/photo.png"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/cdn/photo.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(PHOTO))
+        .mount(&server)
+        .await;
+    (server, TempDir::new().unwrap())
+}
+
+fn export(server: &MockServer, home: &Path, out: &Path) -> String {
+    let out = out.display().to_string();
+    let output = ig(server, home, &["export-all", "--out", &out]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    stdout(&output)
+}
+
+fn manifest(out: &Path) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(out.join("manifest.json")).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn conversations_and_media_are_written_with_hashes() {
+    let (server, home) = setup().await;
+    let out = home.path().join("archive");
+
+    let first = export(&server, home.path(), &out);
+    assert!(first.contains("1 incomplete, 0 failed"), "{}", first);
+    let saved = manifest(&out);
+    assert_eq!(saved["threads"]["101"]["complete"], false);
+    assert!(saved["threads"]["101"]["media"][0]["error"].is_string());
+
+    // The second run retries alice's conversation only
+    let second = export(&server, home.path(), &out);
+    assert!(second.contains("@bob up to date"), "{}", second);
+    assert!(second.contains("Exported 1, 1 already up to date"), "{}", second);
+
+    let saved = manifest(&out);
+    let alice = &saved["threads"]["101"];
+    assert_eq!(alice["complete"], true);
+    assert_eq!(alice["messages"], 2);
+    assert_eq!(alice["media"][0]["file"], "media/101/m3.png");
+    assert_eq!(std::fs::read(out.join("media/101/m3.png")).unwrap(), PHOTO);
+    assert_eq!(alice["media"][0]["bytes"], PHOTO.len());
+
+    let document = std::fs::read_to_string(out.join("threads/101.json")).unwrap();
+    let document: Value = serde_json::from_str(&document).unwrap();
+    // Oldest first
+    assert_eq!(document["messages"][0]["id"], "m3");
+    assert_eq!(document["messages"][1]["text"], "see you at 6");
+    assert_eq!(alice["sha256"].as_str().unwrap().len(), 64);
+}
+
+#[tokio::test]
+async fn a_changed_file_is_exported_again() {
+    let (server, home) = setup().await;
+    let out = home.path().join("archive");
+    export(&server, home.path(), &out);
+    export(&server, home.path(), &out);
+
+    std::fs::write(out.join("threads/102.json"), "tampered").unwrap();
+    let again = export(&server, home.path(), &out);
+    assert!(again.contains("Exported 1, 1 already up to date"), "{}", again);
+    assert!(std::fs::read_to_string(out.join("threads/102.json")).unwrap().contains("thanks!"));
+}
+
+#[tokio::test]
+async fn the_format_of_an_archive_is_kept() {
+    let (server, home) = setup().await;
+    let out = home.path().join("archive");
+    export(&server, home.path(), &out);
+
+    let path = out.display().to_string();
+    let output = ig(&server, home.path(), &["export-all", "--out", &path, "--format", "jsonl"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("pass --format json to resume it"), "{}", stderr(&output));
+}
+
+#[tokio::test]
+async fn a_long_history_resumes_at_the_page_it_stopped_on() {
+    let server = MockServer::start().await;
+    let alice = |messages, next: Option<&str>| {
+        json!({ "success": true, "thread": thread("101", user("2", "alice"), messages), "next_cursor": next })
+    };
+    mount_get(
+        &server,
+        "/inbox",
+        json!({ "success": true, "threads": [thread("101", user("2", "alice"), vec![])] }),
+    )
+    .await;
+    // The newest page is only fetched once, by the first run
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(query_param("cursor", ""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(alice(
+            vec![message("m2", "2", "see you at 6", "2026-10-01T18:00:00")],
+            Some("older"),
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // The network drops on the next page, once
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(query_param("cursor", "older"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(query_param("cursor", "older"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(alice(
+            vec![message("m1", "1", "dinner?", "2026-10-01T12:00:00")],
+            None,
+        )))
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let out = home.path().join("archive");
+    let first = export(&server, home.path(), &out);
+    assert!(first.contains("0 incomplete, 1 failed"), "{}", first);
+    assert!(out.join("checkpoint.json").exists());
+
+    let second = export(&server, home.path(), &out);
+    assert!(second.contains("Exported 1, 0 already up to date"), "{}", second);
+    assert!(!out.join("checkpoint.json").exists());
+    assert!(!out.join("threads/101.partial").exists());
+    let document = std::fs::read_to_string(out.join("threads/101.json")).unwrap();
+    let document: Value = serde_json::from_str(&document).unwrap();
+    assert_eq!(document["messages"][0]["id"], "m1");
+    assert_eq!(document["messages"][1]["id"], "m2");
+}
@@ -0,0 +1,142 @@
+//! `ig assign`: thread assignments shared through the server, and the
+//! inbox's assignment column
+
+mod common;
+
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+async fn setup(config: &str) -> (MockServer, TempDir) {
+    let server = MockServer::start().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    mount_get(&server, "/capabilities", json!({ "assignments": true })).await;
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join("config/insta-cli")).unwrap();
+    std::fs::write(home.path().join("config/insta-cli/config.toml"), config).unwrap();
+    (server, home)
+}
+
+fn assignment(thread_id: &str, assignee: &str, note: Option<&str>) -> Value {
+    json!({
+        "thread_id": thread_id,
+        "assignee": assignee,
+        "note": note,
+        "assigned_by": "bob",
+        "assigned_at": "2026-10-01T09:00:00",
+    })
+}
+
+fn assignments(list: Vec<Value>) -> Value {
+    json!({ "success": true, "assignments": list })
+}
+
+#[tokio::test]
+async fn assign_a_thread_with_a_handoff_note() {
+    let (server, home) = setup("[team]\noperator = \"bob\"\n").await;
+    mount_get(&server, "/assignments", assignments(vec![assignment("101", "carol", None)])).await;
+    Mock::given(method("POST"))
+        .and(path("/thread/101/assign"))
+        .and(body_json(json!({ "assignee": "alice", "note": "refund approved", "assigned_by": "bob" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(assignments(vec![assignment(
+            "101",
+            "alice",
+            Some("refund approved"),
+        )])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = ig(&server, home.path(), &["assign", "101", "@alice", "--note", "refund approved"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    assert!(out.contains("Assigned to alice (was carol)"), "{}", out);
+    assert!(out.contains("Note: refund approved"), "{}", out);
+}
+
+#[tokio::test]
+async fn me_clear_and_showing_who_has_it() {
+    let (server, home) = setup("[team]\noperator = \"bob\"\n").await;
+    mount_get(&server, "/assignments", assignments(vec![assignment("101", "carol", Some("VIP"))])).await;
+    Mock::given(method("POST"))
+        .and(path("/thread/101/assign"))
+        // Reassigning keeps the handoff note
+        .and(body_json(json!({ "assignee": "bob", "note": "VIP", "assigned_by": "bob" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(assignments(vec![assignment("101", "bob", Some("VIP"))])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/thread/101/assign"))
+        .and(body_json(json!({ "assignee": null, "note": null, "assigned_by": "bob" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(assignments(vec![])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let shown = stdout(&ig(&server, home.path(), &["assign", "101"]));
+    assert!(shown.contains("Assigned to carol (by bob"), "{}", shown);
+    assert!(shown.contains("Note: VIP"), "{}", shown);
+
+    let mine = ig(&server, home.path(), &["assign", "101", "me"]);
+    assert!(stdout(&mine).contains("Assigned to bob (was carol)"), "{}", stdout(&mine));
+
+    let cleared = ig(&server, home.path(), &["assign", "101", "--clear"]);
+    assert!(stdout(&cleared).contains("Unassigned (was carol)"), "{}", stdout(&cleared));
+
+    let both = ig(&server, home.path(), &["assign", "101", "alice", "--clear"]);
+    assert!(!both.status.success());
+}
+
+#[tokio::test]
+async fn the_inbox_shows_who_has_each_thread() {
+    let (server, home) = setup("[team]\noperator = \"Bob\"\n").await;
+    let list = vec![assignment("101", "alice", None), assignment("102", "bob", None)];
+    mount_get(&server, "/assignments", assignments(list)).await;
+
+    let plain = stdout(&ig(&server, home.path(), &["inbox"]));
+    assert!(plain.contains("→ alice"), "{}", plain);
+    assert!(plain.contains("→ you"), "{}", plain);
+
+    let table = stdout(&ig(&server, home.path(), &["inbox", "--table"]));
+    assert!(table.contains("Assigned"), "{}", table);
+
+    let json: Value = serde_json::from_str(&stdout(&ig(&server, home.path(), &["--output", "json", "inbox"]))).unwrap();
+    assert_eq!(json[0]["assigned_to"], "alice");
+    assert_eq!(json[1]["assigned_to"], "bob");
+}
+
+#[tokio::test]
+async fn without_team_mode_the_inbox_asks_for_nothing() {
+    let (server, home) = setup("").await;
+    Mock::given(method("GET"))
+        .and(path("/assignments"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(assignments(vec![])))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let table = stdout(&ig(&server, home.path(), &["inbox", "--table"]));
+    assert!(!table.contains("Assigned"), "{}", table);
+
+    let me = ig(&server, home.path(), &["assign", "101", "me"]);
+    assert!(stderr(&me).contains("Set operator under [team]"), "{}", stderr(&me));
+}
+
+#[tokio::test]
+async fn servers_without_assignments_say_so() {
+    let server = MockServer::start().await;
+    mount_get(&server, "/capabilities", json!({ "media": true })).await;
+    let home = TempDir::new().unwrap();
+
+    let output = ig(&server, home.path(), &["assign", "101", "alice"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Assigning threads to team members is not supported by your server"),
+        "{}",
+        stderr(&output)
+    );
+}
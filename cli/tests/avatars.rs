@@ -0,0 +1,50 @@
+//! `[display] avatars`: initial badges before each inbox entry, off by default
+
+mod common;
+
+use serde_json::json;
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+fn enable_avatars(home: &Path) {
+    std::fs::create_dir_all(home.join("config/insta-cli")).unwrap();
+    std::fs::write(home.join("config/insta-cli/config.toml"), "[display]\navatars = true\n").unwrap();
+}
+
+#[tokio::test]
+async fn badges_show_initials_when_enabled() {
+    let server = MockServer::start().await;
+    let mut group = thread("103", user("4", "carol"), vec![message("m3", "4", "hi all", "2026-10-01T12:00:00")]);
+    group["users"] = json!([user("4", "carol"), user("5", "dave")]);
+    group["thread_title"] = json!("Book club");
+    let mut inbox = inbox();
+    inbox["threads"].as_array_mut().unwrap().push(group);
+    inbox["threads"].as_array_mut().unwrap().push(thread("104", user("6", "phyu_sin.htett"), vec![]));
+    mount_get(&server, "/inbox", inbox).await;
+    let home = TempDir::new().unwrap();
+
+    let listing = stdout(&ig(&server, home.path(), &["inbox"]));
+    assert!(!listing.contains("AL alice"), "{}", listing);
+
+    enable_avatars(home.path());
+    let listing = stdout(&ig(&server, home.path(), &["inbox"]));
+    assert!(listing.contains("AL alice @alice"), "{}", listing);
+    assert!(listing.contains("BO bob @bob"), "{}", listing);
+    assert!(listing.contains("BC Book club"), "{}", listing);
+    assert!(listing.contains("PS phyu_sin.htett"), "{}", listing);
+}
+
+#[tokio::test]
+async fn badges_follow_nicknames() {
+    let server = MockServer::start().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    let home = TempDir::new().unwrap();
+    enable_avatars(home.path());
+    ig(&server, home.path(), &["nick", "@alice", "Mom"]);
+
+    let listing = stdout(&ig(&server, home.path(), &["inbox"]));
+    assert!(listing.contains("MO Mom @alice"), "{}", listing);
+}
@@ -0,0 +1,77 @@
+//! `ig board`: columns kept as tags on the people in a thread
+
+mod common;
+
+use insta_cli::commands::tags::Tags;
+use insta_cli::commands::{move_thread, Column};
+use insta_cli::models::Thread;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+fn alice() -> Thread {
+    serde_json::from_value(thread("101", user("2", "alice"), vec![])).unwrap()
+}
+
+#[test]
+fn untagged_threads_are_in_the_inbox_column() {
+    let mut tags = Tags::default();
+    tags.by_user.entry("alice".into()).or_default().insert("work".into());
+    assert_eq!(Column::of(&tags, &alice()), Column::Inbox);
+}
+
+#[test]
+fn moving_a_thread_swaps_its_board_tag() {
+    let mut tags = Tags::default();
+    tags.by_user.entry("alice".into()).or_default().insert("work".into());
+
+    move_thread(&mut tags, &alice(), Column::WaitingOnThem);
+    assert_eq!(Column::of(&tags, &alice()), Column::WaitingOnThem);
+
+    move_thread(&mut tags, &alice(), Column::Done);
+    assert_eq!(Column::of(&tags, &alice()), Column::Done);
+    let alice_tags: Vec<&str> = tags.for_user("alice").unwrap().iter().map(String::as_str).collect();
+    assert_eq!(alice_tags, ["done", "work"]);
+
+    move_thread(&mut tags, &alice(), Column::Inbox);
+    assert_eq!(Column::of(&tags, &alice()), Column::Inbox);
+    assert!(!tags.for_user("alice").unwrap().contains("done"));
+}
+
+#[test]
+fn a_group_has_its_own_column() {
+    let group: Thread = serde_json::from_value(json!({
+        "id": "201",
+        "users": [user("2", "alice"), user("3", "bob")],
+        "thread_title": "Launch",
+    }))
+    .unwrap();
+    let mut tags = Tags::default();
+    tags.by_user.entry("alice".into()).or_default().insert("waiting-on-me".into());
+    assert_eq!(Column::of(&tags, &group), Column::Inbox);
+
+    move_thread(&mut tags, &group, Column::Done);
+    assert_eq!(Column::of(&tags, &group), Column::Done);
+    assert_eq!(Column::of(&tags, &alice()), Column::WaitingOnMe);
+    assert!(tags.for_user("bob").is_none());
+    assert!(tags.thread_has(&group, "done"));
+}
+
+#[tokio::test]
+async fn the_column_shows_as_a_tag_in_the_inbox() {
+    let server = MockServer::start().await;
+    let home = TempDir::new().unwrap();
+    mount_get(&server, "/inbox", inbox()).await;
+
+    let tagged = ig(&server, home.path(), &["tag", "@alice", "waiting-on-them"]);
+    assert!(tagged.status.success(), "{}", stderr(&tagged));
+    let listed = stdout(&ig(&server, home.path(), &["inbox", "--tag", "waiting-on-them"]));
+    assert!(listed.contains("[waiting-on-them]"), "{}", listed);
+    assert!(!listed.contains("bob"), "{}", listed);
+
+    let board = ig(&server, home.path(), &["board"]);
+    assert!(!board.status.success());
+    assert!(stderr(&board).contains("ig board needs a terminal"), "{}", stderr(&board));
+}
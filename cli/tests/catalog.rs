@@ -0,0 +1,54 @@
+//! `ig catalog`: saved replies and their `#CODE` expansion in chat
+
+mod common;
+
+use insta_cli::catalog::Catalog;
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+fn catalog() -> Catalog {
+    let mut catalog = Catalog::default();
+    catalog.by_code.insert("SKU123".into(), "Price is $40, ships in 2 days".into());
+    catalog.by_code.insert("SHIP".into(), "Shipping is free over $50".into());
+    catalog
+}
+
+#[test]
+fn shortcodes_expand_anywhere_in_a_message() {
+    let catalog = catalog();
+    assert_eq!(catalog.expand("#SKU123"), "Price is $40, ships in 2 days");
+    assert_eq!(catalog.expand("Hi! #sku123 (#Ship)"), "Hi! Price is $40, ships in 2 days (Shipping is free over $50)");
+}
+
+#[test]
+fn other_hashtags_are_left_alone() {
+    let catalog = catalog();
+    assert_eq!(catalog.expand("#sale today, #SKU1234"), "#sale today, #SKU1234");
+    assert_eq!(catalog.expand("issue#SKU123 and ##SKU123"), "issue#SKU123 and ##SKU123");
+}
+
+#[tokio::test]
+async fn items_are_added_listed_and_removed() {
+    let server = MockServer::start().await;
+    let home = TempDir::new().unwrap();
+
+    let added = ig(&server, home.path(), &["catalog", "add", "#sku123", "Price is $40, ships in 2 days"]);
+    assert!(added.status.success(), "{}", stderr(&added));
+    assert!(stdout(&added).contains("Saved #SKU123"), "{}", stdout(&added));
+
+    let listed = stdout(&ig(&server, home.path(), &["--output", "json", "catalog", "list"]));
+    assert!(listed.contains(r#""SKU123": "Price is $40, ships in 2 days""#), "{}", listed);
+
+    let invalid = ig(&server, home.path(), &["catalog", "add", "two words", "text"]);
+    assert!(!invalid.status.success());
+
+    let removed = ig(&server, home.path(), &["catalog", "remove", "SKU123"]);
+    assert!(removed.status.success(), "{}", stderr(&removed));
+    let listed = stdout(&ig(&server, home.path(), &["catalog", "list"]));
+    assert!(listed.contains("The catalog is empty"), "{}", listed);
+
+    let missing = ig(&server, home.path(), &["catalog", "remove", "SKU123"]);
+    assert!(stderr(&missing).contains("No catalog entry #SKU123"), "{}", stderr(&missing));
+}
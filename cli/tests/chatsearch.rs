@@ -0,0 +1,43 @@
+//! Searching a chat's history: matching ignores case, and long messages
+//! are cut so the match stays on screen
+
+use insta_cli::commands::chatsearch::{excerpt, find_matches, match_ranges};
+use insta_cli::models::Message;
+
+fn message(text: Option<&str>) -> Message {
+    Message {
+        text: text.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn matches_ignore_case_and_keep_character_boundaries() {
+    assert_eq!(match_ranges("Lunch? lunch!", "LUNCH"), vec![0..5, 7..12]);
+    assert_eq!(match_ranges("Straße in MÜNCHEN", "münchen"), vec![11..19]);
+    assert!(match_ranges("anything", "").is_empty());
+    assert!(match_ranges("hello", "bye").is_empty());
+}
+
+#[test]
+fn matching_messages_are_listed_newest_first() {
+    let messages = vec![
+        message(Some("see you at the party")),
+        message(None),
+        message(Some("what time?")),
+        message(Some("Party tonight")),
+    ];
+    assert_eq!(find_matches(&messages, "party"), vec![0, 3]);
+    assert!(find_matches(&messages, "[media]").is_empty());
+}
+
+#[test]
+fn excerpts_keep_a_late_match_in_view() {
+    assert_eq!(excerpt("short\nmessage", "message", 20), "short message");
+
+    let long = format!("{} needle", "x".repeat(50));
+    let cut = excerpt(&long, "needle", 20);
+    assert!(cut.starts_with("..."));
+    assert!(cut.contains("needle"));
+    assert!(cut.chars().count() <= 20 + 3);
+}
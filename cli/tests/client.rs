@@ -0,0 +1,356 @@
+//! `ApiClient` against a mock server: request shapes, response parsing and
+//! error handling for login, inbox, thread, send and thread actions
+
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use insta_cli::client::{ApiClient, Feature, API_VERSION};
+use rsa::pkcs8::{EncodePublicKey, LineEnding};
+use rsa::sha2::Sha256;
+use rsa::{Oaep, RsaPrivateKey};
+use serde_json::{json, Value};
+use wiremock::matchers::{body_json, header, header_exists, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+async fn setup() -> (MockServer, ApiClient) {
+    isolate();
+    let server = MockServer::start().await;
+    let client = ApiClient::new(Some(&server.uri())).unwrap();
+    (server, client)
+}
+
+#[tokio::test]
+async fn health_sends_api_version() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .and(header("X-Api-Version", API_VERSION.to_string().as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "ok",
+            "authenticated": true,
+            "username": "me",
+            "api_version": API_VERSION,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let health = client.health().await.unwrap();
+    assert!(health.authenticated);
+    assert_eq!(health.username.as_deref(), Some("me"));
+    assert_eq!(health.api_version, Some(API_VERSION));
+}
+
+#[tokio::test]
+async fn login_encrypts_password_with_server_key() {
+    let (server, client) = setup().await;
+    let key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+    let pem = key.to_public_key().to_public_key_pem(LineEnding::LF).unwrap();
+    mount_get(&server, "/auth/public-key", json!({ "public_key": pem })).await;
+    Mock::given(method("POST"))
+        .and(path("/auth/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "user": user("1", "me"),
+            "message": "Login successful",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.login("me", "hunter2", None).await.unwrap();
+    assert!(response.success);
+    assert_eq!(response.user.unwrap().username, "me");
+
+    let requests = server.received_requests().await.unwrap();
+    let login = requests.iter().find(|r| r.url.path() == "/auth/login").unwrap();
+    let body: Value = serde_json::from_slice(&login.body).unwrap();
+    assert_eq!(body["username"], "me");
+    assert!(body.get("password").is_none(), "the plain password must never be sent");
+
+    let encrypted = STANDARD.decode(body["encrypted_password"].as_str().unwrap()).unwrap();
+    let decrypted = key.decrypt(Oaep::new::<Sha256>(), &encrypted).unwrap();
+    assert_eq!(decrypted, b"hunter2");
+}
+
+#[tokio::test]
+async fn login_failure_reports_server_detail() {
+    let (server, client) = setup().await;
+    let key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+    let pem = key.to_public_key().to_public_key_pem(LineEnding::LF).unwrap();
+    mount_get(&server, "/auth/public-key", json!({ "public_key": pem })).await;
+    Mock::given(method("POST"))
+        .and(path("/auth/login"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "detail": "Bad password" })))
+        .mount(&server)
+        .await;
+
+    let error = client.login("me", "wrong", None).await.unwrap_err();
+    assert_eq!(error.to_string(), "Login failed: Bad password");
+}
+
+#[tokio::test]
+async fn login_failure_without_detail_is_unknown_error() {
+    let (server, client) = setup().await;
+    let key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+    let pem = key.to_public_key().to_public_key_pem(LineEnding::LF).unwrap();
+    mount_get(&server, "/auth/public-key", json!({ "public_key": pem })).await;
+    Mock::given(method("POST"))
+        .and(path("/auth/login"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({ "success": false })))
+        .mount(&server)
+        .await;
+
+    let error = client.login("me", "wrong", None).await.unwrap_err();
+    assert_eq!(error.to_string(), "Login failed: Unknown error");
+}
+
+#[tokio::test]
+async fn inbox_parses_threads_and_drops_malformed_ones() {
+    let (server, client) = setup().await;
+    let mut body = inbox();
+    body["threads"].as_array_mut().unwrap().push(json!({ "id": "", "users": [] }));
+    Mock::given(method("GET"))
+        .and(path("/inbox"))
+        .and(query_param("limit", "20"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(&server)
+        .await;
+
+    let response = client.get_inbox(20).await.unwrap();
+    let threads = response.threads.unwrap();
+    assert_eq!(threads.len(), 2);
+    assert_eq!(threads[0].id, "101");
+    assert_eq!(threads[0].users[0].username, "alice");
+    assert_eq!(threads[0].last_message_text.as_deref(), Some("see you at 6"));
+}
+
+#[tokio::test]
+async fn inbox_unauthenticated_asks_to_login() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/inbox"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "detail": "Not logged in" })))
+        .mount(&server)
+        .await;
+
+    let error = client.get_inbox(20).await.unwrap_err();
+    assert_eq!(error.to_string(), "Not authenticated. Please login first.");
+}
+
+#[tokio::test]
+async fn thread_is_revalidated_with_etag() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(alice_thread()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let first = client.get_thread("101", 20).await.unwrap();
+    let second = client.get_thread("101", 20).await.unwrap();
+    let texts = |r: &insta_cli::models::ThreadResponse| -> Vec<String> {
+        r.thread.as_ref().unwrap().messages.as_ref().unwrap().iter().filter_map(|m| m.text.clone()).collect()
+    };
+    assert_eq!(texts(&first), ["see you at 6", "dinner tonight?"]);
+    assert_eq!(texts(&second), texts(&first), "a 304 reuses the cached body");
+    assert_eq!(client.stats().requests(), 2);
+    assert_eq!(client.stats().cache_hits(), 1);
+}
+
+#[tokio::test]
+async fn thread_since_falls_back_on_old_servers() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/thread/101/since/m2"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    assert!(client.get_thread_since("101", "m2").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn send_to_thread_posts_text() {
+    let (server, client) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/thread/101/send"))
+        .and(body_json(json!({ "text": "on my way" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent("on my way")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.send_to_thread("101", "on my way").await.unwrap();
+    assert!(response.success);
+    assert_eq!(response.message.unwrap().text.as_deref(), Some("on my way"));
+}
+
+#[tokio::test]
+async fn traffic_is_counted_both_ways() {
+    let (server, client) = setup().await;
+    let reply = sent("on my way");
+    Mock::given(method("POST"))
+        .and(path("/thread/101/send"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&reply))
+        .mount(&server)
+        .await;
+
+    client.send_to_thread("101", "on my way").await.unwrap();
+    let stats = client.stats();
+    assert_eq!(stats.requests(), 1);
+    assert_eq!(stats.bytes_sent(), json!({ "text": "on my way" }).to_string().len());
+    assert_eq!(stats.bytes_received(), reply.to_string().len());
+    assert_eq!(stats.cache_hits(), 0);
+}
+
+#[tokio::test]
+async fn multipart_uploads_are_counted() {
+    let (server, client) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/send/alice/photo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent("")))
+        .mount(&server)
+        .await;
+
+    client.upload_photo_to_user("alice", "cat.jpg", vec![0; 4096]).await.unwrap();
+    let sent = client.stats().bytes_sent();
+    assert!(sent > 4096 && sent < 4096 + 1024, "{}", sent);
+}
+
+#[tokio::test]
+async fn react_posts_the_message_and_emoji() {
+    let (server, client) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/thread/101/react"))
+        .and(body_json(json!({ "message_id": "m2", "emoji": "😂" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": true })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(client.react("101", "m2", "😂").await.unwrap().success);
+}
+
+#[tokio::test]
+async fn unsupported_features_are_refused_before_any_request() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/capabilities"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "reactions": false, "media": true })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/thread/101/react"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": true })))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let error = client.react("101", "m2", "😂").await.unwrap_err();
+    assert!(error.to_string().contains("not supported by your server"), "{}", error);
+    assert!(client.supports(Feature::Media).await);
+    // Features the server doesn't mention are unsupported
+    assert!(!client.supports(Feature::Websocket).await);
+}
+
+#[tokio::test]
+async fn servers_without_capabilities_get_the_benefit_of_the_doubt() {
+    let (server, client) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/thread/101/react"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    assert_eq!(client.capabilities().await.unwrap(), None);
+    assert!(client.supports(Feature::Reactions).await);
+    // ...and a 404 from the feature itself still says what's missing
+    let error = client.react("101", "m2", "😂").await.unwrap_err();
+    assert!(error.to_string().contains("not supported by your server"), "{}", error);
+}
+
+#[tokio::test]
+async fn send_to_user_posts_to_username() {
+    let (server, client) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/send/bob"))
+        .and(header_exists("content-type"))
+        .and(body_json(json!({ "text": "hi bob" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent("hi bob")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(client.send_to_user("bob", "hi bob").await.unwrap().success);
+}
+
+#[tokio::test]
+async fn send_failure_includes_status() {
+    let (server, client) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/send/bob"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let error = client.send_to_user("bob", "hi").await.unwrap_err();
+    assert_eq!(error.to_string(), "Failed to send message: 500 Internal Server Error");
+}
+
+#[tokio::test]
+async fn thread_actions_post_to_their_endpoints() {
+    let (server, client) = setup().await;
+    for action in ["seen", "mute"] {
+        Mock::given(method("POST"))
+            .and(path(format!("/thread/101/{}", action)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": true })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/thread/101/archive"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": false, "error": "rate limited" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(client.mark_thread_seen("101").await.unwrap().success);
+    assert!(client.mute_thread("101").await.unwrap().success);
+    let archived = client.archive_thread("101").await.unwrap();
+    assert!(!archived.success);
+    assert_eq!(archived.error.as_deref(), Some("rate limited"));
+
+    let error = client.mute_thread("999").await.unwrap_err();
+    assert_eq!(error.to_string(), "Failed to mute thread: 404 Not Found");
+}
+
+#[tokio::test]
+async fn qr_login_is_not_attempted_without_the_capability() {
+    let (server, client) = setup().await;
+    mount_get(&server, "/capabilities", json!({ "media": true, "qr_login": false })).await;
+    Mock::given(method("POST"))
+        .and(path("/auth/qr"))
+        .respond_with(ResponseTemplate::new(501))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    assert!(client.start_qr_login(None).await.unwrap().is_none());
+}
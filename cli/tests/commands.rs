@@ -0,0 +1,495 @@
+//! Top-level command flows: the `ig` binary run against a mock server
+
+mod common;
+
+use rsa::pkcs8::{EncodePublicKey, LineEnding};
+use rsa::RsaPrivateKey;
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use wiremock::matchers::{body_json, body_string_contains, header_regex, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+async fn setup() -> (MockServer, TempDir) {
+    (MockServer::start().await, TempDir::new().unwrap())
+}
+
+#[tokio::test]
+async fn login_with_credentials() {
+    let (server, home) = setup().await;
+    let key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+    let pem = key.to_public_key().to_public_key_pem(LineEnding::LF).unwrap();
+    mount_get(&server, "/auth/public-key", json!({ "public_key": pem })).await;
+    Mock::given(method("POST"))
+        .and(path("/auth/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "user": user("1", "me"),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = ig(&server, home.path(), &["login", "-u", "me", "-p", "hunter2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Login successful!"));
+    assert!(stdout(&output).contains("me"));
+}
+
+#[tokio::test]
+async fn inbox_as_json() {
+    let (server, home) = setup().await;
+    mount_get(&server, "/inbox", inbox()).await;
+
+    let output = ig(&server, home.path(), &["--output", "json", "inbox"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let threads: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let ids: Vec<&str> = threads.as_array().unwrap().iter().map(|t| t["thread_id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["101", "102"]);
+}
+
+#[tokio::test]
+async fn piped_inbox_asks_for_the_default_limit() {
+    let (server, home) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/inbox"))
+        .and(query_param("limit", "20"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(inbox()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = ig(&server, home.path(), &["inbox"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn inbox_limit_fits_the_terminal() {
+    use insta_cli::commands::{fitting_limit, GroupBy};
+    assert_eq!(fitting_limit(24, GroupBy::None), 9);
+    assert_eq!(fitting_limit(50, GroupBy::None), 22);
+    assert_eq!(fitting_limit(5, GroupBy::None), 3, "never fewer than a few");
+    assert_eq!(fitting_limit(24, GroupBy::Recency), 5, "room for the section headers");
+}
+
+#[test]
+fn terminal_sized_inbox_fits_with_unseen_messages_and_sections() {
+    use chrono::Local;
+    use insta_cli::commands::{fitting_limit, group_by_recency, inbox_lines, Assignments, GroupBy, Tags};
+    use insta_cli::models::Thread;
+
+    let rows = 24;
+    for group_by in [GroupBy::None, GroupBy::Recency] {
+        let limit = fitting_limit(rows, group_by) as usize;
+        // Unread threads with several unseen messages each, and read ones
+        // spread over the other sections
+        let now = Local::now();
+        let stamps = [now, now - chrono::Duration::days(2), now - chrono::Duration::days(365)]
+            .map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string());
+        let mut threads: Vec<Thread> = (0..limit)
+            .map(|i| {
+                let mut t = thread(&i.to_string(), user("2", "alice"), vec![message("m1", "2", "hi", &stamps[i % 3])]);
+                if i % 2 == 0 {
+                    t["has_unread"] = json!(true);
+                    t["unseen_count"] = json!(4);
+                    t["unseen_messages"] = json!([
+                        message("m3", "2", "one", "2026-10-01T18:00:00"),
+                        message("m2", "2", "two", "2026-10-01T17:00:00"),
+                    ]);
+                }
+                serde_json::from_value(t).unwrap()
+            })
+            .collect();
+        let sections = match group_by {
+            GroupBy::Recency => Some(group_by_recency(&mut threads, Local::now())),
+            GroupBy::None => None,
+        };
+
+        let lines = inbox_lines(&threads, sections.as_deref(), "Inbox", &Tags::default(), &Assignments::default(), true);
+        // Plus the shell prompt after it
+        assert!(lines.len() < rows as usize, "{:?}: {} lines", group_by, lines.len());
+        assert!(lines.iter().any(|l| l.contains("+ one (+3 earlier)")), "{:?}", lines);
+    }
+}
+
+#[tokio::test]
+async fn inbox_not_logged_in() {
+    let (server, home) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/inbox"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let output = ig(&server, home.path(), &["inbox"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Not authenticated"), "{}", stderr(&output));
+}
+
+#[tokio::test]
+async fn thread_by_username() {
+    let (server, home) = setup().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    mount_get(&server, "/thread/101", alice_thread()).await;
+
+    let output = ig(&server, home.path(), &["thread", "@alice"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let text = stdout(&output);
+    assert!(text.contains("dinner tonight?"));
+    assert!(text.contains("see you at 6"));
+}
+
+#[tokio::test]
+async fn emoji_only_messages_get_a_line_of_their_own() {
+    let (server, home) = setup().await;
+    let mut body = alice_thread();
+    body["thread"]["messages"][0]["text"] = json!("😂😂");
+    mount_get(&server, "/inbox", inbox()).await;
+    mount_get(&server, "/thread/101", body.clone()).await;
+
+    let output = ig(&server, home.path(), &["thread", "@alice"]);
+    assert!(stdout(&output).contains("\n\n    😂   😂\n\n"), "{}", stdout(&output));
+
+    std::fs::create_dir_all(home.path().join("config/insta-cli")).unwrap();
+    std::fs::write(home.path().join("config/insta-cli/config.toml"), "[display]\nbig_emoji = false\n").unwrap();
+    let output = ig(&server, home.path(), &["thread", "@alice"]);
+    assert!(stdout(&output).contains("\n  😂😂\n"), "{}", stdout(&output));
+}
+
+#[tokio::test]
+async fn send_to_known_user() {
+    let (server, home) = setup().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    Mock::given(method("POST"))
+        .and(path("/send/alice"))
+        .and(body_json(json!({ "text": "running late" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent("running late")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = ig(&server, home.path(), &["send", "@alice", "-m", "running late"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Message sent to @alice"));
+}
+
+#[tokio::test]
+async fn send_to_stranger_needs_yes() {
+    let (server, home) = setup().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    Mock::given(method("POST"))
+        .and(path("/send/carol"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent("hello")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let refused = ig(&server, home.path(), &["send", "carol", "-m", "hello"]);
+    assert!(!refused.status.success());
+    assert!(stderr(&refused).contains("pass --yes"), "{}", stderr(&refused));
+
+    let sent = ig(&server, home.path(), &["send", "carol", "-m", "hello", "--yes"]);
+    assert!(sent.status.success(), "{}", stderr(&sent));
+}
+
+#[tokio::test]
+async fn send_a_photo_and_see_it_in_the_thread() {
+    let (server, home) = setup().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    Mock::given(method("POST"))
+        .and(path("/send/alice/photo"))
+        .and(header_regex("content-type", "^multipart/form-data"))
+        .and(body_string_contains("filename=\"cat.png\""))
+        // The file as is, not base64
+        .and(body_string_contains("PNG image bytes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": true })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let mut photo = message("m3", "1", "", "2026-10-02T08:00:00");
+    photo["text"] = Value::Null;
+    photo["item_type"] = json!("media");
+    photo["media_type"] = json!("photo");
+    photo["media_url"] = json!("https://cdn.example.com/v/cat_n.jpg?stp=dst-jpg");
+    let body = json!({ "success": true, "thread": thread("101", user("2", "alice"), vec![photo]) });
+    mount_get(&server, "/thread/101", body).await;
+
+    let file = home.path().join("cat.png");
+    std::fs::write(&file, "PNG image bytes").unwrap();
+    let output = ig(&server, home.path(), &["send", "@alice", "--photo", file.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Sent cat.png"), "{}", stdout(&output));
+
+    let video = ig(&server, home.path(), &["send", "@alice", "--photo", "clip.mp4"]);
+    assert!(stderr(&video).contains("--photo takes a jpg, png or webp image"), "{}", stderr(&video));
+
+    let thread = stdout(&ig(&server, home.path(), &["thread", "101"]));
+    assert!(thread.contains("[photo: cat_n.jpg]"), "{}", thread);
+    assert!(thread.contains("ig download"), "{}", thread);
+}
+
+#[tokio::test]
+async fn staged_attachments_upload_with_the_reply() {
+    let (server, home) = setup().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    let bob = json!({ "success": true, "thread": thread("102", user("3", "bob"), vec![]) });
+    mount_get(&server, "/thread/102", bob).await;
+    Mock::given(method("POST"))
+        .and(path("/thread/102/send"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent("here")))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/thread/102/photo"))
+        .and(header_regex("content-type", "^multipart/form-data"))
+        .and(body_string_contains("filename=\"clip.mp4\""))
+        .and(body_string_contains("MP4 video bytes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": true })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let file = home.path().join("clip.mp4");
+    std::fs::write(&file, "MP4 video bytes").unwrap();
+    let staged = ig(&server, home.path(), &["attach", "add", file.to_str().unwrap()]);
+    assert!(staged.status.success(), "{}", stderr(&staged));
+
+    let output = ig(&server, home.path(), &["reply", "102", "-m", "here"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Sent clip.mp4"), "{}", stdout(&output));
+}
+
+#[tokio::test]
+async fn reply_by_alias_from_inbox() {
+    let (server, home) = setup().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    let bob = json!({ "success": true, "thread": thread("102", user("3", "bob"), vec![]) });
+    mount_get(&server, "/thread/102", bob).await;
+    Mock::given(method("POST"))
+        .and(path("/thread/102/send"))
+        .and(body_json(json!({ "text": "on it" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent("on it")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let listed = ig(&server, home.path(), &["inbox"]);
+    assert!(stdout(&listed).contains("· t2"), "{}", stdout(&listed));
+
+    let output = ig(&server, home.path(), &["reply", "t2", "-m", "on it"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let unknown = ig(&server, home.path(), &["reply", "t9", "-m", "on it"]);
+    assert!(stderr(&unknown).contains("unknown thread alias 't9'"), "{}", stderr(&unknown));
+}
+
+#[tokio::test]
+async fn tui_needs_a_terminal() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["tui"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("ig tui needs a terminal"), "{}", stderr(&output));
+}
+
+#[tokio::test]
+async fn open_counts_in_the_last_listing() {
+    let (server, home) = setup().await;
+    mount_get(&server, "/inbox", inbox()).await;
+
+    let listed = ig(&server, home.path(), &["inbox", "--unread"]);
+    assert!(listed.status.success(), "{}", stderr(&listed));
+
+    // The unread listing was empty, so there is nothing to open even though
+    // the full inbox has conversations
+    let output = ig(&server, home.path(), &["open", "1"]);
+    assert!(
+        stdout(&output).contains("No conversation at position 1. The last inbox listing had 0 conversations."),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[tokio::test]
+async fn inbox_grouped_by_recency_lists_unread_first() {
+    let (server, home) = setup().await;
+    let mut body = inbox();
+    body["threads"][1]["has_unread"] = json!(true);
+    mount_get(&server, "/inbox", body).await;
+
+    let output = ig(&server, home.path(), &["inbox", "--group-by", "recency"]);
+    let text = stdout(&output);
+    let unread = text.find("Unread (1)").expect(&text);
+    let older = text.find("Older (1)").expect(&text);
+    assert!(unread < older, "{}", text);
+    assert!(text.contains("1. bob"), "{}", text);
+    assert!(text.contains("2. alice"), "{}", text);
+}
+
+#[tokio::test]
+async fn unread_conversations_show_their_unseen_messages() {
+    let (server, home) = setup().await;
+    let mut body = inbox();
+    body["threads"][0]["has_unread"] = json!(true);
+    body["threads"][0]["unseen_count"] = json!(3);
+    body["threads"][0]["unseen_messages"] = json!([
+        message("m4", "2", "see you at 6", "2026-10-01T18:00:00"),
+        message("m3", "2", "are you\ncoming?", "2026-10-01T17:58:00"),
+    ]);
+    mount_get(&server, "/inbox", body).await;
+
+    let output = ig(&server, home.path(), &["inbox"]);
+    let text = stdout(&output);
+    let older = text.find("+ are you coming? (+1 earlier)").expect(&text);
+    let newer = text.find("+ see you at 6 · t1").expect(&text);
+    assert!(older < newer, "{}", text);
+    assert!(text.contains("└ thanks!"), "read conversations keep the one-line preview: {}", text);
+}
+
+#[tokio::test]
+async fn thread_around_a_date_pages_back_to_it() {
+    let (server, home) = setup().await;
+    let page = |messages: Vec<Value>, next: Option<&str>| {
+        json!({ "success": true, "thread": thread("101", user("2", "alice"), messages), "next_cursor": next })
+    };
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(query_param("cursor", ""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page(
+            vec![
+                message("m5", "2", "happy new year", "2025-01-01T00:01:00"),
+                message("m4", "1", "back home", "2024-12-27T10:00:00"),
+            ],
+            Some("older"),
+        )))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(query_param("cursor", "older"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page(
+            vec![
+                message("m3", "2", "merry christmas", "2024-12-25T09:00:00"),
+                message("m2", "1", "tree is up", "2024-12-24T20:00:00"),
+                message("m1", "2", "flights booked", "2024-12-20T12:00:00"),
+            ],
+            None,
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = ig(&server, home.path(), &["thread", "101", "--around", "2024-12-25", "-l", "4"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let text = stdout(&output);
+    let before = text.find("tree is up").expect(&text);
+    let marker = text.find("Wednesday, December 25, 2024").expect(&text);
+    let after = text.find("merry christmas").expect(&text);
+    assert!(before < marker && marker < after, "{}", text);
+    assert!(text.contains("flights booked") && text.contains("back home"), "{}", text);
+    assert!(!text.contains("happy new year"), "{}", text);
+}
+
+#[tokio::test]
+async fn context_pages_back_until_the_message_has_its_surroundings() {
+    let (server, home) = setup().await;
+    let page = |messages: Vec<Value>, next: Option<&str>| {
+        json!({ "success": true, "thread": thread("101", user("2", "alice"), messages), "next_cursor": next })
+    };
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(query_param("cursor", ""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page(
+            vec![
+                message("m4", "2", "anyway", "2026-10-03T10:00:00"),
+                message("m3", "1", "the address is 12 Elm St", "2026-10-02T10:00:00"),
+            ],
+            Some("older"),
+        )))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(query_param("cursor", "older"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page(
+            vec![
+                message("m2", "2", "where is the party?", "2026-10-01T10:00:00"),
+                message("m1", "2", "hi", "2026-09-30T10:00:00"),
+            ],
+            Some("oldest"),
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = ig(&server, home.path(), &["context", "101", "m3", "-C", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let text = stdout(&output);
+    let question = text.find("where is the party?").expect(&text);
+    let answer = text.find("12 Elm St").expect(&text);
+    let after = text.find("anyway").expect(&text);
+    assert!(question < answer && answer < after, "{}", text);
+    assert!(!text.contains("hi\n"), "{}", text);
+}
+
+#[tokio::test]
+async fn remind_without_a_time_fails() {
+    let (server, home) = setup().await;
+
+    let output = ig(&server, home.path(), &["remind", "@bob"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Usage: ig remind @user <when>"), "{}", stderr(&output));
+}
+
+#[tokio::test]
+async fn cancelling_an_unknown_reminder_fails() {
+    let (server, home) = setup().await;
+
+    let output = ig(&server, home.path(), &["remind", "cancel", "999"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No reminder #999"), "{}", stderr(&output));
+}
+
+#[tokio::test]
+async fn etags_are_remembered_between_runs() {
+    let (server, home) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .and(header_regex("If-None-Match", "^\"v1\"$"))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/thread/101"))
+        .respond_with(ResponseTemplate::new(200).insert_header("ETag", "\"v1\"").set_body_json(alice_thread()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let first = ig(&server, home.path(), &["thread", "101"]);
+    assert!(first.status.success(), "{}", stderr(&first));
+    let second = ig(&server, home.path(), &["thread", "101"]);
+    assert!(second.status.success(), "{}", stderr(&second));
+    assert!(stdout(&second).contains("see you at 6"), "a 304 reuses the stored body: {}", stdout(&second));
+}
+
+#[tokio::test]
+async fn stats_without_a_report_fails() {
+    let (server, home) = setup().await;
+
+    let output = ig(&server, home.path(), &["stats"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Usage: ig stats --api"), "{}", stderr(&output));
+}
+
+#[tokio::test]
+async fn unknown_message_fails() {
+    let (server, home) = setup().await;
+    let alice = json!({ "success": true, "thread": thread("101", user("2", "alice"), vec![]) });
+    mount_get(&server, "/thread/101", alice).await;
+
+    let output = ig(&server, home.path(), &["msg", "101", "m9"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Message m9 not found"), "{}", stderr(&output));
+}
@@ -0,0 +1,123 @@
+//! Shared fixtures for the integration tests
+//!
+//! A wiremock server stands in for the FastAPI server; the JSON built here
+//! follows the shapes in `server/` (see `models.rs` for what the CLI reads).
+
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::Once;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Point the config and data directories of this test process at an empty
+/// temp directory, so tests never read or write the real ones
+pub fn isolate() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("insta-cli-tests-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::env::set_var("HOME", &dir);
+        std::env::set_var("XDG_CONFIG_HOME", dir.join("config"));
+        std::env::set_var("XDG_DATA_HOME", dir.join("data"));
+    });
+}
+
+/// Run the `ig` binary against `server` with its own config and data
+/// directories under `home`
+pub fn ig(server: &MockServer, home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_insta-cli"))
+        .arg("--server")
+        .arg(server.uri())
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run ig")
+}
+
+/// Stdout of a finished `ig` run
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Stderr of a finished `ig` run
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+pub fn user(pk: &str, username: &str) -> Value {
+    json!({ "pk": pk, "username": username, "full_name": format!("{} Example", username) })
+}
+
+pub fn message(id: &str, user_id: &str, text: &str, timestamp: &str) -> Value {
+    json!({
+        "id": id,
+        "text": text,
+        "timestamp": timestamp,
+        "user_id": user_id,
+        "item_type": "text",
+        "is_sent_by_viewer": user_id == "1",
+    })
+}
+
+/// Thread `id` with one other participant and the given messages (newest first)
+pub fn thread(id: &str, other: Value, messages: Vec<Value>) -> Value {
+    let last = messages.first().cloned().unwrap_or(Value::Null);
+    json!({
+        "id": id,
+        "users": [other],
+        "messages": messages,
+        "thread_title": null,
+        "last_message_text": last["text"],
+        "last_message_timestamp": last["timestamp"],
+        "has_unread": false,
+    })
+}
+
+/// An inbox of two conversations, with @alice and @bob
+pub fn inbox() -> Value {
+    json!({
+        "success": true,
+        "threads": [
+            thread("101", user("2", "alice"), vec![message("m2", "2", "see you at 6", "2026-10-01T18:00:00")]),
+            thread("102", user("3", "bob"), vec![message("m1", "1", "thanks!", "2026-09-30T09:30:00")]),
+        ],
+    })
+}
+
+/// The conversation with @alice, with its full history
+pub fn alice_thread() -> Value {
+    json!({
+        "success": true,
+        "thread": thread(
+            "101",
+            user("2", "alice"),
+            vec![
+                message("m2", "2", "see you at 6", "2026-10-01T18:00:00"),
+                message("m1", "1", "dinner tonight?", "2026-10-01T17:55:00"),
+            ],
+        ),
+    })
+}
+
+/// Serve `body` as JSON for `GET route`
+pub async fn mount_get(server: &MockServer, route: &str, body: Value) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(server)
+        .await;
+}
+
+/// A successful send response echoing `text`
+pub fn sent(text: &str) -> Value {
+    json!({
+        "success": true,
+        "message": message("m9", "1", text, "2026-10-02T08:00:00"),
+    })
+}
@@ -0,0 +1,32 @@
+//! Chat input redraws: typing outside an @mention must not force rustyline
+//! to redraw the whole line
+
+mod common;
+
+use insta_cli::completer::{in_mention, UsernameCompleter};
+use rustyline::highlight::{CmdKind, Highlighter};
+
+use common::isolate;
+
+#[test]
+fn mentions_end_at_the_first_non_name_character() {
+    assert!(in_mention("hi @"));
+    assert!(in_mention("hi @ali_ce2"));
+    assert!(!in_mention("hi @alice "));
+    assert!(!in_mention("hi @alice,"));
+    assert!(!in_mention("hello"));
+    assert!(!in_mention(""));
+}
+
+#[test]
+fn only_typing_a_mention_needs_a_redraw() {
+    isolate();
+    let completer = UsernameCompleter::new(vec!["alice".to_string()]);
+
+    assert!(!completer.highlight_char("hello there", 11, CmdKind::Other));
+    assert!(completer.highlight_char("hello @al", 9, CmdKind::Other));
+    assert!(!completer.highlight_char("hello @al", 9, CmdKind::MoveCursor));
+    assert!(!completer.highlight_char("hello @al", 9, CmdKind::ForcedRefresh));
+    // Cursor back before the mention
+    assert!(!completer.highlight_char("hello @al", 3, CmdKind::Other));
+}
@@ -0,0 +1,50 @@
+//! `ig debug thread-diff`: messages are matched by ID across fetches
+
+use insta_cli::commands::debug::{diff_messages, FieldChange};
+use insta_cli::models::Message;
+
+fn message(id: &str, text: &str) -> Message {
+    Message {
+        id: id.to_string(),
+        text: Some(text.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn added_removed_and_changed_messages_are_told_apart() {
+    let before = vec![message("m3", "see you"), message("m2", "dinner?"), message("m1", "hi")];
+    let mut edited = message("m3", "see you at 6");
+    edited.reactions = Some(vec![serde_json::json!({ "emoji": "❤️" })]);
+    let after = vec![message("m4", "on my way"), edited, message("m2", "dinner?")];
+
+    let diff = diff_messages(&before, &after);
+    assert_eq!(diff.added.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), ["m4"]);
+    assert_eq!(diff.removed.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), ["m1"]);
+    assert_eq!(diff.unchanged, 1);
+    let (changed, fields) = &diff.changed[0];
+    assert_eq!(changed.id, "m3");
+    assert_eq!(
+        fields[0],
+        FieldChange {
+            field: "text",
+            before: "see you".to_string(),
+            after: "see you at 6".to_string()
+        }
+    );
+    assert_eq!(fields[1].field, "reactions");
+    assert!(!diff.reordered);
+}
+
+#[test]
+fn duplicates_and_reordering_are_reported() {
+    let before = vec![message("m2", "b"), message("m1", "a")];
+    let after = vec![message("m1", "a"), message("m2", "b"), message("m1", "a")];
+
+    let diff = diff_messages(&before, &after);
+    assert!(diff.duplicates.0.is_empty());
+    assert_eq!(diff.duplicates.1, ["m1"]);
+    assert!(diff.reordered);
+    assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty());
+    assert!(!diff.is_empty());
+}
@@ -0,0 +1,50 @@
+//! `ig digest`: new conversations, unread messages and the most active
+//! threads inside the window
+
+mod common;
+
+use chrono::{Duration, Local};
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+/// A local timestamp `hours` ago, as the server sends them
+fn hours_ago(hours: i64) -> String {
+    (Local::now() - Duration::hours(hours)).format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+#[tokio::test]
+async fn counts_new_conversations_and_unread_messages() {
+    let server = MockServer::start().await;
+    let home = TempDir::new().unwrap();
+
+    // @alice started talking today; @bob's conversation goes back a month
+    let alice = vec![
+        message("a2", "2", "are you open?", &hours_ago(1)),
+        message("a1", "2", "hi", &hours_ago(2)),
+    ];
+    let bob = vec![
+        message("b2", "3", "any update?", &hours_ago(3)),
+        message("b1", "1", "sent it", &hours_ago(24 * 30)),
+    ];
+    let mut alice_thread = thread("101", user("2", "alice"), alice.clone());
+    alice_thread["has_unread"] = json!(true);
+    alice_thread["unseen_count"] = json!(2);
+    let mut bob_thread = thread("102", user("3", "bob"), bob.clone());
+    bob_thread["has_unread"] = json!(true);
+
+    mount_get(&server, "/inbox", json!({ "success": true, "threads": [alice_thread, bob_thread] })).await;
+    mount_get(&server, "/thread/101", json!({ "success": true, "thread": thread("101", user("2", "alice"), alice) }))
+        .await;
+    mount_get(&server, "/thread/102", json!({ "success": true, "thread": thread("102", user("3", "bob"), bob) })).await;
+
+    let output = ig(&server, home.path(), &["digest", "--since", "24h"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    assert!(out.contains("1 new conversations"), "{}", out);
+    assert!(out.contains("3 unread messages"), "{}", out);
+    assert!(out.contains("3 messages in 2 active conversations"), "{}", out);
+    assert!(out.contains("are you open?"), "{}", out);
+}
@@ -0,0 +1,90 @@
+//! Name resolution settings: pinned hostnames, one address family, and
+//! malformed settings refused up front
+
+mod common;
+
+use serde_json::json;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+/// Run `ig` against `server` under another hostname
+fn ig_at(server: &str, home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_insta-cli"))
+        .args(["--server", server])
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run ig")
+}
+
+fn set_http(home: &Path, http: &str) {
+    std::fs::create_dir_all(home.join("config/insta-cli")).unwrap();
+    std::fs::write(home.join("config/insta-cli/config.toml"), format!("[http]\n{}", http)).unwrap();
+}
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    mount_get(&server, "/health", json!({ "status": "ok", "authenticated": true, "username": "me" })).await;
+    server
+}
+
+#[tokio::test]
+async fn a_pinned_hostname_reaches_its_address() {
+    let server = server().await;
+    let home = TempDir::new().unwrap();
+    set_http(home.path(), "[http.hosts]\n\"ig-server.invalid\" = \"127.0.0.1\"\n");
+    let url = format!("http://ig-server.invalid:{}", server.address().port());
+
+    let output = ig_at(&url, home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("me"), "{}", stdout(&output));
+
+    let output = ig_at(&url, home.path(), &["doctor"]);
+    assert!(stdout(&output).contains("DNS: ig-server.invalid resolves to 127.0.0.1"), "{}", stdout(&output));
+}
+
+#[tokio::test]
+async fn the_address_family_can_be_forced() {
+    // The mock server only listens on 127.0.0.1
+    let server = server().await;
+    let home = TempDir::new().unwrap();
+    let url = format!("http://localhost:{}", server.address().port());
+
+    let output = ig_at(&url, home.path(), &["-4", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = ig_at(&url, home.path(), &["--ipv6", "status"]);
+    assert!(!output.status.success());
+
+    set_http(home.path(), "ip_version = \"ipv6\"\n");
+    let output = ig_at(&url, home.path(), &["status"]);
+    assert!(!output.status.success());
+    // The flag wins over the config
+    let output = ig_at(&url, home.path(), &["--ipv4", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn malformed_settings_are_refused() {
+    let home = TempDir::new().unwrap();
+    set_http(home.path(), "[http.hosts]\nlocalhost = \"not an address\"\n");
+    let output = ig_at("http://localhost:9", home.path(), &["tags"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Invalid address 'not an address' for localhost"),
+        "{}",
+        stderr(&output)
+    );
+
+    set_http(home.path(), "dns_servers = [\"dns.example\"]\n");
+    let output = ig_at("http://localhost:9", home.path(), &["tags"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Invalid DNS server 'dns.example'"), "{}", stderr(&output));
+}
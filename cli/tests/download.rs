@@ -0,0 +1,134 @@
+//! `ig download`: saving the photos, videos and voice clips of a thread
+
+mod common;
+
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+/// A message with `kind` media served by `server` at `/cdn/<file>`
+fn media(server: &MockServer, id: &str, kind: &str, file: &str) -> Value {
+    let mut message = message(id, "2", "", "2026-10-02T08:00:00");
+    message["text"] = Value::Null;
+    message["item_type"] = json!("media");
+    message["media_type"] = json!(kind);
+    message["media_url"] = json!(format!("{}/cdn/{}?stp=dst", server.uri(), file));
+    message
+}
+
+/// Serve `bytes` at `/cdn/<file>`, expecting `times` downloads
+async fn mount_file(server: &MockServer, file: &str, bytes: &[u8], times: u64) {
+    Mock::given(method("GET"))
+        .and(path(format!("/cdn/{}", file)))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(bytes.to_vec()))
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+async fn setup() -> (MockServer, TempDir) {
+    let server = MockServer::start().await;
+    let messages = vec![
+        media(&server, "m3", "photo", "cat_n.jpg"),
+        message("m2", "2", "look", "2026-10-02T07:59:00"),
+        media(&server, "m1", "video", "clip"),
+    ];
+    let body = json!({ "success": true, "thread": thread("101", user("2", "alice"), messages) });
+    mount_get(&server, "/thread/101", body).await;
+    (server, TempDir::new().unwrap())
+}
+
+#[tokio::test]
+async fn every_media_file_in_the_thread_is_saved() {
+    let (server, home) = setup().await;
+    mount_file(&server, "cat_n.jpg", b"jpeg bytes", 1).await;
+    mount_file(&server, "clip", b"mp4 bytes", 1).await;
+    let out = home.path().join("media");
+
+    let output = ig(&server, home.path(), &["download", "101", "--out", out.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(std::fs::read(out.join("m3.jpg")).unwrap(), b"jpeg bytes");
+    assert_eq!(std::fs::read(out.join("m1.mp4")).unwrap(), b"mp4 bytes");
+    assert!(!out.join("m2.jpg").exists());
+    assert!(stdout(&output).contains("photo"), "{}", stdout(&output));
+
+    // Already-saved files aren't downloaded again
+    let again = ig(&server, home.path(), &["download", "101", "--out", out.to_str().unwrap()]);
+    assert!(stdout(&again).contains("already saved"), "{}", stdout(&again));
+}
+
+#[tokio::test]
+async fn one_message_by_id() {
+    let (server, home) = setup().await;
+    mount_file(&server, "cat_n.jpg", b"jpeg bytes", 0).await;
+    mount_file(&server, "clip", b"mp4 bytes", 1).await;
+    let out = home.path().join("media");
+
+    let output = ig(
+        &server,
+        home.path(),
+        &["--output", "json", "download", "101", "-m", "m1", "-o", out.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    let saved: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(saved[0]["message_id"], "m1");
+    assert_eq!(saved[0]["media_type"], "video");
+    assert_eq!(saved[0]["bytes"], 9);
+    assert!(out.join("m1.mp4").exists());
+
+    let text = ig(&server, home.path(), &["download", "101", "-m", "m2"]);
+    assert!(!text.status.success());
+    assert!(stderr(&text).contains("has no photo, video or voice clip"), "{}", stderr(&text));
+}
+
+#[tokio::test]
+async fn a_failed_download_is_reported() {
+    let (server, home) = setup().await;
+    mount_file(&server, "cat_n.jpg", b"jpeg bytes", 1).await;
+    Mock::given(method("GET"))
+        .and(path("/cdn/clip"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&server)
+        .await;
+    let out = home.path().join("media");
+
+    let output = ig(&server, home.path(), &["download", "101", "--out", out.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Media download returned 403"), "{}", stderr(&output));
+    assert!(stderr(&output).contains("1 of 2 downloads failed"), "{}", stderr(&output));
+    assert!(out.join("m3.jpg").exists());
+    assert!(!out.join("m1.mp4").exists());
+}
+
+#[tokio::test]
+async fn keep_media_removes_only_old_downloads() {
+    let (server, home) = setup().await;
+    mount_file(&server, "cat_n.jpg", b"jpeg bytes", 1).await;
+    mount_file(&server, "clip", b"mp4 bytes", 1).await;
+    let out = home.path().join("media");
+    let output = ig(&server, home.path(), &["download", "101", "--out", out.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // Saved two months ago; one since edited, and a file the CLI never saved
+    let log_path = home.path().join("data/insta-cli/downloads.json");
+    let mut log: Value = serde_json::from_str(&std::fs::read_to_string(&log_path).unwrap()).unwrap();
+    let old = (chrono::Local::now() - chrono::Duration::days(60)).to_rfc3339();
+    for file in log["files"].as_array_mut().unwrap() {
+        file["at"] = json!(old);
+    }
+    std::fs::write(&log_path, log.to_string()).unwrap();
+    std::fs::write(out.join("m1.mp4"), b"trimmed").unwrap();
+    std::fs::write(out.join("notes.txt"), b"mine").unwrap();
+    std::fs::create_dir_all(home.path().join("config/insta-cli")).unwrap();
+    std::fs::write(home.path().join("config/insta-cli/config.toml"), "[cache]\nkeep_media = \"30d\"\n").unwrap();
+
+    let output = ig(&server, home.path(), &["cache", "vacuum"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Removed 1 downloaded file(s)"), "{}", stdout(&output));
+    assert!(!out.join("m3.jpg").exists());
+    assert!(out.join("m1.mp4").exists(), "changed since it was saved");
+    assert!(out.join("notes.txt").exists());
+}
@@ -0,0 +1,74 @@
+//! The shared duration parser behind `--since`, `--interval`, reminders and
+//! retention settings
+
+use chrono::Duration;
+use insta_cli::duration::{format_duration, parse, BareUnit, DurationError, Interval, Span};
+
+#[test]
+fn single_units() {
+    assert_eq!(parse("45s", BareUnit::Hours), Ok(Duration::seconds(45)));
+    assert_eq!(parse("2m", BareUnit::Hours), Ok(Duration::minutes(2)));
+    assert_eq!(parse("3h", BareUnit::Hours), Ok(Duration::hours(3)));
+    assert_eq!(parse("7d", BareUnit::Hours), Ok(Duration::days(7)));
+    assert_eq!(parse("2w", BareUnit::Hours), Ok(Duration::weeks(2)));
+}
+
+#[test]
+fn compound_and_spelled_out() {
+    let expected = Ok(Duration::minutes(90));
+    assert_eq!(parse("1h30m", BareUnit::Hours), expected);
+    assert_eq!(parse("1h 30m", BareUnit::Hours), expected);
+    assert_eq!(parse("1 hour 30 minutes", BareUnit::Hours), expected);
+    assert_eq!(parse(" 90MIN ", BareUnit::Hours), expected);
+}
+
+#[test]
+fn bare_numbers_use_the_callers_unit() {
+    assert_eq!(parse("30", BareUnit::Seconds), Ok(Duration::seconds(30)));
+    assert_eq!(parse("30", BareUnit::Hours), Ok(Duration::hours(30)));
+}
+
+#[test]
+fn errors_say_what_is_wrong() {
+    assert_eq!(parse("", BareUnit::Hours), Err(DurationError::Empty));
+    assert_eq!(parse("soon", BareUnit::Hours), Err(DurationError::Invalid("soon".into())));
+    assert_eq!(parse("1.5h", BareUnit::Hours), Err(DurationError::Invalid("1.5h".into())));
+    assert_eq!(parse("-5m", BareUnit::Hours), Err(DurationError::Invalid("-5m".into())));
+    assert_eq!(parse("1h30", BareUnit::Hours), Err(DurationError::MissingUnit("1h30".into())));
+    assert_eq!(
+        parse("3y", BareUnit::Hours),
+        Err(DurationError::UnknownUnit { unit: "y".into(), value: "3y".into() })
+    );
+    assert!(matches!(parse("99999999999999999999w", BareUnit::Hours), Err(DurationError::TooLong(_))));
+    assert!(matches!(parse("9999999999999999w", BareUnit::Hours), Err(DurationError::TooLong(_))));
+}
+
+#[test]
+fn spans_past_a_century_are_too_long() {
+    assert_eq!(parse("5200w", BareUnit::Hours), Ok(Duration::weeks(5_200)));
+    assert_eq!(parse("100000000w", BareUnit::Hours), Err(DurationError::TooLong("100000000w".into())));
+    assert_eq!(parse("36501d", BareUnit::Hours), Err(DurationError::TooLong("36501d".into())));
+    assert!("100000000w".parse::<Span>().is_err());
+}
+
+#[test]
+fn spans_keep_their_text() {
+    let span: Span = "1h 30m".parse().unwrap();
+    assert_eq!(span.to_string(), "1h 30m");
+    assert_eq!(span.duration, Duration::minutes(90));
+}
+
+#[test]
+fn intervals_are_positive() {
+    let interval: Interval = "90".parse().unwrap();
+    assert_eq!(interval.0, std::time::Duration::from_secs(90));
+    assert_eq!(interval.to_string(), "1m30s");
+    assert_eq!("0s".parse::<Interval>().unwrap_err(), DurationError::Zero);
+}
+
+#[test]
+fn format_largest_units_first() {
+    assert_eq!(format_duration(Duration::zero()), "0s");
+    assert_eq!(format_duration(Duration::seconds(45)), "45s");
+    assert_eq!(format_duration(Duration::seconds(90_061)), "1d1h1m1s");
+}
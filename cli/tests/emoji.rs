@@ -0,0 +1,21 @@
+//! Big emoji: only messages of 1-3 emoji and nothing else qualify
+
+use insta_cli::emoji::emoji_only;
+
+#[test]
+fn one_to_three_emoji_qualify() {
+    assert_eq!(emoji_only("❤️"), Some(vec!["❤️"]));
+    assert_eq!(emoji_only(" 😂 😂 "), Some(vec!["😂", "😂"]));
+    // Skin tones, ZWJ families, flags and keycaps are one emoji each
+    assert_eq!(emoji_only("👍🏽👨‍👩‍👧🇲🇲"), Some(vec!["👍🏽", "👨‍👩‍👧", "🇲🇲"]));
+    assert_eq!(emoji_only("1️⃣"), Some(vec!["1️⃣"]));
+}
+
+#[test]
+fn text_and_long_runs_do_not() {
+    assert_eq!(emoji_only("ok 👍"), None);
+    assert_eq!(emoji_only("🎉🎉🎉🎉"), None);
+    assert_eq!(emoji_only("1"), None);
+    assert_eq!(emoji_only(":)"), None);
+    assert_eq!(emoji_only(""), None);
+}
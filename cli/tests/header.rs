@@ -0,0 +1,70 @@
+//! Startup header: built from what earlier runs saw, without requests
+
+mod common;
+
+use insta_cli::colors::disable_colors;
+use insta_cli::header::Ambient;
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+#[test]
+fn header_line_leaves_out_unknown_parts() {
+    disable_colors();
+    let ambient = Ambient {
+        username: Some("me".to_string()),
+        unread: Some(3),
+        server_ok: Some(true),
+    };
+    assert_eq!(
+        ambient.line(Some("work")).as_deref(),
+        Some("@me • 3 unread • server ok • profile: work")
+    );
+
+    let ambient = Ambient { server_ok: Some(false), ..Default::default() };
+    assert_eq!(ambient.line(None).as_deref(), Some("server unreachable"));
+    assert_eq!(Ambient::default().line(None), None);
+}
+
+#[tokio::test]
+async fn earlier_runs_fill_in_the_header() {
+    let (server, home) = (MockServer::start().await, TempDir::new().unwrap());
+    mount_get(
+        &server,
+        "/health",
+        json!({ "status": "ok", "authenticated": true, "username": "me" }),
+    )
+    .await;
+    let mut body = inbox();
+    body["threads"][0]["has_unread"] = json!(true);
+    mount_get(&server, "/inbox", body).await;
+
+    ig(&server, home.path(), &["status"]);
+    ig(&server, home.path(), &["inbox"]);
+
+    let saved: Value = serde_json::from_str(
+        &std::fs::read_to_string(home.path().join("data/insta-cli/header.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(saved, json!({ "username": "me", "unread": 1, "server_ok": true }));
+
+    // A server that can't be reached is remembered as such (nothing listens
+    // on the discard port)
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_insta-cli"))
+        .args(["--server", "http://127.0.0.1:9", "status"])
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join("config"))
+        .env("XDG_DATA_HOME", home.path().join("data"))
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let saved: Value = serde_json::from_str(
+        &std::fs::read_to_string(home.path().join("data/insta-cli/header.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(saved["server_ok"], json!(false));
+    assert_eq!(saved["username"], json!("me"));
+}
@@ -0,0 +1,64 @@
+//! `ig heatmap`: message activity per day and hour, split by direction
+
+mod common;
+
+use chrono::{Duration, Local};
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+/// A timestamp `hours` ago, in the server's format
+fn ago(hours: i64) -> String {
+    (Local::now() - Duration::hours(hours)).format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+async fn setup() -> (MockServer, TempDir) {
+    let server = MockServer::start().await;
+    let messages = vec![
+        message("m4", "2", "see you", &ago(1)),
+        message("m3", "1", "on my way", &ago(2)),
+        message("m2", "1", "dinner?", &ago(96)),
+        message("m1", "2", "long ago", &ago(24 * 200)),
+    ];
+    let alice = thread("101", user("2", "alice"), messages);
+    mount_get(&server, "/inbox", json!({ "success": true, "threads": [alice.clone()] })).await;
+    mount_get(&server, "/thread/101", json!({ "success": true, "thread": alice })).await;
+    (server, TempDir::new().unwrap())
+}
+
+#[tokio::test]
+async fn messages_in_the_window_are_counted_by_direction() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["--output", "json", "heatmap", "@alice", "--weeks", "2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let days = report["days"].as_array().unwrap();
+    assert!(days.len() >= 8 && days.len() <= 14, "{}", report);
+    let count = |key: &str| days.iter().map(|d| d[key].as_u64().unwrap()).sum::<u64>();
+    assert_eq!((count("sent"), count("received")), (2, 1));
+    assert_eq!(report["hours"].as_array().unwrap().len(), 7 * 24);
+}
+
+#[tokio::test]
+async fn the_heatmap_is_drawn_for_the_inbox_and_one_person() {
+    let (server, home) = setup().await;
+    let inbox = stdout(&ig(&server, home.path(), &["heatmap"]));
+    assert!(inbox.contains("Inbox activity (last 12 weeks)"), "{}", inbox);
+    assert!(inbox.contains("3 messages · 2 sent · 1 received"), "{}", inbox);
+    assert!(inbox.contains(" Mon "), "{}", inbox);
+
+    ig(&server, home.path(), &["nick", "@alice", "Mom"]);
+    let alice = stdout(&ig(&server, home.path(), &["heatmap", "alice", "--weeks", "30"]));
+    assert!(alice.contains("Activity with Mom (last 30 weeks)"), "{}", alice);
+    assert!(alice.contains("4 messages · 2 sent · 2 received"), "{}", alice);
+}
+
+#[tokio::test]
+async fn the_number_of_weeks_is_checked() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["heatmap", "--weeks", "0"]);
+    assert!(!output.status.success());
+}
@@ -0,0 +1,57 @@
+//! `ig nick`: nicknames replace handles in what's printed, `--real-names`
+//! and `--clear` bring the handles back
+
+mod common;
+
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+async fn setup() -> (MockServer, TempDir) {
+    let server = MockServer::start().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    mount_get(&server, "/thread/101", alice_thread()).await;
+    (server, TempDir::new().unwrap())
+}
+
+#[tokio::test]
+async fn a_nickname_is_shown_in_the_inbox_and_thread() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["nick", "@Alice", "Mom"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let listing = stdout(&ig(&server, home.path(), &["inbox"]));
+    assert!(listing.contains("Mom @alice"), "{}", listing);
+    assert!(listing.contains("bob @bob"), "{}", listing);
+
+    let thread = stdout(&ig(&server, home.path(), &["thread", "@alice"]));
+    assert!(thread.contains("Conversation with: Mom"), "{}", thread);
+
+    let real = stdout(&ig(&server, home.path(), &["--real-names", "inbox"]));
+    assert!(!real.contains("Mom"), "{}", real);
+    assert!(real.contains("alice @alice"), "{}", real);
+}
+
+#[tokio::test]
+async fn nicknames_are_listed_and_cleared() {
+    let (server, home) = setup().await;
+    ig(&server, home.path(), &["nick", "@alice", "Mom"]);
+
+    let list = stdout(&ig(&server, home.path(), &["nick"]));
+    assert!(list.contains("Mom @alice"), "{}", list);
+
+    let output = ig(&server, home.path(), &["nick", "@alice", "--clear"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let listing = stdout(&ig(&server, home.path(), &["inbox"]));
+    assert!(!listing.contains("Mom"), "{}", listing);
+}
+
+#[tokio::test]
+async fn json_output_keeps_real_usernames() {
+    let (server, home) = setup().await;
+    ig(&server, home.path(), &["nick", "@alice", "Mom"]);
+
+    let json = stdout(&ig(&server, home.path(), &["--output", "json", "inbox"]));
+    assert!(!json.contains("Mom"), "{}", json);
+}
@@ -0,0 +1,96 @@
+//! Notification settings: quiet hours and per-thread overrides
+
+mod common;
+
+use chrono::NaiveTime;
+use insta_cli::commands::remind::{Reminder, Reminders};
+use insta_cli::commands::should_notify;
+use insta_cli::config::{AccentColor, Config, NotifyMode};
+use insta_cli::models::Thread;
+use insta_cli::notify::QuietHours;
+
+use common::*;
+
+fn at(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+#[test]
+fn quiet_hours_wrap_past_midnight() {
+    let quiet = QuietHours::parse("22:00-07:00").unwrap();
+    assert!(quiet.contains(at(22, 0)));
+    assert!(quiet.contains(at(3, 30)));
+    assert!(!quiet.contains(at(7, 0)));
+    assert!(!quiet.contains(at(12, 0)));
+
+    let lunch = QuietHours::parse(" 12:00 - 13:30 ").unwrap();
+    assert!(lunch.contains(at(13, 0)));
+    assert!(!lunch.contains(at(23, 0)));
+}
+
+#[test]
+fn invalid_quiet_hours_are_rejected() {
+    assert!(QuietHours::parse("22:00").is_err());
+    assert!(QuietHours::parse("25:00-07:00").is_err());
+    assert!(QuietHours::parse("late-early").is_err());
+}
+
+#[test]
+fn always_bypasses_quiet_hours_and_never_stays_silent() {
+    assert!(!should_notify(NotifyMode::Default, true));
+    assert!(should_notify(NotifyMode::Default, false));
+    assert!(should_notify(NotifyMode::Always, true));
+    assert!(!should_notify(NotifyMode::Never, false));
+}
+
+#[test]
+fn thread_settings_match_username_id_or_title() {
+    let config: Config = toml::from_str(
+        r#"
+        [thread."@Alice"]
+        color = "yellow"
+        notify = "always"
+
+        [thread."102"]
+        notify = "never"
+        "#,
+    )
+    .unwrap();
+    let alice: Thread = serde_json::from_value(thread("101", user("2", "alice"), vec![])).unwrap();
+    let bob: Thread = serde_json::from_value(thread("102", user("3", "bob"), vec![])).unwrap();
+    let carol: Thread = serde_json::from_value(thread("103", user("4", "carol"), vec![])).unwrap();
+
+    let settings = config.thread_settings(&alice).unwrap();
+    assert_eq!(settings.color, Some(AccentColor::Yellow));
+    assert_eq!(settings.notify, NotifyMode::Always);
+    assert_eq!(config.thread_settings(&bob).unwrap().notify, NotifyMode::Never);
+    assert!(config.thread_settings(&carol).is_none());
+}
+
+#[test]
+fn due_reminders_stay_stored_until_they_have_fired() {
+    isolate();
+    let reminder = |id: u32, due: chrono::DateTime<chrono::Local>| Reminder {
+        id,
+        username: "alice".into(),
+        thread_id: None,
+        note: String::new(),
+        due,
+        created: chrono::Local::now(),
+        open: true,
+    };
+    let now = chrono::Local::now();
+    let reminders = Reminders {
+        next_id: 2,
+        items: vec![reminder(1, now - chrono::Duration::minutes(1)), reminder(2, now + chrono::Duration::hours(1))],
+    };
+    reminders.save().unwrap();
+
+    let due: Vec<u32> = Reminders::load().unwrap().due().iter().map(|r| r.id).collect();
+    assert_eq!(due, [1]);
+    assert_eq!(Reminders::load().unwrap().items.len(), 2);
+
+    Reminders::remove_fired(1).unwrap();
+    let left: Vec<u32> = Reminders::load().unwrap().items.iter().map(|r| r.id).collect();
+    assert_eq!(left, [2]);
+}
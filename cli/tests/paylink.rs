@@ -0,0 +1,77 @@
+//! `ig paylink`: payment links filled in from the `[paylink]` templates
+
+mod common;
+
+use insta_cli::commands::render_paylink;
+use insta_cli::config::PaylinkConfig;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+fn paypal() -> PaylinkConfig {
+    PaylinkConfig {
+        url: Some("https://paypal.me/acme/{amount}{currency}".into()),
+        currency: Some("USD".into()),
+        message: None,
+    }
+}
+
+#[test]
+fn the_template_is_filled_in() {
+    let config = paypal();
+    assert_eq!(render_paylink(&config, "40", None).unwrap(), "https://paypal.me/acme/40USD");
+    assert_eq!(
+        render_paylink(&config, "$12.50", Some("Commission")).unwrap(),
+        "Commission: https://paypal.me/acme/12.50USD"
+    );
+
+    let config = PaylinkConfig {
+        url: Some("https://pay.example.com/?amount={amount}&note={note}".into()),
+        currency: None,
+        message: Some("{note} — {amount}: {url}".into()),
+    };
+    assert_eq!(
+        render_paylink(&config, "40", Some("Sketch & ink")).unwrap(),
+        "Sketch & ink — 40: https://pay.example.com/?amount=40&note=Sketch%20%26%20ink"
+    );
+}
+
+#[test]
+fn bad_amounts_and_missing_config_are_errors() {
+    let config = paypal();
+    for amount in ["forty", "0", "12.505", "12.", "-5", ""] {
+        assert!(render_paylink(&config, amount, None).is_err(), "{}", amount);
+    }
+    let error = render_paylink(&PaylinkConfig::default(), "40", None).unwrap_err();
+    assert!(error.to_string().contains("[paylink]"), "{}", error);
+}
+
+#[tokio::test]
+async fn the_link_is_printed_or_sent() {
+    let server = MockServer::start().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join("config/insta-cli")).unwrap();
+    std::fs::write(
+        home.path().join("config/insta-cli/config.toml"),
+        "[paylink]\nurl = \"https://paypal.me/acme/{amount}\"\n",
+    )
+    .unwrap();
+    let text = "Commission: https://paypal.me/acme/40";
+    Mock::given(method("POST"))
+        .and(path("/send/alice"))
+        .and(body_json(json!({ "text": text })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent(text)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let printed = ig(&server, home.path(), &["paylink", "40", "Commission"]);
+    assert_eq!(stdout(&printed).trim(), text);
+
+    let sent = ig(&server, home.path(), &["paylink", "40", "Commission", "--to", "@alice"]);
+    assert!(sent.status.success(), "{}", stderr(&sent));
+}
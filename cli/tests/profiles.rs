@@ -0,0 +1,77 @@
+//! `ig inbox --all-profiles`: every profile's inbox merged into one
+//! listing, and `ig open` going through the profile a conversation came from
+
+mod common;
+
+use serde_json::{json, Value};
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+/// A home profile with the usual inbox, a read-only work profile with one
+/// newer conversation and a profile whose server is down
+async fn setup() -> (MockServer, MockServer, TempDir) {
+    let (home_server, work_server) = (MockServer::start().await, MockServer::start().await);
+    mount_get(&home_server, "/inbox", inbox()).await;
+    let carol = thread("201", user("4", "carol"), vec![message("m3", "4", "standup moved", "2026-10-02T08:00:00")]);
+    mount_get(&work_server, "/inbox", json!({ "success": true, "threads": [carol] })).await;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join("config/insta-cli")).unwrap();
+    let config = format!(
+        "[profiles.home]\nserver = \"{}\"\n\n\
+         [profiles.work]\nserver = \"{}\"\npermissions = [\"read\"]\n\n\
+         [profiles.old]\nserver = \"http://127.0.0.1:9\"\n",
+        home_server.uri(),
+        work_server.uri()
+    );
+    std::fs::write(dir.path().join("config/insta-cli/config.toml"), config).unwrap();
+    (home_server, work_server, dir)
+}
+
+fn listing(server: &MockServer, dir: &Path, args: &[&str]) -> String {
+    let output = ig(server, dir, args);
+    assert!(output.status.success(), "{}", stderr(&output));
+    stdout(&output)
+}
+
+#[tokio::test]
+async fn inboxes_are_merged_newest_first_with_their_profile() {
+    let (home_server, _work_server, dir) = setup().await;
+
+    let out = listing(&home_server, dir.path(), &["inbox", "--all-profiles"]);
+    let carol = out.find("[work] carol").expect(&out);
+    let alice = out.find("[home] alice").expect(&out);
+    let bob = out.find("[home] bob").expect(&out);
+    assert!(carol < alice && alice < bob, "{}", out);
+    assert!(out.contains("Showing 3 conversations"), "{}", out);
+    // A profile that can't be reached doesn't hide the others
+    assert!(out.contains("old: "), "{}", out);
+
+    let json = listing(&home_server, dir.path(), &["--output", "json", "inbox", "--all-profiles"]);
+    let rows: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(rows[0]["profile"], "work");
+    assert_eq!(rows[0]["thread_id"], "201");
+    assert_eq!(rows[1]["profile"], "home");
+}
+
+#[tokio::test]
+async fn open_uses_the_profile_a_conversation_was_listed_from() {
+    let (home_server, _work_server, dir) = setup().await;
+    listing(&home_server, dir.path(), &["inbox", "--all-profiles"]);
+
+    // Conversation 1 is carol's, in the read-only work profile
+    let output = ig(&home_server, dir.path(), &["open", "1"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Profile 'work' is not allowed to send messages"), "{}", stderr(&output));
+}
+
+#[tokio::test]
+async fn all_profiles_needs_profiles() {
+    let (server, dir) = (MockServer::start().await, TempDir::new().unwrap());
+    let output = ig(&server, dir.path(), &["inbox", "--all-profiles"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No profiles to read from"), "{}", stderr(&output));
+}
@@ -0,0 +1,74 @@
+//! Proxy settings: requests go through the configured proxy, `ig doctor`
+//! checks it, and a malformed proxy URL is refused up front
+
+mod common;
+
+use serde_json::json;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+/// Run `ig` against a server that only exists behind the proxy
+fn ig_behind(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_insta-cli"))
+        .args(["--server", "http://ig-server.invalid"])
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run ig")
+}
+
+fn set_proxy(home: &Path, proxy: &str) {
+    std::fs::create_dir_all(home.join("config/insta-cli")).unwrap();
+    std::fs::write(home.join("config/insta-cli/config.toml"), format!("[http]\nproxy = \"{}\"\n", proxy)).unwrap();
+}
+
+#[tokio::test]
+async fn requests_go_through_the_configured_proxy() {
+    // An HTTP proxy gets the request for the real server and answers it here
+    let proxy = MockServer::start().await;
+    mount_get(&proxy, "/health", json!({ "status": "ok", "authenticated": true, "username": "me" })).await;
+    let home = TempDir::new().unwrap();
+    set_proxy(home.path(), &proxy.uri());
+
+    let output = ig_behind(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("me"), "{}", stdout(&output));
+    let received = proxy.received_requests().await.unwrap();
+    assert_eq!(received[0].url.host_str(), Some("ig-server.invalid"));
+}
+
+#[tokio::test]
+async fn doctor_reports_an_unreachable_proxy() {
+    let home = TempDir::new().unwrap();
+    set_proxy(home.path(), "socks5h://127.0.0.1:9");
+
+    let output = ig_behind(home.path(), &["doctor"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("Proxy: socks5h://127.0.0.1:9 unreachable"), "{}", stdout(&output));
+}
+
+#[test]
+fn a_malformed_proxy_is_refused() {
+    let home = TempDir::new().unwrap();
+    set_proxy(home.path(), "not a url");
+
+    let output = ig_behind(home.path(), &["tags"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Invalid proxy URL 'not a url'"), "{}", stderr(&output));
+}
+
+#[test]
+fn tor_overrides_the_configured_proxy() {
+    let home = TempDir::new().unwrap();
+    set_proxy(home.path(), "not a url");
+
+    let output = ig_behind(home.path(), &["--tor", "tags"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
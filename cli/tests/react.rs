@@ -0,0 +1,32 @@
+//! `:react` in chat: arguments and which message they point at
+
+use insta_cli::commands::react::{parse_react, Visible};
+use insta_cli::models::Message;
+
+#[test]
+fn position_and_emoji_are_both_optional() {
+    assert_eq!(parse_react("").unwrap(), (1, "❤️".to_string()));
+    assert_eq!(parse_react(" 3").unwrap(), (3, "❤️".to_string()));
+    assert_eq!(parse_react("😂").unwrap(), (1, "😂".to_string()));
+    assert_eq!(parse_react("2 👍🏽").unwrap(), (2, "👍🏽".to_string()));
+    assert!(parse_react("0").is_err());
+    assert!(parse_react("2 lol").is_err());
+    assert!(parse_react("😂😂").is_err());
+}
+
+#[test]
+fn messages_are_counted_from_the_newest() {
+    let visible = Visible::default();
+    for (id, text) in [("m1", "dinner?"), ("m2", "see you at 6")] {
+        let message = Message {
+            id: id.to_string(),
+            text: Some(text.to_string()),
+            ..Default::default()
+        };
+        visible.push(&message, "alice");
+    }
+    assert_eq!(visible.nth_newest(1).unwrap().id, "m2");
+    assert_eq!(visible.nth_newest(2).unwrap().text, "dinner?");
+    assert!(visible.nth_newest(3).is_none());
+    assert!(visible.nth_newest(0).is_none());
+}
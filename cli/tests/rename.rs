@@ -0,0 +1,71 @@
+//! `ig rename`: group titles are set through the server, one-to-one
+//! conversations are refused
+
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+#[tokio::test]
+async fn a_group_gets_its_new_title() {
+    let server = MockServer::start().await;
+    let mut group = thread("200", user("2", "alice"), vec![message("m1", "2", "hi all", "2026-10-01T18:00:00")]);
+    group["users"] = json!([user("2", "alice"), user("3", "bob")]);
+    group["thread_title"] = json!("Planning");
+    mount_get(&server, "/thread/200", json!({ "success": true, "thread": group })).await;
+    Mock::given(method("POST"))
+        .and(path("/thread/200/title"))
+        .and(body_json(json!({ "title": "Weekend trip" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": true })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let output = ig(&server, home.path(), &["rename", "200", "  Weekend trip "]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Renamed \"Planning\" to \"Weekend trip\""), "{}", stdout(&output));
+}
+
+#[tokio::test]
+async fn a_one_to_one_conversation_is_not_renamed() {
+    let server = MockServer::start().await;
+    let alice = thread("101", user("2", "alice"), vec![]);
+    mount_get(&server, "/thread/101", json!({ "success": true, "thread": alice })).await;
+    Mock::given(method("POST"))
+        .and(path("/thread/101/title"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "success": true })))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let output = ig(&server, home.path(), &["rename", "101", "Alice"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("this is a conversation with @alice"), "{}", stderr(&output));
+}
+
+#[tokio::test]
+async fn the_server_refusing_is_reported() {
+    let server = MockServer::start().await;
+    let mut group = thread("200", user("2", "alice"), vec![]);
+    group["users"] = json!([user("2", "alice"), user("3", "bob")]);
+    mount_get(&server, "/thread/200", json!({ "success": true, "thread": group })).await;
+    Mock::given(method("POST"))
+        .and(path("/thread/200/title"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "success": false, "error": "Instagram refused to rename the thread" })),
+        )
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let output = ig(&server, home.path(), &["rename", "200", "Trip"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Instagram refused to rename the thread"), "{}", stderr(&output));
+}
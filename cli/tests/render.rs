@@ -0,0 +1,91 @@
+//! Full-screen redraws: only changed lines are written, in one write per
+//! frame, and the screen is only cleared on the first frame
+
+use insta_cli::render::Canvas;
+
+fn draw(canvas: &mut Canvas) -> String {
+    let mut out = Vec::new();
+    canvas.draw(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+const CLEAR_ALL: &str = "\x1b[2J";
+
+#[test]
+fn only_changed_lines_are_redrawn() {
+    let mut canvas = Canvas::new(2);
+    canvas.set(0, vec!["Inbox".into(), "1. alice".into()]);
+    canvas.set(1, vec!["q: Quit".into()]);
+    let first = draw(&mut canvas);
+    assert!(first.contains(CLEAR_ALL));
+    assert!(first.contains("1. alice") && first.contains("q: Quit"));
+
+    canvas.set(0, vec!["Inbox".into(), "1. alice ●".into()]);
+    canvas.set(1, vec!["q: Quit".into()]);
+    let second = draw(&mut canvas);
+    assert!(!second.contains(CLEAR_ALL));
+    assert!(second.contains("1. alice ●"));
+    assert!(!second.contains("Inbox") && !second.contains("q: Quit"));
+
+    assert_eq!(draw(&mut canvas), "");
+}
+
+#[test]
+fn raw_mode_output_is_split_into_lines() {
+    let mut canvas = Canvas::new(1);
+    canvas.set_output(0, b"\r\n\rInbox\r\n\r1. alice\n");
+    let drawn = draw(&mut canvas);
+    assert!(drawn.contains("Inbox") && drawn.contains("1. alice"));
+    assert!(!drawn.contains('\r'));
+
+    // Same lines written differently are not redrawn
+    canvas.set(0, vec![String::new(), "Inbox".into(), "1. alice".into()]);
+    assert_eq!(draw(&mut canvas), "");
+}
+
+#[test]
+fn shrinking_clears_below_and_invalidating_clears_all() {
+    let mut canvas = Canvas::new(1);
+    canvas.set(0, vec!["a".into(), "b".into(), "c".into()]);
+    draw(&mut canvas);
+
+    canvas.set(0, vec!["a".into()]);
+    let shrunk = draw(&mut canvas);
+    assert!(shrunk.contains("\x1b[J"), "{:?}", shrunk);
+    assert!(!shrunk.contains(CLEAR_ALL));
+
+    canvas.invalidate();
+    assert!(draw(&mut canvas).contains(CLEAR_ALL));
+}
+
+/// Counts the writes it receives
+#[derive(Default)]
+struct Writes {
+    count: usize,
+}
+
+impl std::io::Write for Writes {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count += 1;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn each_frame_is_a_single_write() {
+    let mut canvas = Canvas::new(2);
+    canvas.set(0, (1..=20).map(|i| format!("{}. thread", i)).collect());
+    canvas.set(1, vec!["Refreshing every 5s".into()]);
+    let mut out = Writes::default();
+    canvas.draw(&mut out).unwrap();
+    assert_eq!(out.count, 1);
+
+    canvas.set(0, (1..=20).map(|i| format!("{}. thread {}m", i, i)).collect());
+    let mut out = Writes::default();
+    canvas.draw(&mut out).unwrap();
+    assert_eq!(out.count, 1);
+}
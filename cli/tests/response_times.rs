@@ -0,0 +1,72 @@
+//! `ig stats --response-times`: how quickly each side of a conversation replies
+
+mod common;
+
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+async fn setup() -> (MockServer, TempDir) {
+    let server = MockServer::start().await;
+    // Newest first. alice writes twice and you answer 10 minutes after her
+    // first message; she then takes 2 minutes and an hour, you 1 minute and
+    // 30 seconds
+    let messages = vec![
+        message("m7", "1", "great", "2026-10-01T11:13:30"),
+        message("m6", "2", "done", "2026-10-01T11:13:00"),
+        message("m5", "1", "and the invoice?", "2026-10-01T10:13:00"),
+        message("m4", "2", "sent it", "2026-10-01T10:12:00"),
+        message("m3", "1", "checking now", "2026-10-01T10:10:00"),
+        message("m2", "2", "any update?", "2026-10-01T10:05:00"),
+        message("m1", "2", "hello?", "2026-10-01T10:00:00"),
+    ];
+    let alice = thread("101", user("2", "alice"), messages);
+    mount_get(&server, "/inbox", json!({ "success": true, "threads": [alice.clone()] })).await;
+    mount_get(&server, "/thread/101", json!({ "success": true, "thread": alice })).await;
+    (server, TempDir::new().unwrap())
+}
+
+#[tokio::test]
+async fn both_sides_are_measured_from_the_start_of_the_other_turn() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["--output", "json", "stats", "--response-times", "@alice"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let times: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(times["messages"], 7);
+    assert_eq!(times["you"]["replies"], 3);
+    assert_eq!(times["you"]["median_secs"], 60);
+    assert_eq!(times["you"]["slowest_secs"], 600);
+    assert_eq!(times["them"]["replies"], 2);
+    assert_eq!(times["them"]["median_secs"], 120);
+    assert_eq!(times["them"]["p90_secs"], 3600);
+}
+
+#[tokio::test]
+async fn the_table_names_both_sides() {
+    let (server, home) = setup().await;
+    ig(&server, home.path(), &["nick", "@alice", "Support"]);
+    let output = ig(&server, home.path(), &["stats", "--response-times", "alice"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let text = stdout(&output);
+    assert!(text.contains("Response times with Support"), "{}", text);
+    assert!(text.contains("10m"), "{}", text);
+    assert!(text.contains("1h"), "{}", text);
+}
+
+#[tokio::test]
+async fn limit_needs_response_times() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["stats", "--api", "--limit", "5"]);
+    assert!(!output.status.success());
+}
+
+#[tokio::test]
+async fn all_and_limit_conflict() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["stats", "--response-times", "@alice", "--all", "--limit", "5"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("cannot be used with"), "{}", stderr(&output));
+}
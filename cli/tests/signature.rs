@@ -0,0 +1,59 @@
+//! Outgoing signatures: `[send] signature`, a profile's own signature and
+//! `--no-signature`
+
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+async fn setup(config: &str) -> (MockServer, TempDir) {
+    let server = MockServer::start().await;
+    mount_get(&server, "/inbox", inbox()).await;
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join("config/insta-cli")).unwrap();
+    std::fs::write(home.path().join("config/insta-cli/config.toml"), config).unwrap();
+    (server, home)
+}
+
+async fn expect_send(server: &MockServer, text: &str) {
+    Mock::given(method("POST"))
+        .and(path("/send/alice"))
+        .and(body_json(json!({ "text": text })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sent(text)))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn the_signature_is_added_unless_turned_off() {
+    let (server, home) = setup("[send]\nsignature = \"— sent from insta_cli\"\n").await;
+    expect_send(&server, "running late\n\n— sent from insta_cli").await;
+    expect_send(&server, "on my way").await;
+
+    let signed = ig(&server, home.path(), &["send", "@alice", "-m", "running late"]);
+    assert!(signed.status.success(), "{}", stderr(&signed));
+
+    let unsigned = ig(&server, home.path(), &["send", "@alice", "-m", "on my way", "--no-signature"]);
+    assert!(unsigned.status.success(), "{}", stderr(&unsigned));
+}
+
+#[tokio::test]
+async fn a_profile_signature_replaces_the_default() {
+    let config = "[send]\nsignature = \"— sent from insta_cli\"\n\n\
+                  [profiles.work]\nsignature = \"— Acme support\"\n\n\
+                  [profiles.quiet]\nsignature = \"\"\n";
+    let (server, home) = setup(config).await;
+    expect_send(&server, "running late\n\n— Acme support").await;
+    expect_send(&server, "on my way").await;
+
+    let work = ig(&server, home.path(), &["--profile", "work", "send", "@alice", "-m", "running late"]);
+    assert!(work.status.success(), "{}", stderr(&work));
+
+    let quiet = ig(&server, home.path(), &["--profile", "quiet", "send", "@alice", "-m", "on my way"]);
+    assert!(quiet.status.success(), "{}", stderr(&quiet));
+}
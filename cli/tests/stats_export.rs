@@ -0,0 +1,88 @@
+//! `--output csv|json` for `ig stats` and `ig heatmap`: fixed columns and a
+//! schema version, and csv refused for everything else
+
+mod common;
+
+use chrono::{Duration, Local};
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+use common::*;
+
+async fn setup() -> (MockServer, TempDir) {
+    let server = MockServer::start().await;
+    let now = Local::now();
+    let at = |minutes: i64| (now - Duration::minutes(minutes)).format("%Y-%m-%dT%H:%M:%S").to_string();
+    let messages = vec![
+        message("m3", "2", "thanks", &at(5)),
+        message("m2", "1", "here you go", &at(10)),
+        message("m1", "2", "hi", &at(30)),
+    ];
+    let alice = thread("101", user("2", "alice"), messages);
+    mount_get(&server, "/inbox", json!({ "success": true, "threads": [alice.clone()] })).await;
+    mount_get(&server, "/thread/101", json!({ "success": true, "thread": alice })).await;
+    (server, TempDir::new().unwrap())
+}
+
+#[tokio::test]
+async fn response_times_export_as_csv_and_json() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["--output", "csv", "stats", "--response-times", "@alice"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "side,username,replies,median_secs,p90_secs,slowest_secs\nyou,,1,1200,1200,1200\nthem,alice,1,300,300,300\n"
+    );
+
+    let output = ig(&server, home.path(), &["--output", "json", "stats", "--response-times", "@alice"]);
+    let times: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(times["schema"], 1);
+}
+
+#[tokio::test]
+async fn the_heatmap_exports_days_then_hours() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["--output", "csv", "heatmap", "--weeks", "2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let csv = stdout(&output);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "kind,date,weekday,hour,sent,received");
+    let days = lines.iter().filter(|l| l.starts_with("day,")).count();
+    let hours = lines.iter().filter(|l| l.starts_with("hour,")).count();
+    assert!((8..=14).contains(&days), "{}", csv);
+    assert_eq!(hours, 7 * 24);
+    assert_eq!(lines.len(), 1 + days + hours);
+
+    let sum = |column: usize| -> u32 {
+        lines
+            .iter()
+            .filter(|l| l.starts_with("day,"))
+            .map(|l| l.split(',').nth(column).unwrap().parse::<u32>().unwrap())
+            .sum()
+    };
+    assert_eq!((sum(4), sum(5)), (1, 2));
+}
+
+#[tokio::test]
+async fn api_usage_exports_as_csv() {
+    let (server, home) = setup().await;
+    ig(&server, home.path(), &["inbox"]);
+    let output = ig(&server, home.path(), &["--output", "csv", "stats", "--api"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let csv = stdout(&output);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "period,command,runs,requests,bytes_sent,bytes_received,cache_hits,retries");
+    assert!(lines[1].starts_with("last_run,inbox,1,"), "{}", csv);
+    assert!(lines.iter().any(|l| l.starts_with("last_week,,1,")), "{}", csv);
+    assert!(lines.iter().any(|l| l.starts_with("last_day,inbox,,")), "{}", csv);
+}
+
+#[tokio::test]
+async fn csv_is_refused_for_other_commands() {
+    let (server, home) = setup().await;
+    let output = ig(&server, home.path(), &["--output", "csv", "inbox"]);
+    assert!(!output.status.success());
+    let error = stderr(&output);
+    assert!(error.contains("--output csv is only available for ig stats and ig heatmap"), "{}", error);
+}
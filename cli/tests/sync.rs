@@ -0,0 +1,234 @@
+//! The sync task against a mock server that restarts or loses its session:
+//! each outage is reported once, not on every poll
+
+mod common;
+
+use insta_cli::client::ApiClient;
+use insta_cli::exclusions::{Excluded, Exclusions};
+use insta_cli::sync::{SyncEvent, SyncHandle, SyncOptions};
+use insta_cli::tasks::Supervisor;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use common::*;
+
+fn health(started_at: &str, authenticated: bool) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "status": "ok",
+        "authenticated": authenticated,
+        "started_at": started_at,
+    }))
+}
+
+/// Events from a sync task polling the inbox every 20ms, for half a second
+async fn events(server_url: &str) -> Vec<SyncEvent> {
+    let options = SyncOptions {
+        interval: Duration::from_millis(20),
+        inbox_limit: 20,
+        inbox_messages: false,
+        delta: false,
+        threads: Vec::new(),
+    };
+    events_with(&ApiClient::new(Some(server_url)).unwrap(), options).await
+}
+
+/// Events from a sync task with `options`, for half a second
+async fn events_with(client: &ApiClient, options: SyncOptions) -> Vec<SyncEvent> {
+    let mut tasks = Supervisor::new();
+    let sync = SyncHandle::spawn(&mut tasks, client, options);
+    let mut receiver = sync.subscribe();
+    let mut events = Vec::new();
+    let _ = tokio::time::timeout(Duration::from_millis(500), async {
+        while let Ok(event) = receiver.recv().await {
+            events.push(event);
+        }
+    })
+    .await;
+    tasks.shutdown().await;
+    events
+}
+
+fn count(events: &[SyncEvent], wanted: fn(&SyncEvent) -> bool) -> usize {
+    events.iter().filter(|e| wanted(e)).count()
+}
+
+#[tokio::test]
+async fn a_restart_is_announced_once() {
+    isolate();
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(health("2026-10-01T09:00:00Z", true))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(health("2026-10-01T10:00:00Z", true))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/inbox"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let client = ApiClient::new(Some(&server.uri())).unwrap();
+    let options = SyncOptions {
+        interval: Duration::from_millis(20),
+        inbox_limit: 20,
+        inbox_messages: false,
+        delta: false,
+        threads: Vec::new(),
+    };
+    let events = events_with(&client, options).await;
+    assert_eq!(count(&events, |e| matches!(e, SyncEvent::ServerRestarted)), 1, "{:?}", events);
+    assert!(count(&events, |e| matches!(e, SyncEvent::PollFailed(_))) > 1);
+    // Polled again right away after the restart, and only then
+    assert_eq!(client.stats().retries(), 1);
+}
+
+#[tokio::test]
+async fn a_lost_session_is_announced_once() {
+    isolate();
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(health("2026-10-01T09:00:00Z", false))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/inbox"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let events = events(&server.uri()).await;
+    assert_eq!(count(&events, |e| matches!(e, SyncEvent::SessionLost)), 1, "{:?}", events);
+    assert_eq!(count(&events, |e| matches!(e, SyncEvent::PollFailed(_))), 0);
+}
+
+#[tokio::test]
+async fn an_unreachable_server_is_announced_once() {
+    isolate();
+    let events = events("http://127.0.0.1:9").await;
+    assert_eq!(count(&events, |e| matches!(e, SyncEvent::ServerUnavailable(_))), 1, "{:?}", events);
+}
+
+#[tokio::test]
+async fn an_excluded_thread_opened_directly_is_still_polled() {
+    isolate();
+    let excluded = Excluded {
+        thread_id: "301".into(),
+        label: "@alice".into(),
+        added: chrono::Local::now(),
+    };
+    Exclusions { threads: vec![excluded] }.save().unwrap();
+
+    let server = MockServer::start().await;
+    let first = message("1", "2", "hi", "2026-10-01T09:00:00");
+    let reply = message("2", "2", "still there?", "2026-10-01T09:01:00");
+    Mock::given(method("GET"))
+        .and(path("/thread/301"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(
+            json!({ "success": true, "thread": thread("301", user("2", "alice"), vec![first.clone()]) }),
+        ))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/thread/301"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(
+            json!({ "success": true, "thread": thread("301", user("2", "alice"), vec![reply, first]) }),
+        ))
+        .mount(&server)
+        .await;
+
+    let options = SyncOptions {
+        interval: Duration::from_millis(20),
+        inbox_limit: 0,
+        inbox_messages: false,
+        delta: false,
+        threads: vec!["301".into()],
+    };
+    let events = events_with(&ApiClient::new(Some(&server.uri())).unwrap(), options).await;
+    assert_eq!(count(&events, |e| matches!(e, SyncEvent::NewMessage { .. })), 1, "{:?}", events);
+}
+
+#[tokio::test]
+async fn older_messages_coming_back_into_view_are_not_new() {
+    isolate();
+    let server = MockServer::start().await;
+    let m = |id: &str, minute: u32| message(id, "2", id, &format!("2026-10-01T09:{:02}:00", minute));
+    let bob = || user("2", "bob");
+    Mock::given(method("GET"))
+        .and(path("/thread/302"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(
+            json!({ "success": true, "thread": thread("302", bob(), vec![m("c", 3), m("b", 2)]) }),
+        ))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    // "b" was unsent, so "a" scrolls back into the page, and "d" is new
+    Mock::given(method("GET"))
+        .and(path("/thread/302"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(
+            json!({ "success": true, "thread": thread("302", bob(), vec![m("d", 4), m("c", 3), m("a", 1)]) }),
+        ))
+        .mount(&server)
+        .await;
+
+    let options = SyncOptions {
+        interval: Duration::from_millis(20),
+        inbox_limit: 0,
+        inbox_messages: false,
+        delta: false,
+        threads: vec!["302".into()],
+    };
+    let events = events_with(&ApiClient::new(Some(&server.uri())).unwrap(), options).await;
+    let new: Vec<&str> = events
+        .iter()
+        .filter_map(|e| match e {
+            SyncEvent::NewMessage { message, .. } => Some(message.id.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(new, ["d"]);
+}
+
+#[tokio::test]
+async fn presence_changes_are_reported_once_known() {
+    isolate();
+    let server = MockServer::start().await;
+    let inbox_with = |online: bool| {
+        let mut bob = user("3", "bob");
+        bob["is_active"] = json!(online);
+        let mut body = inbox();
+        body["threads"][1]["users"] = json!([bob]);
+        ResponseTemplate::new(200).set_body_json(body)
+    };
+    Mock::given(method("GET"))
+        .and(path("/inbox"))
+        .respond_with(inbox_with(false))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/inbox"))
+        .respond_with(inbox_with(true))
+        .mount(&server)
+        .await;
+
+    let events = events(&server.uri()).await;
+    let changes: Vec<(&str, bool)> = events
+        .iter()
+        .filter_map(|e| match e {
+            SyncEvent::PresenceChanged { username, online } => Some((username.as_str(), *online)),
+            _ => None,
+        })
+        .collect();
+    // alice's state is never reported, so she never changes
+    assert_eq!(changes, [("bob", true)]);
+}
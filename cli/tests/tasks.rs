@@ -0,0 +1,57 @@
+//! Background tasks stop with their supervisor, whether it shuts down or
+//! is dropped on an error
+
+use insta_cli::tasks::Supervisor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Counts ticks until stopped, like a polling loop
+async fn ticker(ticks: Arc<AtomicUsize>) {
+    loop {
+        ticks.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+}
+
+#[tokio::test]
+async fn shutdown_stops_every_task_before_returning() {
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut tasks = Supervisor::new();
+    tasks.spawn(ticker(ticks.clone()));
+    tasks.spawn(ticker(ticks.clone()));
+    tokio::time::sleep(Duration::from_millis(30)).await;
+
+    tasks.shutdown().await;
+    let stopped_at = ticks.load(Ordering::SeqCst);
+    assert!(stopped_at > 0);
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(ticks.load(Ordering::SeqCst), stopped_at);
+}
+
+#[tokio::test]
+async fn dropping_the_supervisor_cancels_its_tasks() {
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut tasks = Supervisor::new();
+    tasks.spawn(ticker(ticks.clone()));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    drop(tasks);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let stopped_at = ticks.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(ticks.load(Ordering::SeqCst), stopped_at);
+}
+
+#[tokio::test]
+async fn tasks_that_finish_on_their_own_are_fine() {
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut tasks = Supervisor::new();
+    let done = ticks.clone();
+    tasks.spawn(async move {
+        done.fetch_add(1, Ordering::SeqCst);
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    tasks.shutdown().await;
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,99 @@
+//! Chat transcripts: which conversations are recorded, the line formats
+//! and removal of old daily files
+
+mod common;
+
+use insta_cli::config::{Config, TranscriptFormat};
+use insta_cli::exclusions::{Excluded, Exclusions};
+use insta_cli::models::{Message, User};
+use insta_cli::transcript::{line, Transcript};
+use serde_json::Value;
+use tempfile::TempDir;
+
+use common::*;
+
+fn config(dir: &TempDir, extra: &str) -> Config {
+    let toml = format!("[transcript]\ndir = {:?}\n{}", dir.path().display().to_string(), extra);
+    toml::from_str(&toml).unwrap()
+}
+
+fn msg(id: &str, user_id: &str, text: &str) -> Message {
+    serde_json::from_value(message(id, user_id, text, "2026-10-01T18:00:00")).unwrap()
+}
+
+fn users(username: &str, pk: &str) -> Vec<User> {
+    serde_json::from_value(serde_json::json!([user(pk, username)])).unwrap()
+}
+
+#[test]
+fn only_opted_in_conversations_are_recorded() {
+    isolate();
+    let dir = TempDir::new().unwrap();
+    let config = config(&dir, "\n[thread.\"alice\"]\ntranscript = true\n");
+    let mut transcript = Transcript::new(config).unwrap().unwrap();
+
+    let alice = users("alice", "2");
+    transcript.record("101", &msg("m1", "2", "see you at 6\nby the door"), &alice).unwrap();
+    transcript.record("101", &msg("m2", "1", "ok!"), &alice).unwrap();
+    // Seen again by a later poll
+    transcript.record("101", &msg("m2", "1", "ok!"), &alice).unwrap();
+    transcript.record("102", &msg("m3", "3", "hi"), &users("bob", "3")).unwrap();
+
+    let day = std::fs::read_to_string(dir.path().join("101/2026-10-01.txt")).unwrap();
+    assert_eq!(
+        day,
+        "2026-10-01 18:00:00 @alice: see you at 6\n    by the door\n2026-10-01 18:00:00 You: ok!\n"
+    );
+    assert!(!dir.path().join("102").exists());
+}
+
+#[test]
+fn jsonl_lines_carry_ids_and_sender() {
+    let alice = users("alice", "2");
+    let line = line(TranscriptFormat::Jsonl, "101", &msg("m1", "2", "hi"), &alice);
+    let entry: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(entry["thread_id"], "101");
+    assert_eq!(entry["message_id"], "m1");
+    assert_eq!(entry["sender"], "alice");
+    assert_eq!(entry["from_me"], false);
+    assert_eq!(entry["text"], "hi");
+}
+
+#[test]
+fn nothing_is_recorded_unless_configured() {
+    let dir = TempDir::new().unwrap();
+    assert!(Transcript::new(config(&dir, "")).unwrap().is_none());
+    assert!(Transcript::new(config(&dir, "all = true\n")).unwrap().is_some());
+}
+
+#[test]
+fn files_older_than_keep_are_removed() {
+    isolate();
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join("101")).unwrap();
+    std::fs::write(dir.path().join("101/2020-01-01.txt"), "old\n").unwrap();
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    std::fs::write(dir.path().join(format!("101/{}.txt", today)), "new\n").unwrap();
+
+    Transcript::new(config(&dir, "all = true\nkeep = \"30d\"\n")).unwrap();
+    assert!(!dir.path().join("101/2020-01-01.txt").exists());
+    assert!(dir.path().join(format!("101/{}.txt", today)).exists());
+}
+
+#[test]
+fn excluded_conversations_are_never_recorded() {
+    isolate();
+    let excluded = Excluded {
+        thread_id: "909".into(),
+        label: "@carol".into(),
+        added: chrono::Local::now(),
+    };
+    Exclusions { threads: vec![excluded] }.save().unwrap();
+    let dir = TempDir::new().unwrap();
+    let mut transcript = Transcript::new(config(&dir, "all = true\n")).unwrap().unwrap();
+
+    transcript.record("909", &msg("m1", "4", "private"), &users("carol", "4")).unwrap();
+    transcript.record("910", &msg("m2", "5", "hello"), &users("dave", "5")).unwrap();
+    assert!(!dir.path().join("909").exists());
+    assert!(dir.path().join("910/2026-10-01.txt").exists());
+}
@@ -0,0 +1,57 @@
+//! `ig tutorial`: each step checks what was typed
+
+use insta_cli::commands::tutorial::{check, Answer, Step};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn steps_accept_their_command_with_or_without_ig() {
+    assert_eq!(check(Step::Status, "ig status"), Ok(Answer::Status));
+    assert_eq!(check(Step::Login, "login -u @me"), Ok(Answer::Login(Some("me".to_string()))));
+    assert_eq!(check(Step::Login, "ig login"), Ok(Answer::Login(None)));
+    assert_eq!(check(Step::Open, "ig open 2"), Ok(Answer::Open(2)));
+    assert_eq!(
+        check(Step::Send, r#"ig send @alice "see you friday""#),
+        Ok(Answer::Send { username: "alice".to_string(), text: "see you friday".to_string() })
+    );
+}
+
+#[test]
+fn mistakes_get_a_hint() {
+    assert!(check(Step::Status, "ig inbox").unwrap_err().contains("another step"));
+    assert!(check(Step::Login, "ig login -u me -p hunter2").unwrap_err().contains("password"));
+    assert!(check(Step::Login, "ig login me").unwrap_err().contains("-u"));
+    assert!(check(Step::Open, "ig open 9").is_err());
+    assert!(check(Step::Open, "ig open").is_err());
+    assert!(check(Step::Send, "ig send @nobody hi").unwrap_err().contains("@alice"));
+    assert!(check(Step::Send, "ig send @alice").is_err());
+}
+
+#[test]
+fn whole_tutorial_runs_without_a_server() {
+    let home = tempfile::TempDir::new().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_insta-cli"))
+        .args(["--server", "http://127.0.0.1:9", "tutorial"])
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join("config"))
+        .env("XDG_DATA_HOME", home.path().join("data"))
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"ig status\nig login -u me\nig open 1\nig inbox\nig open 1\nig send @bob \"hi\"\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("`ig open` comes in another step"), "{}", stdout);
+    assert!(stdout.contains("@alice: yes! 7pm at the usual place?"), "{}", stdout);
+    assert!(stdout.contains("Message sent to @bob"), "{}", stdout);
+    assert!(stdout.contains("That's the whole workflow!"), "{}", stdout);
+}
@@ -0,0 +1,39 @@
+//! API usage log: old runs are dropped and totals cover only their window
+
+use chrono::{Duration, Local};
+use insta_cli::usage::{Session, Usage};
+
+fn session(command: &str, hours_ago: i64, requests: usize) -> Session {
+    Session {
+        at: Local::now() - Duration::hours(hours_ago),
+        command: command.to_string(),
+        requests,
+        bytes_received: requests * 1000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn runs_older_than_a_week_are_dropped() {
+    let mut usage = Usage::default();
+    usage.push(session("inbox", 8 * 24, 5));
+    usage.push(session("inbox", 2, 3));
+    usage.push(session("thread", 0, 1));
+    assert_eq!(usage.sessions.len(), 2);
+}
+
+#[test]
+fn totals_and_busiest_commands_cover_the_window() {
+    let mut usage = Usage::default();
+    for s in [session("tail", 30, 40), session("inbox", 3, 2), session("thread", 0, 1), session("inbox", 0, 4)] {
+        usage.push(s);
+    }
+
+    let hour = usage.totals_since(Local::now() - Duration::hours(1));
+    assert_eq!((hour.sessions, hour.requests, hour.bytes_received), (2, 5, 5000));
+    let week = usage.totals_since(Local::now() - Duration::days(7));
+    assert_eq!(week.requests, 47);
+
+    let day = usage.requests_by_command(Local::now() - Duration::days(1));
+    assert_eq!(day, [("inbox".to_string(), 6), ("thread".to_string(), 1)]);
+}
@@ -0,0 +1,129 @@
+//! Property tests for the formatting helpers in `util.rs`: arbitrary
+//! timestamps, counts, unicode text and widths must never panic and must
+//! keep their output shape
+
+use chrono::{Duration, Local, NaiveDate};
+use insta_cli::util::{ellipsize, format_count, format_time_ago, parse_time_ago, parse_timestamp, truncate, TimeAge};
+use proptest::prelude::*;
+
+/// Seconds in a day
+const DAY: i64 = 86_400;
+
+/// The label `parse_time_ago` should give something `secs` old
+fn expected_label(secs: i64) -> String {
+    match secs {
+        s if s < 60 => "now".to_string(),
+        s if s < 3_600 => format!("{}m", s / 60),
+        s if s < DAY => format!("{}h", s / 3_600),
+        s => format!("{}d", s / DAY),
+    }
+}
+
+proptest! {
+    #[test]
+    fn time_ago_never_panics_on_garbage(input in "\\PC*") {
+        let _ = format_time_ago(&input);
+    }
+
+    #[test]
+    fn time_ago_never_panics_on_any_date(
+        year in -262_000i32..262_000,
+        day in 0u32..366,
+        secs in 0u32..86_400,
+    ) {
+        let Some(date) = NaiveDate::from_yo_opt(year, day + 1) else {
+            return Ok(());
+        };
+        let timestamp = date.and_hms_opt(secs / 3_600, secs / 60 % 60, secs % 60).unwrap();
+        let (text, age) = parse_time_ago(&timestamp.format("%Y-%m-%dT%H:%M:%S").to_string());
+        if age == TimeAge::Unknown {
+            prop_assert!(text.is_empty());
+        } else {
+            prop_assert!(text == "now" || text[..text.len() - 1].parse::<u64>().is_ok(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn time_ago_matches_elapsed_time(ago in 0i64..(4_000 * DAY)) {
+        let now = Local::now();
+        let timestamp = (now - Duration::seconds(ago)).format("%Y-%m-%dT%H:%M:%S").to_string();
+        // Skip local times that don't exist or are ambiguous (DST changes)
+        let Some(parsed) = parse_timestamp(&timestamp) else {
+            return Ok(());
+        };
+        let elapsed = now.signed_duration_since(parsed).num_seconds();
+        let text = format_time_ago(&timestamp);
+        // The clock may tick between building the timestamp and formatting it
+        prop_assert!(
+            text == expected_label(elapsed) || text == expected_label(elapsed + 1),
+            "{} seconds ago shown as {:?}", elapsed, text
+        );
+    }
+
+    #[test]
+    fn future_times_are_now(ahead in 1i64..(365 * DAY)) {
+        let timestamp = (Local::now() + Duration::seconds(ahead)).format("%Y-%m-%dT%H:%M:%S").to_string();
+        if parse_timestamp(&timestamp).is_some() {
+            prop_assert_eq!(format_time_ago(&timestamp), "now");
+        }
+    }
+
+    #[test]
+    fn small_counts_are_exact(n in 0u64..1_000) {
+        prop_assert_eq!(format_count(n), n.to_string());
+    }
+
+    #[test]
+    fn counts_round_within_five_percent(n in 1_000u64..=u64::MAX) {
+        let text = format_count(n);
+        let (number, suffix) = text.split_at(text.len() - 1);
+        let unit = match suffix {
+            "K" => 1e3,
+            "M" => 1e6,
+            "B" => 1e9,
+            other => return Err(TestCaseError::fail(format!("unexpected suffix {:?} in {:?}", other, text))),
+        };
+        let value: f64 = number.parse().unwrap();
+        prop_assert!(value >= 1.0, "{} shown as {}", n, text);
+        prop_assert!(((value * unit) - n as f64).abs() <= 0.05 * unit, "{} shown as {}", n, text);
+    }
+
+    #[test]
+    fn counts_below_a_trillion_have_three_digits_at_most(n in 1_000u64..999_950_000_000) {
+        let text = format_count(n);
+        let whole = text.split('.').next().unwrap();
+        prop_assert!(whole.len() <= 3, "{} shown as {}", n, text);
+    }
+
+    #[test]
+    fn ellipsize_respects_width(text in "\\PC*", max in 0usize..80) {
+        let short = ellipsize(&text, max);
+        if text.chars().count() <= max {
+            prop_assert_eq!(short, text);
+        } else {
+            prop_assert_eq!(short.chars().count(), max + 3);
+            prop_assert!(short.ends_with("..."));
+            prop_assert!(text.starts_with(&short[..short.len() - 3]));
+        }
+    }
+
+    #[test]
+    fn truncate_keeps_the_first_line(text in "(\\PC|\n|\r\n)*", max in 0usize..80) {
+        let short = truncate(&text, max);
+        prop_assert!(!short.contains('\n'));
+        prop_assert!(short.chars().count() <= max + 3);
+        let first = text.lines().next().unwrap_or_default();
+        prop_assert!(first.starts_with(short.trim_end_matches("...")));
+    }
+}
+
+#[test]
+fn count_unit_boundaries() {
+    assert_eq!(format_count(999), "999");
+    assert_eq!(format_count(1_000), "1.0K");
+    assert_eq!(format_count(1_050), "1.1K");
+    assert_eq!(format_count(999_949), "999.9K");
+    assert_eq!(format_count(999_950), "1.0M");
+    assert_eq!(format_count(999_950_000), "1.0B");
+    assert_eq!(format_count(u64::MAX), "18446744073.7B");
+}
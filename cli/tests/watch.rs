@@ -0,0 +1,46 @@
+//! `ig inbox -w` footer: how long ago the list was refreshed, and how it
+//! changes once refreshes start failing
+
+use insta_cli::colors::disable_colors;
+use insta_cli::commands::Freshness;
+use insta_cli::duration::Interval;
+use std::time::{Duration, Instant};
+
+fn every_5s() -> Interval {
+    "5s".parse().unwrap()
+}
+
+#[test]
+fn the_footer_counts_up_since_the_last_refresh() {
+    disable_colors();
+    let start = Instant::now();
+    let mut freshness = Freshness::default();
+    assert!(freshness.footer(start, &every_5s()).contains("Updated never"));
+
+    freshness.refreshed(start);
+    assert_eq!(freshness.footer(start, &every_5s()), "Updated just now • Refreshing every 5s");
+    let later = start + Duration::from_secs(12);
+    assert_eq!(freshness.footer(later, &every_5s()), "Updated 12s ago • Refreshing every 5s");
+}
+
+#[test]
+fn failed_refreshes_are_shown_with_the_age_of_the_data() {
+    disable_colors();
+    let start = Instant::now();
+    let mut freshness = Freshness::default();
+    freshness.refreshed(start);
+
+    freshness.failed("connection refused".to_string());
+    let footer = freshness.footer(start + Duration::from_secs(40), &every_5s());
+    assert!(footer.contains("Refresh failed (connection refused) • last updated 40s ago"), "{}", footer);
+
+    freshness.failed("connection refused".to_string());
+    freshness.failed("connection refused".to_string());
+    let footer = freshness.footer(start + Duration::from_secs(90), &every_5s());
+    assert!(footer.contains("3 refreshes failed"), "{}", footer);
+    assert!(footer.contains("data is stale, last updated 1m30s ago"), "{}", footer);
+
+    // Back to normal once a refresh works again
+    freshness.refreshed(start + Duration::from_secs(95));
+    assert!(freshness.footer(start + Duration::from_secs(95), &every_5s()).starts_with("Updated just now"));
+}
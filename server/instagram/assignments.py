@@ -0,0 +1,65 @@
+"""Thread assignments shared by the team members using this server"""
+
+import json
+import logging
+from datetime import datetime
+from pathlib import Path
+from typing import Optional
+
+from models import Assignment
+
+logger = logging.getLogger(__name__)
+
+ASSIGNMENTS_FILE = Path(__file__).parent.parent / ".ig_assignments.json"
+
+
+def _load(strict: bool = False) -> dict:
+    """The saved assignments; with `strict`, a file that can't be read
+    raises instead of reading as empty (so a change isn't saved over it)"""
+    if not ASSIGNMENTS_FILE.exists():
+        return {}
+    try:
+        return json.loads(ASSIGNMENTS_FILE.read_text())
+    except Exception as e:
+        if strict:
+            raise
+        logger.warning("Failed to read assignments: %s", e)
+        return {}
+
+
+def _save(assignments: dict) -> None:
+    """Write through a temp file so a failed write never leaves half a file.
+    Errors are raised: a change that wasn't saved must not look saved."""
+    tmp = ASSIGNMENTS_FILE.with_suffix(".json.tmp")
+    tmp.write_text(json.dumps(assignments, indent=2))
+    tmp.replace(ASSIGNMENTS_FILE)
+
+
+def list_assignments() -> list[Assignment]:
+    """Every assigned thread, most recently assigned first"""
+    assignments = [
+        Assignment(thread_id=thread_id, **entry)
+        for thread_id, entry in _load().items()
+    ]
+    return sorted(assignments, key=lambda a: a.assigned_at, reverse=True)
+
+
+def assign(
+    thread_id: str,
+    assignee: Optional[str],
+    note: Optional[str],
+    assigned_by: Optional[str],
+) -> None:
+    """Assign a thread to `assignee`, or unassign it when there is none.
+    Raises OSError or ValueError if the change can't be saved."""
+    assignments = _load(strict=True)
+    if assignee:
+        assignments[thread_id] = {
+            "assignee": assignee,
+            "note": note or None,
+            "assigned_by": assigned_by,
+            "assigned_at": datetime.now().isoformat(timespec="seconds"),
+        }
+    else:
+        assignments.pop(thread_id, None)
+    _save(assignments)
@@ -12,7 +12,8 @@ from instagrapi.exceptions import (
     PleaseWaitFewMinutes,
 )
 
-from models import User
+from models import DeviceInfo, QrLoginResponse, User
+from .devices import apply_device, record_login
 from .session import save_session, load_session
 from .parsers import parse_user
 
@@ -22,12 +23,14 @@ logger = logging.getLogger(__name__)
 def login(
     client: Client,
     username: str,
-    password: str
+    password: str,
+    device: Optional[DeviceInfo] = None,
 ) -> tuple[bool, Optional[str], Optional[User]]:
     """
     Login to Instagram. Returns (success, error_message, user).
 
-    Tries to restore session first, then fresh login if needed.
+    Tries to restore session first, then fresh login if needed. A fresh
+    login takes its device IDs from the CLI's device seed, if given.
     """
     # Try to restore existing session
     if load_session(client):
@@ -37,6 +40,8 @@ def login(
             user_info = client.account_info()
             user = parse_user(user_info)
             logger.info("Restored session for %s", user_info.username)
+            if device:
+                record_login(device)
             return True, None, user
         except LoginRequired:
             logger.info("Saved session expired, logging in fresh...")
@@ -45,6 +50,8 @@ def login(
 
     # Fresh login
     try:
+        if device:
+            apply_device(client, device)
         logger.info("Logging in as %s...", username)
         client.login(username, password)
 
@@ -53,6 +60,8 @@ def login(
 
         # Save session for future use
         save_session(client)
+        if device:
+            record_login(device)
 
         logger.info("Logged in as %s", user_info.username)
         return True, None, user
@@ -68,3 +77,23 @@ def login(
     except Exception as e:
         logger.error("Login failed: %s", e)
         return False, str(e), None
+
+
+def start_qr_login(
+    client: Client,
+    device: Optional[DeviceInfo] = None,
+) -> Optional[QrLoginResponse]:
+    """
+    Start a QR code login handoff. Returns None if the backend can't do one.
+
+    instagrapi only speaks the password login of the private API, so there is
+    nothing to hand off to the mobile app yet. A backend that can should
+    return a token and the URL for the CLI to render, then report approval
+    through get_qr_login.
+    """
+    return None
+
+
+def get_qr_login(client: Client, token: str) -> Optional[QrLoginResponse]:
+    """State of a QR code login. Returns None for an unknown token."""
+    return None
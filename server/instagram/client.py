@@ -1,29 +1,65 @@
 """Main Instagram client class"""
 
 import logging
+import os
+from datetime import datetime
 from typing import Optional
 
 from instagrapi import Client
 
 from models import (
+    Device,
+    DeviceInfo,
+    QrLoginResponse,
     User,
     DirectMessage,
     DirectThread,
     DirectThreadPreview,
 )
 from .session import delete_session
-from .auth import login as auth_login
+from .auth import (
+    login as auth_login,
+    start_qr_login as auth_start_qr_login,
+    get_qr_login as auth_get_qr_login,
+)
+from .devices import (
+    list_devices as dev_list_devices,
+    forget_device as dev_forget_device,
+)
 from .messages import (
     get_inbox as msg_get_inbox,
+    get_inbox_page as msg_get_inbox_page,
     get_thread as msg_get_thread,
+    get_thread_page as msg_get_thread_page,
+    get_thread_since as msg_get_thread_since,
     send_message as msg_send_message,
     send_message_to_user as msg_send_message_to_user,
+    send_media as msg_send_media,
+    mark_seen as msg_mark_seen,
+    mute_thread as msg_mute_thread,
+    archive_thread as msg_archive_thread,
+    rename_thread as msg_rename_thread,
+    react_to_message as msg_react_to_message,
     search_user as msg_search_user,
+    get_user as msg_get_user,
 )
 
 logger = logging.getLogger(__name__)
 
 
+def new_client() -> Client:
+    """
+    A fresh instagrapi client, routed through IG_PROXY when it is set
+    (e.g. socks5://127.0.0.1:9050 for Tor)
+    """
+    client = Client()
+    client.delay_range = [1, 3]  # Add delay between requests
+    proxy = os.getenv("IG_PROXY")
+    if proxy:
+        client.set_proxy(proxy)
+    return client
+
+
 class InstagramClient:
     """
     Wrapper around instagrapi.Client with session persistence
@@ -31,32 +67,71 @@ class InstagramClient:
     """
 
     def __init__(self):
-        self.client = Client()
-        self.client.delay_range = [1, 3]  # Add delay between requests
+        self.client = new_client()
         self._logged_in_user: Optional[User] = None
+        self._logged_in_at: Optional[datetime] = None
 
     # ========================================================================
     # Authentication
     # ========================================================================
 
-    def login(self, username: str, password: str) -> tuple[bool, Optional[str]]:
+    def login(
+        self,
+        username: str,
+        password: str,
+        device: Optional[DeviceInfo] = None,
+    ) -> tuple[bool, Optional[str]]:
         """
         Login to Instagram. Returns (success, error_message).
 
         Tries to restore session first, then fresh login if needed.
         """
-        success, error, user = auth_login(self.client, username, password)
+        success, error, user = auth_login(self.client, username, password, device)
         if success:
             self._logged_in_user = user
+            self._logged_in_at = datetime.now()
         return success, error
 
+    def start_qr_login(self, device: Optional[DeviceInfo] = None) -> Optional[QrLoginResponse]:
+        """Start a QR code login. Returns None if the backend can't do one."""
+        return auth_start_qr_login(self.client, device)
+
+    def get_qr_login(self, token: str) -> Optional[QrLoginResponse]:
+        """
+        State of a QR code login. Returns None for an unknown token.
+
+        An approved login becomes the current session.
+        """
+        login = auth_get_qr_login(self.client, token)
+        if login and login.status == "approved" and login.user:
+            self._logged_in_user = login.user
+            self._logged_in_at = datetime.now()
+        return login
+
     def logout(self) -> None:
         """Logout and clear session"""
         delete_session()
-        self.client = Client()
+        self.client = new_client()
         self._logged_in_user = None
+        self._logged_in_at = None
         logger.info("Logged out")
 
+    def list_devices(self) -> list[Device]:
+        """Devices that have logged in through this server"""
+        return dev_list_devices()
+
+    def forget_device(self, device_id: str) -> bool:
+        """
+        Forget a device. Forgetting the current device also ends the session.
+        Returns False if the device is unknown.
+        """
+        was_current = dev_forget_device(device_id)
+        if was_current is None:
+            return False
+        if was_current:
+            self.logout()
+        return True
+
     def is_authenticated(self) -> bool:
         """Check if currently logged in"""
         return self._logged_in_user is not None
@@ -65,6 +140,10 @@ class InstagramClient:
         """Get the currently logged in user"""
         return self._logged_in_user
 
+    def get_session_started_at(self) -> Optional[datetime]:
+        """When the current session logged in (or was restored)"""
+        return self._logged_in_at
+
     # ========================================================================
     # Direct Messages
     # ========================================================================
@@ -77,6 +156,22 @@ class InstagramClient:
         """Get a thread with its messages."""
         return msg_get_thread(self.client, self._logged_in_user, thread_id, amount)
 
+    def get_inbox_page(
+        self, cursor: Optional[str] = None
+    ) -> tuple[list[DirectThreadPreview], Optional[str]]:
+        """Get one page of the inbox and the next page's cursor."""
+        return msg_get_inbox_page(self.client, self._logged_in_user, cursor)
+
+    def get_thread_page(
+        self, thread_id: str, cursor: Optional[str] = None, amount: int = 20
+    ) -> tuple[DirectThread, Optional[str]]:
+        """Get one page of a thread's messages and the next page's cursor."""
+        return msg_get_thread_page(self.client, self._logged_in_user, thread_id, cursor, amount)
+
+    def get_thread_since(self, thread_id: str, message_id: str) -> DirectThread:
+        """Get a thread with only the messages newer than message_id."""
+        return msg_get_thread_since(self.client, self._logged_in_user, thread_id, message_id)
+
     def send_message(self, thread_id: str, text: str) -> DirectMessage:
         """Send a message to an existing thread."""
         return msg_send_message(self.client, self._logged_in_user, thread_id, text)
@@ -85,6 +180,38 @@ class InstagramClient:
         """Send a message to a user by username."""
         return msg_send_message_to_user(self.client, self._logged_in_user, username, text)
 
+    def send_media_to_thread(self, thread_id: str, filename: str, data: bytes) -> DirectMessage:
+        """Send a photo or video to an existing thread."""
+        return msg_send_media(self.client, self._logged_in_user, filename, data, thread_id=thread_id)
+
+    def send_media_to_user(self, username: str, filename: str, data: bytes) -> DirectMessage:
+        """Send a photo or video to a user by username."""
+        return msg_send_media(self.client, self._logged_in_user, filename, data, username=username)
+
+    def mark_seen(self, thread_id: str) -> None:
+        """Mark a thread's messages as seen."""
+        msg_mark_seen(self.client, self._logged_in_user, thread_id)
+
+    def mute_thread(self, thread_id: str) -> None:
+        """Mute notifications for a thread."""
+        msg_mute_thread(self.client, self._logged_in_user, thread_id)
+
+    def archive_thread(self, thread_id: str) -> None:
+        """Hide a thread from the inbox until a new message arrives."""
+        msg_archive_thread(self.client, self._logged_in_user, thread_id)
+
+    def rename_thread(self, thread_id: str, title: str) -> None:
+        """Set a group thread's title."""
+        msg_rename_thread(self.client, self._logged_in_user, thread_id, title)
+
+    def react(self, thread_id: str, message_id: str, emoji: str) -> None:
+        """React to a message with an emoji."""
+        msg_react_to_message(self.client, self._logged_in_user, thread_id, message_id, emoji)
+
     def search_user(self, username: str) -> Optional[User]:
         """Search for a user by exact username."""
         return msg_search_user(self.client, self._logged_in_user, username)
+
+    def get_user(self, user_pk: str) -> Optional[User]:
+        """Look up a user by ID."""
+        return msg_get_user(self.client, self._logged_in_user, user_pk)
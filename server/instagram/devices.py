@@ -0,0 +1,105 @@
+"""Device fingerprints and the registry of devices that logged in"""
+
+import hashlib
+import json
+import logging
+import uuid
+from datetime import datetime
+from pathlib import Path
+from typing import Optional
+
+from instagrapi import Client
+
+from models import Device, DeviceInfo
+
+logger = logging.getLogger(__name__)
+
+DEVICES_FILE = Path(__file__).parent.parent / ".ig_devices.json"
+
+
+def device_id(info: DeviceInfo) -> str:
+    """Stable short ID for a device, derived from its seed"""
+    return hashlib.sha256(info.user_agent_seed.encode()).hexdigest()[:12]
+
+
+def apply_device(client: Client, info: DeviceInfo) -> None:
+    """
+    Derive the client's device IDs from the seed, so every fresh login from
+    the same CLI looks like the same phone to Instagram.
+    """
+    def seeded_uuid(purpose: str) -> str:
+        return str(uuid.uuid5(uuid.NAMESPACE_OID, f"{info.user_agent_seed}:{purpose}"))
+
+    digest = hashlib.sha256(info.user_agent_seed.encode()).hexdigest()
+    client.set_uuids({
+        "phone_id": seeded_uuid("phone_id"),
+        "uuid": seeded_uuid("uuid"),
+        "client_session_id": seeded_uuid("client_session_id"),
+        "advertising_id": seeded_uuid("advertising_id"),
+        "android_device_id": f"android-{digest[:16]}",
+        "request_id": seeded_uuid("request_id"),
+        "tray_session_id": seeded_uuid("tray_session_id"),
+    })
+
+
+def _load() -> dict:
+    if not DEVICES_FILE.exists():
+        return {"current": None, "devices": {}}
+    try:
+        return json.loads(DEVICES_FILE.read_text())
+    except Exception as e:
+        logger.warning("Failed to read device registry: %s", e)
+        return {"current": None, "devices": {}}
+
+
+def _save(registry: dict) -> None:
+    try:
+        DEVICES_FILE.write_text(json.dumps(registry, indent=2))
+    except Exception as e:
+        logger.warning("Failed to save device registry: %s", e)
+
+
+def record_login(info: DeviceInfo) -> None:
+    """Remember a device after a successful login and mark it current"""
+    registry = _load()
+    key = device_id(info)
+    registry["devices"][key] = {
+        "name": info.name,
+        "last_login": datetime.now().isoformat(timespec="seconds"),
+    }
+    registry["current"] = key
+    _save(registry)
+
+
+def list_devices() -> list[Device]:
+    """All devices that have logged in, most recent first"""
+    registry = _load()
+    devices = [
+        Device(
+            id=key,
+            name=entry.get("name", ""),
+            last_login=entry.get("last_login"),
+            current=key == registry.get("current"),
+        )
+        for key, entry in registry["devices"].items()
+    ]
+    return sorted(devices, key=lambda d: d.last_login or "", reverse=True)
+
+
+def forget_device(key: str) -> Optional[bool]:
+    """
+    Remove a device from the registry.
+
+    Returns None if the device is unknown, otherwise whether it was the
+    current device (whose session the caller should end).
+    """
+    registry = _load()
+    if key not in registry["devices"]:
+        return None
+
+    del registry["devices"][key]
+    was_current = registry.get("current") == key
+    if was_current:
+        registry["current"] = None
+    _save(registry)
+    return was_current
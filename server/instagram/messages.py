@@ -1,10 +1,15 @@
 """Direct message operations for Instagram client"""
 
 import logging
+import os
+import subprocess
+import tempfile
+from pathlib import Path
 from typing import Optional
 
 from instagrapi import Client
 from instagrapi.exceptions import LoginRequired
+from instagrapi.extractors import extract_direct_thread
 
 from models import (
     User,
@@ -16,6 +21,28 @@ from .parsers import parse_user, parse_message, parse_thread, parse_thread_previ
 
 logger = logging.getLogger(__name__)
 
+# Recent messages fetched per inbox thread, for the unseen previews
+INBOX_MESSAGE_LIMIT = 5
+
+
+def get_presence(client: Client) -> dict[str, bool]:
+    """
+    Who is online, by user pk.
+
+    Best effort: accounts that hide their activity status are missing, and
+    when the request fails the result is empty, so users are listed without
+    a presence rather than failing the whole inbox.
+    """
+    try:
+        result = client.private_request("direct_v2/get_presence/")
+    except Exception as e:
+        logger.debug("Presence unavailable: %s", e)
+        return {}
+    return {
+        str(pk): bool(state.get("is_active"))
+        for pk, state in (result.get("user_presence") or {}).items()
+    }
+
 
 def get_inbox(
     client: Client,
@@ -36,8 +63,9 @@ def get_inbox(
     if not logged_in_user:
         raise LoginRequired("Not logged in")
 
-    threads = client.direct_threads(amount=amount)
-    return [parse_thread_preview(t) for t in threads]
+    threads = client.direct_threads(amount=amount, thread_message_limit=INBOX_MESSAGE_LIMIT)
+    presence = get_presence(client)
+    return [parse_thread_preview(t, logged_in_user.pk, presence) for t in threads]
 
 
 def get_thread(
@@ -62,7 +90,109 @@ def get_thread(
         raise LoginRequired("Not logged in")
 
     thread = client.direct_thread(thread_id=int(thread_id), amount=amount)
-    return parse_thread(thread, logged_in_user.pk)
+    return parse_thread(thread, logged_in_user.pk, get_presence(client))
+
+
+def get_inbox_page(
+    client: Client,
+    logged_in_user: Optional[User],
+    cursor: Optional[str] = None
+) -> tuple[list[DirectThreadPreview], Optional[str]]:
+    """
+    Get one page of the inbox.
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        cursor: Cursor from the previous page (None for the first page)
+
+    Returns:
+        Thread previews and the cursor of the next page (None on the last page)
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    threads, next_cursor = client.direct_threads_chunk(
+        cursor=cursor or None, thread_message_limit=INBOX_MESSAGE_LIMIT
+    )
+    return [parse_thread_preview(t, logged_in_user.pk) for t in threads], next_cursor or None
+
+
+def get_thread_page(
+    client: Client,
+    logged_in_user: Optional[User],
+    thread_id: str,
+    cursor: Optional[str] = None,
+    amount: int = 20
+) -> tuple[DirectThread, Optional[str]]:
+    """
+    Get one page of a thread's messages, newest first.
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        thread_id: Thread ID
+        cursor: Cursor from the previous page (None for the newest messages)
+        amount: Number of messages in the page
+
+    Returns:
+        Thread with this page of messages and the cursor of the next (older)
+        page (None when there are no older messages)
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    params = {
+        "visual_message_return_type": "unseen",
+        "direction": "older",
+        "limit": str(amount),
+    }
+    if cursor:
+        params["cursor"] = cursor
+
+    result = client.private_request(f"direct_v2/threads/{thread_id}/", params=params)
+    raw = result["thread"]
+    next_cursor = raw.get("oldest_cursor") if raw.get("has_older") else None
+    return parse_thread(extract_direct_thread(raw), logged_in_user.pk), next_cursor
+
+
+def get_thread_since(
+    client: Client,
+    logged_in_user: Optional[User],
+    thread_id: str,
+    message_id: str,
+    max_pages: int = 5
+) -> DirectThread:
+    """
+    Get the messages of a thread that are newer than a given message.
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        thread_id: Thread ID
+        message_id: Last message the caller has
+        max_pages: Pages to search for message_id before giving up
+
+    Returns:
+        Thread with only the newer messages, newest first (everything
+        searched if message_id wasn't found)
+    """
+    thread = None
+    newer: list[DirectMessage] = []
+    cursor = None
+    for _ in range(max_pages):
+        page, cursor = get_thread_page(client, logged_in_user, thread_id, cursor)
+        thread = thread or page
+        for message in page.messages:
+            if message.id == message_id:
+                thread.messages = newer
+                return thread
+            newer.append(message)
+        if not cursor:
+            break
+
+    thread.messages = newer
+    return thread
 
 
 def send_message(
@@ -120,6 +250,201 @@ def send_message_to_user(
     return parse_message(result, logged_in_user.pk)
 
 
+# Extensions sent with direct_send_video; everything else goes as a photo
+VIDEO_EXTENSIONS = {".mp4", ".mov", ".m4v"}
+# Extensions sent as voice messages (transcoded to AAC first)
+AUDIO_EXTENSIONS = {".wav", ".m4a"}
+
+
+def transcode_voice(path: str) -> str:
+    """
+    Convert a recording to the mono AAC .m4a Instagram voice messages use.
+    Returns the new file's path; the caller deletes it.
+    """
+    out = os.path.splitext(path)[0] + ".voice.m4a"
+    try:
+        subprocess.run(
+            ["ffmpeg", "-y", "-loglevel", "error", "-i", path,
+             "-ac", "1", "-ar", "44100", "-c:a", "aac", "-b:a", "64k", out],
+            check=True,
+            capture_output=True,
+        )
+    except FileNotFoundError:
+        raise RuntimeError("Voice messages need ffmpeg installed on the server")
+    except subprocess.CalledProcessError as e:
+        raise RuntimeError(f"Failed to transcode voice message: {e.stderr.decode().strip()}")
+    return out
+
+
+def send_media(
+    client: Client,
+    logged_in_user: Optional[User],
+    filename: str,
+    data: bytes,
+    thread_id: Optional[str] = None,
+    username: Optional[str] = None,
+) -> DirectMessage:
+    """
+    Send a photo, video or voice message to a thread, or to a user by
+    username.
+
+    instagrapi uploads from a path, so the file is written to a temporary
+    file with the original extension first.
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        filename: Original file name (its extension picks photo, video or voice)
+        data: File contents
+        thread_id: Thread ID to send to
+        username: Username to send to if no thread ID is given
+
+    Returns:
+        The sent message
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    if thread_id:
+        targets = {"thread_ids": [int(thread_id)]}
+    else:
+        targets = {"user_ids": [client.user_id_from_username(username)]}
+
+    suffix = Path(filename).suffix.lower()
+    fd, path = tempfile.mkstemp(suffix=suffix)
+    try:
+        with os.fdopen(fd, "wb") as f:
+            f.write(data)
+        if suffix in AUDIO_EXTENSIONS:
+            # Not every instagrapi release can send voice messages
+            send_voice = getattr(client, "direct_send_voice", None)
+            if send_voice is None:
+                raise RuntimeError("This server's instagrapi version can't send voice messages")
+            voice_path = transcode_voice(path)
+            try:
+                result = send_voice(Path(voice_path), **targets)
+            finally:
+                os.unlink(voice_path)
+        elif suffix in VIDEO_EXTENSIONS:
+            result = client.direct_send_video(Path(path), **targets)
+        else:
+            result = client.direct_send_photo(Path(path), **targets)
+    finally:
+        os.unlink(path)
+    return parse_message(result, logged_in_user.pk)
+
+
+def mark_seen(client: Client, logged_in_user: Optional[User], thread_id: str) -> None:
+    """
+    Mark a thread's messages as seen.
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        thread_id: Thread ID
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    client.direct_send_seen(int(thread_id))
+
+
+def mute_thread(client: Client, logged_in_user: Optional[User], thread_id: str) -> None:
+    """
+    Mute notifications for a thread.
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        thread_id: Thread ID
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    if not client.direct_thread_mute(int(thread_id)):
+        raise RuntimeError("Instagram refused to mute the thread")
+
+
+def archive_thread(client: Client, logged_in_user: Optional[User], thread_id: str) -> None:
+    """
+    Hide a thread from the inbox until a new message arrives in it.
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        thread_id: Thread ID
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    if not client.direct_thread_hide(int(thread_id)):
+        raise RuntimeError("Instagram refused to archive the thread")
+
+
+def rename_thread(client: Client, logged_in_user: Optional[User], thread_id: str, title: str) -> None:
+    """
+    Set a group thread's title (Instagram only allows it for groups).
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        thread_id: Thread ID
+        title: New title
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    result = client.private_request(
+        f"direct_v2/threads/{thread_id}/update_title/",
+        data={"title": title, "_uuid": client.uuid},
+        with_signature=False,
+    )
+    if result.get("status") != "ok":
+        raise RuntimeError("Instagram refused to rename the thread")
+
+
+def react_to_message(
+    client: Client,
+    logged_in_user: Optional[User],
+    thread_id: str,
+    message_id: str,
+    emoji: str,
+) -> None:
+    """
+    React to a message with an emoji (replaces an earlier reaction).
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        thread_id: Thread ID
+        message_id: Message to react to
+        emoji: Reaction emoji
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    token = client.generate_mutation_token()
+    result = client.private_request(
+        "direct_v2/threads/broadcast/reaction/",
+        data={
+            "action": "send_item",
+            "item_type": "reaction",
+            "reaction_type": "like",
+            "reaction_status": "created",
+            "node_type": "item",
+            "item_id": message_id,
+            "emoji": emoji,
+            "thread_ids": f"[{thread_id}]",
+            "client_context": token,
+            "mutation_token": token,
+            "_uuid": client.uuid,
+        },
+        with_signature=False,
+    )
+    if result.get("status") != "ok":
+        raise RuntimeError("Instagram refused the reaction")
+
+
 def search_user(
     client: Client,
     logged_in_user: Optional[User],
@@ -144,3 +469,29 @@ def search_user(
         return parse_user(user_info)
     except Exception:
         return None
+
+
+def get_user(
+    client: Client,
+    logged_in_user: Optional[User],
+    user_pk: str
+) -> Optional[User]:
+    """
+    Look up a user by their numeric ID (pk).
+
+    Args:
+        client: Instagram client
+        logged_in_user: Currently logged in user
+        user_pk: User ID to look up
+
+    Returns:
+        User info or None if not found
+    """
+    if not logged_in_user:
+        raise LoginRequired("Not logged in")
+
+    try:
+        user_info = client.user_info(user_pk)
+        return parse_user(user_info)
+    except Exception:
+        return None
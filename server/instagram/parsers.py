@@ -13,6 +13,9 @@ from models import (
     DirectThreadPreview,
 )
 
+# Unseen messages sent along with each inbox thread
+UNSEEN_PREVIEWS = 2
+
 
 def parse_user(user) -> User:
     """Convert instagrapi user to our User model"""
@@ -26,13 +29,17 @@ def parse_user(user) -> User:
     )
 
 
-def parse_user_short(user) -> UserShort:
-    """Convert instagrapi user to our UserShort model"""
+def parse_user_short(user, presence: dict[str, bool] | None = None) -> UserShort:
+    """Convert instagrapi user to our UserShort model
+
+    presence maps user pks to whether they're online (see get_presence).
+    """
     return UserShort(
         pk=str(user.pk),
         username=user.username,
         full_name=user.full_name or "",
         profile_pic_url=str(user.profile_pic_url) if user.profile_pic_url else None,
+        is_active=(presence or {}).get(str(user.pk)),
     )
 
 
@@ -71,9 +78,13 @@ def parse_message(msg: IGDirectMessage, logged_in_user_pk: str | None = None) ->
     )
 
 
-def parse_thread(thread: IGDirectThread, logged_in_user_pk: str | None = None) -> DirectThread:
+def parse_thread(
+    thread: IGDirectThread,
+    logged_in_user_pk: str | None = None,
+    presence: dict[str, bool] | None = None,
+) -> DirectThread:
     """Convert instagrapi DirectThread to our model (with messages)"""
-    users = [parse_user_short(u) for u in thread.users]
+    users = [parse_user_short(u, presence) for u in thread.users]
     messages = [parse_message(m, logged_in_user_pk) for m in (thread.messages or [])]
 
     # Build thread title from usernames if not set
@@ -94,9 +105,32 @@ def parse_thread(thread: IGDirectThread, logged_in_user_pk: str | None = None) -
     )
 
 
-def parse_thread_preview(thread: IGDirectThread) -> DirectThreadPreview:
+def unseen_messages(thread: IGDirectThread, logged_in_user_pk: str | None) -> list[IGDirectMessage]:
+    """Messages newer than the viewer's read position, newest first
+
+    Stops at the last message the viewer has seen or sent, so only what
+    arrived since then counts. Limited to the messages the inbox returned.
+    """
+    if not logged_in_user_pk:
+        return []
+    last_seen = (getattr(thread, 'last_seen_at', None) or {}).get(logged_in_user_pk) or {}
+    seen_item = str(last_seen.get("item_id") or "")
+
+    unseen = []
+    for msg in thread.messages or []:
+        if str(msg.id) == seen_item or str(msg.user_id) == logged_in_user_pk:
+            break
+        unseen.append(msg)
+    return unseen
+
+
+def parse_thread_preview(
+    thread: IGDirectThread,
+    logged_in_user_pk: str | None = None,
+    presence: dict[str, bool] | None = None,
+) -> DirectThreadPreview:
     """Convert instagrapi DirectThread to our preview model (for inbox)"""
-    users = [parse_user_short(u) for u in thread.users]
+    users = [parse_user_short(u, presence) for u in thread.users]
 
     # Build thread title from usernames if not set
     thread_title = thread.thread_title or ""
@@ -134,6 +168,9 @@ def parse_thread_preview(thread: IGDirectThread) -> DirectThreadPreview:
             }
             last_msg_text = type_previews.get(last_msg_type, f"[{last_msg_type}]")
 
+    has_unread = getattr(thread, 'has_newer', False)
+    unseen = unseen_messages(thread, logged_in_user_pk) if has_unread else []
+
     return DirectThreadPreview(
         id=str(thread.id),
         pk=str(thread.pk),
@@ -142,8 +179,10 @@ def parse_thread_preview(thread: IGDirectThread) -> DirectThreadPreview:
         last_activity_at=getattr(thread, 'last_activity_at', None),
         is_group=thread.is_group if hasattr(thread, 'is_group') else len(users) > 1,
         is_muted=getattr(thread, 'muted', False),
-        has_unread=getattr(thread, 'has_newer', False),
+        has_unread=has_unread,
         last_message_text=last_msg_text,
         last_message_type=last_msg_type,
         last_message_timestamp=last_msg_timestamp,
+        unseen_count=len(unseen),
+        unseen_messages=[parse_message(m, logged_in_user_pk) for m in unseen[:UNSEEN_PREVIEWS]],
     )
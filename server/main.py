@@ -8,29 +8,46 @@ Uses instagrapi for Instagram communication.
 import logging
 import os
 from contextlib import asynccontextmanager
+from datetime import datetime, timezone
+from typing import Optional
 
 from dotenv import load_dotenv
-from fastapi import FastAPI, HTTPException, status
+from fastapi import FastAPI, File, Header, HTTPException, Request, UploadFile, status
+from fastapi.middleware.gzip import GZipMiddleware
+from fastapi.responses import JSONResponse
+from instagrapi.exceptions import LoginRequired
 
 # Load environment variables from .env file
 load_dotenv()
-from fastapi.responses import JSONResponse
-from instagrapi.exceptions import LoginRequired
 
 from models import (
     LoginRequest,
     LoginResponse,
+    QrLoginRequest,
+    QrLoginResponse,
     PublicKeyResponse,
     SendMessageRequest,
+    ReactRequest,
+    RenameThreadRequest,
     SendMessageResponse,
     InboxResponse,
     ThreadResponse,
+    ThreadActionResponse,
     HealthResponse,
+    CapabilitiesResponse,
+    DevicesResponse,
+    AssignRequest,
+    AssignmentsResponse,
     ErrorResponse,
     User,
 )
-from instagram import instagram_client
-from middleware import get_public_key_pem, decrypt_password, ensure_keys_exist
+from instagram import instagram_client, assignments
+from middleware import (
+    get_public_key_pem,
+    decrypt_password,
+    ensure_keys_exist,
+    conditional_response,
+)
 
 # Configure logging
 logging.basicConfig(
@@ -39,6 +56,14 @@ logging.basicConfig(
 )
 logger = logging.getLogger(__name__)
 
+# API version spoken by this server. Bump when the response schema changes
+# in a way the CLI needs to know about.
+API_VERSION = 1
+# Oldest CLI API version this server still supports
+MIN_CLIENT_API_VERSION = 1
+# When this server process started; lets clients notice a restart
+STARTED_AT = datetime.now(timezone.utc).isoformat(timespec="seconds")
+
 
 @asynccontextmanager
 async def lifespan(app: FastAPI):
@@ -75,6 +100,10 @@ app = FastAPI(
     lifespan=lifespan,
 )
 
+# Compress larger responses (inbox and thread payloads) for clients that
+# advertise gzip in Accept-Encoding
+app.add_middleware(GZipMiddleware, minimum_size=1000)
+
 
 # ============================================================================
 # Error Handlers
@@ -102,13 +131,41 @@ async def generic_exception_handler(request, exc):
 # ============================================================================
 
 @app.get("/health", response_model=HealthResponse, tags=["Health"])
-async def health_check():
-    """Check server status and authentication state"""
+async def health_check(x_api_version: Optional[int] = Header(default=None)):
+    """
+    Check server status and authentication state.
+
+    Also serves as the API version handshake: the CLI sends its version in the
+    X-Api-Version header and compares it against the versions returned here.
+    """
+    if x_api_version is not None and x_api_version != API_VERSION:
+        logger.info("CLI API version %s differs from server version %s", x_api_version, API_VERSION)
+
     user = instagram_client.get_current_user()
+    started_at = instagram_client.get_session_started_at()
     return HealthResponse(
         status="ok",
         authenticated=instagram_client.is_authenticated(),
         username=user.username if user else None,
+        api_version=API_VERSION,
+        min_client_api_version=MIN_CLIENT_API_VERSION,
+        session_started_at=started_at.isoformat(timespec="seconds") if started_at else None,
+        started_at=STARTED_AT,
+    )
+
+
+@app.get("/capabilities", response_model=CapabilitiesResponse, tags=["Health"])
+async def capabilities():
+    """
+    Optional features this server supports.
+
+    The CLI checks this before using one, so an unsupported feature is
+    reported as such instead of failing with a 404.
+    """
+    return CapabilitiesResponse(
+        reactions=True, media=True, presence=True, websocket=False, assignments=True,
+        # instagrapi can't hand logins off to the app (see start_qr_login)
+        qr_login=False,
     )
 
 
@@ -159,7 +216,7 @@ async def login(request: LoginRequest):
             detail="Either password or encrypted_password is required"
         )
 
-    success, error = instagram_client.login(request.username, password)
+    success, error = instagram_client.login(request.username, password, request.device)
 
     if success:
         return LoginResponse(
@@ -174,6 +231,33 @@ async def login(request: LoginRequest):
         )
 
 
+@app.post("/auth/qr", response_model=QrLoginResponse, tags=["Auth"])
+async def start_qr_login(request: QrLoginRequest):
+    """
+    Start a QR code login.
+
+    Returns a one-time token and the URL to show as a QR code; approving it
+    in the Instagram app logs the server in. Poll GET /auth/qr/{token} for
+    the result. Answers 501 if the Instagram backend can't hand off logins.
+    """
+    login = instagram_client.start_qr_login(request.device)
+    if login is None:
+        raise HTTPException(
+            status_code=status.HTTP_501_NOT_IMPLEMENTED,
+            detail="QR login is not supported by this server's Instagram backend"
+        )
+    return login
+
+
+@app.get("/auth/qr/{token}", response_model=QrLoginResponse, tags=["Auth"])
+async def qr_login_status(token: str):
+    """Check whether a QR code login has been approved"""
+    login = instagram_client.get_qr_login(token)
+    if login is None:
+        return QrLoginResponse(success=False, status="expired", error="Unknown or expired QR login")
+    return login
+
+
 @app.post("/auth/logout", response_model=dict, tags=["Auth"])
 async def logout():
     """Logout and clear saved session"""
@@ -181,23 +265,47 @@ async def logout():
     return {"success": True, "message": "Logged out"}
 
 
+@app.get("/auth/devices", response_model=DevicesResponse, tags=["Auth"])
+async def list_devices():
+    """List devices that have logged in through this server"""
+    return DevicesResponse(success=True, devices=instagram_client.list_devices())
+
+
+@app.delete("/auth/devices/{device_id}", response_model=DevicesResponse, tags=["Auth"])
+async def forget_device(device_id: str):
+    """
+    Forget a device. Forgetting the current device also logs out and
+    deletes the saved session.
+    """
+    if not instagram_client.forget_device(device_id):
+        return DevicesResponse(success=False, error=f"Unknown device: {device_id}")
+    return DevicesResponse(success=True, devices=instagram_client.list_devices())
+
+
 # ============================================================================
 # Direct Messages
 # ============================================================================
 
 @app.get("/inbox", response_model=InboxResponse, tags=["DM"])
-async def get_inbox(limit: int = 20):
+async def get_inbox(request: Request, limit: int = 20, cursor: Optional[str] = None):
     """
     Get DM inbox (list of conversations).
+    Carries an ETag; a matching If-None-Match gets 304 Not Modified.
     
     Args:
         limit: Number of threads to fetch (default 20, max 100)
+        cursor: Page through the whole inbox instead: pass an empty cursor
+            for the first page, then each response's next_cursor. Pages are
+            Instagram's size and limit is ignored.
     """
     limit = min(max(limit, 1), 100)  # Clamp between 1 and 100
     
     try:
+        if cursor is not None:
+            threads, next_cursor = instagram_client.get_inbox_page(cursor)
+            return InboxResponse(success=True, threads=threads, next_cursor=next_cursor)
         threads = instagram_client.get_inbox(amount=limit)
-        return InboxResponse(success=True, threads=threads)
+        return conditional_response(request, InboxResponse(success=True, threads=threads))
     except LoginRequired:
         raise
     except Exception as e:
@@ -206,19 +314,27 @@ async def get_inbox(limit: int = 20):
 
 
 @app.get("/thread/{thread_id}", response_model=ThreadResponse, tags=["DM"])
-async def get_thread(thread_id: str, limit: int = 20):
+async def get_thread(
+    request: Request, thread_id: str, limit: int = 20, cursor: Optional[str] = None
+):
     """
     Get a conversation thread with messages.
+    Carries an ETag; a matching If-None-Match gets 304 Not Modified.
     
     Args:
         thread_id: Thread ID
         limit: Number of messages to fetch (default 20, max 100)
+        cursor: Page back through the history instead: pass an empty cursor
+            for the newest page, then each response's next_cursor
     """
     limit = min(max(limit, 1), 100)
     
     try:
+        if cursor is not None:
+            thread, next_cursor = instagram_client.get_thread_page(thread_id, cursor, amount=limit)
+            return ThreadResponse(success=True, thread=thread, next_cursor=next_cursor)
         thread = instagram_client.get_thread(thread_id, amount=limit)
-        return ThreadResponse(success=True, thread=thread)
+        return conditional_response(request, ThreadResponse(success=True, thread=thread))
     except LoginRequired:
         raise
     except Exception as e:
@@ -226,6 +342,26 @@ async def get_thread(thread_id: str, limit: int = 20):
         return ThreadResponse(success=False, error=str(e))
 
 
+@app.get("/thread/{thread_id}/since/{message_id}", response_model=ThreadResponse, tags=["DM"])
+async def get_thread_since(request: Request, thread_id: str, message_id: str):
+    """
+    Get only the messages of a thread newer than message_id (for polling).
+    Carries an ETag; a matching If-None-Match gets 304 Not Modified.
+    
+    Args:
+        thread_id: Thread ID
+        message_id: Newest message the client already has
+    """
+    try:
+        thread = instagram_client.get_thread_since(thread_id, message_id)
+        return conditional_response(request, ThreadResponse(success=True, thread=thread))
+    except LoginRequired:
+        raise
+    except Exception as e:
+        logger.error("Failed to fetch thread %s since %s: %s", thread_id, message_id, e)
+        return ThreadResponse(success=False, error=str(e))
+
+
 @app.post("/thread/{thread_id}/send", response_model=SendMessageResponse, tags=["DM"])
 async def send_message_to_thread(thread_id: str, request: SendMessageRequest):
     """
@@ -269,6 +405,171 @@ async def send_message_to_user(username: str, request: SendMessageRequest):
         return SendMessageResponse(success=False, error=str(e))
 
 
+@app.post("/thread/{thread_id}/photo", response_model=SendMessageResponse, tags=["DM"])
+async def upload_photo_to_thread(thread_id: str, file: UploadFile = File(...)):
+    """
+    Send a photo, video or voice message to an existing thread, uploaded as
+    multipart form data in the `file` field.
+
+    .mp4, .mov and .m4v files go as videos, .wav and .m4a as voice messages
+    (transcoded with ffmpeg), anything else as a photo.
+    """
+    data = await file.read()
+    try:
+        message = instagram_client.send_media_to_thread(thread_id, file.filename or "photo.jpg", data)
+        return SendMessageResponse(success=True, message=message)
+    except LoginRequired:
+        raise
+    except Exception as e:
+        logger.error("Failed to send media to thread %s: %s", thread_id, e)
+        return SendMessageResponse(success=False, error=str(e))
+
+
+@app.post("/send/{username}/photo", response_model=SendMessageResponse, tags=["DM"])
+async def upload_photo_to_user(username: str, file: UploadFile = File(...)):
+    """
+    Send a photo, video or voice message to a user by username, uploaded as
+    multipart form data in the `file` field.
+
+    The file's extension picks the kind, as for /thread/{thread_id}/photo.
+    """
+    username = username.lstrip("@")
+    data = await file.read()
+    try:
+        message = instagram_client.send_media_to_user(username, file.filename or "photo.jpg", data)
+        return SendMessageResponse(success=True, message=message)
+    except LoginRequired:
+        raise
+    except Exception as e:
+        logger.error("Failed to send media to %s: %s", username, e)
+        return SendMessageResponse(success=False, error=str(e))
+
+
+@app.post("/thread/{thread_id}/seen", response_model=ThreadActionResponse, tags=["DM"])
+async def mark_thread_seen(thread_id: str):
+    """
+    Mark a thread as read.
+    
+    Args:
+        thread_id: Thread ID
+    """
+    try:
+        instagram_client.mark_seen(thread_id)
+        return ThreadActionResponse(success=True)
+    except LoginRequired:
+        raise
+    except Exception as e:
+        logger.error("Failed to mark thread %s as seen: %s", thread_id, e)
+        return ThreadActionResponse(success=False, error=str(e))
+
+
+@app.post("/thread/{thread_id}/mute", response_model=ThreadActionResponse, tags=["DM"])
+async def mute_thread(thread_id: str):
+    """
+    Mute notifications for a thread.
+    
+    Args:
+        thread_id: Thread ID
+    """
+    try:
+        instagram_client.mute_thread(thread_id)
+        return ThreadActionResponse(success=True)
+    except LoginRequired:
+        raise
+    except Exception as e:
+        logger.error("Failed to mute thread %s: %s", thread_id, e)
+        return ThreadActionResponse(success=False, error=str(e))
+
+
+@app.post("/thread/{thread_id}/archive", response_model=ThreadActionResponse, tags=["DM"])
+async def archive_thread(thread_id: str):
+    """
+    Hide a thread from the inbox until a new message arrives in it.
+    
+    Args:
+        thread_id: Thread ID
+    """
+    try:
+        instagram_client.archive_thread(thread_id)
+        return ThreadActionResponse(success=True)
+    except LoginRequired:
+        raise
+    except Exception as e:
+        logger.error("Failed to archive thread %s: %s", thread_id, e)
+        return ThreadActionResponse(success=False, error=str(e))
+
+
+@app.post("/thread/{thread_id}/title", response_model=ThreadActionResponse, tags=["DM"])
+async def rename_thread(thread_id: str, request: RenameThreadRequest):
+    """
+    Set a group thread's title.
+    
+    Args:
+        thread_id: Thread ID
+        request: New title
+    """
+    try:
+        instagram_client.rename_thread(thread_id, request.title)
+        return ThreadActionResponse(success=True)
+    except LoginRequired:
+        raise
+    except Exception as e:
+        logger.error("Failed to rename thread %s: %s", thread_id, e)
+        return ThreadActionResponse(success=False, error=str(e))
+
+
+@app.post("/thread/{thread_id}/react", response_model=ThreadActionResponse, tags=["DM"])
+async def react_to_message(thread_id: str, request: ReactRequest):
+    """
+    React to a message with an emoji.
+    
+    Args:
+        thread_id: Thread ID
+        request: Message ID and emoji
+    """
+    try:
+        instagram_client.react(thread_id, request.message_id, request.emoji)
+        return ThreadActionResponse(success=True)
+    except LoginRequired:
+        raise
+    except Exception as e:
+        logger.error("Failed to react to message %s in thread %s: %s", request.message_id, thread_id, e)
+        return ThreadActionResponse(success=False, error=str(e))
+
+
+# ============================================================================
+# Team
+# ============================================================================
+
+@app.get("/assignments", response_model=AssignmentsResponse, tags=["Team"])
+async def list_assignments():
+    """
+    Which team member is answering which thread.
+
+    Kept by the server so everyone sharing the account sees the same list.
+    """
+    return AssignmentsResponse(success=True, assignments=assignments.list_assignments())
+
+
+@app.post("/thread/{thread_id}/assign", response_model=AssignmentsResponse, tags=["Team"])
+async def assign_thread(thread_id: str, request: AssignRequest):
+    """
+    Assign a thread to a team member, with an optional handoff note.
+    Without an assignee the thread is unassigned.
+
+    Args:
+        thread_id: Thread ID
+        request: Assignee, note and who is making the change
+    """
+    assignee = (request.assignee or "").strip().lstrip("@") or None
+    try:
+        assignments.assign(thread_id, assignee, request.note, request.assigned_by)
+    except (OSError, ValueError) as e:
+        logger.error("Failed to save assignment: %s", e)
+        return AssignmentsResponse(success=False, error=f"Failed to save assignment: {e}")
+    return AssignmentsResponse(success=True, assignments=assignments.list_assignments())
+
+
 # ============================================================================
 # User Search
 # ============================================================================
@@ -304,6 +605,35 @@ async def search_user(username: str):
         )
 
 
+@app.get("/user/id/{user_pk}", response_model=dict, tags=["User"])
+async def get_user(user_pk: str):
+    """
+    Look up a user by ID (used to fill in users missing from thread data).
+    
+    Args:
+        user_pk: Numeric Instagram user ID
+    """
+    try:
+        user = instagram_client.get_user(user_pk)
+        if user:
+            return {"success": True, "user": user}
+        else:
+            raise HTTPException(
+                status_code=status.HTTP_404_NOT_FOUND,
+                detail=f"User {user_pk} not found"
+            )
+    except LoginRequired:
+        raise
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error("Failed to look up user %s: %s", user_pk, e)
+        raise HTTPException(
+            status_code=status.HTTP_500_INTERNAL_SERVER_ERROR,
+            detail=str(e)
+        )
+
+
 # ============================================================================
 # Main
 # ============================================================================
@@ -323,12 +653,21 @@ Endpoints:
   GET  /health                - Check server status
   GET  /auth/public-key       - Get encryption public key
   POST /auth/login            - Login (encrypted or plain password)
+  POST /auth/qr               - Start a QR code login
+  GET  /auth/qr/{{token}}       - Check a QR code login
   POST /auth/logout           - Logout and clear session
+  GET  /auth/devices          - List devices that logged in
+  DELETE /auth/devices/{{device_id}} - Forget a device
   GET  /inbox                 - Get DM inbox
   GET  /thread/{{thread_id}}    - Get messages in a thread
+  GET  /thread/{{thread_id}}/since/{{message_id}} - Messages newer than one
   POST /thread/{{thread_id}}/send - Send message to thread
   POST /send/{{username}}       - Send message to user
+  POST /thread/{{thread_id}}/photo - Upload a photo, video or voice message to thread (multipart)
+  POST /send/{{username}}/photo - Upload a photo, video or voice message to user (multipart)
+  POST /thread/{{thread_id}}/react - React to a message
   GET  /user/{{username}}       - Search for a user
+  GET  /user/id/{{user_pk}}     - Look up a user by ID
 
 Docs: http://localhost:{port}/docs
 ━━━━━━━━━━━━━━━━━━━━━━━━━━
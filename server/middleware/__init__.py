@@ -1,9 +1,11 @@
 """Middleware module for encryption and security."""
 
 from .crypto import get_public_key_pem, decrypt_password, ensure_keys_exist
+from .etag import conditional_response
 
 __all__ = [
     "get_public_key_pem",
     "decrypt_password",
     "ensure_keys_exist",
+    "conditional_response",
 ]
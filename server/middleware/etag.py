@@ -0,0 +1,25 @@
+"""
+Conditional responses for polled endpoints.
+
+The CLI polls the inbox and open threads every few seconds. Each response
+carries an ETag (a hash of its body); when the CLI sends it back in
+If-None-Match and nothing changed, the server answers 304 Not Modified with
+an empty body instead of re-sending the same payload.
+"""
+
+import hashlib
+
+from fastapi import Request, Response
+from pydantic import BaseModel
+
+
+def conditional_response(request: Request, payload: BaseModel) -> Response:
+    """Serve a payload with an ETag, or 304 if the client already has it."""
+    body = payload.model_dump_json()
+    etag = '"' + hashlib.sha256(body.encode()).hexdigest()[:32] + '"'
+
+    if_none_match = request.headers.get("if-none-match", "")
+    if etag in (tag.strip() for tag in if_none_match.split(",")):
+        return Response(status_code=304, headers={"ETag": etag})
+
+    return Response(content=body, media_type="application/json", headers={"ETag": etag})
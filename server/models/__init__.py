@@ -8,14 +8,26 @@ They're designed to be simpler than instagrapi's internal models.
 from .user_models import User, UserShort
 from .message_models import DirectMessage, DirectThread, DirectThreadPreview
 from .api_models import (
+    DeviceInfo,
     LoginRequest,
     LoginResponse,
+    QrLoginRequest,
+    QrLoginResponse,
     PublicKeyResponse,
     SendMessageRequest,
+    ReactRequest,
+    RenameThreadRequest,
     SendMessageResponse,
     InboxResponse,
     ThreadResponse,
+    ThreadActionResponse,
     HealthResponse,
+    CapabilitiesResponse,
+    Device,
+    DevicesResponse,
+    AssignRequest,
+    Assignment,
+    AssignmentsResponse,
     ErrorResponse,
 )
 
@@ -28,13 +40,25 @@ __all__ = [
     "DirectThread",
     "DirectThreadPreview",
     # API models
+    "DeviceInfo",
     "LoginRequest",
     "LoginResponse",
+    "QrLoginRequest",
+    "QrLoginResponse",
     "PublicKeyResponse",
     "SendMessageRequest",
+    "ReactRequest",
+    "RenameThreadRequest",
     "SendMessageResponse",
     "InboxResponse",
     "ThreadResponse",
+    "ThreadActionResponse",
     "HealthResponse",
+    "CapabilitiesResponse",
+    "Device",
+    "DevicesResponse",
+    "AssignRequest",
+    "Assignment",
+    "AssignmentsResponse",
     "ErrorResponse",
 ]
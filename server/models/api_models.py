@@ -7,11 +7,18 @@ from .user_models import User
 from .message_models import DirectMessage, DirectThread, DirectThreadPreview
 
 
+class DeviceInfo(BaseModel):
+    """Device metadata the CLI sends at login"""
+    name: str = Field(description="Human-readable device name")
+    user_agent_seed: str = Field(description="Stable seed the device IDs are derived from")
+
+
 class LoginRequest(BaseModel):
     """Login request body - supports both plain and encrypted passwords"""
     username: str
     password: str = Field(default="", description="Plain text password (for testing only)")
     encrypted_password: str = Field(default="", description="RSA-encrypted password (base64)")
+    device: Optional[DeviceInfo] = Field(default=None, description="Device fingerprint settings")
 
 
 class PublicKeyResponse(BaseModel):
@@ -26,11 +33,38 @@ class LoginResponse(BaseModel):
     message: Optional[str] = None
 
 
+class QrLoginRequest(BaseModel):
+    """Request to start a QR code login"""
+    device: Optional[DeviceInfo] = Field(default=None, description="Device fingerprint settings")
+
+
+class QrLoginResponse(BaseModel):
+    """State of a QR code login handoff"""
+    success: bool
+    token: Optional[str] = Field(default=None, description="One-time handoff token")
+    url: Optional[str] = Field(default=None, description="What the QR code encodes")
+    status: str = Field(default="pending", description="pending, approved or expired")
+    expires_in: Optional[int] = Field(default=None, description="Seconds until the token expires")
+    user: Optional[User] = None
+    error: Optional[str] = None
+
+
 class SendMessageRequest(BaseModel):
     """Send message request body"""
     text: str
 
 
+class ReactRequest(BaseModel):
+    """React to a message request body"""
+    message_id: str
+    emoji: str = Field(description="A single emoji, e.g. ❤️")
+
+
+class RenameThreadRequest(BaseModel):
+    """Rename a group thread request body"""
+    title: str = Field(min_length=1, description="New title for the group")
+
+
 class SendMessageResponse(BaseModel):
     """Send message response"""
     success: bool
@@ -42,6 +76,7 @@ class InboxResponse(BaseModel):
     """Inbox listing response"""
     success: bool
     threads: list[DirectThreadPreview] = Field(default_factory=list)
+    next_cursor: Optional[str] = None
     error: Optional[str] = None
 
 
@@ -49,6 +84,13 @@ class ThreadResponse(BaseModel):
     """Single thread with messages response"""
     success: bool
     thread: Optional[DirectThread] = None
+    next_cursor: Optional[str] = None
+    error: Optional[str] = None
+
+
+class ThreadActionResponse(BaseModel):
+    """Result of marking, muting, archiving or renaming a thread"""
+    success: bool
     error: Optional[str] = None
 
 
@@ -57,6 +99,58 @@ class HealthResponse(BaseModel):
     status: str
     authenticated: bool
     username: Optional[str] = None
+    api_version: int = Field(description="API version spoken by this server")
+    min_client_api_version: int = Field(description="Oldest CLI API version still supported")
+    session_started_at: Optional[str] = Field(default=None, description="When the current session logged in (ISO 8601)")
+    started_at: Optional[str] = Field(default=None, description="When the server process started (ISO 8601)")
+
+
+class CapabilitiesResponse(BaseModel):
+    """Optional features this server supports"""
+    reactions: bool = Field(default=False, description="POST /thread/{id}/react")
+    media: bool = Field(default=False, description="Photo, video and voice uploads")
+    presence: bool = Field(default=False, description="Online / last active status")
+    websocket: bool = Field(default=False, description="Live updates over a websocket")
+    assignments: bool = Field(default=False, description="GET /assignments, POST /thread/{id}/assign")
+    qr_login: bool = Field(default=False, description="POST /auth/qr, GET /auth/qr/{token}")
+
+
+class Device(BaseModel):
+    """A device that has logged in through this server"""
+    id: str
+    name: str
+    last_login: Optional[str] = None
+    current: bool = False
+
+
+class DevicesResponse(BaseModel):
+    """Device list response"""
+    success: bool
+    devices: list[Device] = Field(default_factory=list)
+    error: Optional[str] = None
+
+
+class AssignRequest(BaseModel):
+    """Hand a thread to a team member; no assignee unassigns it"""
+    assignee: Optional[str] = Field(default=None, description="Team member answering the thread")
+    note: Optional[str] = Field(default=None, description="Handoff note for whoever picks it up")
+    assigned_by: Optional[str] = Field(default=None, description="Team member making the change")
+
+
+class Assignment(BaseModel):
+    """Who on the team is answering a thread"""
+    thread_id: str
+    assignee: str
+    note: Optional[str] = None
+    assigned_by: Optional[str] = None
+    assigned_at: str
+
+
+class AssignmentsResponse(BaseModel):
+    """Thread assignments shared by everyone using this server"""
+    success: bool
+    assignments: list[Assignment] = Field(default_factory=list)
+    error: Optional[str] = None
 
 
 class ErrorResponse(BaseModel):
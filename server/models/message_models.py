@@ -57,3 +57,7 @@ class DirectThreadPreview(BaseModel):
     last_message_text: Optional[str] = None
     last_message_type: Optional[str] = None
     last_message_timestamp: Optional[datetime] = None
+
+    # Newest messages since the viewer last read the thread (newest first)
+    unseen_count: int = Field(default=0, description="Unseen messages among those the inbox returned")
+    unseen_messages: list[DirectMessage] = Field(default_factory=list, description="Up to 2 of them, newest first")
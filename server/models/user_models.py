@@ -20,3 +20,4 @@ class UserShort(BaseModel):
     username: str
     full_name: str = ""
     profile_pic_url: Optional[str] = None
+    is_active: Optional[bool] = Field(default=None, description="Online now; None when Instagram didn't say")